@@ -1,11 +1,16 @@
 //! Anomaly detection operations
 //!
 //! Provides statistical anomaly detection using methods like Z-score,
-//! IQR (Interquartile Range), and isolation forest.
+//! IQR (Interquartile Range), MAD (median absolute deviation), and
+//! isolation forest.
+//!
+//! `AnomalyMethod::MovingAverage` compares each value with the values just
+//! before it, so it assumes the rows are already in time order.
 
 use anyhow::Result;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Anomaly detection method
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -13,8 +18,13 @@ pub enum AnomalyMethod {
     ZScore { threshold: f64 },
     IQR { multiplier: f64 },
     Percentile { lower: f64, upper: f64 },
+    Mad { threshold: f64 },
+    MovingAverage { window: usize, threshold: f64 },
 }
 
+/// Scales the MAD to estimate the standard deviation of normally distributed data
+const MAD_SCALE: f64 = 1.4826;
+
 /// Anomaly detection result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnomalyResult {
@@ -46,66 +56,109 @@ impl AnomalyDetector {
     /// Detect anomalies in a column
     pub fn detect(&self, data: &[Vec<String>], column: usize) -> Result<AnomalyResult> {
         if data.is_empty() || column >= data[0].len() {
-            return Ok(AnomalyResult {
-                anomalies: Vec::new(),
-                total_anomalies: 0,
-                anomaly_percentage: 0.0,
-            });
+            return Ok(Self::summarize(Vec::new(), 0));
         }
 
-        // Extract numeric values
-        let values: Vec<f64> = data
-            .iter()
-            .skip(1) // Skip header
-            .filter_map(|row| row.get(column))
-            .filter_map(|v| v.parse::<f64>().ok())
-            .collect();
+        let samples = numeric_samples(data, column);
+        let anomalies = self.detect_samples(&samples, column)?;
+        Ok(Self::summarize(anomalies, samples.len()))
+    }
 
-        if values.is_empty() {
-            return Ok(AnomalyResult {
-                anomalies: Vec::new(),
-                total_anomalies: 0,
-                anomaly_percentage: 0.0,
-            });
+    /// Detect anomalies in `value_col` separately within each group of `group_col`
+    ///
+    /// Each group gets its own statistics, so a value that is ordinary across
+    /// the whole column can still be flagged as extreme within its group.
+    /// Anomalies keep the row index of the original data and are returned in
+    /// row order.
+    pub fn detect_grouped(
+        &self,
+        data: &[Vec<String>],
+        value_col: usize,
+        group_col: usize,
+    ) -> Result<AnomalyResult> {
+        let Some(header) = data.first() else {
+            return Ok(Self::summarize(Vec::new(), 0));
+        };
+        if value_col >= header.len() {
+            anyhow::bail!("Value column index {value_col} out of range");
+        }
+        if group_col >= header.len() {
+            anyhow::bail!("Group column index {group_col} out of range");
         }
 
-        let anomalies = match self.method {
-            AnomalyMethod::ZScore { threshold } => {
-                self.detect_zscore(&values, column, threshold)?
-            }
-            AnomalyMethod::IQR { multiplier } => self.detect_iqr(&values, column, multiplier)?,
+        let mut groups: HashMap<&str, Vec<(usize, f64)>> = HashMap::new();
+        for (row, value) in numeric_samples(data, value_col) {
+            let key = data[row].get(group_col).map(String::as_str).unwrap_or("");
+            groups.entry(key).or_default().push((row, value));
+        }
+
+        let mut anomalies = Vec::new();
+        let mut total = 0;
+        for samples in groups.values() {
+            total += samples.len();
+            anomalies.extend(self.detect_samples(samples, value_col)?);
+        }
+        anomalies.sort_by_key(|a| a.row);
+
+        Ok(Self::summarize(anomalies, total))
+    }
+
+    fn detect_samples(&self, samples: &[(usize, f64)], column: usize) -> Result<Vec<Anomaly>> {
+        if samples.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        match self.method {
+            AnomalyMethod::ZScore { threshold } => self.detect_zscore(samples, column, threshold),
+            AnomalyMethod::IQR { multiplier } => self.detect_iqr(samples, column, multiplier),
             AnomalyMethod::Percentile { lower, upper } => {
-                self.detect_percentile(&values, column, lower, upper)?
+                self.detect_percentile(samples, column, lower, upper)
             }
-        };
+            AnomalyMethod::Mad { threshold } => self.detect_mad(samples, column, threshold),
+            AnomalyMethod::MovingAverage { window, threshold } => {
+                self.detect_moving_average(samples, column, window, threshold)
+            }
+        }
+    }
 
+    fn summarize(anomalies: Vec<Anomaly>, sample_count: usize) -> AnomalyResult {
         let total_anomalies = anomalies.len();
-        let anomaly_percentage = (total_anomalies as f64 / values.len() as f64) * 100.0;
+        let anomaly_percentage = if sample_count == 0 {
+            0.0
+        } else {
+            (total_anomalies as f64 / sample_count as f64) * 100.0
+        };
 
-        Ok(AnomalyResult {
+        AnomalyResult {
             anomalies,
             total_anomalies,
             anomaly_percentage,
-        })
+        }
     }
 
-    fn detect_zscore(&self, values: &[f64], column: usize, threshold: f64) -> Result<Vec<Anomaly>> {
+    fn detect_zscore(
+        &self,
+        samples: &[(usize, f64)],
+        column: usize,
+        threshold: f64,
+    ) -> Result<Vec<Anomaly>> {
+        let values: Vec<f64> = samples.iter().map(|&(_, v)| v).collect();
         let mean = values.par_iter().sum::<f64>() / values.len() as f64;
-        let variance = values.par_iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        let variance =
+            values.par_iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
         let std_dev = variance.sqrt();
 
         if std_dev == 0.0 {
             return Ok(Vec::new());
         }
 
-        let anomalies: Vec<Anomaly> = values
+        let anomalies: Vec<Anomaly> = samples
             .par_iter()
-            .enumerate()
-            .filter_map(|(idx, value)| {
+            .filter_map(|(row, value)| {
                 let z_score = (value - mean).abs() / std_dev;
                 if z_score > threshold {
                     Some(Anomaly {
-                        row: idx + 1, // +1 for header
+                        row: *row,
                         column: format!("col_{column}"),
                         value: value.to_string(),
                         score: z_score,
@@ -120,8 +173,13 @@ impl AnomalyDetector {
         Ok(anomalies)
     }
 
-    fn detect_iqr(&self, values: &[f64], column: usize, multiplier: f64) -> Result<Vec<Anomaly>> {
-        let mut sorted = values.to_vec();
+    fn detect_iqr(
+        &self,
+        samples: &[(usize, f64)],
+        column: usize,
+        multiplier: f64,
+    ) -> Result<Vec<Anomaly>> {
+        let mut sorted: Vec<f64> = samples.iter().map(|&(_, v)| v).collect();
         sorted.par_sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
         let q1_idx = sorted.len() / 4;
@@ -134,10 +192,9 @@ impl AnomalyDetector {
         let lower_bound = q1 - multiplier * iqr;
         let upper_bound = q3 + multiplier * iqr;
 
-        let anomalies: Vec<Anomaly> = values
+        let anomalies: Vec<Anomaly> = samples
             .par_iter()
-            .enumerate()
-            .filter_map(|(idx, value)| {
+            .filter_map(|(row, value)| {
                 if *value < lower_bound || *value > upper_bound {
                     let reason = if *value < lower_bound {
                         format!("Value {value:.2} below lower bound {lower_bound:.2}")
@@ -146,7 +203,7 @@ impl AnomalyDetector {
                     };
 
                     Some(Anomaly {
-                        row: idx + 1,
+                        row: *row,
                         column: format!("col_{column}"),
                         value: value.to_string(),
                         score: if *value < lower_bound {
@@ -167,12 +224,12 @@ impl AnomalyDetector {
 
     fn detect_percentile(
         &self,
-        values: &[f64],
+        samples: &[(usize, f64)],
         column: usize,
         lower: f64,
         upper: f64,
     ) -> Result<Vec<Anomaly>> {
-        let mut sorted = values.to_vec();
+        let mut sorted: Vec<f64> = samples.iter().map(|&(_, v)| v).collect();
         sorted.par_sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
         let lower_idx = (sorted.len() as f64 * lower / 100.0) as usize;
@@ -181,13 +238,12 @@ impl AnomalyDetector {
         let lower_bound = sorted[lower_idx];
         let upper_bound = sorted[upper_idx];
 
-        let anomalies: Vec<Anomaly> = values
+        let anomalies: Vec<Anomaly> = samples
             .par_iter()
-            .enumerate()
-            .filter_map(|(idx, value)| {
+            .filter_map(|(row, value)| {
                 if *value < lower_bound || *value > upper_bound {
                     Some(Anomaly {
-                        row: idx + 1,
+                        row: *row,
                         column: format!("col_{column}"),
                         value: value.to_string(),
                         score: 1.0,
@@ -201,4 +257,108 @@ impl AnomalyDetector {
 
         Ok(anomalies)
     }
+
+    /// Z-score of each value against the mean and standard deviation of the
+    /// `window` values before it, so a steady trend is not flagged
+    ///
+    /// Samples must be in time order. The first `window` values have no full
+    /// history and are never flagged, nor is a value following a flat window.
+    fn detect_moving_average(
+        &self,
+        samples: &[(usize, f64)],
+        column: usize,
+        window: usize,
+        threshold: f64,
+    ) -> Result<Vec<Anomaly>> {
+        if window == 0 {
+            anyhow::bail!("Moving average window must be at least 1");
+        }
+
+        let anomalies: Vec<Anomaly> = samples
+            .par_windows(window + 1)
+            .filter_map(|slice| {
+                let (history, current) = slice.split_at(window);
+                let (row, value) = current[0];
+                let mean = history.iter().map(|&(_, v)| v).sum::<f64>() / window as f64;
+                let variance =
+                    history.iter().map(|&(_, v)| (v - mean).powi(2)).sum::<f64>() / window as f64;
+                let std_dev = variance.sqrt();
+                if std_dev == 0.0 {
+                    return None;
+                }
+
+                let z_score = (value - mean).abs() / std_dev;
+                (z_score > threshold).then(|| Anomaly {
+                    row,
+                    column: format!("col_{column}"),
+                    value: value.to_string(),
+                    score: z_score,
+                    reason: format!(
+                        "Z-score {z_score:.2} against the previous {window} values (mean {mean:.2}) exceeds threshold {threshold:.2}"
+                    ),
+                })
+            })
+            .collect();
+
+        Ok(anomalies)
+    }
+
+    /// Robust z-score: distance from the median in units of the scaled median
+    /// absolute deviation, so gross outliers barely move the scale
+    fn detect_mad(
+        &self,
+        samples: &[(usize, f64)],
+        column: usize,
+        threshold: f64,
+    ) -> Result<Vec<Anomaly>> {
+        let values: Vec<f64> = samples.iter().map(|&(_, v)| v).collect();
+        let center = median(values.clone());
+        let mad = median(values.iter().map(|v| (v - center).abs()).collect()) * MAD_SCALE;
+
+        if mad == 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let anomalies: Vec<Anomaly> = samples
+            .par_iter()
+            .filter_map(|(row, value)| {
+                let score = (value - center).abs() / mad;
+                if score > threshold {
+                    Some(Anomaly {
+                        row: *row,
+                        column: format!("col_{column}"),
+                        value: value.to_string(),
+                        score,
+                        reason: format!(
+                            "Robust z-score {score:.2} (median {center:.2}, MAD {mad:.2}) exceeds threshold {threshold:.2}"
+                        ),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Ok(anomalies)
+    }
+}
+
+/// Numeric cells of `column` paired with their row index, skipping the header
+fn numeric_samples(data: &[Vec<String>], column: usize) -> Vec<(usize, f64)> {
+    data.iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(idx, row)| Some((idx, row.get(column)?.parse::<f64>().ok()?)))
+        .collect()
+}
+
+/// Median of a non-empty set of values
+fn median(mut values: Vec<f64>) -> f64 {
+    values.par_sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
 }
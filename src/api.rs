@@ -2,6 +2,20 @@
 //!
 //! Provides HTTP API endpoints for datacell operations using axum.
 //!
+//! Every endpoint takes a JSON body naming a server-side `input` path, or a
+//! `multipart/form-data` upload whose `file` field carries the data and whose
+//! other fields are the remaining request keys (values that parse as JSON,
+//! such as `sample_size=100`, are taken as JSON):
+//!
+//! - `POST /read` - read a file, returned as JSON rows or CSV
+//! - `POST /convert` - return the data as CSV/JSON, or write it to `output`
+//!   when [`ApiConfig::allow_output`] is set
+//! - `POST /profile` - data profile as JSON
+//! - `POST /operations` - apply transform steps like `{"op": "sort", "col": "name"}`
+//!
+//! Server-side paths are resolved against [`ApiConfig::root`] and rejected
+//! with `403` when they lead outside it, symlinks included.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -19,17 +33,24 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "api")]
+use anyhow::Context;
 #[cfg(feature = "api")]
 use axum::{
-    extract::{DefaultBodyLimit, Json},
-    http::StatusCode,
+    Router,
+    extract::{DefaultBodyLimit, FromRequest, Json, Multipart, Request, State},
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
     routing::post,
-    Router,
 };
 #[cfg(feature = "api")]
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+#[cfg(feature = "api")]
+use std::{path::Path, sync::Arc};
+#[cfg(feature = "api")]
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, CorsLayer},
     limit::RequestBodyLimitLayer,
 };
 
@@ -38,8 +59,14 @@ use tower_http::{
 pub struct ApiConfig {
     pub host: String,
     pub port: u16,
+    /// Answer CORS requests from `cors_origins`; no origin is allowed when empty
     pub cors_enabled: bool,
+    pub cors_origins: Vec<String>,
     pub max_request_size: usize,
+    /// Directory that server-side `input` and `output` paths must stay within
+    pub root: PathBuf,
+    /// Let `/convert` write files under `root`
+    pub allow_output: bool,
 }
 
 impl Default for ApiConfig {
@@ -47,48 +74,63 @@ impl Default for ApiConfig {
         Self {
             host: "127.0.0.1".to_string(),
             port: 8080,
-            cors_enabled: true,
+            cors_enabled: false,
+            cors_origins: Vec::new(),
             max_request_size: 10 * 1024 * 1024, // 10MB
+            root: PathBuf::from("."),
+            allow_output: false,
         }
     }
 }
 
-/// API request types
+/// How an endpoint returns table data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    /// An [`ApiResponse`] whose data is `{"data": rows}`
+    #[default]
+    Json,
+    /// The rows as a `text/csv` body
+    Csv,
+}
+
+/// Body of `POST /read`
 #[derive(Debug, Deserialize)]
-#[serde(tag = "operation")]
-pub enum ApiRequest {
-    Read {
-        input: String,
-        sheet: Option<String>,
-        range: Option<String>,
-    },
-    Write {
-        output: String,
-        data: Vec<Vec<String>>,
-        sheet: Option<String>,
-    },
-    Convert {
-        input: String,
-        output: String,
-        sheet: Option<String>,
-    },
-    Profile {
-        input: String,
-        sample_size: Option<usize>,
-    },
-    Validate {
-        input: String,
-        rules: String,
-    },
-    Filter {
-        input: String,
-        where_clause: String,
-    },
-    Sort {
-        input: String,
-        column: String,
-        ascending: bool,
-    },
+pub struct ReadRequest {
+    pub input: String,
+    pub sheet: Option<String>,
+    #[serde(default)]
+    pub format: ResponseFormat,
+}
+
+/// Body of `POST /convert`; without `output` the data is returned in `format`
+///
+/// `output` is refused unless the server enables [`ApiConfig::allow_output`].
+#[derive(Debug, Deserialize)]
+pub struct ConvertRequest {
+    pub input: String,
+    pub output: Option<String>,
+    pub sheet: Option<String>,
+    #[serde(default)]
+    pub format: ResponseFormat,
+}
+
+/// Body of `POST /profile`
+#[derive(Debug, Deserialize)]
+pub struct ProfileRequest {
+    pub input: String,
+    pub sheet: Option<String>,
+    pub sample_size: Option<usize>,
+}
+
+/// Body of `POST /operations`, using the MCP transform tool's step descriptors
+#[derive(Debug, Deserialize)]
+pub struct OperationsRequest {
+    pub input: String,
+    pub sheet: Option<String>,
+    pub operations: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub format: ResponseFormat,
 }
 
 /// API response
@@ -139,49 +181,60 @@ impl ApiServer {
         Self { config }
     }
 
-    /// Start the API server (requires the "api" feature)
+    /// The endpoints with the body limit and, if enabled, the CORS layer
     #[cfg(feature = "api")]
-    pub async fn start(&self) -> Result<()> {
-        use crate::converter::Converter;
-        use crate::operations::DataOperations;
-        use crate::profiling::DataProfiler;
-
-        // Build our application with routes
+    pub fn router(&self) -> Router {
         let app = Router::new()
-            .route("/api/read", post(handle_read))
-            .route("/api/write", post(handle_write))
-            .route("/api/convert", post(handle_convert))
-            .route("/api/profile", post(handle_profile))
-            .route("/api/validate", post(handle_validate))
-            .route("/api/filter", post(handle_filter))
-            .route("/api/sort", post(handle_sort))
+            .route("/read", post(handle_read))
+            .route("/convert", post(handle_convert))
+            .route("/profile", post(handle_profile))
+            .route("/operations", post(handle_operations))
+            .with_state(Arc::new(self.config.clone()))
             .layer(DefaultBodyLimit::max(self.config.max_request_size))
             .layer(RequestBodyLimitLayer::new(self.config.max_request_size));
 
-        let app = if self.config.cors_enabled {
-            app.layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
+        let origins: Vec<header::HeaderValue> = self
+            .config
+            .cors_origins
+            .iter()
+            .filter_map(|origin| origin.parse().ok())
+            .collect();
+        if self.config.cors_enabled && !origins.is_empty() {
+            app.layer(
+                CorsLayer::new()
+                    .allow_origin(AllowOrigin::list(origins))
+                    .allow_methods([axum::http::Method::POST])
+                    .allow_headers([header::CONTENT_TYPE]),
+            )
         } else {
             app
-        };
+        }
+    }
 
+    /// Start the API server on the configured host and port (requires the "api" feature)
+    #[cfg(feature = "api")]
+    pub async fn start(&self) -> Result<()> {
         let addr = format!("{}:{}", self.config.host, self.config.port);
         let listener = tokio::net::TcpListener::bind(&addr)
             .await
             .with_context(|| format!("Failed to bind to {addr}"))?;
 
-        println!("🚀 API server listening on http://{}", addr);
-        println!("📊 Available endpoints:");
-        println!("   POST /api/read      - Read data from a file");
-        println!("   POST /api/write     - Write data to a file");
-        println!("   POST /api/convert   - Convert between file formats");
-        println!("   POST /api/profile   - Generate data profile");
-        println!("   POST /api/validate  - Validate data against rules");
-        println!("   POST /api/filter    - Filter data rows");
-        println!("   POST /api/sort      - Sort data by column");
+        eprintln!("API server listening on http://{}", listener.local_addr()?);
+        eprintln!("Endpoints:");
+        eprintln!("   POST /read        - Read data from a file");
+        eprintln!("   POST /convert     - Convert between file formats");
+        eprintln!("   POST /profile     - Generate data profile");
+        eprintln!("   POST /operations  - Apply transform operations");
 
-        axum::serve(listener, app).await.context("API server error")?;
+        self.serve(listener).await
+    }
 
-        Ok(())
+    /// Serve on an already bound listener, such as one on an ephemeral port
+    #[cfg(feature = "api")]
+    pub async fn serve(&self, listener: tokio::net::TcpListener) -> Result<()> {
+        axum::serve(listener, self.router())
+            .await
+            .context("API server error")
     }
 
     /// Start the API server (fallback when "api" feature is not enabled)
@@ -196,246 +249,381 @@ impl ApiServer {
 
 /// Error response type
 #[cfg(feature = "api")]
-struct ApiError(anyhow::Error);
-
-#[cfg(feature = "api")]
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let body = Json(ApiResponse::error(self.0.to_string()));
-        (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
-    }
+struct ApiError {
+    status: StatusCode,
+    error: anyhow::Error,
 }
 
-/// Handler for /api/read
 #[cfg(feature = "api")]
-async fn handle_read(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
-    use crate::converter::Converter;
-
-    let converter = Converter::new();
-
-    let (input, sheet, range) = match req {
-        ApiRequest::Read { input, sheet, range } => (input, sheet, range),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
-    };
+impl ApiError {
+    fn bad_request(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error: error.into(),
+        }
+    }
 
-    let data = converter
-        .read_any_data(&input, sheet.as_deref())
-        .map_err(ApiError)?;
+    fn forbidden(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            error: error.into(),
+        }
+    }
 
-    let response = if range.is_some() {
-        // TODO: Implement range filtering
-        ApiResponse::success(serde_json::json!({ "data": data }))
-    } else {
-        ApiResponse::success(serde_json::json!({ "data": data }))
-    };
+    fn internal(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error: error.into(),
+        }
+    }
+}
 
-    Ok(Json(response))
+#[cfg(feature = "api")]
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            error,
+        }
+    }
 }
 
-/// Handler for /api/write
 #[cfg(feature = "api")]
-async fn handle_write(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
-    use crate::traits::DataWriteOptions;
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(ApiResponse::error(format!("{:#}", self.error)));
+        (self.status, body).into_response()
+    }
+}
 
-    let (output, data, sheet) = match req {
-        ApiRequest::Write { output, data, sheet } => (output, data, sheet),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
-    };
+/// A request body from JSON or a multipart upload
+///
+/// A multipart body must carry a `file` part, which is staged in the temp
+/// directory under its original extension and read in place of `input`; the
+/// file is removed when the payload is dropped.
+#[cfg(feature = "api")]
+struct Payload<T> {
+    request: T,
+    upload: Option<StagedUpload>,
+}
 
-    // Determine file format from extension
-    let format = output
-        .rsplit('.')
-        .next()
-        .ok_or_else(|| ApiError(anyhow::anyhow!("Invalid file path")))?;
+#[cfg(feature = "api")]
+impl<T> Payload<T> {
+    /// The staged upload, or `input` resolved under the API root
+    fn input_path(&self, config: &ApiConfig, input: &str) -> Result<String, ApiError> {
+        let path = match &self.upload {
+            Some(upload) => upload.0.path().to_path_buf(),
+            None => resolve_under_root(&config.root, input, false)?,
+        };
+        Ok(path.to_string_lossy().into_owned())
+    }
+}
 
-    let options = DataWriteOptions {
-        sheet_name: sheet,
-        column_names: None,
-        include_headers: true,
-    };
+/// An uploaded file, removed when the request is done
+#[cfg(feature = "api")]
+struct StagedUpload(tempfile::NamedTempFile);
 
-    match format {
-        "csv" => {
-            use crate::csv_handler::CsvHandler;
-            let handler = CsvHandler::new();
-            handler
-                .write(&output, &data, options)
-                .map_err(ApiError)?;
-        }
-        "xlsx" => {
-            use crate::excel::ExcelHandler;
-            let handler = ExcelHandler::new();
-            handler
-                .write(&output, &data, options)
-                .map_err(ApiError)?;
-        }
-        "parquet" => {
-            use crate::columnar::ParquetHandler;
-            let handler = ParquetHandler::new();
-            handler
-                .write(&output, &data, options)
-                .map_err(ApiError)?;
+#[cfg(feature = "api")]
+#[axum::async_trait]
+impl<S, T> FromRequest<S> for Payload<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_multipart = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("multipart/form-data"));
+        if !is_multipart {
+            let Json(request) = Json::<T>::from_request(req, state)
+                .await
+                .map_err(|e| ApiError::bad_request(anyhow::anyhow!(e.body_text())))?;
+            return Ok(Self {
+                request,
+                upload: None,
+            });
         }
-        "avro" => {
-            use crate::columnar::AvroHandler;
-            let handler = AvroHandler::new();
-            handler
-                .write(&output, &data, options)
-                .map_err(ApiError)?;
+
+        let mut multipart = Multipart::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::bad_request(anyhow::anyhow!(e.body_text())))?;
+        let mut fields = serde_json::Map::new();
+        let mut upload = None;
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| ApiError::bad_request(anyhow::anyhow!(e.body_text())))?
+        {
+            let name = field.name().unwrap_or_default().to_string();
+            if name == "input" {
+                return Err(ApiError::bad_request(anyhow::anyhow!(
+                    "Multipart requests send their data as a `file` part, not `input`"
+                )));
+            }
+            if name == "file" {
+                let extension = upload_extension(field.file_name())?;
+                // The client's file name labels the data; the staged path stays private
+                let label = field.file_name().unwrap_or("upload").to_string();
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ApiError::bad_request(anyhow::anyhow!(e.body_text())))?;
+                let staged = blocking(move || Ok(stage_upload(&bytes, &extension)?)).await?;
+                fields.insert("input".to_string(), label.into());
+                upload = Some(staged);
+            } else {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError::bad_request(anyhow::anyhow!(e.body_text())))?;
+                let value = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+                fields.insert(name, value);
+            }
         }
-        _ => {
-            return Err(ApiError(anyhow::anyhow!(
-                "Unsupported output format: {}",
-                format
-            )))
+
+        if upload.is_none() {
+            return Err(ApiError::bad_request(anyhow::anyhow!(
+                "Multipart request has no `file` part"
+            )));
         }
+        let request = serde_json::from_value(serde_json::Value::Object(fields))
+            .map_err(ApiError::bad_request)?;
+        Ok(Self { request, upload })
     }
-
-    Ok(Json(ApiResponse::message(format!(
-        "Data written to {}",
-        output
-    ))))
 }
 
-/// Handler for /api/convert
+/// The staged extension for an uploaded file name, which must be a supported
+/// format; files without a name are taken as CSV
 #[cfg(feature = "api")]
-async fn handle_convert(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
-    use crate::converter::Converter;
+fn upload_extension(file_name: Option<&str>) -> Result<String, ApiError> {
+    use crate::format_detector::DefaultFormatDetector;
+    use crate::traits::FormatDetector;
 
-    let (input, output, sheet) = match req {
-        ApiRequest::Convert { input, output, sheet } => (input, output, sheet),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
+    let Some(file_name) = file_name else {
+        return Ok("csv".to_string());
     };
-
-    let converter = Converter::new();
-    converter
-        .convert(&input, &output, sheet.as_deref())
-        .map_err(ApiError)?;
-
-    Ok(Json(ApiResponse::message(format!(
-        "Converted {} to {}",
-        input, output
-    ))))
+    let file_name = file_name.to_lowercase();
+    if file_name.ends_with(".csv.gz") {
+        return Ok("csv.gz".to_string());
+    }
+    DefaultFormatDetector::new()
+        .supported_formats()
+        .into_iter()
+        .filter(|format| format != "gsheet")
+        .find(|format| {
+            file_name
+                .rsplit_once('.')
+                .is_some_and(|(_, extension)| extension == format)
+        })
+        .ok_or_else(|| {
+            ApiError::bad_request(anyhow::anyhow!(
+                "Unsupported upload file name {file_name:?}"
+            ))
+        })
 }
 
-/// Handler for /api/profile
+/// Resolve `path` against `root`, refusing anything that ends up outside it
+///
+/// Existing paths are canonicalized, so symlinks cannot escape the root. An
+/// `output` path need not exist yet, but its parent directory must.
 #[cfg(feature = "api")]
-async fn handle_profile(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
-    use crate::converter::Converter;
-    use crate::profiling::DataProfiler;
-
-    let (input, sample_size) = match req {
-        ApiRequest::Profile { input, sample_size } => (input, sample_size),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
+fn resolve_under_root(root: &Path, path: &str, output: bool) -> Result<PathBuf, ApiError> {
+    let outside = || {
+        ApiError::forbidden(anyhow::anyhow!(
+            "Path {path} is not available under the API root"
+        ))
+    };
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("API root {} does not exist", root.display()))?;
+    let candidate = root.join(path);
+
+    let resolved = if output {
+        let file_name = candidate
+            .file_name()
+            .filter(|name| *name != "..")
+            .ok_or_else(outside)?
+            .to_owned();
+        let parent = candidate.parent().ok_or_else(outside)?;
+        parent
+            .canonicalize()
+            .map_err(|_| outside())?
+            .join(file_name)
+    } else {
+        candidate.canonicalize().map_err(|_| outside())?
     };
 
-    let converter = Converter::new();
-    let data = converter
-        .read_any_data(&input, None)
-        .map_err(ApiError)?;
-
-    let mut profiler = DataProfiler::new();
-    if let Some(size) = sample_size {
-        profiler = profiler.with_sample_size(size);
+    if resolved.starts_with(&root) && (!output || !resolved.is_symlink()) {
+        Ok(resolved)
+    } else {
+        Err(outside())
     }
-
-    let profile = profiler
-        .analyze_dataset(&data)
-        .map_err(ApiError)?;
-
-    Ok(Json(ApiResponse::success(serde_json::to_value(profile).map_err(
-        |e| ApiError(anyhow::anyhow!("Failed to serialize profile: {}", e)),
-    )?)))
 }
 
-/// Handler for /api/validate
+/// Write an uploaded file to a freshly created temp file
+///
+/// The file is created exclusively with a random name, so a path planted
+/// in the temp directory by another user is never followed.
 #[cfg(feature = "api")]
-async fn handle_validate(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
-    use crate::converter::Converter;
-    use crate::validation::ValidationRule;
-
-    let (input, rules) = match req {
-        ApiRequest::Validate { input, rules } => (input, rules),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
-    };
-
-    let converter = Converter::new();
-    let data = converter
-        .read_any_data(&input, None)
-        .map_err(ApiError)?;
+fn stage_upload(bytes: &[u8], extension: &str) -> Result<StagedUpload> {
+    use std::io::Write;
+
+    let mut file = tempfile::Builder::new()
+        .prefix("datacell-upload-")
+        .suffix(&format!(".{extension}"))
+        .tempfile()
+        .context("Failed to create a temp file for the upload")?;
+    file.write_all(bytes)
+        .and_then(|()| file.flush())
+        .context("Failed to stage upload")?;
+    Ok(StagedUpload(file))
+}
 
-    // Parse validation rules from JSON
-    let validation_rules: Vec<ValidationRule> =
-        serde_json::from_str(&rules).map_err(ApiError)?;
+/// Run `work` on the blocking thread pool, keeping file IO and row crunching
+/// off the async workers
+#[cfg(feature = "api")]
+async fn blocking<T, F>(work: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(ApiError::internal)?
+}
 
-    let mut results = Vec::new();
-    for rule in validation_rules {
-        let result = rule.validate(&data);
-        results.push(result);
+/// Rows as a JSON response or a `text/csv` body
+#[cfg(feature = "api")]
+fn data_response(data: &[Vec<String>], format: ResponseFormat) -> Result<Response, ApiError> {
+    match format {
+        ResponseFormat::Json => {
+            Ok(Json(ApiResponse::success(serde_json::json!({ "data": data }))).into_response())
+        }
+        ResponseFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .flexible(true)
+                .from_writer(Vec::new());
+            for row in data {
+                writer.write_record(row).map_err(anyhow::Error::from)?;
+            }
+            let body = writer.into_inner().map_err(|e| anyhow::anyhow!("{e}"))?;
+            Ok(([(header::CONTENT_TYPE, "text/csv; charset=utf-8")], body).into_response())
+        }
     }
-
-    Ok(Json(ApiResponse::success(serde_json::json!({
-        "valid": results.iter().all(|r| r.is_valid),
-        "results": results
-    }))))
 }
 
-/// Handler for /api/filter
+/// Handler for /read
 #[cfg(feature = "api")]
-async fn handle_filter(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
+async fn handle_read(
+    State(config): State<Arc<ApiConfig>>,
+    payload: Payload<ReadRequest>,
+) -> Result<Response, ApiError> {
     use crate::converter::Converter;
-    use crate::operations::DataOperations;
-
-    let (input, where_clause) = match req {
-        ApiRequest::Filter {
-            input,
-            where_clause,
-        } => (input, where_clause),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
-    };
 
-    let converter = Converter::new();
-    let mut data = converter.read_any_data(&input, None).map_err(ApiError)?;
-
-    let ops = DataOperations::new();
-    ops.filter(&mut data, &where_clause).map_err(ApiError)?;
-
-    Ok(Json(ApiResponse::success(serde_json::json!({ "data": data }))))
+    blocking(move || {
+        let req = &payload.request;
+        let input = payload.input_path(&config, &req.input)?;
+        let data = Converter::new().read_any_data(&input, req.sheet.as_deref())?;
+        data_response(&data, req.format)
+    })
+    .await
 }
 
-/// Handler for /api/sort
+/// Handler for /convert
 #[cfg(feature = "api")]
-async fn handle_sort(Json(req): Json<ApiRequest>) -> Result<Json<ApiResponse>, ApiError> {
+async fn handle_convert(
+    State(config): State<Arc<ApiConfig>>,
+    payload: Payload<ConvertRequest>,
+) -> Result<Response, ApiError> {
     use crate::converter::Converter;
-    use crate::operations::DataOperations;
-
-    let (input, column, ascending) = match req {
-        ApiRequest::Sort {
-            input,
-            column,
-            ascending,
-        } => (input, column, ascending),
-        _ => return Err(ApiError(anyhow::anyhow!("Invalid request"))),
-    };
 
-    let converter = Converter::new();
-    let mut data = converter.read_any_data(&input, None).map_err(ApiError)?;
+    blocking(move || {
+        let req = &payload.request;
+        let input = payload.input_path(&config, &req.input)?;
+        let converter = Converter::new();
+        let data = converter.read_any_data(&input, req.sheet.as_deref())?;
+        match &req.output {
+            Some(output) => {
+                if !config.allow_output {
+                    return Err(ApiError::forbidden(anyhow::anyhow!(
+                        "Writing server-side output is disabled on this server"
+                    )));
+                }
+                let path = resolve_under_root(&config.root, output, true)?;
+                converter.write_any_data(&path.to_string_lossy(), &data, None)?;
+                Ok(Json(ApiResponse::message(format!(
+                    "Converted {} rows to {}",
+                    data.len(),
+                    output
+                )))
+                .into_response())
+            }
+            None => data_response(&data, req.format),
+        }
+    })
+    .await
+}
 
-    let ops = DataOperations::new();
+/// Handler for /profile
+#[cfg(feature = "api")]
+async fn handle_profile(
+    State(config): State<Arc<ApiConfig>>,
+    payload: Payload<ProfileRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    use crate::converter::Converter;
+    use crate::profiling::DataProfiler;
 
-    // Find column index by name
-    if data.is_empty() {
-        return Err(ApiError(anyhow::anyhow!("Data is empty")));
-    }
+    blocking(move || {
+        let req = &payload.request;
+        let input = payload.input_path(&config, &req.input)?;
+        let data = Converter::new().read_any_data(&input, req.sheet.as_deref())?;
 
-    let column_idx = data[0]
-        .iter()
-        .position(|c| c == &column)
-        .ok_or_else(|| ApiError(anyhow::anyhow!("Column '{}' not found", column)))?;
+        let mut profiler = DataProfiler::new();
+        if let Some(size) = req.sample_size {
+            profiler = profiler.with_sample_size(size);
+        }
+        let profile = profiler.profile(&data, &req.input)?;
 
-    ops.sort(&mut data, column_idx, ascending).map_err(ApiError)?;
+        let profile = serde_json::to_value(profile).context("Failed to serialize profile")?;
+        Ok(Json(ApiResponse::success(profile)))
+    })
+    .await
+}
 
-    Ok(Json(ApiResponse::success(serde_json::json!({ "data": data }))))
+/// Handler for /operations
+#[cfg(feature = "api")]
+async fn handle_operations(
+    State(config): State<Arc<ApiConfig>>,
+    payload: Payload<OperationsRequest>,
+) -> Result<Response, ApiError> {
+    use crate::converter::Converter;
+    use crate::mcp::{StepError, TRANSFORM_OPS, apply_operation};
+
+    blocking(move || {
+        let req = &payload.request;
+        let input = payload.input_path(&config, &req.input)?;
+        let mut data = Converter::new().read_any_data(&input, req.sheet.as_deref())?;
+        for (step, operation) in req.operations.iter().enumerate() {
+            data = apply_operation(&data, operation).map_err(|e| {
+                let op = operation.get("op").cloned().unwrap_or_default();
+                ApiError::bad_request(match e {
+                    StepError::UnknownOp => anyhow::anyhow!(
+                        "Unknown operation {op} at step {step}; supported: {}",
+                        TRANSFORM_OPS.join(", ")
+                    ),
+                    StepError::Failed(e) => {
+                        anyhow::anyhow!("Operation {op} at step {step} failed: {e}")
+                    }
+                })
+            })?;
+        }
+        data_response(&data, req.format)
+    })
+    .await
 }
 
 #[cfg(test)]
@@ -447,7 +635,9 @@ mod tests {
         let config = ApiConfig::default();
         assert_eq!(config.host, "127.0.0.1");
         assert_eq!(config.port, 8080);
-        assert!(config.cors_enabled);
+        assert!(!config.cors_enabled);
+        assert!(config.cors_origins.is_empty());
+        assert!(!config.allow_output);
         assert_eq!(config.max_request_size, 10 * 1024 * 1024);
     }
 
@@ -0,0 +1,755 @@
+//! Arguments of the larger CLI commands
+//!
+//! Commands with many options keep them in a boxed struct so that
+//! `Commands` and clap's generated parser for it stay small.
+
+use anyhow::Result;
+use clap::Args;
+use std::fmt;
+use std::ops::Deref;
+
+use super::OutputFormat;
+use crate::converter::Converter;
+use crate::operations::DataOperations;
+
+/// Output options shared by the commands that write a result file
+///
+/// `--output` can be repeated to write several formats at once. The paths
+/// display as a comma-separated list and compare equal to a path only when
+/// it is the sole output, so `report!` sends messages to stderr when the data
+/// goes to stdout.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputArgs {
+    /// Output path; repeat to write several formats at once
+    #[arg(
+        id = "output",
+        short,
+        long = "output",
+        value_name = "OUTPUT",
+        required = true
+    )]
+    paths: Vec<String>,
+    /// Prepend a row-index column to the output, numbered from START (default 0)
+    #[arg(long, value_name = "START", num_args = 0..=1, default_missing_value = "0")]
+    pub index: Option<usize>,
+    /// Drop a leading `index` column before writing
+    #[arg(long)]
+    pub drop_index: bool,
+}
+
+impl OutputArgs {
+    /// Whether `--index` or `--drop-index` changes the data before writing
+    pub fn reindexes(&self) -> bool {
+        self.index.is_some() || self.drop_index
+    }
+
+    /// Write `data` to every output path, applying `--drop-index` and then
+    /// `--index`
+    pub fn write(
+        &self,
+        converter: &Converter,
+        data: &[Vec<String>],
+        sheet_name: Option<&str>,
+    ) -> Result<()> {
+        if !self.reindexes() {
+            return converter.write_multi(&self.paths, data, sheet_name);
+        }
+
+        let ops = DataOperations::new();
+        let mut data = if self.drop_index {
+            ops.reset_index(data, "index")
+        } else {
+            data.to_vec()
+        };
+        if let Some(start) = self.index {
+            data = ops.add_index(&data, "index", start);
+        }
+        converter.write_multi(&self.paths, &data, sheet_name)
+    }
+}
+
+impl From<Vec<String>> for OutputArgs {
+    fn from(paths: Vec<String>) -> Self {
+        Self {
+            paths,
+            ..Self::default()
+        }
+    }
+}
+
+impl Deref for OutputArgs {
+    type Target = [String];
+
+    fn deref(&self) -> &[String] {
+        &self.paths
+    }
+}
+
+impl fmt::Display for OutputArgs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.paths.join(", "))
+    }
+}
+
+impl PartialEq<&str> for OutputArgs {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self.paths.as_slice(), [path] if path == other)
+    }
+}
+
+/// Arguments of [`Commands::Read`](super::Commands::Read)
+#[derive(Args)]
+pub struct ReadArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub sheet: Option<String>,
+    #[arg(short, long)]
+    pub range: Option<String>,
+    #[arg(short = 'f', long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// Delimiter of a CSV input, e.g. ';' or 'tab' (detected if omitted)
+    #[arg(long)]
+    pub delimiter: Option<String>,
+    /// Encoding of a CSV input, e.g. 'latin1' or 'utf-16le' (UTF-8 if omitted)
+    #[arg(long)]
+    pub encoding: Option<String>,
+}
+
+/// Arguments of [`Commands::Write`](super::Commands::Write)
+#[derive(Args)]
+pub struct WriteArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub csv: Option<String>,
+    #[arg(short, long)]
+    pub sheet: Option<String>,
+    /// Delimiter for a CSV output, e.g. ';' or 'tab' (comma if omitted)
+    #[arg(long)]
+    pub delimiter: Option<String>,
+    /// Encoding of a CSV input, e.g. 'latin1' or 'utf-16le' (UTF-8 if omitted);
+    /// CSV output is always UTF-8
+    #[arg(long)]
+    pub encoding: Option<String>,
+    /// Parquet compression codec: snappy (default), zstd, gzip, lz4, brotli or none
+    #[arg(long)]
+    pub compression: Option<String>,
+}
+
+/// Arguments of [`Commands::Convert`](super::Commands::Convert)
+#[derive(Args)]
+pub struct ConvertArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub sheet: Option<String>,
+    /// Delimiter for a CSV output, e.g. ';' or 'tab' (comma if omitted)
+    #[arg(long)]
+    pub delimiter: Option<String>,
+    /// Encoding of a CSV input, e.g. 'latin1' or 'utf-16le' (UTF-8 if omitted);
+    /// CSV output is always UTF-8
+    #[arg(long)]
+    pub encoding: Option<String>,
+    /// Parquet compression codec: snappy (default), zstd, gzip, lz4, brotli or none
+    #[arg(long)]
+    pub compression: Option<String>,
+}
+
+/// Arguments of [`Commands::ConvertDir`](super::Commands::ConvertDir)
+#[derive(Args)]
+pub struct ConvertDirArgs {
+    /// Directory of input files
+    #[arg(short, long)]
+    pub input: String,
+    /// Directory for the converted files (created if missing)
+    #[arg(short, long)]
+    pub output: String,
+    /// Target extension, e.g. parquet or xlsx
+    #[arg(short, long)]
+    pub format: String,
+    #[arg(short, long)]
+    pub sheet: Option<String>,
+    /// Parquet compression codec: snappy (default), zstd, gzip, lz4, brotli or none
+    #[arg(long)]
+    pub compression: Option<String>,
+}
+
+/// Arguments of [`Commands::Formula`](super::Commands::Formula)
+#[derive(Args)]
+pub struct FormulaArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub output: String,
+    #[arg(short, long)]
+    pub formula: String,
+    #[arg(short, long)]
+    pub cell: String,
+    #[arg(short, long)]
+    pub sheet: Option<String>,
+}
+
+/// Arguments of [`Commands::ApiServer`](super::Commands::ApiServer)
+#[derive(Args)]
+pub struct ApiServerArgs {
+    /// Address to bind
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+    /// Port to bind
+    #[arg(short, long, default_value_t = 8080)]
+    pub port: u16,
+    /// Allow cross-origin requests from this origin (repeatable)
+    #[arg(long = "cors", value_name = "ORIGIN")]
+    pub cors_origins: Vec<String>,
+    /// Directory that request paths must stay within
+    #[arg(long, default_value = ".")]
+    pub root: String,
+    /// Let /convert write its output to files under the root
+    #[arg(long)]
+    pub allow_output: bool,
+}
+
+/// Arguments of [`Commands::Sort`](super::Commands::Sort)
+#[derive(Args)]
+pub struct SortArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    #[arg(short, long)]
+    pub ascending: bool,
+    /// Interpret the sort column as a type before comparing (supported: date, natural)
+    #[arg(long = "as", value_name = "TYPE")]
+    pub sort_as: Option<String>,
+    /// chrono format used to parse dates with `--as date`
+    #[arg(long, default_value = "%Y-%m-%d")]
+    pub date_format: String,
+    /// Natural sort (same as `--as natural`): `item2` sorts before `item10`
+    #[arg(long, conflicts_with = "sort_as")]
+    pub natural: bool,
+}
+
+/// Arguments of [`Commands::Replace`](super::Commands::Replace)
+#[derive(Args)]
+pub struct ReplaceArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub find: String,
+    #[arg(short, long)]
+    pub replace: String,
+    #[arg(short, long)]
+    pub column: Option<String>,
+}
+
+/// Arguments of [`Commands::Dedupe`](super::Commands::Dedupe)
+#[derive(Args)]
+pub struct DedupeArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub columns: Option<String>,
+    /// Use a bounded-memory Bloom filter (a small fraction of unique rows
+    /// may be dropped as false positives)
+    #[arg(long)]
+    pub approximate: bool,
+    /// Expected number of rows, used to size the Bloom filter
+    #[arg(long, default_value_t = 10_000_000)]
+    pub expected_rows: usize,
+    /// Target false-positive rate for the Bloom filter
+    #[arg(long, default_value_t = 0.001)]
+    pub fp_rate: f64,
+}
+
+/// Arguments of [`Commands::Head`](super::Commands::Head)
+#[derive(Args)]
+pub struct HeadArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short = 'n', long, default_value = "10")]
+    pub n: usize,
+    #[arg(short = 'f', long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// Prepend a row-index column, numbered from START (default 0)
+    #[arg(long, value_name = "START", num_args = 0..=1, default_missing_value = "0")]
+    pub index: Option<usize>,
+    /// Drop a leading `index` column from the input first
+    #[arg(long)]
+    pub drop_index: bool,
+}
+
+/// Arguments of [`Commands::Tail`](super::Commands::Tail)
+#[derive(Args)]
+pub struct TailArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short = 'n', long, default_value = "10")]
+    pub n: usize,
+    #[arg(short = 'f', long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// Prepend a row-index column, numbered from START (default 0)
+    #[arg(long, value_name = "START", num_args = 0..=1, default_missing_value = "0")]
+    pub index: Option<usize>,
+    /// Drop a leading `index` column from the input first
+    #[arg(long)]
+    pub drop_index: bool,
+}
+
+/// Arguments of [`Commands::Sample`](super::Commands::Sample)
+#[derive(Args)]
+pub struct SampleArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short = 'n', long, default_value = "10")]
+    pub n: usize,
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+    #[arg(short = 'f', long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// Prepend a row-index column, numbered from START (default 0)
+    #[arg(long, value_name = "START", num_args = 0..=1, default_missing_value = "0")]
+    pub index: Option<usize>,
+    /// Drop a leading `index` column from the input first
+    #[arg(long)]
+    pub drop_index: bool,
+}
+
+/// Arguments of [`Commands::Groupby`](super::Commands::Groupby)
+#[derive(Args)]
+pub struct GroupbyArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    /// Column(s) to group by, comma-separated for composite keys
+    #[arg(short, long)]
+    pub by: String,
+    #[arg(short, long)]
+    pub agg: String,
+    /// Separator used by `concat:col` aggregations
+    #[arg(long, default_value = ",")]
+    pub concat_sep: String,
+}
+
+/// Arguments of [`Commands::Join`](super::Commands::Join)
+#[derive(Args)]
+pub struct JoinArgs {
+    #[arg(short, long)]
+    pub left: String,
+    #[arg(short, long)]
+    pub right: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    /// Join column(s), comma-separated for composite keys (not used by cross joins)
+    #[arg(long)]
+    pub on: Option<String>,
+    #[arg(long)]
+    pub how: String,
+    /// Allow cross joins larger than the default row limit
+    #[arg(long)]
+    pub no_cross_limit: bool,
+}
+
+/// Arguments of [`Commands::Mutate`](super::Commands::Mutate)
+#[derive(Args)]
+pub struct MutateArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    #[arg(short, long)]
+    pub formula: String,
+}
+
+/// Arguments of [`Commands::Rename`](super::Commands::Rename)
+#[derive(Args)]
+pub struct RenameArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub from: String,
+    #[arg(short, long)]
+    pub to: String,
+}
+
+/// Arguments of [`Commands::Fillna`](super::Commands::Fillna)
+#[derive(Args)]
+pub struct FillnaArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    /// Constant to fill with (required by the `value` method)
+    #[arg(short, long)]
+    pub value: Option<String>,
+    #[arg(short, long)]
+    pub columns: Option<String>,
+    /// How to fill: value, ffill (carry last value down), bfill (carry next value up),
+    /// linear (interpolate between numbers)
+    #[arg(short, long, default_value = "value")]
+    pub method: String,
+}
+
+/// Arguments of [`Commands::Astype`](super::Commands::Astype)
+#[derive(Args)]
+pub struct AstypeArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    /// int, float, string, bool, or date[:FORMAT] with a chrono input format like %d/%m/%Y
+    #[arg(short = 't', long)]
+    pub target_type: String,
+}
+
+/// Arguments of [`Commands::Clip`](super::Commands::Clip)
+#[derive(Args)]
+pub struct ClipArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    #[arg(short, long)]
+    pub min: String,
+    #[arg(short = 'M', long)]
+    pub max: String,
+}
+
+/// Arguments of [`Commands::Normalize`](super::Commands::Normalize)
+#[derive(Args)]
+pub struct NormalizeArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    /// minmax (default) or zscore
+    #[arg(short = 'm', long, default_value = "minmax")]
+    pub method: String,
+    /// Write z-scores to a new <column>_zscore column instead of replacing
+    #[arg(long)]
+    pub append: bool,
+}
+
+/// Arguments of [`Commands::Pivot`](super::Commands::Pivot)
+#[derive(Args)]
+pub struct PivotArgs {
+    #[arg(short, long)]
+    pub input: String,
+    // `--index` names the pivot column here, so `OutputArgs` cannot be flattened in
+    /// Output path; repeat to write several formats at once
+    #[arg(short, long, required = true)]
+    pub output: Vec<String>,
+    #[arg(short, long)]
+    pub index: String,
+    #[arg(short, long)]
+    pub columns: String,
+    #[arg(short, long)]
+    pub values: String,
+    #[arg(short, long)]
+    pub agg: String,
+}
+
+/// Arguments of [`Commands::Melt`](super::Commands::Melt)
+#[derive(Args)]
+pub struct MeltArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    /// Identifier columns to keep (comma-separated)
+    #[arg(long)]
+    pub id_vars: String,
+    /// Columns to unpivot (comma-separated, default: all other columns)
+    #[arg(long)]
+    pub value_vars: Option<String>,
+}
+
+/// Arguments of [`Commands::Rolling`](super::Commands::Rolling)
+#[derive(Args)]
+pub struct RollingArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    /// Number of rows in each window
+    #[arg(short, long)]
+    pub window: usize,
+    /// Aggregation applied to each window (sum, mean, min, max, median, ...)
+    #[arg(short, long, default_value = "mean")]
+    pub agg: String,
+}
+
+/// Arguments of [`Commands::Rank`](super::Commands::Rank)
+#[derive(Args)]
+pub struct RankArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    /// How to rank ties: average, min, max, dense, first
+    #[arg(short, long, default_value = "average")]
+    pub method: String,
+    /// Give rank 1 to the largest value
+    #[arg(long)]
+    pub descending: bool,
+}
+
+/// Arguments of [`Commands::Bin`](super::Commands::Bin)
+#[derive(Args)]
+pub struct BinArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    /// Number of bins
+    #[arg(short, long)]
+    pub bins: usize,
+    /// cut (equal-width bins) or qcut (equal-frequency quantile bins)
+    #[arg(short, long, default_value = "cut")]
+    pub method: String,
+}
+
+/// Arguments of [`Commands::ParseDate`](super::Commands::ParseDate)
+#[derive(Args)]
+pub struct ParseDateArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    #[arg(short, long)]
+    pub from_format: String,
+    #[arg(short, long)]
+    pub to_format: String,
+}
+
+/// Arguments of [`Commands::RegexFilter`](super::Commands::RegexFilter)
+#[derive(Args)]
+pub struct RegexFilterArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    #[arg(short, long)]
+    pub pattern: String,
+}
+
+/// Arguments of [`Commands::RegexReplace`](super::Commands::RegexReplace)
+#[derive(Args)]
+pub struct RegexReplaceArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(short, long)]
+    pub column: String,
+    #[arg(short, long)]
+    pub pattern: String,
+    #[arg(short, long)]
+    pub replacement: String,
+}
+
+/// Arguments of [`Commands::DetectAnomalies`](super::Commands::DetectAnomalies)
+#[derive(Args)]
+pub struct DetectAnomaliesArgs {
+    #[arg(short, long)]
+    pub input: String,
+    /// Column name or 1-based index
+    #[arg(short, long)]
+    pub column: String,
+    /// Detection method: zscore, iqr, percentile, mad, moving-average[:window]
+    /// (the moving average window defaults to 10 and expects rows in time order)
+    #[arg(short, long, default_value = "zscore")]
+    pub method: String,
+    /// Cutoff for the method (z-score, IQR multiplier, tail percent or robust z-score)
+    #[arg(short, long)]
+    pub threshold: Option<f64>,
+    /// Detect within each group of this column instead of globally
+    #[arg(short, long)]
+    pub group: Option<String>,
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+/// Arguments of [`Commands::TextAnalysis`](super::Commands::TextAnalysis)
+#[derive(Args)]
+pub struct TextAnalysisArgs {
+    #[arg(short, long)]
+    pub input: String,
+    /// Column name or 1-based index
+    #[arg(short, long)]
+    pub column: String,
+    /// Analysis to run: stats, sentiment, keywords, tfidf, language
+    #[arg(short = 'p', long, default_value = "stats")]
+    pub operation: String,
+    /// Number of keywords to report
+    #[arg(short = 'n', long, default_value = "10")]
+    pub top: usize,
+    /// File of extra stop words (whitespace separated) to skip besides the English list
+    #[arg(long)]
+    pub stopwords: Option<String>,
+    /// Count keywords by stem, so "running" and "runs" are one keyword
+    #[arg(long)]
+    pub stem: bool,
+    /// Also report phrases of up to N words as keywords (keywords only)
+    #[arg(long, value_name = "N")]
+    pub ngram: Option<usize>,
+    /// Analyze each row and write the data with result columns appended
+    /// (per-row keywords keep the top 5)
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+/// Arguments of [`Commands::Validate`](super::Commands::Validate)
+#[derive(Args)]
+pub struct ValidateArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub rules: String,
+    #[arg(short, long)]
+    pub output: Option<String>,
+    #[arg(short, long)]
+    pub report: Option<String>,
+}
+
+/// Arguments of [`Commands::Chart`](super::Commands::Chart)
+#[derive(Args)]
+pub struct ChartArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub output: String,
+    #[arg(short, long)]
+    pub chart_type: String,
+    #[arg(short, long)]
+    pub title: Option<String>,
+    #[arg(short, long)]
+    pub x_column: Option<String>,
+    #[arg(short, long)]
+    pub y_column: Option<String>,
+}
+
+/// Arguments of [`Commands::Encrypt`](super::Commands::Encrypt)
+#[derive(Args)]
+pub struct EncryptArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub output: String,
+    /// Algorithm: aes256, aes-gcm (authenticated, needs a 32-byte key), xor;
+    /// required with a raw key, while passwords always use aes-gcm
+    #[arg(short, long)]
+    pub algorithm: Option<String>,
+    #[arg(short, long)]
+    pub key_file: Option<String>,
+    /// Derive an aes-gcm key from a password (Argon2) instead of using raw key
+    /// bytes; the password comes from --password-file, DATACELL_PASSWORD or a
+    /// prompt
+    #[arg(long, conflicts_with = "key_file")]
+    pub password: bool,
+    /// Read the password from this file (implies --password)
+    #[arg(long, conflicts_with = "key_file")]
+    pub password_file: Option<String>,
+}
+
+/// Arguments of [`Commands::Decrypt`](super::Commands::Decrypt)
+#[derive(Args)]
+pub struct DecryptArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub output: String,
+    /// Algorithm the file was encrypted with: aes256 (default), aes-gcm, xor;
+    /// password-encrypted files record their own
+    #[arg(short, long)]
+    pub algorithm: Option<String>,
+    #[arg(short, long)]
+    pub key_file: Option<String>,
+    /// The file was encrypted with a password; it comes from
+    /// --password-file, DATACELL_PASSWORD or a prompt
+    #[arg(long, conflicts_with = "key_file")]
+    pub password: bool,
+    /// Read the password from this file (implies --password)
+    #[arg(long, conflicts_with = "key_file")]
+    pub password_file: Option<String>,
+}
+
+/// Arguments of [`Commands::Batch`](super::Commands::Batch)
+#[derive(Args)]
+pub struct BatchArgs {
+    #[arg(short, long)]
+    pub inputs: String,
+    #[arg(short, long)]
+    pub output_dir: String,
+    #[arg(short = 'p', long)]
+    pub operation: String,
+    #[arg(short, long)]
+    pub args: Vec<String>,
+    /// Number of worker threads (defaults to the number of CPUs)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+}
+
+/// Arguments of [`Commands::Plugin`](super::Commands::Plugin)
+#[derive(Args)]
+pub struct PluginArgs {
+    /// Function to run, or `list` to show the registered functions
+    #[arg(short, long)]
+    pub function: String,
+    #[arg(short, long)]
+    pub input: Option<String>,
+    /// Output path; repeat to write several formats at once
+    #[arg(short, long)]
+    pub output: Vec<String>,
+    #[arg(short, long)]
+    pub args: Vec<String>,
+    /// Register `function` from this WebAssembly module (requires the "wasm" feature)
+    #[arg(long)]
+    pub wasm: Option<String>,
+    /// Fuel the WebAssembly function may burn before it is stopped
+    #[arg(long, default_value_t = crate::plugins::DEFAULT_WASM_FUEL)]
+    pub fuel: u64,
+}
+
+/// Arguments of [`Commands::Stream`](super::Commands::Stream)
+#[derive(Args)]
+pub struct StreamArgs {
+    #[arg(short, long)]
+    pub input: String,
+    #[arg(short, long)]
+    pub output: String,
+    #[arg(long, default_value_t = 1000)]
+    pub chunk_size: usize,
+    /// Checkpoint file for resuming an interrupted conversion (csv/ndjson output)
+    #[arg(long)]
+    pub checkpoint: Option<String>,
+}
@@ -0,0 +1,111 @@
+//! Anomaly detection command handler
+
+use crate::{
+    anomaly::{AnomalyDetector, AnomalyMethod},
+    converter::Converter,
+};
+use anyhow::{Context, Result};
+
+/// Handle the detect-anomalies command
+///
+/// Prints the anomaly report as JSON, or saves it to `output`.
+pub fn handle_detect_anomalies(
+    input: String,
+    column: String,
+    method: String,
+    threshold: Option<f64>,
+    group: Option<String>,
+    output: Option<String>,
+) -> Result<()> {
+    let converter = Converter::new();
+    let data = converter.read_any_data(&input, None)?;
+
+    let method = method.to_lowercase();
+    let (name, window) = match method.split_once(':') {
+        Some((name, window)) => (name, Some(window)),
+        None => (method.as_str(), None),
+    };
+    if window.is_some() && name != "moving-average" {
+        anyhow::bail!("Only moving-average takes a window, got '{}'", method);
+    }
+
+    let method = match name {
+        "zscore" => AnomalyMethod::ZScore {
+            threshold: threshold.unwrap_or(3.0),
+        },
+        "iqr" => AnomalyMethod::IQR {
+            multiplier: threshold.unwrap_or(1.5),
+        },
+        "percentile" => {
+            let tail = threshold.unwrap_or(5.0);
+            AnomalyMethod::Percentile {
+                lower: tail,
+                upper: 100.0 - tail,
+            }
+        }
+        "mad" => AnomalyMethod::Mad {
+            threshold: threshold.unwrap_or(3.5),
+        },
+        "moving-average" => AnomalyMethod::MovingAverage {
+            window: match window {
+                Some(window) => window
+                    .parse()
+                    .with_context(|| format!("Invalid moving average window '{window}'"))?,
+                None => 10,
+            },
+            threshold: threshold.unwrap_or(3.0),
+        },
+        _ => anyhow::bail!(
+            "Unknown anomaly method: {}. Use: zscore, iqr, percentile, mad, moving-average[:window]",
+            method
+        ),
+    };
+
+    let value_col = find_column_index(&data, &column)?;
+    let detector = AnomalyDetector::new(method);
+    let result = match group {
+        Some(group) => {
+            let group_col = find_column_index(&data, &group)?;
+            detector.detect_grouped(&data, value_col, group_col)?
+        }
+        None => detector.detect(&data, value_col)?,
+    };
+
+    let report = serde_json::to_string_pretty(&result)?;
+
+    if let Some(output_path) = output {
+        std::fs::write(&output_path, report)
+            .context(format!("Failed to write anomaly report to {output_path}"))?;
+        println!(
+            "Found {} anomalies ({:.1}%), report saved to {}",
+            result.total_anomalies, result.anomaly_percentage, output_path
+        );
+    } else {
+        println!("{}", report);
+    }
+
+    Ok(())
+}
+
+/// Find column index by name or number
+fn find_column_index(data: &[Vec<String>], column: &str) -> Result<usize> {
+    if data.is_empty() {
+        anyhow::bail!("Data is empty");
+    }
+
+    let header = &data[0];
+
+    // Try to parse as number first
+    if let Ok(index) = column.parse::<usize>() {
+        if index == 0 {
+            anyhow::bail!("Column indices start from 1");
+        }
+        return Ok(index - 1);
+    }
+
+    // Try to find by name
+    header
+        .iter()
+        .position(|col_name| col_name == column)
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", column))
+}
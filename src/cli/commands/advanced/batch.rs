@@ -3,16 +3,19 @@
 use crate::{converter::Converter, operations::DataOperations, traits::SortOperator};
 use anyhow::{Context, Result};
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Handle the batch command
 ///
-/// Processes multiple files with the same operation.
+/// Processes multiple files with the same operation on a rayon pool of
+/// `jobs` threads. A failing file does not stop the others; failures are
+/// summarized at the end.
 pub fn handle_batch(
     inputs: String,
     output_dir: String,
     operation: String,
     args: Vec<String>,
+    jobs: Option<usize>,
 ) -> Result<()> {
     // Ensure output directory exists
     std::fs::create_dir_all(&output_dir)
@@ -40,92 +43,117 @@ pub fn handle_batch(
         anyhow::bail!("No input files found for pattern: {inputs}");
     }
 
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()
+        .context("Failed to create batch thread pool")?;
+
     println!(
-        "Processing {} files with operation '{operation}' in parallel...",
-        input_files.len()
+        "Processing {} files with operation '{operation}' on {} threads...",
+        input_files.len(),
+        pool.current_num_threads()
     );
 
-    let success_count = Arc::new(Mutex::new(0));
-    let error_count = Arc::new(Mutex::new(0));
-
-    // Process files in parallel using rayon
-    input_files.par_iter().for_each(|input_file| {
-        // Generate output filename
-        let file_stem = std::path::Path::new(input_file)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        let output_file = format!("{}/{}.csv", output_dir, file_stem);
-
-        // Execute operation based on type
-        let result = match operation.as_str() {
-            "convert" => {
-                if args.is_empty() {
-                    Err(anyhow::anyhow!(
-                        "Convert operation requires output format argument"
-                    ))
-                } else {
-                    let format = &args[0];
-                    let output_with_ext = format!("{}/{}.{}", output_dir, file_stem, format);
-                    batch_convert(input_file, &output_with_ext)
-                }
-            }
-            "sort" => {
-                if args.is_empty() {
-                    Err(anyhow::anyhow!("Sort operation requires column argument"))
-                } else {
-                    batch_sort(input_file, &output_file, &args[0], true)
-                }
-            }
-            "filter" => {
-                if args.is_empty() {
-                    Err(anyhow::anyhow!(
-                        "Filter operation requires where clause argument"
-                    ))
-                } else {
-                    batch_filter(input_file, &output_file, &args[0])
-                }
-            }
-            "dedupe" => batch_dedupe(input_file, &output_file),
-            "normalize" => {
-                if args.is_empty() {
-                    Err(anyhow::anyhow!(
-                        "Normalize operation requires column argument"
-                    ))
-                } else {
-                    batch_normalize(input_file, &output_file, &args[0])
-                }
-            }
-            _ => Err(anyhow::anyhow!("Unknown batch operation: {}", operation)),
-        };
+    let started = Instant::now();
 
+    // Process files in parallel; each file reports its own outcome
+    let outcomes: Vec<(String, Result<()>, Duration)> = pool.install(|| {
+        input_files
+            .par_iter()
+            .map(|input_file| {
+                let file_started = Instant::now();
+                let result = run_batch_operation(input_file, &output_dir, &operation, &args);
+                (input_file.clone(), result, file_started.elapsed())
+            })
+            .collect()
+    });
+
+    let mut failures = Vec::new();
+    for (input_file, result, elapsed) in &outcomes {
         match result {
-            Ok(_) => {
-                println!("  ✓ {}", input_file);
-                *success_count.lock().unwrap() += 1;
-            }
+            Ok(_) => println!("  ✓ {} ({:.2?})", input_file, elapsed),
             Err(e) => {
                 println!("  ✗ {input_file}: {e}");
-                *error_count.lock().unwrap() += 1;
+                failures.push(input_file.as_str());
             }
         }
-    });
-
-    let final_success = *success_count.lock().unwrap();
-    let final_errors = *error_count.lock().unwrap();
+    }
 
     println!(
-        "Batch processing complete: {} successful, {} failed",
-        final_success, final_errors
+        "Batch processing complete in {:.2?}: {} successful, {} failed",
+        started.elapsed(),
+        outcomes.len() - failures.len(),
+        failures.len()
     );
 
-    if final_errors > 0 {
-        anyhow::bail!("Some batch operations failed");
+    if !failures.is_empty() {
+        anyhow::bail!(
+            "{} batch operations failed: {}",
+            failures.len(),
+            failures.join(", ")
+        );
     }
 
     Ok(())
 }
 
+/// Run the batch operation for a single input file
+fn run_batch_operation(
+    input_file: &str,
+    output_dir: &str,
+    operation: &str,
+    args: &[String],
+) -> Result<()> {
+    // Generate output filename
+    let file_stem = std::path::Path::new(input_file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let output_file = format!("{}/{}.csv", output_dir, file_stem);
+
+    // Execute operation based on type
+    match operation {
+        "convert" => {
+            if args.is_empty() {
+                Err(anyhow::anyhow!(
+                    "Convert operation requires output format argument"
+                ))
+            } else {
+                let format = &args[0];
+                let output_with_ext = format!("{}/{}.{}", output_dir, file_stem, format);
+                batch_convert(input_file, &output_with_ext)
+            }
+        }
+        "sort" => {
+            if args.is_empty() {
+                Err(anyhow::anyhow!("Sort operation requires column argument"))
+            } else {
+                batch_sort(input_file, &output_file, &args[0], true)
+            }
+        }
+        "filter" => {
+            if args.is_empty() {
+                Err(anyhow::anyhow!(
+                    "Filter operation requires where clause argument"
+                ))
+            } else {
+                batch_filter(input_file, &output_file, &args[0])
+            }
+        }
+        "dedupe" => batch_dedupe(input_file, &output_file),
+        "normalize" => {
+            if args.is_empty() {
+                Err(anyhow::anyhow!(
+                    "Normalize operation requires column argument"
+                ))
+            } else {
+                batch_normalize(input_file, &output_file, &args[0])
+            }
+        }
+        _ => Err(anyhow::anyhow!("Unknown batch operation: {}", operation)),
+    }
+}
+
 /// Batch convert operation
 fn batch_convert(input_file: &str, output_file: &str) -> Result<()> {
     let converter = Converter::new();
@@ -175,7 +203,7 @@ fn batch_normalize(input_file: &str, output_file: &str, column: &str) -> Result<
 
     let mut data = converter.read_any_data(input_file, None)?;
     let col_idx = find_column_index(&data, column)?;
-    ops.normalize(&mut data, col_idx)?;
+    ops.normalize_minmax(&mut data, col_idx)?;
     converter.write_any_data(output_file, &data, None)?;
     Ok(())
 }
@@ -1,31 +1,38 @@
 //! Encryption/decryption command handlers
 
 use crate::encryption::{DataEncryptor, EncryptionAlgorithm};
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 /// Handle the encrypt command
 ///
-/// Encrypts a file using the specified algorithm.
+/// Encrypts a file using the specified algorithm, with a raw key or a key
+/// derived from a password. Passwords always use AES-256-GCM.
 pub fn handle_encrypt(
     input: String,
     output: String,
-    algorithm: String,
+    algorithm: Option<String>,
     key_file: Option<String>,
+    password: bool,
+    password_file: Option<String>,
 ) -> Result<()> {
-    let algorithm = match algorithm.to_lowercase().as_str() {
-        "aes" | "aes256" => EncryptionAlgorithm::Aes256,
-        "xor" => EncryptionAlgorithm::Xor,
-        _ => anyhow::bail!("Unknown encryption algorithm: {}", algorithm),
+    let with_password = password || password_file.is_some();
+    let algorithm = match resolve_algorithm(algorithm.as_deref(), with_password)? {
+        Some(algorithm) => algorithm,
+        None => anyhow::bail!("--algorithm is required when encrypting with a key"),
     };
-
     let encryptor = DataEncryptor::new(algorithm);
-    let key = get_encryption_key(key_file)?;
 
     // Validate input/output paths for security
     validate_file_path(&input)?;
     validate_file_path(&output)?;
 
-    encryptor.encrypt_file(&input, &output, &key)?;
+    if with_password {
+        let password = get_password(password_file)?;
+        encryptor.encrypt_file_with_password(&input, &output, &password)?;
+    } else {
+        let key = get_encryption_key(key_file)?;
+        encryptor.encrypt_file(&input, &output, &key)?;
+    }
 
     println!("Encrypted {} to {} using {:?}", input, output, algorithm);
 
@@ -34,22 +41,73 @@ pub fn handle_encrypt(
 
 /// Handle the decrypt command
 ///
-/// Decrypts a file.
-pub fn handle_decrypt(input: String, output: String, key_file: Option<String>) -> Result<()> {
-    let encryptor = DataEncryptor::new(EncryptionAlgorithm::Aes256);
-    let key = get_encryption_key(key_file)?;
+/// Decrypts a file encrypted with the given algorithm (AES-256 by default),
+/// with a raw key or the password it was encrypted with. Password-encrypted
+/// files name their algorithm in their header.
+pub fn handle_decrypt(
+    input: String,
+    output: String,
+    algorithm: Option<String>,
+    key_file: Option<String>,
+    password: bool,
+    password_file: Option<String>,
+) -> Result<()> {
+    let with_password = password || password_file.is_some();
+    let algorithm = resolve_algorithm(algorithm.as_deref(), with_password)?
+        .unwrap_or(EncryptionAlgorithm::Aes256);
+    let encryptor = DataEncryptor::new(algorithm);
 
     // Validate input/output paths for security
     validate_file_path(&input)?;
     validate_file_path(&output)?;
 
-    encryptor.decrypt_file(&input, &output, &key)?;
+    if with_password {
+        let password = get_password(password_file)?;
+        encryptor.decrypt_file_with_password(&input, &output, &password)?;
+    } else {
+        let key = get_encryption_key(key_file)?;
+        encryptor.decrypt_file(&input, &output, &key)?;
+    }
 
     println!("Decrypted {} to {}", input, output);
 
     Ok(())
 }
 
+/// The algorithm named on the command line, if any
+///
+/// With a password only AES-256-GCM is allowed, and it is implied when no
+/// algorithm is named.
+fn resolve_algorithm(
+    algorithm: Option<&str>,
+    with_password: bool,
+) -> Result<Option<EncryptionAlgorithm>> {
+    let algorithm = algorithm.map(parse_algorithm).transpose()?;
+    if !with_password {
+        return Ok(algorithm);
+    }
+    match algorithm {
+        None | Some(EncryptionAlgorithm::Aes256Gcm) => Ok(Some(EncryptionAlgorithm::Aes256Gcm)),
+        Some(other) => anyhow::bail!(
+            "Passwords always use aes-gcm; {:?} cannot be used with --password",
+            other
+        ),
+    }
+}
+
+/// Parse an algorithm name from the command line
+fn parse_algorithm(algorithm: &str) -> Result<EncryptionAlgorithm> {
+    match algorithm.to_lowercase().as_str() {
+        "aes" | "aes256" => Ok(EncryptionAlgorithm::Aes256),
+        "aes-gcm" | "aes256gcm" | "aes256-gcm" => Ok(EncryptionAlgorithm::Aes256Gcm),
+        "xor" => Ok(EncryptionAlgorithm::Xor),
+        _ => anyhow::bail!(
+            "Unknown encryption algorithm: {}. Use: aes256, aes-gcm, xor",
+            algorithm
+        ),
+    }
+}
+
 /// Get encryption key from file or environment variable
 ///
 /// Security: Requires explicit key source - no hardcoded defaults
@@ -73,6 +131,80 @@ fn get_encryption_key(key_file: Option<String>) -> Result<Vec<u8>> {
     );
 }
 
+/// Get the password for key derivation
+///
+/// Read from `password_file`, then the `DATACELL_PASSWORD` environment
+/// variable, then prompted for on the terminal. It is never taken from the
+/// command line, where other users can see it in the process list.
+fn get_password(password_file: Option<String>) -> Result<String> {
+    if let Some(path) = password_file {
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read password file: {}", path))?;
+        return non_empty_password(contents.trim_end_matches(['\r', '\n']));
+    }
+
+    if let Ok(password) = std::env::var("DATACELL_PASSWORD") {
+        return non_empty_password(&password);
+    }
+
+    non_empty_password(&prompt_password()?)
+}
+
+fn non_empty_password(password: &str) -> Result<String> {
+    if password.is_empty() {
+        anyhow::bail!("Password must not be empty");
+    }
+    Ok(password.to_string())
+}
+
+/// Read a password from the terminal with echo turned off
+#[cfg(unix)]
+fn prompt_password() -> Result<String> {
+    use std::io::{BufRead, IsTerminal, Write};
+    use std::os::fd::AsRawFd;
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        anyhow::bail!(
+            "No password provided. Use --password-file or set DATACELL_PASSWORD environment variable"
+        );
+    }
+    let fd = stdin.as_raw_fd();
+
+    let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+    // SAFETY: `fd` is the open stdin descriptor and `termios` is only read
+    // after `tcgetattr` reports that it filled it in
+    let original = unsafe {
+        if libc::tcgetattr(fd, termios.as_mut_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        termios.assume_init()
+    };
+    let mut silent = original;
+    silent.c_lflag &= !libc::ECHO;
+
+    eprint!("Password: ");
+    std::io::stderr().flush()?;
+
+    let mut line = String::new();
+    // SAFETY: both calls pass a fully initialised `termios` for stdin
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &silent) };
+    let read = stdin.lock().read_line(&mut line);
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    eprintln!();
+
+    read?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Read a password from the terminal with echo turned off
+#[cfg(not(unix))]
+fn prompt_password() -> Result<String> {
+    anyhow::bail!(
+        "No password provided. Use --password-file or set DATACELL_PASSWORD environment variable"
+    );
+}
+
 /// Validate file path for security
 fn validate_file_path(path: &str) -> Result<()> {
     // Basic security checks
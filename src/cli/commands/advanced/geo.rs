@@ -0,0 +1,21 @@
+//! Geospatial command handler
+
+use crate::geospatial::GeospatialCalculator;
+use anyhow::Result;
+
+/// Handle the geo-bearing command
+///
+/// Prints the initial compass bearing and the great-circle midpoint between
+/// two "lat,lon" coordinates.
+pub fn handle_geo_bearing(from: String, to: String) -> Result<()> {
+    let calculator = GeospatialCalculator::new();
+    let bearing = calculator.bearing_from_strings(&from, &to)?;
+    let midpoint = calculator.midpoint_from_strings(&from, &to)?;
+
+    println!("Bearing: {bearing:.2}°");
+    println!(
+        "Midpoint: {:.6},{:.6}",
+        midpoint.latitude, midpoint.longitude
+    );
+    Ok(())
+}
@@ -2,19 +2,27 @@
 //!
 //! Implements advanced features like validation, charting, encryption, batch processing, etc.
 
+pub mod anomaly;
 pub mod batch;
 pub mod chart;
 pub mod encryption;
+pub mod geo;
+pub mod pipeline;
 pub mod plugins;
 pub mod profile;
+pub mod text_analysis;
 pub mod utils;
 pub mod validation;
 
 // Re-export all handlers for convenience
+pub use anomaly::handle_detect_anomalies;
 pub use batch::handle_batch;
 pub use chart::handle_chart;
 pub use encryption::{handle_decrypt, handle_encrypt};
+pub use geo::handle_geo_bearing;
+pub use pipeline::handle_pipeline;
 pub use plugins::{handle_plugin, handle_stream};
 pub use profile::handle_profile;
+pub use text_analysis::{TextAnalysisOptions, handle_text_analysis};
 pub use utils::{handle_completions, handle_config_init, handle_export_styled};
 pub use validation::handle_validate;
@@ -0,0 +1,11 @@
+//! Pipeline command handler
+
+use crate::workflow::WorkflowExecutor;
+use anyhow::Result;
+
+/// Handle the pipeline command
+///
+/// Runs the steps of a YAML, JSON or TOML pipeline file in order.
+pub fn handle_pipeline(config: String) -> Result<()> {
+    WorkflowExecutor::new().execute(&config)
+}
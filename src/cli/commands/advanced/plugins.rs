@@ -1,42 +1,163 @@
 //! Plugin and streaming command handlers
 
-use crate::{converter::Converter, plugins::PluginRegistry};
+use crate::{
+    cli::args::OutputArgs,
+    columnar::ParquetWriteOptions,
+    converter::Converter,
+    operations::StderrProgress,
+    plugins::PluginRegistry,
+    streaming::{convert_csv_to_parquet_streaming, convert_parquet_streaming, convert_streaming},
+};
 use anyhow::Result;
 
 /// Handle the plugin command
 ///
-/// Executes a plugin function.
+/// Executes a plugin function, first registering it from a WebAssembly
+/// module when `wasm` is given; each call may burn `fuel`. The function
+/// `list` prints the registered functions instead. `converter` reads the
+/// input and writes the result.
 pub fn handle_plugin(
+    converter: Converter,
     function: String,
-    input: String,
-    output: String,
+    input: Option<String>,
+    output: OutputArgs,
     args: Vec<String>,
+    wasm: Option<String>,
+    fuel: u64,
 ) -> Result<()> {
-    let registry = PluginRegistry::new();
+    let mut registry = PluginRegistry::default();
+    if let Some(path) = wasm {
+        registry.register_wasm_with_fuel(&function, &path, fuel)?;
+    }
+
+    if function == "list" {
+        let mut functions: Vec<_> = registry
+            .list_plugins()
+            .into_iter()
+            .flat_map(|plugin| &plugin.functions)
+            .collect();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        for function in functions {
+            let params: Vec<&str> = function
+                .parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect();
+            if params.is_empty() {
+                println!("{:<22} {}", function.name, function.description);
+            } else {
+                println!(
+                    "{:<22} {} (args: {})",
+                    function.name,
+                    function.description,
+                    params.join(", ")
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let input = input.ok_or_else(|| anyhow::anyhow!("--input is required to run '{function}'"))?;
+    if output.is_empty() {
+        anyhow::bail!("--output is required to run '{function}'");
+    }
 
-    let converter = Converter::new();
     let data = converter.read_any_data(&input, None)?;
 
     // Execute plugin function
     let result = registry.execute(&function, &args, &data)?;
 
-    converter.write_any_data(&output, &result, None)?;
-    println!("Executed plugin '{function}' on {input}; wrote {output}");
+    output.write(&converter, &result, None)?;
+    report!(
+        output,
+        "Executed plugin '{function}' on {input}; wrote {output}"
+    );
 
     Ok(())
 }
 
 /// Handle the stream command
 ///
-/// Processes a large file in chunks to reduce memory usage.
-pub fn handle_stream(input: String, output: String, _chunk_size: usize) -> Result<()> {
-    println!("Streaming support is a placeholder. Processing file normally...");
+/// Processes a large file in chunks to reduce memory usage. CSV input with a
+/// CSV or NDJSON output is streamed batch by batch (and can resume from a
+/// checkpoint), Parquet input is streamed row group by row group; other
+/// combinations fall back to an in-memory conversion through `converter`.
+pub fn handle_stream(
+    converter: Converter,
+    input: String,
+    output: String,
+    chunk_size: usize,
+    checkpoint: Option<String>,
+) -> Result<()> {
+    let output_lower = output.to_lowercase();
+    let appendable = [".csv", ".ndjson", ".jsonl"]
+        .iter()
+        .any(|ext| output_lower.ends_with(ext));
+
+    if input.to_lowercase().ends_with(".csv") && appendable {
+        let mut progress = StderrProgress::new();
+        let stats = convert_streaming(
+            &input,
+            &output,
+            chunk_size,
+            &mut progress,
+            checkpoint.as_deref(),
+        )?;
+        report!(
+            output,
+            "Streamed {} rows in {} batches ({} skipped from checkpoint); wrote {}",
+            stats.rows_written,
+            stats.batches_written,
+            stats.batches_skipped,
+            output
+        );
+        return Ok(());
+    }
+
+    if checkpoint.is_some() {
+        anyhow::bail!("--checkpoint requires CSV input and a CSV or NDJSON output");
+    }
+
+    if input.to_lowercase().ends_with(".csv") && output_lower.ends_with(".parquet") {
+        let mut progress = StderrProgress::new();
+        let stats = convert_csv_to_parquet_streaming(
+            &input,
+            &output,
+            chunk_size,
+            &ParquetWriteOptions::default(),
+            &mut progress,
+        )?;
+        report!(
+            output,
+            "Streamed {} rows in {} row groups; wrote {}",
+            stats.rows_written,
+            stats.batches_written,
+            output
+        );
+        return Ok(());
+    }
+
+    if input.to_lowercase().ends_with(".parquet") && appendable {
+        let mut progress = StderrProgress::new();
+        let stats = convert_parquet_streaming(&input, &output, chunk_size, &mut progress)?;
+        report!(
+            output,
+            "Streamed {} rows in {} chunks; wrote {}",
+            stats.rows_written,
+            stats.batches_written,
+            output
+        );
+        return Ok(());
+    }
+    report!(
+        output,
+        "Streaming not available for this format pair. Processing file normally..."
+    );
 
-    let converter = Converter::new();
     let data = converter.read_any_data(&input, None)?;
     converter.write_any_data(&output, &data, None)?;
 
-    println!("Processed {} rows; wrote {}", data.len(), output);
+    report!(output, "Processed {} rows; wrote {}", data.len(), output);
 
     Ok(())
 }
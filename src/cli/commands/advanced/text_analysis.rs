@@ -0,0 +1,119 @@
+//! Text analysis command handler
+
+use crate::{
+    converter::Converter,
+    text_analysis::{TextAnalyzer, TextOperation},
+};
+use anyhow::{Context, Result};
+
+/// Options for the text-analysis command
+#[derive(Debug, Clone, Default)]
+pub struct TextAnalysisOptions {
+    /// Number of keywords to report
+    pub top: usize,
+    /// File of extra stop words, added to the English defaults
+    pub stopwords: Option<String>,
+    /// Count keywords by stem
+    pub stem: bool,
+    /// Longest phrase, in words, to report as a keyword
+    pub ngram: Option<usize>,
+    /// Write per-row results here instead of printing an aggregate
+    pub output: Option<String>,
+}
+
+/// Handle the text-analysis command
+///
+/// Without an output path the cells of the column are analyzed as one text,
+/// except `tfidf` which treats each cell as its own document, and the result
+/// is printed as JSON. With an output path each row is analyzed separately
+/// and the data is written with the results appended as new columns.
+pub fn handle_text_analysis(
+    input: String,
+    column: String,
+    operation: String,
+    options: TextAnalysisOptions,
+) -> Result<()> {
+    let converter = Converter::new();
+    let data = converter.read_any_data(&input, None)?;
+    let operation: TextOperation = operation.parse()?;
+
+    if options.ngram.is_some() && (operation != TextOperation::Keywords || options.output.is_some())
+    {
+        anyhow::bail!("--ngram only applies to the keywords analysis without --output");
+    }
+
+    let mut stop_words = TextAnalyzer::default_stop_words();
+    if let Some(path) = &options.stopwords {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read stop words from {path}"))?;
+        stop_words.extend(content.split_whitespace().map(str::to_string));
+    }
+    let analyzer = TextAnalyzer::new()
+        .with_stopwords(stop_words)
+        .with_stemming(options.stem);
+
+    let col_idx = find_column_index(&data, &column)?;
+
+    if let Some(output_path) = &options.output {
+        let analyzed = analyzer.analyze_column(&data, col_idx, operation);
+        converter.write_any_data(output_path, &analyzed, None)?;
+        report!(
+            output_path,
+            "Analyzed {} rows, saved to {}",
+            analyzed.len().saturating_sub(1),
+            output_path
+        );
+        return Ok(());
+    }
+
+    let cells: Vec<String> = data
+        .iter()
+        .skip(1)
+        .map(|row| row.get(col_idx).cloned().unwrap_or_default())
+        .collect();
+    let text = cells.join("\n");
+
+    let report = match operation {
+        TextOperation::Stats => serde_json::to_string_pretty(&analyzer.analyze_stats(&text))?,
+        TextOperation::Sentiment => {
+            serde_json::to_string_pretty(&analyzer.analyze_sentiment(&text))?
+        }
+        TextOperation::Keywords => {
+            let keywords = match options.ngram {
+                Some(n) => analyzer.extract_phrase_keywords(&text, n, options.top),
+                None => analyzer.extract_keywords(&text, options.top),
+            };
+            serde_json::to_string_pretty(&keywords)?
+        }
+        TextOperation::Tfidf => {
+            serde_json::to_string_pretty(&analyzer.extract_keywords_tfidf(&cells, options.top))?
+        }
+        TextOperation::Language => serde_json::to_string_pretty(&analyzer.detect_language(&text))?,
+    };
+
+    println!("{}", report);
+    Ok(())
+}
+
+/// Find column index by name or number
+fn find_column_index(data: &[Vec<String>], column: &str) -> Result<usize> {
+    if data.is_empty() {
+        anyhow::bail!("Data is empty");
+    }
+
+    let header = &data[0];
+
+    // Try to parse as number first
+    if let Ok(index) = column.parse::<usize>() {
+        if index == 0 {
+            anyhow::bail!("Column indices start from 1");
+        }
+        return Ok(index - 1);
+    }
+
+    // Try to find by name
+    header
+        .iter()
+        .position(|col_name| col_name == column)
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", column))
+}
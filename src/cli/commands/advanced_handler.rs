@@ -2,12 +2,16 @@
 //!
 //! Implements advanced features like validation, charting, encryption, batch processing, etc.
 
+use crate::cli::args::OutputArgs;
 use crate::cli::commands::advanced;
+use crate::converter::Converter;
 use anyhow::Result;
 
 /// Advanced command handler
 #[derive(Default)]
-pub struct AdvancedCommandHandler;
+pub struct AdvancedCommandHandler {
+    stdin_format: Option<String>,
+}
 
 impl AdvancedCommandHandler {
     /// Create a new advanced command handler
@@ -15,11 +19,59 @@ impl AdvancedCommandHandler {
         Self::default()
     }
 
+    /// Read `-` inputs as `format` instead of CSV
+    pub fn with_stdin_format(mut self, format: &str) -> Self {
+        self.stdin_format = Some(format.to_string());
+        self
+    }
+
+    /// Converter that reads `-` inputs in the `--input-format`
+    fn converter(&self) -> Converter {
+        match &self.stdin_format {
+            Some(format) => Converter::new().with_stdin_format(format),
+            None => Converter::new(),
+        }
+    }
+
     /// Handle the profile command
     pub fn handle_profile(&self, input: String, output: Option<String>) -> Result<()> {
         advanced::handle_profile(input, output)
     }
 
+    /// Handle the detect-anomalies command
+    pub fn handle_detect_anomalies(
+        &self,
+        input: String,
+        column: String,
+        method: String,
+        threshold: Option<f64>,
+        group: Option<String>,
+        output: Option<String>,
+    ) -> Result<()> {
+        advanced::handle_detect_anomalies(input, column, method, threshold, group, output)
+    }
+
+    /// Handle the text-analysis command
+    pub fn handle_text_analysis(
+        &self,
+        input: String,
+        column: String,
+        operation: String,
+        options: advanced::TextAnalysisOptions,
+    ) -> Result<()> {
+        advanced::handle_text_analysis(input, column, operation, options)
+    }
+
+    /// Handle the geo-bearing command
+    pub fn handle_geo_bearing(&self, from: String, to: String) -> Result<()> {
+        advanced::handle_geo_bearing(from, to)
+    }
+
+    /// Handle the pipeline command
+    pub fn handle_pipeline(&self, config: String) -> Result<()> {
+        advanced::handle_pipeline(config)
+    }
+
     /// Handle the validate command
     pub fn handle_validate(
         &self,
@@ -49,10 +101,12 @@ impl AdvancedCommandHandler {
         &self,
         input: String,
         output: String,
-        algorithm: String,
+        algorithm: Option<String>,
         key_file: Option<String>,
+        password: bool,
+        password_file: Option<String>,
     ) -> Result<()> {
-        advanced::handle_encrypt(input, output, algorithm, key_file)
+        advanced::handle_encrypt(input, output, algorithm, key_file, password, password_file)
     }
 
     /// Handle the decrypt command
@@ -60,9 +114,12 @@ impl AdvancedCommandHandler {
         &self,
         input: String,
         output: String,
+        algorithm: Option<String>,
         key_file: Option<String>,
+        password: bool,
+        password_file: Option<String>,
     ) -> Result<()> {
-        advanced::handle_decrypt(input, output, key_file)
+        advanced::handle_decrypt(input, output, algorithm, key_file, password, password_file)
     }
 
     /// Handle the batch command
@@ -72,24 +129,33 @@ impl AdvancedCommandHandler {
         output_dir: String,
         operation: String,
         args: Vec<String>,
+        jobs: Option<usize>,
     ) -> Result<()> {
-        advanced::handle_batch(inputs, output_dir, operation, args)
+        advanced::handle_batch(inputs, output_dir, operation, args, jobs)
     }
 
     /// Handle the plugin command
     pub fn handle_plugin(
         &self,
         function: String,
-        input: String,
-        output: String,
+        input: Option<String>,
+        output: OutputArgs,
         args: Vec<String>,
+        wasm: Option<String>,
+        fuel: u64,
     ) -> Result<()> {
-        advanced::handle_plugin(function, input, output, args)
+        advanced::handle_plugin(self.converter(), function, input, output, args, wasm, fuel)
     }
 
     /// Handle the stream command
-    pub fn handle_stream(&self, input: String, output: String, chunk_size: usize) -> Result<()> {
-        advanced::handle_stream(input, output, chunk_size)
+    pub fn handle_stream(
+        &self,
+        input: String,
+        output: String,
+        chunk_size: usize,
+        checkpoint: Option<String>,
+    ) -> Result<()> {
+        advanced::handle_stream(self.converter(), input, output, chunk_size, checkpoint)
     }
 
     /// Handle the completions command
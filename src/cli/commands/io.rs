@@ -3,14 +3,26 @@
 //! Implements read, write, convert, and related I/O operations.
 
 use crate::{
-    cli::OutputFormat, config::Config, converter::Converter, excel::ExcelHandler,
-    formula::FormulaEvaluator, google_sheets::GoogleSheetsHandler, handler_registry::HandlerRegistry,
+    api::{ApiConfig, ApiServer},
+    cli::{OutputFormat, args::OutputArgs},
+    columnar::ParquetWriteOptions,
+    config::Config,
+    converter::Converter,
+    csv_handler::{CsvHandler, CsvOptions},
+    excel::ExcelHandler,
+    formula::FormulaEvaluator,
+    google_sheets::GoogleSheetsHandler,
+    handler_registry::HandlerRegistry,
+    operations::DataOperations,
+    text_encoding::encoding_for_label,
 };
 use anyhow::{Context, Result};
 
 /// I/O command handler
 #[derive(Default)]
-pub struct IoCommandHandler;
+pub struct IoCommandHandler {
+    stdin_format: Option<String>,
+}
 
 impl IoCommandHandler {
     /// Create a new I/O command handler
@@ -18,6 +30,12 @@ impl IoCommandHandler {
         Self::default()
     }
 
+    /// Read `-` inputs as `format` instead of CSV
+    pub fn with_stdin_format(mut self, format: &str) -> Self {
+        self.stdin_format = Some(format.to_string());
+        self
+    }
+
     /// Handle the read command
     ///
     /// Reads data from a file and displays it in the specified format.
@@ -27,8 +45,10 @@ impl IoCommandHandler {
         sheet: Option<String>,
         range: Option<String>,
         format: OutputFormat,
+        delimiter: Option<String>,
+        encoding: Option<String>,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter(delimiter.as_deref(), encoding.as_deref(), true)?;
 
         // Read data
         let mut data = if let Some(sheet_name) = sheet {
@@ -47,6 +67,8 @@ impl IoCommandHandler {
             OutputFormat::Csv => self.print_csv(&data),
             OutputFormat::Json => self.print_json(&data)?,
             OutputFormat::Markdown => self.print_markdown(&data),
+            OutputFormat::Html => print!("{}", DataOperations::new().to_html(&data)),
+            OutputFormat::Tsv => print!("{}", DataOperations::new().to_tsv(&data)),
         }
 
         Ok(())
@@ -57,11 +79,16 @@ impl IoCommandHandler {
     /// Writes data to a file in the appropriate format.
     pub fn handle_write(
         &self,
-        output: String,
+        output: OutputArgs,
         csv: Option<String>,
         sheet: Option<String>,
+        delimiter: Option<String>,
+        encoding: Option<String>,
+        compression: Option<String>,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self
+            .converter(delimiter.as_deref(), encoding.as_deref(), false)?
+            .with_parquet_options(self.parquet_options(compression.as_deref())?);
 
         // Read from CSV if provided, otherwise stdin
         let data = if let Some(csv_path) = csv {
@@ -71,16 +98,23 @@ impl IoCommandHandler {
             let mut input = String::new();
             std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)
                 .context("Failed to read from stdin")?;
-            input
-                .lines()
-                .filter(|l| !l.is_empty())
-                .map(|l| l.split(',').map(|s| s.trim().to_string()).collect())
-                .collect()
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .trim(csv::Trim::All)
+                .from_reader(input.as_bytes());
+            reader
+                .records()
+                .map(|r| {
+                    r.map(|record| record.iter().map(|s| s.to_string()).collect())
+                        .context("Failed to parse CSV from stdin")
+                })
+                .collect::<Result<Vec<Vec<String>>>>()?
         };
 
         // Write to output
-        converter.write_any_data(&output, &data, sheet.as_deref())?;
-        println!("Wrote {output}");
+        output.write(&converter, &data, sheet.as_deref())?;
+        report!(output, "Wrote {output}");
 
         Ok(())
     }
@@ -88,21 +122,70 @@ impl IoCommandHandler {
     /// Handle the convert command
     ///
     /// Converts a file from one format to another.
+    /// Several outputs, `--index` or `--drop-index` go through an in-memory
+    /// copy of the data instead of a direct conversion.
     pub fn handle_convert(
+        &self,
+        input: String,
+        output: OutputArgs,
+        sheet: Option<String>,
+        delimiter: Option<String>,
+        encoding: Option<String>,
+        compression: Option<String>,
+    ) -> Result<()> {
+        let converter = self
+            .converter(delimiter.as_deref(), encoding.as_deref(), false)?
+            .with_parquet_options(self.parquet_options(compression.as_deref())?);
+        match &output[..] {
+            [path] if !output.reindexes() => converter.convert(&input, path, sheet.as_deref())?,
+            _ => {
+                let data = converter.read_any_data(&input, sheet.as_deref())?;
+                output.write(&converter, &data, sheet.as_deref())?;
+            }
+        }
+        report!(output, "Converted {input} to {output}");
+        Ok(())
+    }
+
+    /// Handle the convert-dir command
+    ///
+    /// Converts each supported file in a directory, reporting every failure
+    /// and returning an error if any file could not be converted.
+    pub fn handle_convert_dir(
         &self,
         input: String,
         output: String,
+        format: String,
         sheet: Option<String>,
+        compression: Option<String>,
     ) -> Result<()> {
-        let converter = Converter::new();
-        converter.convert(&input, &output, sheet.as_deref())?;
-        println!("Converted {input} to {output}");
+        let converter = self
+            .default_converter()
+            .with_parquet_options(self.parquet_options(compression.as_deref())?);
+        let summary = converter.convert_dir(&input, &output, &format, sheet.as_deref())?;
+
+        for path in &summary.converted {
+            println!("  ✓ {path}");
+        }
+        for (path, error) in &summary.failed {
+            println!("  ✗ {path}: {error}");
+        }
+        println!(
+            "Converted {} files, {} failed",
+            summary.converted.len(),
+            summary.failed.len()
+        );
+
+        if !summary.failed.is_empty() {
+            anyhow::bail!("{} files failed to convert", summary.failed.len());
+        }
         Ok(())
     }
 
     /// Handle the formula command
     ///
-    /// Applies a formula to a specific cell in a spreadsheet.
+    /// Applies a formula to a specific cell in a spreadsheet, or to every cell
+    /// of a range such as `C2:C100` with its references shifted per cell.
     pub fn handle_formula(
         &self,
         input: String,
@@ -113,7 +196,9 @@ impl IoCommandHandler {
     ) -> Result<()> {
         let evaluator = FormulaEvaluator::new();
 
-        if input.ends_with(".csv") {
+        if cell.contains(':') {
+            evaluator.apply_formula_to_range(&input, &output, &formula, &cell, sheet.as_deref())?
+        } else if input.ends_with(".csv") {
             evaluator.apply_to_csv(&input, &output, &formula, &cell)?
         } else if input.ends_with(".xls") || input.ends_with(".xlsx") {
             evaluator.apply_to_excel(&input, &output, &formula, &cell, sheet.as_deref())?
@@ -136,6 +221,31 @@ impl IoCommandHandler {
         Ok(())
     }
 
+    /// Handle the api-server command
+    ///
+    /// Runs the REST API server until it is stopped.
+    pub fn handle_api_server(
+        &self,
+        host: String,
+        port: u16,
+        cors_origins: Vec<String>,
+        root: String,
+        allow_output: bool,
+    ) -> Result<()> {
+        let config = ApiConfig {
+            host,
+            port,
+            cors_enabled: !cors_origins.is_empty(),
+            cors_origins,
+            root: root.into(),
+            allow_output,
+            ..ApiConfig::default()
+        };
+        tokio::runtime::Runtime::new()
+            .context("Failed to create tokio runtime")?
+            .block_on(ApiServer::new(config).start())
+    }
+
     /// Handle the sheets command
     ///
     /// Lists all sheets in an Excel file.
@@ -160,7 +270,7 @@ impl IoCommandHandler {
 
         for sheet in &sheets {
             println!("=== Sheet: {sheet} ===");
-            self.handle_read(input.clone(), Some(sheet.clone()), None, format)?;
+            self.handle_read(input.clone(), Some(sheet.clone()), None, format, None, None)?;
             println!();
         }
 
@@ -170,8 +280,13 @@ impl IoCommandHandler {
     /// Handle the write_range command
     ///
     /// Writes data starting at a specific cell.
-    pub fn handle_write_range(&self, input: String, output: String, start: String) -> Result<()> {
-        let converter = Converter::new();
+    pub fn handle_write_range(
+        &self,
+        input: String,
+        output: OutputArgs,
+        start: String,
+    ) -> Result<()> {
+        let converter = self.default_converter();
         let data = converter.read_any_data(&input, None)?;
 
         // Parse start cell
@@ -183,15 +298,15 @@ impl IoCommandHandler {
             offset_data.push(row);
         }
 
-        converter.write_any_data(&output, &offset_data, None)?;
-        println!("Wrote data starting at {start} in {output}");
+        output.write(&converter, &offset_data, None)?;
+        report!(output, "Wrote data starting at {start} in {output}");
 
         Ok(())
     }
 
     /// Handle the append command
     pub fn handle_append(&self, source: String, target: String) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.default_converter();
 
         // Read source data
         let data = converter.read_any_data(&source, None)?;
@@ -288,6 +403,55 @@ impl IoCommandHandler {
         Ok(())
     }
 
+    /// Build a converter from the `--delimiter` and `--encoding` flags
+    ///
+    /// The encoding applies to CSV input. The delimiter applies to CSV input
+    /// when `delimiter_for_input` is set (the read command) and to CSV
+    /// output otherwise.
+    fn converter(
+        &self,
+        delimiter: Option<&str>,
+        encoding: Option<&str>,
+        delimiter_for_input: bool,
+    ) -> Result<Converter> {
+        let fixed = match delimiter {
+            Some(delimiter) => CsvHandler::with_delimiter(CsvOptions::parse_delimiter(delimiter)?),
+            None => CsvHandler::new(),
+        };
+        let (reader, writer) = if delimiter_for_input {
+            (fixed, CsvHandler::new())
+        } else {
+            (CsvHandler::new(), fixed)
+        };
+        let reader = match encoding {
+            Some(label) => reader.with_encoding(encoding_for_label(label)?),
+            None => reader,
+        };
+
+        Ok(self.with_stdin(Converter::with_csv_handlers(reader, writer)))
+    }
+
+    /// Converter with default CSV dialects that reads `-` in `--input-format`
+    fn default_converter(&self) -> Converter {
+        self.with_stdin(Converter::new())
+    }
+
+    fn with_stdin(&self, converter: Converter) -> Converter {
+        match &self.stdin_format {
+            Some(format) => converter.with_stdin_format(format),
+            None => converter,
+        }
+    }
+
+    /// Build Parquet write options from the `--compression` flag
+    fn parquet_options(&self, compression: Option<&str>) -> Result<ParquetWriteOptions> {
+        let mut options = ParquetWriteOptions::default();
+        if let Some(codec) = compression {
+            options.compression = ParquetWriteOptions::parse_compression(codec)?;
+        }
+        Ok(options)
+    }
+
     /// Parse Excel-style cell reference (e.g., "A1" -> row=0, col=0)
     fn parse_cell_ref(&self, cell: &str) -> Result<(usize, usize)> {
         let cell = cell.to_uppercase();
@@ -3,16 +3,21 @@
 //! Implements pandas-inspired operations like head, tail, join, groupby, concat, etc.
 
 use crate::{
-    cli::OutputFormat,
-    common::validation,
+    cli::{OutputFormat, args::OutputArgs},
+    columnar::{AvroHandler, ParquetHandler},
+    common::{self, validation},
     converter::Converter,
-    operations::{AggFunc, DataOperations, JoinType},
+    operations::{AggFunc, CROSS_JOIN_MAX_ROWS, DataOperations, JoinType, RankMethod, SortOrder},
+    profiling::DataProfiler,
 };
 use anyhow::Result;
+use std::io::IsTerminal;
 
 /// Pandas-style operation command handler
 #[derive(Default)]
-pub struct PandasCommandHandler;
+pub struct PandasCommandHandler {
+    stdin_format: Option<String>,
+}
 
 impl PandasCommandHandler {
     /// Create a new pandas command handler
@@ -20,12 +25,38 @@ impl PandasCommandHandler {
         Self::default()
     }
 
+    /// Read `-` inputs as `format` instead of CSV
+    pub fn with_stdin_format(mut self, format: &str) -> Self {
+        self.stdin_format = Some(format.to_string());
+        self
+    }
+
+    /// Converter that reads `-` inputs in the `--input-format`
+    fn converter(&self) -> Converter {
+        match &self.stdin_format {
+            Some(format) => Converter::new().with_stdin_format(format),
+            None => Converter::new(),
+        }
+    }
+
     /// Handle the head command
     ///
     /// Displays the first N rows of data.
-    pub fn handle_head(&self, input: String, n: usize, format: OutputFormat) -> Result<()> {
-        let converter = Converter::new();
-        let data = converter.read_any_data(&input, None)?;
+    pub fn handle_head(
+        &self,
+        input: String,
+        n: usize,
+        format: OutputFormat,
+        index: Option<usize>,
+        drop_index: bool,
+    ) -> Result<()> {
+        // Parquet can stop decoding after the first rows instead of reading everything
+        let data = if common::format::from_extension(&input) == "parquet" {
+            ParquetHandler::new().read_head(&input, n)?
+        } else {
+            self.converter().read_any_data(&input, None)?
+        };
+        let data = self.apply_index_options(data, index, drop_index);
 
         let ops = DataOperations::new();
         let head_data = ops.head(&data, n);
@@ -39,9 +70,24 @@ impl PandasCommandHandler {
     /// Handle the tail command
     ///
     /// Displays the last N rows of data.
-    pub fn handle_tail(&self, input: String, n: usize, format: OutputFormat) -> Result<()> {
-        let converter = Converter::new();
-        let data = converter.read_any_data(&input, None)?;
+    pub fn handle_tail(
+        &self,
+        input: String,
+        n: usize,
+        format: OutputFormat,
+        index: Option<usize>,
+        drop_index: bool,
+    ) -> Result<()> {
+        // Parquet only needs the trailing row group(s) to produce the last rows;
+        // the index still counts the rows that were skipped
+        let (data, skipped) = if common::format::from_extension(&input) == "parquet" {
+            let handler = ParquetHandler::new();
+            let total = handler.row_count(&input)?;
+            (handler.read_tail(&input, n)?, total.saturating_sub(n))
+        } else {
+            (self.converter().read_any_data(&input, None)?, 0)
+        };
+        let data = self.apply_index_options(data, index.map(|start| start + skipped), drop_index);
 
         let ops = DataOperations::new();
         let tail_data = ops.tail(&data, n);
@@ -61,9 +107,12 @@ impl PandasCommandHandler {
         n: usize,
         seed: Option<u64>,
         format: OutputFormat,
+        index: Option<usize>,
+        drop_index: bool,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
+        let data = self.apply_index_options(data, index, drop_index);
 
         let ops = DataOperations::new();
         let sample_data = ops.sample(&data, n, seed);
@@ -74,11 +123,76 @@ impl PandasCommandHandler {
         Ok(())
     }
 
+    /// Handle the peek command
+    ///
+    /// Prints a one-shot overview of a file: shape, inferred column types with
+    /// null rates and numeric ranges, then the first N rows as a table.
+    pub fn handle_peek(&self, input: String, n: usize) -> Result<()> {
+        let converter = self.converter();
+        let data = converter.read_any_data(&input, None)?;
+
+        let profile = DataProfiler::new().profile(&data, &input)?;
+        let head_data = DataOperations::new().head(&data, n + 1);
+
+        let color = std::io::stderr().is_terminal();
+        let bold = |text: &str| {
+            if color {
+                format!("\x1b[1m{text}\x1b[0m")
+            } else {
+                text.to_string()
+            }
+        };
+
+        println!(
+            "{} {} rows x {} columns",
+            bold(&format!("{input}:")),
+            profile.total_rows,
+            profile.total_columns
+        );
+
+        println!("\n{}", bold("Columns:"));
+        let name_width = profile
+            .columns
+            .iter()
+            .map(|c| c.name.len())
+            .max()
+            .unwrap_or(0);
+        for column in &profile.columns {
+            let dtype = format!("{:?}", column.data_type);
+            let mut line = format!(
+                "  {:<name_width$}  {:<8}  nulls {:>5.1}%",
+                column.name, dtype, column.null_percentage
+            );
+            if let Some(stats) = &column.numeric_stats {
+                line.push_str(&format!(
+                    "  min {} max {} mean {:.2}",
+                    stats.min, stats.max, stats.mean
+                ));
+            }
+            if color && column.null_percentage > 0.0 {
+                // Highlight columns with missing values
+                line = format!("\x1b[33m{line}\x1b[0m");
+            }
+            println!("{line}");
+        }
+
+        println!(
+            "\n{}",
+            bold(&format!(
+                "First {} rows:",
+                head_data.len().saturating_sub(1)
+            ))
+        );
+        self.print_data(&head_data, OutputFormat::Markdown)?;
+
+        Ok(())
+    }
+
     /// Handle the describe command
     ///
     /// Displays descriptive statistics for the data.
     pub fn handle_describe(&self, input: String, format: OutputFormat) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let ops = DataOperations::new();
@@ -90,11 +204,34 @@ impl PandasCommandHandler {
         Ok(())
     }
 
+    /// Handle the col-stats command
+    ///
+    /// Displays detailed statistics for a single column.
+    pub fn handle_col_stats(
+        &self,
+        input: String,
+        column: String,
+        format: OutputFormat,
+    ) -> Result<()> {
+        let converter = self.converter();
+        let data = converter.read_any_data(&input, None)?;
+
+        let col_idx = self.find_column_index(&data, &column)?;
+        validation::validate_column_index(&data, col_idx)?;
+
+        let ops = DataOperations::new();
+        let stats = ops.column_stats(&data, col_idx)?;
+
+        self.print_data(&stats, format)?;
+
+        Ok(())
+    }
+
     /// Handle the value_counts command
     ///
     /// Counts unique values in a column.
     pub fn handle_value_counts(&self, input: String, column: String) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let col_idx = self.find_column_index(&data, &column)?;
@@ -105,9 +242,7 @@ impl PandasCommandHandler {
 
         println!("Value counts for column '{column}':");
         for row in &counts[1..] {
-            if row.len() >= 2 {
-                println!("  {}: {}", row[0], row[1]);
-            }
+            println!("  {}: {} ({}%)", row[0], row[1], row[2]);
         }
 
         Ok(())
@@ -117,21 +252,24 @@ impl PandasCommandHandler {
     ///
     /// Calculates the correlation matrix for numeric columns.
     pub fn handle_corr(&self, input: String, columns: Option<String>) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
-        let col_indices = if let Some(cols_str) = columns {
-            cols_str
+        let col_indices = match columns {
+            Some(cols_str) => cols_str
                 .split(',')
                 .map(|c| self.find_column_index(&data, c.trim()))
-                .collect::<Result<Vec<_>>>()?
-        } else {
-            // Use all numeric columns
-            self.find_numeric_columns(&data)?
+                .collect::<Result<Vec<_>>>()?,
+            None => (0..data.first().map_or(0, Vec::len)).collect(),
         };
 
         let ops = DataOperations::new();
-        let corr_matrix = ops.correlation(&data, &col_indices)?;
+        for &col in &col_indices {
+            if !ops.is_numeric_column(&data, col) {
+                eprintln!("Note: skipping non-numeric column '{}'", data[0][col]);
+            }
+        }
+        let corr_matrix = ops.correlation(&data, Some(&col_indices))?;
 
         println!("Correlation Matrix:");
         for row in &corr_matrix {
@@ -150,28 +288,36 @@ impl PandasCommandHandler {
     pub fn handle_groupby(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         by: String,
         agg: String,
+        concat_sep: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
-        let by_idx = self.find_column_index(&data, &by)?;
-        validation::validate_column_index(&data, by_idx)?;
-
-        // Parse aggregation function
-        let agg_func = AggFunc::from_str(&agg)?;
+        let mut by_cols = Vec::new();
+        for name in by.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let idx = self.find_column_index(&data, name)?;
+            validation::validate_column_index(&data, idx)?;
+            by_cols.push(idx);
+        }
 
-        // For simple groupby, aggregate the first value column (column 1 if exists)
-        let value_col = if data[0].len() > 1 { 1 } else { 0 };
-        let aggregations = vec![(value_col, agg_func)];
+        let mut aggregations = self.parse_aggregations(&data, &agg)?;
+        for (_, func) in &mut aggregations {
+            if let AggFunc::Concat { sep } = func {
+                *sep = concat_sep.clone();
+            }
+        }
 
         let ops = DataOperations::new();
-        let grouped = ops.groupby(&data, by_idx, &aggregations)?;
+        let grouped = ops.groupby_multi(&data, &by_cols, &aggregations)?;
 
-        converter.write_any_data(&output, &grouped, None)?;
-        println!("Grouped by '{by}' with '{agg}' aggregation; wrote {output}");
+        output.write(&converter, &grouped, None)?;
+        report!(
+            output,
+            "Grouped by '{by}' with '{agg}' aggregation; wrote {output}"
+        );
 
         Ok(())
     }
@@ -183,31 +329,54 @@ impl PandasCommandHandler {
         &self,
         left: String,
         right: String,
-        output: String,
-        on: String,
+        output: OutputArgs,
+        on: Option<String>,
         how: String,
+        no_cross_limit: bool,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
+
+        // Parse join type
+        let join_type = JoinType::from_str(&how)?;
 
         // Read both files
         let left_data = converter.read_any_data(&left, None)?;
         let right_data = converter.read_any_data(&right, None)?;
 
-        // Find column indices
-        let left_col = self.find_column_index(&left_data, &on)?;
-        let right_col = self.find_column_index(&right_data, &on)?;
+        let ops = DataOperations::new();
 
-        validation::validate_column_index(&left_data, left_col)?;
-        validation::validate_column_index(&right_data, right_col)?;
+        if join_type == JoinType::Cross {
+            let max_rows = (!no_cross_limit).then_some(CROSS_JOIN_MAX_ROWS);
+            let joined = ops.cross_join(&left_data, &right_data, max_rows)?;
 
-        // Parse join type
-        let join_type = JoinType::from_str(&how)?;
+            output.write(&converter, &joined, None)?;
+            report!(output, "Cross joined {left} and {right}; wrote {output}");
+            return Ok(());
+        }
 
-        let ops = DataOperations::new();
-        let joined = ops.join(&left_data, &right_data, left_col, right_col, join_type)?;
+        let on = on.ok_or_else(|| anyhow::anyhow!("--on is required for {} joins", how))?;
+
+        // Find column indices; a comma-separated list joins on a composite key
+        let mut left_cols = Vec::new();
+        let mut right_cols = Vec::new();
+        for name in on.split(',').map(|s| s.trim()) {
+            let left_col = self.find_column_index(&left_data, name)?;
+            let right_col = self.find_column_index(&right_data, name)?;
 
-        converter.write_any_data(&output, &joined, None)?;
-        println!("Joined {left} and {right} on '{on}' ({how} join); wrote {output}");
+            validation::validate_column_index(&left_data, left_col)?;
+            validation::validate_column_index(&right_data, right_col)?;
+
+            left_cols.push(left_col);
+            right_cols.push(right_col);
+        }
+
+        let joined = ops.join_multi(&left_data, &right_data, &left_cols, &right_cols, join_type)?;
+
+        output.write(&converter, &joined, None)?;
+        report!(
+            output,
+            "Joined {left} and {right} on '{on}' ({how} join); wrote {output}"
+        );
 
         Ok(())
     }
@@ -215,8 +384,8 @@ impl PandasCommandHandler {
     /// Handle the concat command
     ///
     /// Concatenates multiple files vertically.
-    pub fn handle_concat(&self, inputs: String, output: String) -> Result<()> {
-        let converter = Converter::new();
+    pub fn handle_concat(&self, inputs: String, output: OutputArgs) -> Result<()> {
+        let converter = self.converter();
 
         // Parse input files (glob pattern or comma-separated)
         let input_files: Vec<String> = if inputs.contains('*') {
@@ -250,8 +419,13 @@ impl PandasCommandHandler {
         let ops = DataOperations::new();
         let concatenated = ops.concat(&datasets);
 
-        converter.write_any_data(&output, &concatenated, None)?;
-        println!("Concatenated {} files; wrote {}", input_files.len(), output);
+        output.write(&converter, &concatenated, None)?;
+        report!(
+            output,
+            "Concatenated {} files; wrote {}",
+            input_files.len(),
+            output
+        );
 
         Ok(())
     }
@@ -260,7 +434,7 @@ impl PandasCommandHandler {
     ///
     /// Returns unique values from a column.
     pub fn handle_unique(&self, input: String, column: String) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let col_idx = self.find_column_index(&data, &column)?;
@@ -270,10 +444,8 @@ impl PandasCommandHandler {
         let unique = ops.unique(&data, col_idx);
 
         println!("Unique values in column '{column}':");
-        for row in &unique[1..] {
-            if let Some(val) = row.first() {
-                println!("  {val}");
-            }
+        for val in &unique {
+            println!("  {val}");
         }
 
         Ok(())
@@ -283,40 +455,30 @@ impl PandasCommandHandler {
     ///
     /// Displays information about the dataset.
     pub fn handle_info(&self, input: String) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
+        let ops = DataOperations::new();
+        let rows = data.len().saturating_sub(1);
+
         println!("Dataset Info:");
-        println!("  Rows: {}", data.len().saturating_sub(1));
+        println!("  Rows: {rows}");
         println!("  Columns: {}", data.first().map(|h| h.len()).unwrap_or(0));
+        println!(
+            "  Memory: ~{:.1} KB",
+            ops.memory_usage(&data) as f64 / 1024.0
+        );
 
-        if let Some(header) = data.first() {
+        let dtypes = ops.infer_dtypes(&data);
+        if !dtypes.is_empty() {
             println!("\nColumns:");
-            for (i, col) in header.iter().enumerate() {
-                // Detect type
-                let mut type_count: std::collections::HashMap<&str, usize> =
-                    std::collections::HashMap::new();
-                for row in data.iter().skip(1) {
-                    if let Some(cell) = row.get(i) {
-                        let dtype = if cell.parse::<f64>().is_ok() {
-                            "number"
-                        } else if cell.is_empty() {
-                            "empty"
-                        } else {
-                            "string"
-                        };
-                        *type_count.entry(dtype).or_insert(0) += 1;
-                    }
-                }
-
-                let most_common = type_count
+            for (idx, (col, dtype)) in dtypes.iter().enumerate() {
+                let nulls = data
                     .iter()
-                    .max_by_key(|&(_, count)| count)
-                    .map(|(dtype, _)| *dtype)
-                    .unwrap_or("unknown");
-
-                let non_empty = data.len() - 1 - type_count.get("empty").unwrap_or(&0);
-                println!("  {}: {} ({} non-null)", col, most_common, non_empty);
+                    .skip(1)
+                    .filter(|row| row.get(idx).is_none_or(|cell| cell.trim().is_empty()))
+                    .count();
+                println!("  {col}: {dtype} ({} non-null, {nulls} null)", rows - nulls);
             }
         }
 
@@ -325,41 +487,40 @@ impl PandasCommandHandler {
 
     /// Handle the dtypes command
     ///
-    /// Shows the data types of each column.
+    /// Shows the inferred type of each column.
     pub fn handle_dtypes(&self, input: String) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
-        if let Some(header) = data.first() {
+        let ops = DataOperations::new();
+        let dtypes = ops.infer_dtypes(&data);
+        if !dtypes.is_empty() {
             println!("Column Types:");
-            for (i, col) in header.iter().enumerate() {
-                // Detect type
-                let mut has_numbers = false;
-                let mut has_strings = false;
-
-                for row in data.iter().skip(1) {
-                    if let Some(cell) = row.get(i) {
-                        if cell.is_empty() {
-                            continue;
-                        }
-                        if cell.parse::<f64>().is_ok() {
-                            has_numbers = true;
-                        } else {
-                            has_strings = true;
-                        }
-                    }
-                }
+            for (col, dtype) in dtypes {
+                println!("  {col}: {dtype}");
+            }
+        }
 
-                let dtype = if has_strings {
-                    "string"
-                } else if has_numbers {
-                    "number"
-                } else {
-                    "empty"
-                };
+        Ok(())
+    }
 
-                println!("  {}: {}", col, dtype);
-            }
+    /// Handle the schema command
+    ///
+    /// Prints the types stored in the file footer or header, unlike `dtypes`
+    /// which infers them from the cell text.
+    pub fn handle_schema(&self, input: String) -> Result<()> {
+        let schema = match common::format::from_extension(&input) {
+            "parquet" => ParquetHandler::new().schema(&input)?,
+            "avro" => AvroHandler::new().schema(&input)?,
+            _ => anyhow::bail!(
+                "'{}' has no stored schema; use dtypes to infer column types",
+                input
+            ),
+        };
+
+        println!("Schema:");
+        for (name, dtype) in schema {
+            println!("  {}: {}", name, dtype);
         }
 
         Ok(())
@@ -371,13 +532,13 @@ impl PandasCommandHandler {
     pub fn handle_pivot(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         index: String,
         columns: String,
         values: String,
         agg: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let index_idx = self.find_column_index(&data, &index)?;
@@ -389,12 +550,172 @@ impl PandasCommandHandler {
         let ops = DataOperations::new();
         let pivoted = ops.pivot(&data, index_idx, cols_idx, vals_idx, agg_func)?;
 
-        converter.write_any_data(&output, &pivoted, None)?;
-        println!("Created pivot table; wrote {}", output);
+        output.write(&converter, &pivoted, None)?;
+        report!(output, "Created pivot table; wrote {}", output);
 
         Ok(())
     }
 
+    /// Handle the rolling command
+    ///
+    /// Appends a moving aggregate of a column.
+    pub fn handle_rolling(
+        &self,
+        input: String,
+        output: OutputArgs,
+        column: String,
+        window: usize,
+        agg: String,
+    ) -> Result<()> {
+        let converter = self.converter();
+        let data = converter.read_any_data(&input, None)?;
+
+        let col_idx = self.find_column_index(&data, &column)?;
+        let agg_func = AggFunc::from_str(&agg)?;
+
+        let ops = DataOperations::new();
+        let rolled = ops.rolling(&data, col_idx, window, agg_func)?;
+
+        output.write(&converter, &rolled, None)?;
+        report!(
+            output,
+            "Computed rolling {agg} of '{column}' over {window} rows; wrote {output}"
+        );
+
+        Ok(())
+    }
+
+    /// Handle the rank command
+    ///
+    /// Appends a rank column for a numeric column.
+    pub fn handle_rank(
+        &self,
+        input: String,
+        output: OutputArgs,
+        column: String,
+        method: String,
+        descending: bool,
+    ) -> Result<()> {
+        let converter = self.converter();
+        let data = converter.read_any_data(&input, None)?;
+
+        let col_idx = self.find_column_index(&data, &column)?;
+        let rank_method: RankMethod = method.parse()?;
+        let order = if descending {
+            SortOrder::Descending
+        } else {
+            SortOrder::Ascending
+        };
+
+        let ops = DataOperations::new();
+        let ranked = ops.rank(&data, col_idx, order, rank_method);
+
+        output.write(&converter, &ranked, None)?;
+        report!(
+            output,
+            "Ranked by '{column}' ({method}, {order:?}); wrote {output}"
+        );
+
+        Ok(())
+    }
+
+    /// Handle the bin command
+    ///
+    /// Appends a bin-label column using equal-width (`cut`) or quantile
+    /// (`qcut`) bins.
+    pub fn handle_bin(
+        &self,
+        input: String,
+        output: OutputArgs,
+        column: String,
+        bins: usize,
+        method: String,
+    ) -> Result<()> {
+        let converter = self.converter();
+        let data = converter.read_any_data(&input, None)?;
+
+        let col_idx = self.find_column_index(&data, &column)?;
+        let ops = DataOperations::new();
+        let binned = match method.to_lowercase().as_str() {
+            "cut" => ops.cut(&data, col_idx, bins)?,
+            "qcut" => ops.qcut(&data, col_idx, bins)?,
+            _ => anyhow::bail!("Unknown bin method: {method}. Use: cut, qcut"),
+        };
+
+        output.write(&converter, &binned, None)?;
+        report!(
+            output,
+            "Binned '{column}' into {bins} {method} bins; wrote {output}"
+        );
+
+        Ok(())
+    }
+
+    /// Handle the melt command
+    ///
+    /// Unpivots value columns into variable/value rows.
+    pub fn handle_melt(
+        &self,
+        input: String,
+        output: OutputArgs,
+        id_vars: String,
+        value_vars: Option<String>,
+    ) -> Result<()> {
+        let converter = self.converter();
+        let data = converter.read_any_data(&input, None)?;
+
+        let id_cols = id_vars
+            .split(',')
+            .map(|c| self.find_column_index(&data, c.trim()))
+            .collect::<Result<Vec<_>>>()?;
+        let value_cols = match value_vars {
+            Some(cols) => cols
+                .split(',')
+                .map(|c| self.find_column_index(&data, c.trim()))
+                .collect::<Result<Vec<_>>>()?,
+            None => (0..data[0].len())
+                .filter(|col| !id_cols.contains(col))
+                .collect(),
+        };
+
+        let ops = DataOperations::new();
+        let melted = ops.melt(&data, &id_cols, &value_cols);
+
+        output.write(&converter, &melted, None)?;
+        report!(
+            output,
+            "Melted {} column(s) into {} rows; wrote {}",
+            value_cols.len(),
+            melted.len().saturating_sub(1),
+            output
+        );
+
+        Ok(())
+    }
+
+    /// Apply `--drop-index` and `--index` to freshly read data
+    ///
+    /// The index is added before rows are selected so it reflects each
+    /// row's position in the input.
+    fn apply_index_options(
+        &self,
+        data: Vec<Vec<String>>,
+        index: Option<usize>,
+        drop_index: bool,
+    ) -> Vec<Vec<String>> {
+        let ops = DataOperations::new();
+        let data = if drop_index {
+            ops.reset_index(&data, "index")
+        } else {
+            data
+        };
+
+        match index {
+            Some(start) => ops.add_index(&data, "index", start),
+            None => data,
+        }
+    }
+
     /// Find column index by name
     fn find_column_index(&self, data: &[Vec<String>], column: &str) -> Result<usize> {
         if data.is_empty() {
@@ -408,31 +729,30 @@ impl PandasCommandHandler {
             .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", column))
     }
 
-    /// Find all numeric columns
-    fn find_numeric_columns(&self, data: &[Vec<String>]) -> Result<Vec<usize>> {
-        if data.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let mut numeric_cols = Vec::new();
-        let num_cols = data[0].len();
+    /// Parse `--agg` specs such as `sum`, `count_distinct:customer` or
+    /// `sum:amount,ndistinct:customer`
+    ///
+    /// A spec without a column aggregates the first value column (column 1
+    /// if it exists).
+    fn parse_aggregations(&self, data: &[Vec<String>], agg: &str) -> Result<Vec<(usize, AggFunc)>> {
+        let default_col = if data.first().is_some_and(|h| h.len() > 1) {
+            1
+        } else {
+            0
+        };
 
-        for i in 0..num_cols {
-            let mut is_numeric = true;
-            for row in data.iter().skip(1) {
-                if let Some(cell) = row.get(i) {
-                    if !cell.is_empty() && cell.parse::<f64>().is_err() {
-                        is_numeric = false;
-                        break;
+        agg.split(',')
+            .map(|spec| {
+                let spec = spec.trim();
+                match spec.split_once(':') {
+                    Some((func, column)) => {
+                        let col_idx = self.find_column_index(data, column.trim())?;
+                        Ok((col_idx, AggFunc::from_str(func.trim())?))
                     }
+                    None => Ok((default_col, AggFunc::from_str(spec)?)),
                 }
-            }
-            if is_numeric {
-                numeric_cols.push(i);
-            }
-        }
-
-        Ok(numeric_cols)
+            })
+            .collect()
     }
 
     /// Print data in the specified format
@@ -464,6 +784,8 @@ impl PandasCommandHandler {
 
                 println!("{}", serde_json::to_string_pretty(&rows)?);
             }
+            OutputFormat::Html => print!("{}", DataOperations::new().to_html(data)),
+            OutputFormat::Tsv => print!("{}", DataOperations::new().to_tsv(data)),
             OutputFormat::Markdown => {
                 if data.is_empty() {
                     return Ok(());
@@ -3,15 +3,24 @@
 //! Implements data manipulation operations like sort, filter, replace, etc.
 
 use crate::{
-    common::validation,
+    cli::args::OutputArgs,
+    columnar::ParquetHandler,
+    common::{format, validation},
     converter::Converter,
-    operations::{DataOperations, SortOrder},
+    csv_handler::StreamingCsvReader,
+    operations::{DataOperations, FillMethod, SortOrder},
+    streaming::{self, DedupeMode},
 };
 use anyhow::Result;
 
+/// Input size above which `dedupe` switches to the streaming path (256 MiB)
+const STREAMING_DEDUPE_THRESHOLD: u64 = 256 * 1024 * 1024;
+
 /// Data transformation command handler
 #[derive(Default)]
-pub struct TransformCommandHandler;
+pub struct TransformCommandHandler {
+    stdin_format: Option<String>,
+}
 
 impl TransformCommandHandler {
     /// Create a new transformation command handler
@@ -19,17 +28,33 @@ impl TransformCommandHandler {
         Self::default()
     }
 
+    /// Read `-` inputs as `format` instead of CSV
+    pub fn with_stdin_format(mut self, format: &str) -> Self {
+        self.stdin_format = Some(format.to_string());
+        self
+    }
+
+    /// Converter that reads `-` inputs in the `--input-format`
+    fn converter(&self) -> Converter {
+        match &self.stdin_format {
+            Some(format) => Converter::new().with_stdin_format(format),
+            None => Converter::new(),
+        }
+    }
+
     /// Handle the sort command
     ///
     /// Sorts rows by a specific column in ascending or descending order.
     pub fn handle_sort(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         column: String,
         ascending: bool,
+        sort_as: Option<String>,
+        date_format: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let mut data = converter.read_any_data(&input, None)?;
 
         // Find column index
@@ -43,44 +68,45 @@ impl TransformCommandHandler {
         } else {
             SortOrder::Descending
         };
-        ops.sort_by_column(&mut data, col_idx, order)?;
+        match sort_as.as_deref() {
+            None => ops.sort_by_column(&mut data, col_idx, order, true)?,
+            Some("date") => ops.sort_by_date(&mut data, col_idx, &date_format, order)?,
+            Some("natural") => ops.sort_by_column_natural(&mut data, col_idx, order, true)?,
+            Some(other) => anyhow::bail!(
+                "Unsupported sort type '{}' (expected: date, natural)",
+                other
+            ),
+        }
 
         // Write output
-        converter.write_any_data(&output, &data, None)?;
-        println!("Sorted by {column} ({order:?}); wrote {output}");
+        output.write(&converter, &data, None)?;
+        report!(output, "Sorted by {column} ({order:?}); wrote {output}");
 
         Ok(())
     }
 
     /// Handle the filter command
     ///
-    /// Filters rows based on a WHERE clause condition.
-    pub fn handle_filter(&self, input: String, output: String, where_clause: String) -> Result<()> {
-        let converter = Converter::new();
+    /// Keeps the rows matching a WHERE clause such as `age > 30 AND city = "NYC"`.
+    pub fn handle_filter(
+        &self,
+        input: String,
+        output: OutputArgs,
+        where_clause: String,
+    ) -> Result<()> {
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
-        // Parse WHERE clause (simple implementation)
-        // Format: column operator value
-        // Example: "age > 25" or "name == John"
-        let parts: Vec<&str> = where_clause.split_whitespace().collect();
-        if parts.len() < 3 {
-            anyhow::bail!(
-                "Invalid WHERE clause format. Expected: 'column operator value', got: '{where_clause}'"
-            );
-        }
-
-        let column = parts[0];
-        let operator = parts[1];
-        let value = parts[2..].join(" ");
-
-        let col_idx = self.find_column_index(&data, column)?;
-        validation::validate_column_index(&data, col_idx)?;
-
         let ops = DataOperations::new();
-        let filtered = ops.filter_rows(&data, col_idx, operator, &value)?;
+        let filtered = ops.filter_where(&data, &where_clause)?;
 
-        converter.write_any_data(&output, &filtered, None)?;
-        println!("Filtered to {} rows; wrote {}", filtered.len(), output);
+        output.write(&converter, &filtered, None)?;
+        report!(
+            output,
+            "Filtered to {} rows; wrote {}",
+            filtered.len(),
+            output
+        );
 
         Ok(())
     }
@@ -91,12 +117,12 @@ impl TransformCommandHandler {
     pub fn handle_replace(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         find: String,
         replace: String,
         column: Option<String>,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let mut data = converter.read_any_data(&input, None)?;
 
         if let Some(col_name) = column {
@@ -113,7 +139,10 @@ impl TransformCommandHandler {
                     }
                 }
             }
-            println!("Replaced {count} occurrences in column '{col_name}'");
+            report!(
+                output,
+                "Replaced {count} occurrences in column '{col_name}'"
+            );
         } else {
             // Replace in all cells
             let mut count = 0;
@@ -125,25 +154,39 @@ impl TransformCommandHandler {
                     }
                 }
             }
-            println!("Replaced {count} occurrences in all cells");
+            report!(output, "Replaced {count} occurrences in all cells");
         }
 
-        converter.write_any_data(&output, &data, None)?;
-        println!("Wrote {output}");
+        output.write(&converter, &data, None)?;
+        report!(output, "Wrote {output}");
 
         Ok(())
     }
 
     /// Handle the dedupe command
     ///
-    /// Removes duplicate rows from the data.
+    /// Removes duplicate rows from the data. Large CSV files, or any CSV run
+    /// with an approximate mode, are deduplicated row by row instead of being
+    /// loaded into memory.
     pub fn handle_dedupe(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         columns: Option<String>,
+        mode: DedupeMode,
     ) -> Result<()> {
-        let converter = Converter::new();
+        // The streaming path writes a single file, so several outputs go in-memory
+        let csv_to_csv = format::from_extension(&input) == "csv"
+            && matches!(&output[..], [path] if format::from_extension(path) == "csv");
+        let is_large = std::fs::metadata(&input)
+            .map(|m| m.len() >= STREAMING_DEDUPE_THRESHOLD)
+            .unwrap_or(false);
+
+        if csv_to_csv && (is_large || matches!(mode, DedupeMode::Approximate { .. })) {
+            return self.handle_dedupe_streaming(&input, &output[0], columns.as_deref(), mode);
+        }
+
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let ops = DataOperations::new();
@@ -171,8 +214,9 @@ impl TransformCommandHandler {
             ops.deduplicate(&data)
         };
 
-        converter.write_any_data(&output, &deduped, None)?;
-        println!(
+        output.write(&converter, &deduped, None)?;
+        report!(
+            output,
             "Removed {} duplicates; wrote {}",
             data.len() - deduped.len(),
             output
@@ -181,18 +225,56 @@ impl TransformCommandHandler {
         Ok(())
     }
 
+    /// Deduplicate a CSV file without holding its rows in memory
+    fn handle_dedupe_streaming(
+        &self,
+        input: &str,
+        output: &str,
+        columns: Option<&str>,
+        mode: DedupeMode,
+    ) -> Result<()> {
+        let key_columns = match columns {
+            Some(cols_str) => {
+                // Only the header is needed to resolve column names
+                let header = StreamingCsvReader::open(input)?
+                    .next()
+                    .transpose()?
+                    .unwrap_or_default();
+                let header = vec![header];
+                Some(
+                    cols_str
+                        .split(',')
+                        .map(|c| self.find_column_index(&header, c.trim()))
+                        .collect::<Result<Vec<_>>>()?,
+                )
+            }
+            None => None,
+        };
+
+        let stats = streaming::stream_dedupe(input, output, key_columns.as_deref(), mode)?;
+        report!(
+            output,
+            "Removed {} duplicates; wrote {}",
+            stats.duplicates_removed(),
+            output
+        );
+
+        Ok(())
+    }
+
     /// Handle the transpose command
     ///
     /// Transposes data (rows become columns, columns become rows).
-    pub fn handle_transpose(&self, input: String, output: String) -> Result<()> {
-        let converter = Converter::new();
+    pub fn handle_transpose(&self, input: String, output: OutputArgs) -> Result<()> {
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let ops = DataOperations::new();
         let transposed = ops.transpose(&data);
 
-        converter.write_any_data(&output, &transposed, None)?;
-        println!(
+        output.write(&converter, &transposed, None)?;
+        report!(
+            output,
             "Transposed {}x{} to {}x{}; wrote {}",
             data.len(),
             data.first().map(|r| r.len()).unwrap_or(0),
@@ -207,18 +289,28 @@ impl TransformCommandHandler {
     /// Handle the select command
     ///
     /// Selects specific columns from the data.
-    pub fn handle_select(&self, input: String, output: String, columns: String) -> Result<()> {
-        let converter = Converter::new();
-        let data = converter.read_any_data(&input, None)?;
+    pub fn handle_select(&self, input: String, output: OutputArgs, columns: String) -> Result<()> {
+        let converter = self.converter();
 
         // Parse column names
         let col_names: Vec<&str> = columns.split(',').map(|c| c.trim()).collect();
 
-        let ops = DataOperations::new();
-        let selected = ops.select_columns_by_name(&data, &col_names)?;
+        // Parquet can skip the unselected columns entirely
+        let selected = if format::from_extension(&input) == "parquet" {
+            ParquetHandler::new().read_columns(&input, &col_names)?
+        } else {
+            let data = converter.read_any_data(&input, None)?;
+            let ops = DataOperations::new();
+            ops.select_columns_by_name(&data, &col_names)?
+        };
 
-        converter.write_any_data(&output, &selected, None)?;
-        println!("Selected {} columns; wrote {}", col_names.len(), output);
+        output.write(&converter, &selected, None)?;
+        report!(
+            output,
+            "Selected {} columns; wrote {}",
+            col_names.len(),
+            output
+        );
 
         Ok(())
     }
@@ -229,18 +321,18 @@ impl TransformCommandHandler {
     pub fn handle_rename(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         from: String,
         to: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let mut data = converter.read_any_data(&input, None)?;
 
         let ops = DataOperations::new();
         ops.rename_columns(&mut data, &[(from.as_str(), to.as_str())])?;
 
-        converter.write_any_data(&output, &data, None)?;
-        println!("Renamed column '{from}' to '{to}'; wrote {output}");
+        output.write(&converter, &data, None)?;
+        report!(output, "Renamed column '{from}' to '{to}'; wrote {output}");
 
         Ok(())
     }
@@ -248,8 +340,8 @@ impl TransformCommandHandler {
     /// Handle the drop command
     ///
     /// Drops specified columns from the data.
-    pub fn handle_drop(&self, input: String, output: String, columns: String) -> Result<()> {
-        let converter = Converter::new();
+    pub fn handle_drop(&self, input: String, output: OutputArgs, columns: String) -> Result<()> {
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         // Parse column names and find indices
@@ -261,25 +353,62 @@ impl TransformCommandHandler {
         let ops = DataOperations::new();
         let dropped = ops.drop_columns(&data, &col_indices);
 
-        converter.write_any_data(&output, &dropped, None)?;
-        println!("Dropped {} columns; wrote {}", col_indices.len(), output);
+        output.write(&converter, &dropped, None)?;
+        report!(
+            output,
+            "Dropped {} columns; wrote {}",
+            col_indices.len(),
+            output
+        );
 
         Ok(())
     }
 
     /// Handle the fillna command
     ///
-    /// Fills missing/empty values with a specified value.
+    /// Fills missing/empty values with a specified value, with the
+    /// neighbouring value in the column for `ffill`/`bfill`, or by linear
+    /// interpolation for `linear`.
     pub fn handle_fillna(
         &self,
         input: String,
-        output: String,
-        value: String,
+        output: OutputArgs,
+        value: Option<String>,
         columns: Option<String>,
+        method: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let method: FillMethod = method.parse()?;
+        let converter = self.converter();
         let mut data = converter.read_any_data(&input, None)?;
 
+        if method != FillMethod::Value {
+            if value.is_some() {
+                anyhow::bail!("--value only applies to the value fill method");
+            }
+            let col_indices: Vec<usize> = match columns {
+                Some(cols_str) => cols_str
+                    .split(',')
+                    .map(|c| self.find_column_index(&data, c.trim()))
+                    .collect::<Result<Vec<_>>>()?,
+                None => (0..data.first().map_or(0, |header| header.len())).collect(),
+            };
+
+            let ops = DataOperations::new();
+            let count = match method {
+                FillMethod::Forward => ops.fill_forward(&mut data, &col_indices),
+                FillMethod::Backward => ops.fill_backward(&mut data, &col_indices),
+                _ => col_indices
+                    .iter()
+                    .map(|&col| ops.interpolate_linear(&mut data, col))
+                    .sum(),
+            };
+            output.write(&converter, &data, None)?;
+            report!(output, "Filled {count} cells; wrote {output}");
+            return Ok(());
+        }
+
+        let value = value
+            .ok_or_else(|| anyhow::anyhow!("--value is required for the value fill method"))?;
         if let Some(cols_str) = columns {
             // Fill specific columns
             let col_indices: Vec<usize> = cols_str
@@ -299,16 +428,16 @@ impl TransformCommandHandler {
                     }
                 }
             }
-            println!("Filled {} cells in specified columns", count);
+            report!(output, "Filled {} cells in specified columns", count);
         } else {
             // Fill all columns
             let ops = DataOperations::new();
             ops.fillna(&mut data, &value);
-            println!("Filled all empty cells with '{value}'");
+            report!(output, "Filled all empty cells with '{value}'");
         }
 
-        converter.write_any_data(&output, &data, None)?;
-        println!("Wrote {output}");
+        output.write(&converter, &data, None)?;
+        report!(output, "Wrote {output}");
 
         Ok(())
     }
@@ -316,15 +445,16 @@ impl TransformCommandHandler {
     /// Handle the dropna command
     ///
     /// Drops rows that contain any empty values.
-    pub fn handle_dropna(&self, input: String, output: String) -> Result<()> {
-        let converter = Converter::new();
+    pub fn handle_dropna(&self, input: String, output: OutputArgs) -> Result<()> {
+        let converter = self.converter();
         let data = converter.read_any_data(&input, None)?;
 
         let ops = DataOperations::new();
         let filtered = ops.dropna(&data);
 
-        converter.write_any_data(&output, &filtered, None)?;
-        println!(
+        output.write(&converter, &filtered, None)?;
+        report!(
+            output,
             "Dropped {} rows with empty values; wrote {}",
             data.len() - filtered.len(),
             output
@@ -339,37 +469,21 @@ impl TransformCommandHandler {
     pub fn handle_mutate(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         column: String,
         formula: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let mut data = converter.read_any_data(&input, None)?;
 
-        // Simple formula evaluation for common operations
-        // Format: "column1 + column2" or "column * 2"
-        let result_values = self.evaluate_formula(&data, &formula)?;
-
-        // Add or update column
-        if data.is_empty() {
-            return Ok(()); // No data to modify
-        }
-
-        // Add header if new column
-        if let Some(header) = data.first_mut() {
-            if !header.contains(&column) {
-                header.push(column.clone());
-            }
-        }
-
-        // Add values to each row
-        for (i, row) in data.iter_mut().enumerate().skip(1) {
-            let value = result_values.get(i - 1).map(|s| s.as_str()).unwrap_or("");
-            row.push(value.to_string());
-        }
+        let ops = DataOperations::new();
+        ops.mutate(&mut data, &column, &formula)?;
 
-        converter.write_any_data(&output, &data, None)?;
-        println!("Added column '{column}' with formula '{formula}'; wrote {output}");
+        output.write(&converter, &data, None)?;
+        report!(
+            output,
+            "Added column '{column}' with formula '{formula}'; wrote {output}"
+        );
 
         Ok(())
     }
@@ -377,9 +491,12 @@ impl TransformCommandHandler {
     /// Handle the query command
     ///
     /// Executes SQL-like query on the data.
-    pub fn handle_query(&self, input: String, output: String, where_clause: String) -> Result<()> {
-        // Query is similar to filter but with more advanced syntax
-        // For now, delegate to filter
+    pub fn handle_query(
+        &self,
+        input: String,
+        output: OutputArgs,
+        where_clause: String,
+    ) -> Result<()> {
         self.handle_filter(input, output, where_clause)
     }
 
@@ -389,21 +506,24 @@ impl TransformCommandHandler {
     pub fn handle_astype(
         &self,
         input: String,
-        output: String,
+        output: OutputArgs,
         column: String,
         target_type: String,
     ) -> Result<()> {
-        let converter = Converter::new();
+        let converter = self.converter();
         let mut data = converter.read_any_data(&input, None)?;
 
         let col_idx = self.find_column_index(&data, &column)?;
         validation::validate_column_index(&data, col_idx)?;
 
         let ops = DataOperations::new();
-        let converted = ops.astype(&mut data, col_idx, &target_type)?;
+        let converted = ops.astype(&mut data, col_idx, target_type.parse()?)?;
 
-        converter.write_any_data(&output, &data, None)?;
-        println!("Converted {converted} cells to type '{target_type}'; wrote {output}");
+        output.write(&converter, &data, None)?;
+        report!(
+            output,
+            "Converted {converted} cells to type '{target_type}'; wrote {output}"
+        );
 
         Ok(())
     }
@@ -420,19 +540,4 @@ impl TransformCommandHandler {
             .position(|h| h == column)
             .ok_or_else(|| anyhow::anyhow!("Column '{column}' not found"))
     }
-
-    /// Simple formula evaluator for mutate command
-    fn evaluate_formula(&self, data: &[Vec<String>], formula: &str) -> Result<Vec<String>> {
-        // This is a simplified implementation
-        // A full implementation would parse arithmetic expressions
-        let mut results = Vec::new();
-
-        for (_i, _row) in data.iter().enumerate().skip(1) {
-            // For now, just return the formula as-is (placeholder)
-            // A real implementation would evaluate the formula against row data
-            results.push(formula.to_string());
-        }
-
-        Ok(results)
-    }
 }
@@ -16,6 +16,12 @@ pub enum OutputFormat {
 
     /// Markdown table format
     Markdown,
+
+    /// HTML table format
+    Html,
+
+    /// TSV format (tab-separated values)
+    Tsv,
 }
 
 impl OutputFormat {
@@ -25,6 +31,8 @@ impl OutputFormat {
             OutputFormat::Csv => "csv",
             OutputFormat::Json => "json",
             OutputFormat::Markdown => "md",
+            OutputFormat::Html => "html",
+            OutputFormat::Tsv => "tsv",
         }
     }
 
@@ -34,6 +42,8 @@ impl OutputFormat {
             OutputFormat::Csv => "text/csv",
             OutputFormat::Json => "application/json",
             OutputFormat::Markdown => "text/markdown",
+            OutputFormat::Html => "text/html",
+            OutputFormat::Tsv => "text/tab-separated-values",
         }
     }
 }
@@ -44,6 +54,8 @@ impl std::fmt::Display for OutputFormat {
             OutputFormat::Csv => write!(f, "csv"),
             OutputFormat::Json => write!(f, "json"),
             OutputFormat::Markdown => write!(f, "markdown"),
+            OutputFormat::Html => write!(f, "html"),
+            OutputFormat::Tsv => write!(f, "tsv"),
         }
     }
 }
@@ -3,13 +3,16 @@
 //! This module provides the default command handler that delegates
 //! to specialized command handlers based on the command type.
 
+use crate::cli::args::*;
+use crate::cli::commands::advanced::TextAnalysisOptions;
 use crate::cli::{
+    Commands,
     commands::{
-        io::IoCommandHandler, pandas::PandasCommandHandler, transform::TransformCommandHandler,
-        AdvancedCommandHandler,
+        AdvancedCommandHandler, io::IoCommandHandler, pandas::PandasCommandHandler,
+        transform::TransformCommandHandler,
     },
-    Commands,
 };
+use crate::streaming::DedupeMode;
 use anyhow::{Context, Result};
 
 /// Default command handler
@@ -20,6 +23,7 @@ pub struct DefaultCommandHandler {
     transform: TransformCommandHandler,
     pandas: PandasCommandHandler,
     advanced: AdvancedCommandHandler,
+    stdin_format: Option<String>,
 }
 
 impl DefaultCommandHandler {
@@ -30,6 +34,26 @@ impl DefaultCommandHandler {
             transform: TransformCommandHandler::new(),
             pandas: PandasCommandHandler::new(),
             advanced: AdvancedCommandHandler::new(),
+            stdin_format: None,
+        }
+    }
+
+    /// Read `-` inputs as `format` instead of CSV
+    pub fn with_stdin_format(self, format: &str) -> Self {
+        Self {
+            io: self.io.with_stdin_format(format),
+            transform: self.transform.with_stdin_format(format),
+            pandas: self.pandas.with_stdin_format(format),
+            advanced: self.advanced.with_stdin_format(format),
+            stdin_format: Some(format.to_string()),
+        }
+    }
+
+    /// Converter that reads `-` inputs in the `--input-format`
+    fn converter(&self) -> crate::converter::Converter {
+        match &self.stdin_format {
+            Some(format) => crate::converter::Converter::new().with_stdin_format(format),
+            None => crate::converter::Converter::new(),
         }
     }
 }
@@ -44,32 +68,167 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
     /// Handle a command by delegating to the appropriate specialized handler
     fn handle(&self, command: Commands) -> Result<()> {
         match command {
-            // I/O commands
-            Commands::Read {
-                input,
-                sheet,
-                range,
-                format,
-            } => self.io.handle_read(input, sheet, range, format),
+            // I/O and Google Sheets commands
+            command @ (Commands::Read(_)
+            | Commands::Write(_)
+            | Commands::Convert(_)
+            | Commands::ConvertDir(_)
+            | Commands::Formula(_)
+            | Commands::Serve
+            | Commands::ApiServer(_)
+            | Commands::Sheets { .. }
+            | Commands::ReadAll { .. }
+            | Commands::WriteRange { .. }
+            | Commands::Append { .. }
+            | Commands::GSheetsList { .. }
+            | Commands::GSheetsAuth
+            | Commands::GSheetsSetDefault { .. }) => self.handle_io(command),
 
-            Commands::Write { output, csv, sheet } => self.io.handle_write(output, csv, sheet),
+            // Transform commands
+            command @ (Commands::Sort(_)
+            | Commands::Filter { .. }
+            | Commands::Replace(_)
+            | Commands::Dedupe(_)
+            | Commands::Transpose { .. }
+            | Commands::Select { .. }
+            | Commands::Rename(_)
+            | Commands::Drop { .. }
+            | Commands::Fillna(_)
+            | Commands::Dropna { .. }
+            | Commands::Mutate(_)
+            | Commands::Query { .. }
+            | Commands::Astype(_)
+            | Commands::Clip(_)
+            | Commands::Normalize(_)
+            | Commands::ParseDate(_)
+            | Commands::RegexFilter(_)
+            | Commands::RegexReplace(_)) => self.handle_transform(command),
 
-            Commands::Convert {
-                input,
-                output,
-                sheet,
-            } => self.io.handle_convert(input, output, sheet),
+            // Pandas-style commands
+            command @ (Commands::Head(_)
+            | Commands::Tail(_)
+            | Commands::Sample(_)
+            | Commands::Peek { .. }
+            | Commands::Describe { .. }
+            | Commands::ColStats { .. }
+            | Commands::ValueCounts { .. }
+            | Commands::Corr { .. }
+            | Commands::Groupby(_)
+            | Commands::Join(_)
+            | Commands::Concat { .. }
+            | Commands::Unique { .. }
+            | Commands::Info { .. }
+            | Commands::Dtypes { .. }
+            | Commands::Schema { .. }
+            | Commands::Pivot(_)
+            | Commands::Melt(_)
+            | Commands::Rolling(_)
+            | Commands::Rank(_)
+            | Commands::Bin(_)) => self.handle_pandas(command),
 
-            Commands::Formula {
-                input,
-                output,
-                formula,
-                cell,
-                sheet,
-            } => self.io.handle_formula(input, output, formula, cell, sheet),
+            // Advanced commands
+            command @ (Commands::Profile { .. }
+            | Commands::DetectAnomalies(_)
+            | Commands::TextAnalysis(_)
+            | Commands::GeoBearing { .. }
+            | Commands::Pipeline { .. }
+            | Commands::Validate(_)
+            | Commands::Chart(_)
+            | Commands::Encrypt(_)
+            | Commands::Decrypt(_)
+            | Commands::Batch(_)
+            | Commands::Plugin(_)
+            | Commands::Stream(_)
+            | Commands::Completions { .. }
+            | Commands::ConfigInit
+            | Commands::ExportStyled { .. }) => self.handle_advanced(command),
+        }
+    }
+}
+
+// Import validation utility
+use crate::common::validation;
+
+impl DefaultCommandHandler {
+    /// Handle file I/O, server and Google Sheets commands
+    fn handle_io(&self, command: Commands) -> Result<()> {
+        match command {
+            Commands::Read(args) => {
+                let ReadArgs {
+                    input,
+                    sheet,
+                    range,
+                    format,
+                    delimiter,
+                    encoding,
+                } = *args;
+                self.io
+                    .handle_read(input, sheet, range, format, delimiter, encoding)
+            }
+
+            Commands::Write(args) => {
+                let WriteArgs {
+                    output,
+                    csv,
+                    sheet,
+                    delimiter,
+                    encoding,
+                    compression,
+                } = *args;
+                self.io
+                    .handle_write(output, csv, sheet, delimiter, encoding, compression)
+            }
+
+            Commands::Convert(args) => {
+                let ConvertArgs {
+                    input,
+                    output,
+                    sheet,
+                    delimiter,
+                    encoding,
+                    compression,
+                } = *args;
+                self.io
+                    .handle_convert(input, output, sheet, delimiter, encoding, compression)
+            }
+
+            Commands::ConvertDir(args) => {
+                let ConvertDirArgs {
+                    input,
+                    output,
+                    format,
+                    sheet,
+                    compression,
+                } = *args;
+                self.io
+                    .handle_convert_dir(input, output, format, sheet, compression)
+            }
+
+            Commands::Formula(args) => {
+                let FormulaArgs {
+                    input,
+                    output,
+                    formula,
+                    cell,
+                    sheet,
+                } = *args;
+                self.io.handle_formula(input, output, formula, cell, sheet)
+            }
 
             Commands::Serve => self.io.handle_serve(),
 
+            Commands::ApiServer(args) => {
+                let ApiServerArgs {
+                    host,
+                    port,
+                    cors_origins,
+                    root,
+                    allow_output,
+                } = *args;
+                self.io
+                    .handle_api_server(host, port, cors_origins, root, allow_output)
+            }
+
             Commands::Sheets { input } => self.io.handle_sheets(input),
 
             Commands::ReadAll { input, format } => self.io.handle_read_all(input, format),
@@ -82,13 +241,37 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
 
             Commands::Append { source, target } => self.io.handle_append(source, target),
 
-            // Transform commands
-            Commands::Sort {
-                input,
-                output,
-                column,
-                ascending,
-            } => self.transform.handle_sort(input, output, column, ascending),
+            Commands::GSheetsList { spreadsheet } => self.io.handle_gsheets_list(spreadsheet),
+            Commands::GSheetsAuth => self.io.handle_gsheets_auth(),
+            Commands::GSheetsSetDefault { spreadsheet } => {
+                self.io.handle_gsheets_set_default(spreadsheet)
+            }
+
+            _ => unreachable!("not an I/O command"),
+        }
+    }
+
+    /// Handle row and column transform commands
+    fn handle_transform(&self, command: Commands) -> Result<()> {
+        match command {
+            Commands::Sort(args) => {
+                let SortArgs {
+                    input,
+                    output,
+                    column,
+                    ascending,
+                    sort_as,
+                    date_format,
+                    natural,
+                } = *args;
+                let sort_as = if natural {
+                    Some("natural".to_string())
+                } else {
+                    sort_as
+                };
+                self.transform
+                    .handle_sort(input, output, column, ascending, sort_as, date_format)
+            }
 
             Commands::Filter {
                 input,
@@ -96,21 +279,37 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 where_clause,
             } => self.transform.handle_filter(input, output, where_clause),
 
-            Commands::Replace {
-                input,
-                output,
-                find,
-                replace,
-                column,
-            } => self
-                .transform
-                .handle_replace(input, output, find, replace, column),
+            Commands::Replace(args) => {
+                let ReplaceArgs {
+                    input,
+                    output,
+                    find,
+                    replace,
+                    column,
+                } = *args;
+                self.transform
+                    .handle_replace(input, output, find, replace, column)
+            }
 
-            Commands::Dedupe {
-                input,
-                output,
-                columns,
-            } => self.transform.handle_dedupe(input, output, columns),
+            Commands::Dedupe(args) => {
+                let DedupeArgs {
+                    input,
+                    output,
+                    columns,
+                    approximate,
+                    expected_rows,
+                    fp_rate,
+                } = *args;
+                let mode = if approximate {
+                    DedupeMode::Approximate {
+                        expected_rows,
+                        false_positive_rate: fp_rate,
+                    }
+                } else {
+                    DedupeMode::Exact
+                };
+                self.transform.handle_dedupe(input, output, columns, mode)
+            }
 
             Commands::Transpose { input, output } => self.transform.handle_transpose(input, output),
 
@@ -120,12 +319,15 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 columns,
             } => self.transform.handle_select(input, output, columns),
 
-            Commands::Rename {
-                input,
-                output,
-                from,
-                to,
-            } => self.transform.handle_rename(input, output, from, to),
+            Commands::Rename(args) => {
+                let RenameArgs {
+                    input,
+                    output,
+                    from,
+                    to,
+                } = *args;
+                self.transform.handle_rename(input, output, from, to)
+            }
 
             Commands::Drop {
                 input,
@@ -133,21 +335,29 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 columns,
             } => self.transform.handle_drop(input, output, columns),
 
-            Commands::Fillna {
-                input,
-                output,
-                value,
-                columns,
-            } => self.transform.handle_fillna(input, output, value, columns),
+            Commands::Fillna(args) => {
+                let FillnaArgs {
+                    input,
+                    output,
+                    value,
+                    columns,
+                    method,
+                } = *args;
+                self.transform
+                    .handle_fillna(input, output, value, columns, method)
+            }
 
             Commands::Dropna { input, output } => self.transform.handle_dropna(input, output),
 
-            Commands::Mutate {
-                input,
-                output,
-                column,
-                formula,
-            } => self.transform.handle_mutate(input, output, column, formula),
+            Commands::Mutate(args) => {
+                let MutateArgs {
+                    input,
+                    output,
+                    column,
+                    formula,
+                } = *args;
+                self.transform.handle_mutate(input, output, column, formula)
+            }
 
             Commands::Query {
                 input,
@@ -155,153 +365,26 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 where_clause,
             } => self.transform.handle_query(input, output, where_clause),
 
-            Commands::Astype {
-                input,
-                output,
-                column,
-                target_type,
-            } => self
-                .transform
-                .handle_astype(input, output, column, target_type),
-
-            // Pandas-style commands
-            Commands::Head { input, n, format } => self.pandas.handle_head(input, n, format),
-
-            Commands::Tail { input, n, format } => self.pandas.handle_tail(input, n, format),
-
-            Commands::Sample {
-                input,
-                n,
-                seed,
-                format,
-            } => self.pandas.handle_sample(input, n, seed, format),
-
-            Commands::Describe { input, format } => self.pandas.handle_describe(input, format),
-
-            Commands::ValueCounts { input, column } => {
-                self.pandas.handle_value_counts(input, column)
+            Commands::Astype(args) => {
+                let AstypeArgs {
+                    input,
+                    output,
+                    column,
+                    target_type,
+                } = *args;
+                self.transform
+                    .handle_astype(input, output, column, target_type)
             }
 
-            Commands::Corr { input, columns } => self.pandas.handle_corr(input, columns),
-
-            Commands::Groupby {
-                input,
-                output,
-                by,
-                agg,
-            } => self.pandas.handle_groupby(input, output, by, agg),
-
-            Commands::Join {
-                left,
-                right,
-                output,
-                on,
-                how,
-            } => self.pandas.handle_join(left, right, output, on, how),
-
-            Commands::Concat { inputs, output } => self.pandas.handle_concat(inputs, output),
-
-            Commands::Unique { input, column } => self.pandas.handle_unique(input, column),
-
-            Commands::Info { input } => self.pandas.handle_info(input),
-
-            Commands::Dtypes { input } => self.pandas.handle_dtypes(input),
-
-            Commands::Pivot {
-                input,
-                output,
-                index,
-                columns,
-                values,
-                agg,
-            } => self
-                .pandas
-                .handle_pivot(input, output, index, columns, values, agg),
-
-            // Advanced commands
-            Commands::Profile { input, output } => self.advanced.handle_profile(input, output),
-
-            Commands::Validate {
-                input,
-                rules,
-                output,
-                report,
-            } => self.advanced.handle_validate(input, rules, output, report),
-
-            Commands::Chart {
-                input,
-                output,
-                chart_type,
-                title,
-                x_column,
-                y_column,
-            } => self
-                .advanced
-                .handle_chart(input, output, chart_type, title, x_column, y_column),
-
-            Commands::Encrypt {
-                input,
-                output,
-                algorithm,
-                key_file,
-            } => self
-                .advanced
-                .handle_encrypt(input, output, algorithm, key_file),
-
-            Commands::Decrypt {
-                input,
-                output,
-                key_file,
-            } => self.advanced.handle_decrypt(input, output, key_file),
-
-            Commands::Batch {
-                inputs,
-                output_dir,
-                operation,
-                args,
-            } => self
-                .advanced
-                .handle_batch(inputs, output_dir, operation, args),
-
-            Commands::Plugin {
-                function,
-                input,
-                output,
-                args,
-            } => self.advanced.handle_plugin(function, input, output, args),
-
-            Commands::Stream {
-                input,
-                output,
-                chunk_size,
-            } => self.advanced.handle_stream(input, output, chunk_size),
-
-            Commands::Completions { shell } => self.advanced.handle_completions(shell),
-
-            Commands::ConfigInit => self.advanced.handle_config_init(),
-
-            Commands::ExportStyled {
-                input,
-                output,
-                style,
-            } => self.advanced.handle_export_styled(input, output, style),
-
-            // Google Sheets commands
-            Commands::GSheetsList { spreadsheet } => self.io.handle_gsheets_list(spreadsheet),
-            Commands::GSheetsAuth => self.io.handle_gsheets_auth(),
-            Commands::GSheetsSetDefault { spreadsheet } => {
-                self.io.handle_gsheets_set_default(spreadsheet)
-            }
-
-            // Additional transform commands that were in the original cli.rs
-            Commands::Clip {
-                input,
-                output,
-                column,
-                min,
-                max,
-            } => {
-                let converter = crate::converter::Converter::new();
+            Commands::Clip(args) => {
+                let ClipArgs {
+                    input,
+                    output,
+                    column,
+                    min,
+                    max,
+                } = *args;
+                let converter = self.converter();
                 let mut data = converter.read_any_data(&input, None)?;
 
                 let col_idx = Self::find_column_index(&data, &column)?;
@@ -315,60 +398,73 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                     .with_context(|| format!("Invalid max value: {}", max))?;
 
                 let ops = crate::operations::DataOperations::new();
-                let clipped = ops.clip(&mut data, col_idx, Some(min_val), Some(max_val))?;
+                let clipped = ops.clip_range(&mut data, col_idx, min_val, max_val)?;
 
-                converter.write_any_data(&output, &data, None)?;
-                println!("Clipped {} cells; wrote {}", clipped, output);
+                output.write(&converter, &data, None)?;
+                report!(output, "Clipped {} cells; wrote {}", clipped, output);
                 Ok(())
             }
 
-            Commands::Normalize {
-                input,
-                output,
-                column,
-            } => {
-                let converter = crate::converter::Converter::new();
+            Commands::Normalize(args) => {
+                let NormalizeArgs {
+                    input,
+                    output,
+                    column,
+                    method,
+                    append,
+                } = *args;
+                let converter = self.converter();
                 let mut data = converter.read_any_data(&input, None)?;
 
                 let col_idx = Self::find_column_index(&data, &column)?;
                 validation::validate_column_index(&data, col_idx)?;
 
                 let ops = crate::operations::DataOperations::new();
-                ops.normalize(&mut data, col_idx)?;
-
-                converter.write_any_data(&output, &data, None)?;
-                println!("Normalized column {}; wrote {}", column, output);
+                let method: crate::operations::NormalizeMethod = method.parse()?;
+                if append {
+                    if method != crate::operations::NormalizeMethod::ZScore {
+                        anyhow::bail!("--append is only supported with --method zscore");
+                    }
+                    ops.standardize(&mut data, col_idx, true)?;
+                } else {
+                    ops.normalize(&mut data, col_idx, method)?;
+                }
+
+                output.write(&converter, &data, None)?;
+                report!(output, "Normalized column {}; wrote {}", column, output);
                 Ok(())
             }
 
-            Commands::ParseDate {
-                input,
-                output,
-                column,
-                from_format,
-                to_format,
-            } => {
-                let converter = crate::converter::Converter::new();
-                let mut data = converter.read_any_data(&input, None)?;
+            Commands::ParseDate(args) => {
+                let ParseDateArgs {
+                    input,
+                    output,
+                    column,
+                    from_format,
+                    to_format,
+                } = *args;
+                let converter = self.converter();
+                let data = converter.read_any_data(&input, None)?;
 
                 let col_idx = Self::find_column_index(&data, &column)?;
                 validation::validate_column_index(&data, col_idx)?;
 
                 let ops = crate::operations::DataOperations::new();
-                let converted = ops.parse_date(&mut data, col_idx, &from_format, &to_format)?;
+                let data = ops.parse_date(&data, col_idx, &from_format, &to_format)?;
 
-                converter.write_any_data(&output, &data, None)?;
-                println!("Converted {} dates; wrote {}", converted, output);
+                output.write(&converter, &data, None)?;
+                report!(output, "Reformatted dates in {column}; wrote {output}");
                 Ok(())
             }
 
-            Commands::RegexFilter {
-                input,
-                output,
-                column,
-                pattern,
-            } => {
-                let converter = crate::converter::Converter::new();
+            Commands::RegexFilter(args) => {
+                let RegexFilterArgs {
+                    input,
+                    output,
+                    column,
+                    pattern,
+                } = *args;
+                let converter = self.converter();
                 let data = converter.read_any_data(&input, None)?;
 
                 let col_idx = Self::find_column_index(&data, &column)?;
@@ -377,8 +473,9 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 let ops = crate::operations::DataOperations::new();
                 let filtered = ops.regex_filter(&data, col_idx, &pattern)?;
 
-                converter.write_any_data(&output, &filtered, None)?;
-                println!(
+                output.write(&converter, &filtered, None)?;
+                report!(
+                    output,
                     "Filtered to {} rows; wrote {}",
                     filtered.len().saturating_sub(1),
                     output
@@ -386,14 +483,15 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 Ok(())
             }
 
-            Commands::RegexReplace {
-                input,
-                output,
-                column,
-                pattern,
-                replacement,
-            } => {
-                let converter = crate::converter::Converter::new();
+            Commands::RegexReplace(args) => {
+                let RegexReplaceArgs {
+                    input,
+                    output,
+                    column,
+                    pattern,
+                    replacement,
+                } = *args;
+                let converter = self.converter();
                 let mut data = converter.read_any_data(&input, None)?;
 
                 let col_idx = Self::find_column_index(&data, &column)?;
@@ -402,18 +500,324 @@ impl super::commands::CommandHandler for DefaultCommandHandler {
                 let ops = crate::operations::DataOperations::new();
                 let replaced = ops.regex_replace(&mut data, col_idx, &pattern, &replacement)?;
 
-                converter.write_any_data(&output, &data, None)?;
-                println!("Replaced {} cells; wrote {}", replaced, output);
+                output.write(&converter, &data, None)?;
+                report!(output, "Replaced {} cells; wrote {}", replaced, output);
                 Ok(())
             }
+
+            _ => unreachable!("not a transform command"),
         }
     }
-}
 
-// Import validation utility
-use crate::common::validation;
+    /// Handle pandas-style inspection and reshaping commands
+    fn handle_pandas(&self, command: Commands) -> Result<()> {
+        match command {
+            Commands::Head(args) => {
+                let HeadArgs {
+                    input,
+                    n,
+                    format,
+                    index,
+                    drop_index,
+                } = *args;
+                self.pandas.handle_head(input, n, format, index, drop_index)
+            }
+
+            Commands::Tail(args) => {
+                let TailArgs {
+                    input,
+                    n,
+                    format,
+                    index,
+                    drop_index,
+                } = *args;
+                self.pandas.handle_tail(input, n, format, index, drop_index)
+            }
+
+            Commands::Sample(args) => {
+                let SampleArgs {
+                    input,
+                    n,
+                    seed,
+                    format,
+                    index,
+                    drop_index,
+                } = *args;
+                self.pandas
+                    .handle_sample(input, n, seed, format, index, drop_index)
+            }
+
+            Commands::Peek { input, n } => self.pandas.handle_peek(input, n),
+
+            Commands::Describe { input, format } => self.pandas.handle_describe(input, format),
+
+            Commands::ColStats {
+                input,
+                column,
+                format,
+            } => self.pandas.handle_col_stats(input, column, format),
+
+            Commands::ValueCounts { input, column } => {
+                self.pandas.handle_value_counts(input, column)
+            }
+
+            Commands::Corr { input, columns } => self.pandas.handle_corr(input, columns),
+
+            Commands::Groupby(args) => {
+                let GroupbyArgs {
+                    input,
+                    output,
+                    by,
+                    agg,
+                    concat_sep,
+                } = *args;
+                self.pandas
+                    .handle_groupby(input, output, by, agg, concat_sep)
+            }
+
+            Commands::Join(args) => {
+                let JoinArgs {
+                    left,
+                    right,
+                    output,
+                    on,
+                    how,
+                    no_cross_limit,
+                } = *args;
+                self.pandas
+                    .handle_join(left, right, output, on, how, no_cross_limit)
+            }
+
+            Commands::Concat { inputs, output } => self.pandas.handle_concat(inputs, output),
+
+            Commands::Unique { input, column } => self.pandas.handle_unique(input, column),
+
+            Commands::Info { input } => self.pandas.handle_info(input),
+
+            Commands::Dtypes { input } => self.pandas.handle_dtypes(input),
+
+            Commands::Schema { input } => self.pandas.handle_schema(input),
+
+            Commands::Pivot(args) => {
+                let PivotArgs {
+                    input,
+                    output,
+                    index,
+                    columns,
+                    values,
+                    agg,
+                } = *args;
+                self.pandas
+                    .handle_pivot(input, output.into(), index, columns, values, agg)
+            }
+
+            Commands::Melt(args) => {
+                let MeltArgs {
+                    input,
+                    output,
+                    id_vars,
+                    value_vars,
+                } = *args;
+                self.pandas.handle_melt(input, output, id_vars, value_vars)
+            }
+
+            Commands::Rolling(args) => {
+                let RollingArgs {
+                    input,
+                    output,
+                    column,
+                    window,
+                    agg,
+                } = *args;
+                self.pandas
+                    .handle_rolling(input, output, column, window, agg)
+            }
+
+            Commands::Rank(args) => {
+                let RankArgs {
+                    input,
+                    output,
+                    column,
+                    method,
+                    descending,
+                } = *args;
+                self.pandas
+                    .handle_rank(input, output, column, method, descending)
+            }
+
+            Commands::Bin(args) => {
+                let BinArgs {
+                    input,
+                    output,
+                    column,
+                    bins,
+                    method,
+                } = *args;
+                self.pandas.handle_bin(input, output, column, bins, method)
+            }
+
+            _ => unreachable!("not a pandas-style command"),
+        }
+    }
+
+    /// Handle profiling, analysis, security and automation commands
+    fn handle_advanced(&self, command: Commands) -> Result<()> {
+        match command {
+            Commands::Profile { input, output } => self.advanced.handle_profile(input, output),
+
+            Commands::DetectAnomalies(args) => {
+                let DetectAnomaliesArgs {
+                    input,
+                    column,
+                    method,
+                    threshold,
+                    group,
+                    output,
+                } = *args;
+                self.advanced
+                    .handle_detect_anomalies(input, column, method, threshold, group, output)
+            }
+
+            Commands::TextAnalysis(args) => {
+                let TextAnalysisArgs {
+                    input,
+                    column,
+                    operation,
+                    top,
+                    stopwords,
+                    stem,
+                    ngram,
+                    output,
+                } = *args;
+                self.advanced.handle_text_analysis(
+                    input,
+                    column,
+                    operation,
+                    TextAnalysisOptions {
+                        top,
+                        stopwords,
+                        stem,
+                        ngram,
+                        output,
+                    },
+                )
+            }
+
+            Commands::GeoBearing { from, to } => self.advanced.handle_geo_bearing(from, to),
+
+            Commands::Pipeline { config } => self.advanced.handle_pipeline(config),
+
+            Commands::Validate(args) => {
+                let ValidateArgs {
+                    input,
+                    rules,
+                    output,
+                    report,
+                } = *args;
+                self.advanced.handle_validate(input, rules, output, report)
+            }
+
+            Commands::Chart(args) => {
+                let ChartArgs {
+                    input,
+                    output,
+                    chart_type,
+                    title,
+                    x_column,
+                    y_column,
+                } = *args;
+                self.advanced
+                    .handle_chart(input, output, chart_type, title, x_column, y_column)
+            }
+
+            Commands::Encrypt(args) => {
+                let EncryptArgs {
+                    input,
+                    output,
+                    algorithm,
+                    key_file,
+                    password,
+                    password_file,
+                } = *args;
+                self.advanced.handle_encrypt(
+                    input,
+                    output,
+                    algorithm,
+                    key_file,
+                    password,
+                    password_file,
+                )
+            }
+
+            Commands::Decrypt(args) => {
+                let DecryptArgs {
+                    input,
+                    output,
+                    algorithm,
+                    key_file,
+                    password,
+                    password_file,
+                } = *args;
+                self.advanced.handle_decrypt(
+                    input,
+                    output,
+                    algorithm,
+                    key_file,
+                    password,
+                    password_file,
+                )
+            }
+
+            Commands::Batch(args) => {
+                let BatchArgs {
+                    inputs,
+                    output_dir,
+                    operation,
+                    args,
+                    jobs,
+                } = *args;
+                self.advanced
+                    .handle_batch(inputs, output_dir, operation, args, jobs)
+            }
+
+            Commands::Plugin(args) => {
+                let PluginArgs {
+                    function,
+                    input,
+                    output,
+                    args,
+                    wasm,
+                    fuel,
+                } = *args;
+                self.advanced
+                    .handle_plugin(function, input, output.into(), args, wasm, fuel)
+            }
+
+            Commands::Stream(args) => {
+                let StreamArgs {
+                    input,
+                    output,
+                    chunk_size,
+                    checkpoint,
+                } = *args;
+                self.advanced
+                    .handle_stream(input, output, chunk_size, checkpoint)
+            }
+
+            Commands::Completions { shell } => self.advanced.handle_completions(shell),
+
+            Commands::ConfigInit => self.advanced.handle_config_init(),
+
+            Commands::ExportStyled {
+                input,
+                output,
+                style,
+            } => self.advanced.handle_export_styled(input, output, style),
+
+            _ => unreachable!("not an advanced command"),
+        }
+    }
 
-impl DefaultCommandHandler {
     /// Find column index by name (helper method)
     fn find_column_index(data: &[Vec<String>], column: &str) -> Result<usize> {
         if data.is_empty() {
@@ -5,13 +5,31 @@
 //! - `commands/transform`: Data transformation commands (sort, filter, etc.)
 //! - `commands/pandas`: Pandas-style operations (head, tail, join, etc.)
 //! - `commands/advanced`: Advanced features (validate, chart, batch, etc.)
+//! - `args`: Boxed arguments of the commands with many options
 
+/// Print a status line about writing `output`
+///
+/// When `output` is stdout the data itself goes there, so the status line
+/// goes to stderr instead and pipelines stay clean.
+macro_rules! report {
+    ($output:expr, $($arg:tt)*) => {
+        if $output == crate::converter::STDIO_PATH {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub mod args;
 pub mod commands;
 pub mod format;
 pub mod handler;
 
 use clap::{Parser, Subcommand};
 
+use args::*;
+
 pub use commands::CommandHandler;
 pub use format::OutputFormat;
 pub use handler::DefaultCommandHandler;
@@ -24,6 +42,10 @@ pub use handler::DefaultCommandHandler;
     version = env!("CARGO_PKG_VERSION")
 )]
 pub struct Cli {
+    /// Format of data piped to stdin when an input path is `-` (default: csv)
+    #[arg(long, global = true)]
+    pub input_format: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -31,110 +53,57 @@ pub struct Cli {
 /// CLI commands
 ///
 /// This enum represents all available commands in the datacell CLI.
-/// Each command variant includes its specific parameters.
+/// Each command variant includes its specific parameters; those with four or
+/// more box them in an [`args`] struct so that clap's generated parser does
+/// not need more stack than a default thread has.
 #[derive(Subcommand)]
 pub enum Commands {
     /// Read data from a file and display it
-    Read {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        sheet: Option<String>,
-        #[arg(short, long)]
-        range: Option<String>,
-        #[arg(short = 'f', long, default_value = "csv")]
-        format: OutputFormat,
-    },
+    Read(Box<ReadArgs>),
 
     /// Write data to a file
-    Write {
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        csv: Option<String>,
-        #[arg(short, long)]
-        sheet: Option<String>,
-    },
+    Write(Box<WriteArgs>),
 
     /// Convert between file formats
-    Convert {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        sheet: Option<String>,
-    },
+    Convert(Box<ConvertArgs>),
+
+    /// Convert every supported file in a directory to another format
+    ConvertDir(Box<ConvertDirArgs>),
 
     /// Apply formulas to a file
-    Formula {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        formula: String,
-        #[arg(short, long)]
-        cell: String,
-        #[arg(short, long)]
-        sheet: Option<String>,
-    },
+    Formula(Box<FormulaArgs>),
 
     /// Start MCP server
     Serve,
 
+    /// Start the REST API server (requires the "api" feature)
+    ApiServer(Box<ApiServerArgs>),
+
     /// Sort data by column
-    Sort {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short, long)]
-        ascending: bool,
-    },
+    Sort(Box<SortArgs>),
 
     /// Filter rows by condition
     Filter {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
         #[arg(short = 'w', long)]
         where_clause: String,
     },
 
     /// Find and replace values
-    Replace {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        find: String,
-        #[arg(short, long)]
-        replace: String,
-        #[arg(short, long)]
-        column: Option<String>,
-    },
+    Replace(Box<ReplaceArgs>),
 
     /// Remove duplicate rows
-    Dedupe {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        columns: Option<String>,
-    },
+    Dedupe(Box<DedupeArgs>),
 
     /// Transpose data (rows to columns)
     Transpose {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
     },
 
     /// Append data to existing file
@@ -163,8 +132,8 @@ pub enum Commands {
     WriteRange {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
         #[arg(short, long)]
         start: String,
     },
@@ -173,48 +142,43 @@ pub enum Commands {
     Select {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
         #[arg(short, long)]
         columns: String,
     },
 
     /// Show first N rows
-    Head {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short = 'n', long, default_value = "10")]
-        n: usize,
-        #[arg(short = 'f', long, default_value = "csv")]
-        format: OutputFormat,
-    },
+    Head(Box<HeadArgs>),
 
     /// Show last N rows
-    Tail {
+    Tail(Box<TailArgs>),
+
+    /// Sample random rows
+    Sample(Box<SampleArgs>),
+
+    /// Quick overview: column types, null rates, counts and the first rows
+    Peek {
         #[arg(short, long)]
         input: String,
-        #[arg(short = 'n', long, default_value = "10")]
+        #[arg(short = 'n', long, default_value = "5")]
         n: usize,
-        #[arg(short = 'f', long, default_value = "csv")]
-        format: OutputFormat,
     },
 
-    /// Sample random rows
-    Sample {
+    /// Show descriptive statistics
+    Describe {
         #[arg(short, long)]
         input: String,
-        #[arg(short = 'n', long, default_value = "10")]
-        n: usize,
-        #[arg(short, long)]
-        seed: Option<u64>,
         #[arg(short = 'f', long, default_value = "csv")]
         format: OutputFormat,
     },
 
-    /// Show descriptive statistics
-    Describe {
+    /// Show detailed statistics for a single column
+    ColStats {
         #[arg(short, long)]
         input: String,
+        #[arg(short, long)]
+        column: String,
         #[arg(short = 'f', long, default_value = "csv")]
         format: OutputFormat,
     },
@@ -236,91 +200,44 @@ pub enum Commands {
     },
 
     /// Group by column with aggregation
-    Groupby {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        by: String,
-        #[arg(short, long)]
-        agg: String,
-    },
+    Groupby(Box<GroupbyArgs>),
 
     /// Join/merge two files
-    Join {
-        #[arg(short, long)]
-        left: String,
-        #[arg(short, long)]
-        right: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        on: String,
-        #[arg(short, long)]
-        how: String,
-    },
+    Join(Box<JoinArgs>),
 
     /// Concatenate multiple files
     Concat {
         #[arg(short, long)]
         inputs: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
     },
 
     /// Add computed column
-    Mutate {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short, long)]
-        formula: String,
-    },
+    Mutate(Box<MutateArgs>),
 
     /// Rename columns
-    Rename {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        from: String,
-        #[arg(short, long)]
-        to: String,
-    },
+    Rename(Box<RenameArgs>),
 
     /// Drop columns
     Drop {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
         #[arg(short, long)]
         columns: String,
     },
 
     /// Fill missing values
-    Fillna {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        value: String,
-        #[arg(short, long)]
-        columns: Option<String>,
-    },
+    Fillna(Box<FillnaArgs>),
 
     /// Drop rows with missing values
     Dropna {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
     },
 
     /// Show column data types
@@ -329,18 +246,15 @@ pub enum Commands {
         input: String,
     },
 
-    /// Cast column types
-    Astype {
+    /// Show the column types stored in a Parquet or Avro file
+    Schema {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short = 't', long)]
-        target_type: String,
     },
 
+    /// Cast column types
+    Astype(Box<AstypeArgs>),
+
     /// Get unique values
     Unique {
         #[arg(short, long)]
@@ -356,94 +270,44 @@ pub enum Commands {
     },
 
     /// Clip values to range
-    Clip {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short, long)]
-        min: String,
-        #[arg(short, long)]
-        max: String,
-    },
+    Clip(Box<ClipArgs>),
 
-    /// Normalize column (0-1)
-    Normalize {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-    },
+    /// Normalize column (0-1, or z-scores with --method zscore)
+    Normalize(Box<NormalizeArgs>),
 
     /// Query with SQL-like syntax
     Query {
         #[arg(short, long)]
         input: String,
-        #[arg(short, long)]
-        output: String,
+        #[command(flatten)]
+        output: OutputArgs,
         #[arg(short = 'w', long)]
         where_clause: String,
     },
 
     /// Create pivot table
-    Pivot {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        index: String,
-        #[arg(short, long)]
-        columns: String,
-        #[arg(short, long)]
-        values: String,
-        #[arg(short, long)]
-        agg: String,
-    },
+    Pivot(Box<PivotArgs>),
+
+    /// Melt (unpivot) wide columns into variable/value rows
+    Melt(Box<MeltArgs>),
+
+    /// Append a rolling-window aggregate of a column
+    Rolling(Box<RollingArgs>),
+
+    /// Append a rank column ordered by a numeric column
+    Rank(Box<RankArgs>),
+
+    /// Append a column bucketing a numeric column into bins
+    Bin(Box<BinArgs>),
 
     /// Parse and convert dates
-    ParseDate {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short, long)]
-        from_format: String,
-        #[arg(short, long)]
-        to_format: String,
-    },
+    ParseDate(Box<ParseDateArgs>),
 
     /// Filter by regex pattern
-    RegexFilter {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short, long)]
-        pattern: String,
-    },
+    RegexFilter(Box<RegexFilterArgs>),
 
     /// Replace by regex pattern
-    RegexReplace {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        column: String,
-        #[arg(short, long)]
-        pattern: String,
-        #[arg(short, long)]
-        replacement: String,
-    },
+    RegexReplace(Box<RegexReplaceArgs>),
 
     /// Profile data quality
     Profile {
@@ -453,89 +317,49 @@ pub enum Commands {
         output: Option<String>,
     },
 
-    /// Validate data with rules
-    Validate {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        rules: String,
-        #[arg(short, long)]
-        output: Option<String>,
-        #[arg(short, long)]
-        report: Option<String>,
+    /// Detect anomalies in a numeric column
+    DetectAnomalies(Box<DetectAnomaliesArgs>),
+
+    /// Analyze the text of a column
+    TextAnalysis(Box<TextAnalysisArgs>),
+
+    /// Show the initial bearing and midpoint between two coordinates
+    GeoBearing {
+        /// Start point as "lat,lon"
+        #[arg(long, allow_hyphen_values = true)]
+        from: String,
+        /// End point as "lat,lon"
+        #[arg(long, allow_hyphen_values = true)]
+        to: String,
     },
 
-    /// Create chart from data
-    Chart {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
+    /// Run a pipeline of read, filter, sort, groupby, join and write steps
+    Pipeline {
+        /// Pipeline file in YAML, JSON or TOML
         #[arg(short, long)]
-        chart_type: String,
-        #[arg(short, long)]
-        title: Option<String>,
-        #[arg(short, long)]
-        x_column: Option<String>,
-        #[arg(short, long)]
-        y_column: Option<String>,
+        config: String,
     },
 
+    /// Validate data with rules
+    Validate(Box<ValidateArgs>),
+
+    /// Create chart from data
+    Chart(Box<ChartArgs>),
+
     /// Encrypt file
-    Encrypt {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        algorithm: String,
-        #[arg(short, long)]
-        key_file: Option<String>,
-    },
+    Encrypt(Box<EncryptArgs>),
 
     /// Decrypt file
-    Decrypt {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        key_file: Option<String>,
-    },
+    Decrypt(Box<DecryptArgs>),
 
     /// Batch process multiple files
-    Batch {
-        #[arg(short, long)]
-        inputs: String,
-        #[arg(short, long)]
-        output_dir: String,
-        #[arg(short, long)]
-        operation: String,
-        #[arg(short, long)]
-        args: Vec<String>,
-    },
+    Batch(Box<BatchArgs>),
 
     /// Run plugin function
-    Plugin {
-        #[arg(short, long)]
-        function: String,
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(short, long)]
-        args: Vec<String>,
-    },
+    Plugin(Box<PluginArgs>),
 
     /// Stream process large file
-    Stream {
-        #[arg(short, long)]
-        input: String,
-        #[arg(short, long)]
-        output: String,
-        #[arg(long, default_value_t = 1000)]
-        chunk_size: usize,
-    },
+    Stream(Box<StreamArgs>),
 
     /// Generate shell completions
     Completions {
@@ -1,14 +1,19 @@
 //! Avro file handling
 
 use anyhow::{Context, Result};
+use std::collections::HashSet;
 use std::fs::File;
 
 use apache_avro::{
-    types::Value as AvroValue, Reader as AvroReader, Schema as AvroSchema, Writer as AvroWriter,
+    schema::SchemaKind, types::Value as AvroValue, Reader as AvroReader, Schema as AvroSchema,
+    Writer as AvroWriter,
 };
 
 use crate::csv_handler::CellRange;
-use crate::helpers::{default_column_names, filter_by_range, max_column_count};
+use crate::helpers::{
+    ColumnType, default_column_names, filter_by_range, infer_column_type, max_column_count,
+    parse_bool,
+};
 use crate::traits::{DataReader, DataWriteOptions, DataWriter, FileHandler, SchemaProvider};
 
 /// Handler for Avro files
@@ -68,7 +73,10 @@ impl AvroHandler {
         Ok(all_rows)
     }
 
-    /// Write data to Avro file (all fields as strings)
+    /// Write data to Avro file
+    ///
+    /// Fields whose values are all integers, floats or booleans are written as
+    /// `long`, `double` or `boolean`; other fields are written as strings.
     pub fn write(
         &self,
         path: &str,
@@ -82,25 +90,38 @@ impl AvroHandler {
         let num_cols = max_column_count(data);
 
         // Generate field names if not provided
-        let names: Vec<String> = field_names
+        let headers: Vec<String> = field_names
             .map(|n| n.to_vec())
             .unwrap_or_else(|| default_column_names(num_cols, "field"));
+        let names = avro_field_names(&headers);
+
+        let types: Vec<ColumnType> = (0..names.len())
+            .map(|col| infer_column_type(data, col))
+            .collect();
+
+        // Build Avro schema; every field is a nullable union, and a header
+        // that had to be renamed is kept as the field's alias
+        let fields: Vec<serde_json::Value> = names
+            .iter()
+            .zip(&headers)
+            .zip(&types)
+            .map(|((name, header), t)| {
+                let avro_type = match t {
+                    ColumnType::Int64 => "long",
+                    ColumnType::Float64 => "double",
+                    ColumnType::Boolean => "boolean",
+                    ColumnType::Utf8 => "string",
+                };
+                let mut field = serde_json::json!({ "name": name, "type": ["null", avro_type] });
+                if name != header {
+                    field["aliases"] = serde_json::json!([header]);
+                }
+                field
+            })
+            .collect();
+        let schema_json = serde_json::json!({ "type": "record", "name": "Row", "fields": fields });
 
-        // Build Avro schema
-        let schema_json = format!(
-            r#"{{
-                "type": "record",
-                "name": "Row",
-                "fields": [{}]
-            }}"#,
-            names
-                .iter()
-                .map(|n| format!(r#"{{"name": "{}", "type": ["null", "string"]}}"#, n))
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
-
-        let schema = AvroSchema::parse_str(&schema_json)?;
+        let schema = AvroSchema::parse_str(&schema_json.to_string())?;
 
         let file =
             File::create(path).with_context(|| format!("Failed to create Avro file: {path}"))?;
@@ -113,7 +134,8 @@ impl AvroHandler {
                 for (i, name) in names.iter().enumerate() {
                     let value = row
                         .get(i)
-                        .map(|s| AvroValue::Union(1, Box::new(AvroValue::String(s.clone()))))
+                        .and_then(|s| Self::typed_value(s, types[i]))
+                        .map(|v| AvroValue::Union(1, Box::new(v)))
                         .unwrap_or(AvroValue::Union(0, Box::new(AvroValue::Null)));
                     record.push((name.clone(), value));
                 }
@@ -128,8 +150,11 @@ impl AvroHandler {
         Ok(())
     }
 
-    /// Get schema information from Avro file
-    pub fn get_schema(&self, path: &str) -> Result<Vec<(String, String)>> {
+    /// Field names and Avro types, read from the file header only
+    ///
+    /// Nullable unions such as `["null", "long"]` are reported as the
+    /// non-null type.
+    pub fn schema(&self, path: &str) -> Result<Vec<(String, String)>> {
         let file = File::open(path).with_context(|| format!("Failed to open Avro file: {path}"))?;
 
         let reader = AvroReader::new(file)?;
@@ -138,7 +163,7 @@ impl AvroHandler {
             record
                 .fields
                 .iter()
-                .map(|f| (f.name.clone(), format!("{:?}", f.schema)))
+                .map(|f| (f.name.clone(), Self::type_name(&f.schema)))
                 .collect()
         } else {
             Vec::new()
@@ -147,6 +172,44 @@ impl AvroHandler {
         Ok(fields)
     }
 
+    /// Get schema information from Avro file
+    pub fn get_schema(&self, path: &str) -> Result<Vec<(String, String)>> {
+        self.schema(path)
+    }
+
+    fn type_name(schema: &AvroSchema) -> String {
+        match schema {
+            AvroSchema::Union(union) => {
+                let variants: Vec<String> = union
+                    .variants()
+                    .iter()
+                    .filter(|v| !matches!(v, AvroSchema::Null))
+                    .map(Self::type_name)
+                    .collect();
+                if variants.is_empty() {
+                    "null".to_string()
+                } else {
+                    variants.join(" | ")
+                }
+            }
+            AvroSchema::Record(record) => record.name.name.clone(),
+            other => format!("{:?}", SchemaKind::from(other)).to_lowercase(),
+        }
+    }
+
+    /// Convert a cell to the Avro value for its column type
+    ///
+    /// Empty cells in typed columns are `None` and written as null.
+    fn typed_value(cell: &str, column_type: ColumnType) -> Option<AvroValue> {
+        let trimmed = cell.trim();
+        match column_type {
+            ColumnType::Utf8 => Some(AvroValue::String(cell.to_string())),
+            ColumnType::Int64 => trimmed.parse().ok().map(AvroValue::Long),
+            ColumnType::Float64 => trimmed.parse().ok().map(AvroValue::Double),
+            ColumnType::Boolean => parse_bool(trimmed).map(AvroValue::Boolean),
+        }
+    }
+
     fn avro_value_to_string(&self, value: &AvroValue) -> String {
         match value {
             AvroValue::Null => String::new(),
@@ -253,3 +316,31 @@ impl SchemaProvider for AvroHandler {
         Ok(data.first().map(|r| r.len()).unwrap_or(0))
     }
 }
+
+/// Avro field names for `headers`
+///
+/// Avro names must match `[A-Za-z_][A-Za-z0-9_]*`, so other characters become
+/// `_`, a leading digit gets a `_` prefix, and names that collide get a
+/// `_2`, `_3`, ... suffix.
+fn avro_field_names(headers: &[String]) -> Vec<String> {
+    let mut used = HashSet::new();
+    headers
+        .iter()
+        .map(|header| {
+            let mut base: String = header
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                base.insert(0, '_');
+            }
+            let mut name = base.clone();
+            let mut n = 2;
+            while !used.insert(name.clone()) {
+                name = format!("{base}_{n}");
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
@@ -3,8 +3,9 @@
 mod avro;
 mod parquet;
 
+pub use ::parquet::basic::Compression as ParquetCompression;
 pub use avro::AvroHandler;
-pub use parquet::ParquetHandler;
+pub use parquet::{ParquetHandler, ParquetStreamWriter, ParquetWriteOptions};
 
 #[cfg(test)]
 mod tests {
@@ -35,6 +36,117 @@ mod tests {
         fs::remove_file(path).ok();
     }
 
+    #[test]
+    fn test_parquet_read_chunked_multiple_row_groups() {
+        use ::parquet::arrow::ArrowWriter;
+        use ::parquet::file::properties::WriterProperties;
+        use arrow_array::{ArrayRef, RecordBatch, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+        use std::sync::Arc;
+
+        let path = "/tmp/test_datacell_chunked.parquet";
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, true)]));
+        let ids: Vec<String> = (0..25).map(|i| i.to_string()).collect();
+        let column: ArrayRef = Arc::new(StringArray::from(ids.clone()));
+        let batch = RecordBatch::try_new(schema.clone(), vec![column]).unwrap();
+
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(fs::File::create(path).unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut chunk_sizes = Vec::new();
+        let mut seen = Vec::new();
+        let total = ParquetHandler::new()
+            .read_chunked(path, 4, |chunk| {
+                chunk_sizes.push(chunk.len());
+                seen.extend(chunk.iter().map(|row| row[0].clone()));
+                Ok(())
+            })
+            .unwrap();
+
+        // Three row groups (10, 10, 5) split into chunks of at most 4 rows
+        assert_eq!(chunk_sizes, vec![4, 4, 2, 4, 4, 2, 4, 1]);
+        assert_eq!(total, 25);
+        assert_eq!(seen, ids);
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_parquet_read_columns_projection() {
+        let handler = ParquetHandler::new();
+        let path = "/tmp/test_datacell_projection.parquet";
+        let names: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+        let data: Vec<Vec<String>> = [["1", "2", "3", "4"], ["5", "6", "7", "8"]]
+            .iter()
+            .map(|row| row.iter().map(|s| s.to_string()).collect())
+            .collect();
+        handler.write(path, &data, Some(&names)).unwrap();
+
+        // Requested order wins over file order
+        let projected = handler.read_columns(path, &["d", "b"]).unwrap();
+        assert_eq!(projected.len(), 3);
+        assert!(projected.iter().all(|row| row.len() == 2));
+        assert_eq!(projected[0], vec!["d", "b"]);
+        assert_eq!(projected[1], vec!["4", "2"]);
+        assert_eq!(projected[2], vec!["8", "6"]);
+
+        let err = handler.read_columns(path, &["a", "missing"]).unwrap_err();
+        assert!(err.to_string().contains("'missing'"));
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_parquet_write_compression_codecs() {
+        use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let codec_of = |path: &str| {
+            let reader = SerializedFileReader::new(fs::File::open(path).unwrap()).unwrap();
+            reader.metadata().row_group(0).column(0).compression()
+        };
+
+        let handler = ParquetHandler::new();
+        let names = vec!["name".to_string(), "value".to_string()];
+        let data: Vec<Vec<String>> = (0..100)
+            .map(|i| vec![format!("row{i}"), (i * 3).to_string()])
+            .collect();
+
+        let mut read_back = Vec::new();
+        for (codec, path) in [
+            ("zstd", "/tmp/test_datacell_zstd.parquet"),
+            ("none", "/tmp/test_datacell_uncompressed.parquet"),
+        ] {
+            let options = ParquetWriteOptions {
+                compression: ParquetWriteOptions::parse_compression(codec).unwrap(),
+                ..Default::default()
+            };
+            handler
+                .write_with_options(path, &data, Some(&names), &options)
+                .unwrap();
+
+            assert_eq!(codec_of(path), options.compression);
+            read_back.push(handler.read(path).unwrap());
+            fs::remove_file(path).ok();
+        }
+
+        assert_eq!(read_back[0], data);
+        assert_eq!(read_back[0], read_back[1]);
+
+        // Plain `write` defaults to Snappy
+        let path = "/tmp/test_datacell_snappy.parquet";
+        handler.write(path, &data, Some(&names)).unwrap();
+        assert_eq!(codec_of(path), ParquetCompression::SNAPPY);
+        fs::remove_file(path).ok();
+
+        assert!(ParquetWriteOptions::parse_compression("zstd(9)").is_ok());
+        assert!(ParquetWriteOptions::parse_compression("bogus").is_err());
+    }
+
     #[test]
     fn test_avro_write_read() {
         let handler = AvroHandler::new();
@@ -4,16 +4,63 @@ use anyhow::{Context, Result};
 use std::fs::File;
 use std::sync::Arc;
 
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type};
 use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray};
-use arrow_schema::{DataType, Field, Schema};
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use parquet::arrow::ArrowWriter;
-use parquet::file::properties::WriterProperties;
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
+use parquet::file::properties::{DEFAULT_MAX_ROW_GROUP_SIZE, WriterProperties};
 
 use crate::csv_handler::CellRange;
-use crate::helpers::{default_column_names, filter_by_range, max_column_count};
+use crate::helpers::{
+    ColumnType, default_column_names, filter_by_range, infer_column_type, max_column_count,
+    parse_bool,
+};
 use crate::traits::{DataReader, DataWriteOptions, DataWriter, FileHandler, SchemaProvider};
 
+/// Settings for writing Parquet files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParquetWriteOptions {
+    /// Codec applied to every column chunk
+    pub compression: Compression,
+    /// Maximum number of rows per row group
+    pub row_group_size: usize,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::SNAPPY,
+            row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    /// Parse a codec name such as `snappy`, `zstd`, `gzip` or `none`
+    ///
+    /// Codecs with levels use their default level unless one is given in
+    /// parentheses, e.g. `zstd(9)`.
+    pub fn parse_compression(value: &str) -> Result<Compression> {
+        let compression = match value.trim().to_lowercase().as_str() {
+            "none" | "uncompressed" => Compression::UNCOMPRESSED,
+            "snappy" => Compression::SNAPPY,
+            "gzip" => Compression::GZIP(GzipLevel::default()),
+            "zstd" => Compression::ZSTD(ZstdLevel::default()),
+            "brotli" => Compression::BROTLI(BrotliLevel::default()),
+            "lz4" => Compression::LZ4_RAW,
+            other => other
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Unknown Parquet compression '{value}'"))?,
+        };
+        Ok(compression)
+    }
+}
+
 /// Handler for Parquet files
 #[derive(Default)]
 pub struct ParquetHandler;
@@ -34,19 +81,7 @@ impl ParquetHandler {
         let mut all_rows: Vec<Vec<String>> = Vec::new();
 
         for batch_result in reader {
-            let batch = batch_result?;
-            let num_rows = batch.num_rows();
-            let num_cols = batch.num_columns();
-
-            for row_idx in 0..num_rows {
-                let mut row: Vec<String> = Vec::with_capacity(num_cols);
-                for col_idx in 0..num_cols {
-                    let col = batch.column(col_idx);
-                    let value = self.array_value_to_string(col, row_idx);
-                    row.push(value);
-                }
-                all_rows.push(row);
-            }
+            self.append_batch_rows(&batch_result?, &mut all_rows);
         }
 
         Ok(all_rows)
@@ -67,31 +102,178 @@ impl ParquetHandler {
         let headers: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
         all_rows.push(headers);
 
+        for batch_result in reader {
+            self.append_batch_rows(&batch_result?, &mut all_rows);
+        }
+
+        Ok(all_rows)
+    }
+
+    /// Read the header plus the first `n` data rows.
+    ///
+    /// Decoding stops as soon as `n` rows have been produced, so only the
+    /// leading row groups of a large file are touched.
+    pub fn read_head(&self, path: &str, n: usize) -> Result<Vec<Vec<String>>> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let schema = builder.schema().clone();
+        let reader = builder.with_limit(n).build()?;
+
+        let mut rows: Vec<Vec<String>> = Vec::with_capacity(n.min(1024) + 1);
+        rows.push(schema.fields().iter().map(|f| f.name().clone()).collect());
+
+        for batch_result in reader {
+            self.append_batch_rows(&batch_result?, &mut rows);
+        }
+
+        Ok(rows)
+    }
+
+    /// Read the header plus the last `n` data rows.
+    ///
+    /// Row counts from the footer metadata are used to select only the
+    /// trailing row group(s) that contain those rows; earlier row groups
+    /// are never decoded.
+    pub fn read_tail(&self, path: &str, n: usize) -> Result<Vec<Vec<String>>> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let schema = builder.schema().clone();
+
+        // Walk row groups from the end until they cover `n` rows
+        let mut selected: Vec<usize> = Vec::new();
+        let mut covered = 0usize;
+        for (idx, row_group) in builder.metadata().row_groups().iter().enumerate().rev() {
+            if covered >= n {
+                break;
+            }
+            covered += row_group.num_rows().max(0) as usize;
+            selected.push(idx);
+        }
+        selected.reverse();
+
+        let reader = builder
+            .with_row_groups(selected)
+            .with_offset(covered.saturating_sub(n))
+            .build()?;
+
+        let mut rows: Vec<Vec<String>> = Vec::with_capacity(n.min(1024) + 1);
+        rows.push(schema.fields().iter().map(|f| f.name().clone()).collect());
+
+        for batch_result in reader {
+            self.append_batch_rows(&batch_result?, &mut rows);
+        }
+
+        Ok(rows)
+    }
+
+    /// Read only the named columns, with column names as the first row
+    ///
+    /// Uses a projection mask so the other columns are never decoded.
+    /// Columns come back in the order of `names`, which may differ from the
+    /// file order.
+    pub fn read_columns(&self, path: &str, names: &[&str]) -> Result<Vec<Vec<String>>> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let schema = builder.schema().clone();
+
+        let mut indices = Vec::with_capacity(names.len());
+        for name in names {
+            let idx = schema
+                .index_of(name)
+                .map_err(|_| anyhow::anyhow!("Column '{name}' not found in {path}"))?;
+            indices.push(idx);
+        }
+
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices.iter().copied());
+        let reader = builder.with_projection(mask).build()?;
+
+        let mut rows: Vec<Vec<String>> = vec![names.iter().map(|n| n.to_string()).collect()];
         for batch_result in reader {
             let batch = batch_result?;
-            let num_rows = batch.num_rows();
-            let num_cols = batch.num_columns();
-
-            for row_idx in 0..num_rows {
-                let mut row: Vec<String> = Vec::with_capacity(num_cols);
-                for col_idx in 0..num_cols {
-                    let col = batch.column(col_idx);
-                    let value = self.array_value_to_string(col, row_idx);
-                    row.push(value);
+            // The projected batch keeps file order; map back to the requested order
+            let columns: Vec<usize> = names
+                .iter()
+                .map(|name| batch.schema().index_of(name))
+                .collect::<std::result::Result<_, _>>()?;
+
+            for row_idx in 0..batch.num_rows() {
+                rows.push(
+                    columns
+                        .iter()
+                        .map(|&col| self.array_value_to_string(batch.column(col), row_idx))
+                        .collect(),
+                );
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Read a Parquet file chunk by chunk, without loading it all into memory
+    ///
+    /// Row groups are decoded one at a time and each is split into chunks of
+    /// at most `chunk_size` data rows, which are passed to `callback` in file
+    /// order. The header is not included; use `get_schema` for column names.
+    /// Returns the total number of rows read.
+    pub fn read_chunked<F>(&self, path: &str, chunk_size: usize, mut callback: F) -> Result<usize>
+    where
+        F: FnMut(&[Vec<String>]) -> Result<()>,
+    {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+        let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::default())?;
+        let num_row_groups = metadata.metadata().num_row_groups();
+
+        let mut total = 0;
+        let mut chunk: Vec<Vec<String>> = Vec::with_capacity(chunk_size.min(8192));
+        for row_group in 0..num_row_groups {
+            let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(
+                file.try_clone()?,
+                metadata.clone(),
+            )
+            .with_row_groups(vec![row_group])
+            .with_batch_size(chunk_size.max(1))
+            .build()?;
+
+            for batch_result in reader {
+                chunk.clear();
+                self.append_batch_rows(&batch_result?, &mut chunk);
+                if !chunk.is_empty() {
+                    total += chunk.len();
+                    callback(&chunk)?;
                 }
-                all_rows.push(row);
             }
         }
 
-        Ok(all_rows)
+        Ok(total)
     }
 
-    /// Write data to Parquet file (all columns as strings)
+    /// Write data to Parquet file (Snappy-compressed)
+    ///
+    /// Columns whose values are all integers, floats or booleans are stored
+    /// as Int64, Float64 or Boolean; other columns are stored as strings.
     pub fn write(
         &self,
         path: &str,
         data: &[Vec<String>],
         column_names: Option<&[String]>,
+    ) -> Result<()> {
+        self.write_with_options(path, data, column_names, &ParquetWriteOptions::default())
+    }
+
+    /// Write data to Parquet file with an explicit codec and row group size
+    pub fn write_with_options(
+        &self,
+        path: &str,
+        data: &[Vec<String>],
+        column_names: Option<&[String]>,
+        options: &ParquetWriteOptions,
     ) -> Result<()> {
         if data.is_empty() {
             anyhow::bail!("Cannot write empty data to Parquet");
@@ -104,30 +286,25 @@ impl ParquetHandler {
             .map(|names| names.to_vec())
             .unwrap_or_else(|| default_column_names(num_cols, "col"));
 
-        // Create schema with string columns
-        let fields: Vec<Field> = col_names
-            .iter()
-            .map(|name| Field::new(name, DataType::Utf8, true))
-            .collect();
-        let schema = Arc::new(Schema::new(fields));
-
-        // Build column arrays
+        // Numeric and boolean columns get typed storage; everything else is Utf8
+        let mut fields: Vec<Field> = Vec::with_capacity(num_cols);
         let mut columns: Vec<ArrayRef> = Vec::with_capacity(num_cols);
-        for col_idx in 0..num_cols {
-            let values: Vec<Option<&str>> = data
-                .iter()
-                .map(|row| row.get(col_idx).map(|s| s.as_str()))
-                .collect();
-            let array = StringArray::from(values);
-            columns.push(Arc::new(array));
+        for (col_idx, name) in col_names.iter().enumerate().take(num_cols) {
+            let (data_type, array) = Self::build_column(data, col_idx)?;
+            fields.push(Field::new(name, data_type, true));
+            columns.push(array);
         }
+        let schema = Arc::new(Schema::new(fields));
 
         let batch = RecordBatch::try_new(schema.clone(), columns)?;
 
         let file =
             File::create(path).with_context(|| format!("Failed to create Parquet file: {path}"))?;
 
-        let props = WriterProperties::builder().build();
+        let props = WriterProperties::builder()
+            .set_compression(options.compression)
+            .set_max_row_group_size(options.row_group_size.max(1))
+            .build();
         let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
         writer.write(&batch)?;
         writer.close()?;
@@ -135,21 +312,153 @@ impl ParquetHandler {
         Ok(())
     }
 
-    /// Get schema information from Parquet file
-    pub fn get_schema(&self, path: &str) -> Result<Vec<(String, String)>> {
+    /// Open a Parquet file for writing rows in chunks (Snappy-compressed)
+    ///
+    /// See [`ParquetStreamWriter`]; `schema` is usually built with
+    /// [`ParquetHandler::infer_schema`] from the first chunk.
+    pub fn open_writer(&self, path: &str, schema: SchemaRef) -> Result<ParquetStreamWriter> {
+        self.open_writer_with_options(path, schema, &ParquetWriteOptions::default())
+    }
+
+    /// Open a Parquet file for chunked writing with an explicit codec
+    pub fn open_writer_with_options(
+        &self,
+        path: &str,
+        schema: SchemaRef,
+        options: &ParquetWriteOptions,
+    ) -> Result<ParquetStreamWriter> {
         let file =
-            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+            File::create(path).with_context(|| format!("Failed to create Parquet file: {path}"))?;
 
-        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
-        let schema = builder.schema();
+        let props = WriterProperties::builder()
+            .set_compression(options.compression)
+            .set_max_row_group_size(options.row_group_size.max(1))
+            .build();
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(props))?;
+
+        Ok(ParquetStreamWriter {
+            writer,
+            schema,
+            rows_written: 0,
+        })
+    }
 
-        let fields: Vec<(String, String)> = schema
-            .fields()
+    /// Build a schema naming each column, typed as `write` would type `sample`
+    pub fn infer_schema(&self, column_names: &[String], sample: &[Vec<String>]) -> SchemaRef {
+        let types: Vec<ColumnType> = (0..column_names.len())
+            .map(|col_idx| infer_column_type(sample, col_idx))
+            .collect();
+        self.schema_for_types(column_names, &types)
+    }
+
+    /// Build a schema naming each column with the matching entry of `types`
+    pub fn schema_for_types(&self, column_names: &[String], types: &[ColumnType]) -> SchemaRef {
+        let fields: Vec<Field> = column_names
             .iter()
-            .map(|f| (f.name().clone(), format!("{:?}", f.data_type())))
+            .zip(types)
+            .map(|(name, column_type)| Field::new(name, arrow_type(*column_type), true))
             .collect();
+        Arc::new(Schema::new(fields))
+    }
 
-        Ok(fields)
+    /// Column names and Arrow types, read from the file footer only
+    ///
+    /// No row data is decoded, so this is cheap even for large files.
+    pub fn schema(&self, path: &str) -> Result<Vec<(String, String)>> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+        let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::default())?;
+
+        Ok(metadata
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| (f.name().clone(), f.data_type().to_string()))
+            .collect())
+    }
+
+    /// Number of data rows, read from the file footer only
+    pub fn row_count(&self, path: &str) -> Result<usize> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open Parquet file: {path}"))?;
+        let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::default())?;
+
+        Ok(metadata.metadata().file_metadata().num_rows().max(0) as usize)
+    }
+
+    /// Get schema information from Parquet file
+    pub fn get_schema(&self, path: &str) -> Result<Vec<(String, String)>> {
+        self.schema(path)
+    }
+
+    /// Build an Arrow array of `data_type` for one column
+    ///
+    /// Empty cells in typed columns become nulls; a non-empty cell that does
+    /// not parse as `data_type` is an error.
+    fn typed_column(
+        data: &[Vec<String>],
+        col_idx: usize,
+        data_type: &DataType,
+    ) -> Result<ArrayRef> {
+        let cells = data
+            .iter()
+            .map(|row| row.get(col_idx).map(|s| s.trim()).filter(|s| !s.is_empty()));
+        let mismatch = |value: &str| {
+            anyhow::anyhow!(
+                "Value '{value}' in column {} is not {data_type}",
+                col_idx + 1
+            )
+        };
+
+        let array: ArrayRef = match data_type {
+            DataType::Int64 => Arc::new(Int64Array::from(
+                cells
+                    .map(|v| v.map(|s| s.parse().map_err(|_| mismatch(s))).transpose())
+                    .collect::<Result<Vec<Option<i64>>>>()?,
+            )),
+            DataType::Float64 => Arc::new(Float64Array::from(
+                cells
+                    .map(|v| v.map(|s| s.parse().map_err(|_| mismatch(s))).transpose())
+                    .collect::<Result<Vec<Option<f64>>>>()?,
+            )),
+            DataType::Boolean => Arc::new(BooleanArray::from(
+                cells
+                    .map(|v| {
+                        v.map(|s| parse_bool(s).ok_or_else(|| mismatch(s)))
+                            .transpose()
+                    })
+                    .collect::<Result<Vec<Option<bool>>>>()?,
+            )),
+            _ => {
+                let values: Vec<Option<&str>> = data
+                    .iter()
+                    .map(|row| row.get(col_idx).map(|s| s.as_str()))
+                    .collect();
+                Arc::new(StringArray::from(values))
+            }
+        };
+        Ok(array)
+    }
+
+    /// Build a typed Arrow array for one column, using its inferred type
+    fn build_column(data: &[Vec<String>], col_idx: usize) -> Result<(DataType, ArrayRef)> {
+        let data_type = arrow_type(infer_column_type(data, col_idx));
+        let array = Self::typed_column(data, col_idx, &data_type)?;
+        Ok((data_type, array))
+    }
+
+    /// Convert every row of a record batch to strings and append them to `rows`
+    fn append_batch_rows(&self, batch: &RecordBatch, rows: &mut Vec<Vec<String>>) {
+        let num_cols = batch.num_columns();
+
+        for row_idx in 0..batch.num_rows() {
+            let mut row: Vec<String> = Vec::with_capacity(num_cols);
+            for col_idx in 0..num_cols {
+                let col = batch.column(col_idx);
+                row.push(self.array_value_to_string(col, row_idx));
+            }
+            rows.push(row);
+        }
     }
 
     fn array_value_to_string(&self, array: &ArrayRef, idx: usize) -> String {
@@ -168,18 +477,12 @@ impl ParquetHandler {
                 .downcast_ref::<arrow_array::LargeStringArray>()
                 .map(|arr| arr.value(idx).to_string())
                 .unwrap_or_else(|| format!("{:?}", array)),
-            DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
-                array
-                    .as_any()
-                    .downcast_ref::<Int64Array>()
-                    .map(|a| a.value(idx).to_string())
-                    .unwrap_or_else(|| format!("{:?}", array))
-            }
-            DataType::Float32 | DataType::Float64 => array
-                .as_any()
-                .downcast_ref::<Float64Array>()
-                .map(|a| a.value(idx).to_string())
-                .unwrap_or_else(|| format!("{:?}", array)),
+            DataType::Int8 => array.as_primitive::<Int8Type>().value(idx).to_string(),
+            DataType::Int16 => array.as_primitive::<Int16Type>().value(idx).to_string(),
+            DataType::Int32 => array.as_primitive::<Int32Type>().value(idx).to_string(),
+            DataType::Int64 => array.as_primitive::<Int64Type>().value(idx).to_string(),
+            DataType::Float32 => array.as_primitive::<Float32Type>().value(idx).to_string(),
+            DataType::Float64 => array.as_primitive::<Float64Type>().value(idx).to_string(),
             DataType::Boolean => array
                 .as_any()
                 .downcast_ref::<BooleanArray>()
@@ -190,6 +493,67 @@ impl ParquetHandler {
     }
 }
 
+/// Arrow type used to store a column of the given inferred type
+fn arrow_type(column_type: ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Int64 => DataType::Int64,
+        ColumnType::Float64 => DataType::Float64,
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Utf8 => DataType::Utf8,
+    }
+}
+
+/// Parquet file written a chunk of rows at a time
+///
+/// Each `write_rows` call becomes its own row group (split further if it
+/// exceeds the configured row group size) and is flushed to disk before
+/// returning, so memory stays bounded by the chunk size. The file is not
+/// readable until [`ParquetStreamWriter::close`] writes the footer.
+pub struct ParquetStreamWriter {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    rows_written: usize,
+}
+
+impl ParquetStreamWriter {
+    /// Append rows, typed according to the writer's schema
+    ///
+    /// Cells beyond the schema's columns are ignored, as in
+    /// [`ParquetHandler::write`]. A value that does not fit its column's type
+    /// is an error.
+    pub fn write_rows(&mut self, rows: &[Vec<String>]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(col_idx, field)| ParquetHandler::typed_column(rows, col_idx, field.data_type()))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("Failed to write rows after row {}", self.rows_written))?;
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+
+        self.writer.write(&batch)?;
+        self.writer.flush()?;
+        self.rows_written += rows.len();
+        Ok(())
+    }
+
+    /// Number of rows written so far
+    pub fn rows_written(&self) -> usize {
+        self.rows_written
+    }
+
+    /// Write the footer and close the file, returning the total row count
+    pub fn close(self) -> Result<usize> {
+        self.writer.close()?;
+        Ok(self.rows_written)
+    }
+}
+
 impl DataReader for ParquetHandler {
     fn read(&self, path: &str) -> Result<Vec<Vec<String>>> {
         self.read(path)
@@ -9,6 +9,10 @@ pub mod format {
 
     /// Get file format from extension
     pub fn from_extension(path: &str) -> &'static str {
+        if path.to_lowercase().ends_with(".csv.gz") {
+            return "csv";
+        }
+
         let ext = Path::new(path)
             .extension()
             .and_then(|s| s.to_str())
@@ -17,11 +21,13 @@ pub mod format {
 
         match ext.as_str() {
             "csv" => "csv",
+            "tsv" => "tsv",
             "xlsx" | "xls" => "excel",
             "ods" => "ods",
             "parquet" => "parquet",
             "avro" => "avro",
             "json" => "json",
+            "ndjson" | "jsonl" => "ndjson",
             _ => "unknown",
         }
     }
@@ -30,7 +36,7 @@ pub mod format {
     pub fn is_supported(format: &str) -> bool {
         matches!(
             format,
-            "csv" | "excel" | "ods" | "parquet" | "avro" | "json"
+            "csv" | "tsv" | "excel" | "ods" | "parquet" | "avro" | "json" | "ndjson"
         )
     }
 }
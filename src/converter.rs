@@ -1,17 +1,37 @@
-use crate::csv_handler::CsvHandler;
+use crate::columnar::{ParquetHandler, ParquetWriteOptions};
+use crate::csv_handler::{CsvHandler, CsvOptions, StreamingCsvWriter};
 use crate::excel::ExcelHandler;
 use crate::format_detector::DefaultFormatDetector;
 use crate::handler_registry::HandlerRegistry;
-use crate::traits::{DataWriteOptions, FormatDetector};
+use crate::operations::{NoProgress, PROGRESS_INTERVAL, ProgressCallback};
+use crate::traits::{DataReader, DataWriteOptions, FormatDetector};
 use anyhow::{Context, Result};
 use csv::ReaderBuilder;
-use std::io::Cursor;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Path that means stdin when reading and stdout when writing
+pub const STDIO_PATH: &str = "-";
+
+/// Outcome of `Converter::convert_dir`
+#[derive(Debug, Default)]
+pub struct ConvertDirSummary {
+    /// Output paths that were written
+    pub converted: Vec<String>,
+    /// Input paths that failed, with the reason
+    pub failed: Vec<(String, String)>,
+}
 
 pub struct Converter {
     registry: HandlerRegistry,
     excel_handler: ExcelHandler,
-    csv_handler: CsvHandler,
+    csv_reader: CsvHandler,
+    csv_writer: CsvHandler,
+    parquet_options: ParquetWriteOptions,
     format_detector: DefaultFormatDetector,
+    stdin_format: String,
 }
 
 impl Converter {
@@ -19,11 +39,58 @@ impl Converter {
         Self {
             registry: HandlerRegistry::new(),
             excel_handler: ExcelHandler::new(),
-            csv_handler: CsvHandler::new(),
+            csv_reader: CsvHandler::new(),
+            csv_writer: CsvHandler::new(),
+            parquet_options: ParquetWriteOptions::default(),
             format_detector: DefaultFormatDetector,
+            stdin_format: "csv".to_string(),
+        }
+    }
+
+    /// Converter that reads and writes CSV files with a fixed dialect
+    pub fn with_csv_options(options: CsvOptions) -> Self {
+        Self {
+            csv_reader: CsvHandler::with_options(options),
+            csv_writer: CsvHandler::with_options(options),
+            ..Self::new()
+        }
+    }
+
+    /// Converter that writes CSV files with a fixed dialect
+    ///
+    /// CSV inputs still have their delimiter detected, so this can convert
+    /// a semicolon file into a tab-separated one.
+    pub fn with_csv_write_options(options: CsvOptions) -> Self {
+        Self {
+            csv_writer: CsvHandler::with_options(options),
+            ..Self::new()
         }
     }
 
+    /// Converter using separate handlers for CSV input and CSV output
+    pub fn with_csv_handlers(reader: CsvHandler, writer: CsvHandler) -> Self {
+        Self {
+            csv_reader: reader,
+            csv_writer: writer,
+            ..Self::new()
+        }
+    }
+
+    /// Use `options` (codec, row group size) for Parquet outputs
+    pub fn with_parquet_options(mut self, options: ParquetWriteOptions) -> Self {
+        self.parquet_options = options;
+        self
+    }
+
+    /// Parse stdin as `format` (an extension such as `tsv` or `parquet`)
+    ///
+    /// Stdin has no extension to detect a format from, so it is read as CSV
+    /// unless told otherwise.
+    pub fn with_stdin_format(mut self, format: &str) -> Self {
+        self.stdin_format = format.trim_start_matches('.').to_lowercase();
+        self
+    }
+
     pub fn read_any_data(&self, path: &str, sheet_name: Option<&str>) -> Result<Vec<Vec<String>>> {
         self.read_any(path, sheet_name)
     }
@@ -34,35 +101,175 @@ impl Converter {
         data: &[Vec<String>],
         sheet_name: Option<&str>,
     ) -> Result<()> {
-        self.write_any(path, data, sheet_name)
+        self.write_any(path, data, sheet_name, &mut NoProgress)
+    }
+
+    /// Write the same data to several outputs, dispatching each by extension
+    ///
+    /// A single path behaves exactly like `write_any_data`. Stdout (`-`) can
+    /// only be used as the sole output.
+    pub fn write_multi(
+        &self,
+        paths: &[String],
+        data: &[Vec<String>],
+        sheet_name: Option<&str>,
+    ) -> Result<()> {
+        if paths.is_empty() {
+            anyhow::bail!("No output path given");
+        }
+        if paths.len() > 1 && paths.iter().any(|path| path == STDIO_PATH) {
+            anyhow::bail!("Stdout ('{STDIO_PATH}') must be the only output");
+        }
+
+        for path in paths {
+            self.write_any(path, data, sheet_name, &mut NoProgress)
+                .with_context(|| format!("Failed to write {}", path))?;
+        }
+
+        Ok(())
     }
 
     /// Convert between any supported formats
     /// Supported: csv, xlsx, xls, ods, parquet, avro
     pub fn convert(&self, input: &str, output: &str, sheet_name: Option<&str>) -> Result<()> {
+        self.convert_with_progress(input, output, sheet_name, &mut NoProgress)
+    }
+
+    /// [`Converter::convert`] reporting rows written
+    ///
+    /// `progress` sees zero rows once the input has been read and the full
+    /// row count once the output has been written. CSV and TSV outputs are
+    /// written row by row and also report every [`PROGRESS_INTERVAL`] rows.
+    pub fn convert_with_progress(
+        &self,
+        input: &str,
+        output: &str,
+        sheet_name: Option<&str>,
+        progress: &mut dyn ProgressCallback,
+    ) -> Result<()> {
         // Validate input format is supported
-        let input_format = self.format_detector.detect_format(input)?;
-        if !self.format_detector.is_supported(&input_format) {
-            anyhow::bail!("Unsupported input format: {}", input_format);
+        if input != STDIO_PATH {
+            let input_format = self.format_detector.detect_format(input)?;
+            if !self.format_detector.is_supported(&input_format) {
+                anyhow::bail!("Unsupported input format: {}", input_format);
+            }
         }
 
         // Validate output format is supported
-        let output_format = self.format_detector.detect_format(output)?;
-        if !self.format_detector.is_supported(&output_format) {
-            anyhow::bail!("Unsupported output format: {}", output_format);
+        if output != STDIO_PATH {
+            let output_format = self.format_detector.detect_format(output)?;
+            if !self.format_detector.is_supported(&output_format) {
+                anyhow::bail!("Unsupported output format: {}", output_format);
+            }
         }
 
         // Read input data
         let data = self.read_any(input, sheet_name)?;
+        progress.on_progress(0, Some(data.len()), "Converting");
 
         // Write to output format
-        self.write_any(output, &data, sheet_name)?;
+        self.write_any(output, &data, sheet_name, progress)?;
+        progress.on_progress(data.len(), Some(data.len()), "Converting");
 
         Ok(())
     }
 
+    /// Convert every supported file in `input_dir` to `target_ext`
+    ///
+    /// Each output keeps its input's base name, e.g. `in/sales.csv` becomes
+    /// `out/sales.parquet`. Inputs sharing a base name, such as `x.csv` and
+    /// `x.tsv`, keep their whole file name instead (`x.csv.parquet`) so none
+    /// overwrites another. Files are converted in parallel and a file that
+    /// fails is recorded in the summary instead of stopping the run.
+    pub fn convert_dir(
+        &self,
+        input_dir: &str,
+        output_dir: &str,
+        target_ext: &str,
+        sheet_name: Option<&str>,
+    ) -> Result<ConvertDirSummary> {
+        let target_ext = target_ext.trim_start_matches('.');
+        let target_format = self
+            .format_detector
+            .detect_format(&format!("output.{target_ext}"))?;
+        if !self.format_detector.is_supported(&target_format) {
+            anyhow::bail!("Unsupported output format: {}", target_format);
+        }
+
+        let pattern = Path::new(input_dir).join("*");
+        let mut inputs: Vec<String> = glob::glob(&pattern.to_string_lossy())
+            .context("Failed to parse input directory pattern")?
+            .filter_map(|entry| entry.ok())
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().to_string())
+            .filter(|path| {
+                self.format_detector
+                    .detect_format(path)
+                    .is_ok_and(|format| self.format_detector.is_supported(&format))
+            })
+            .collect();
+        inputs.sort();
+
+        std::fs::create_dir_all(output_dir)
+            .with_context(|| format!("Failed to create output directory {output_dir}"))?;
+
+        let stem = |input: &str| {
+            Path::new(input)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "output".to_string())
+        };
+        let mut stem_counts: HashMap<String, usize> = HashMap::new();
+        for input in &inputs {
+            *stem_counts.entry(stem(input)).or_insert(0) += 1;
+        }
+        let outputs: Vec<String> = inputs
+            .iter()
+            .map(|input| {
+                let base = stem(input);
+                let base = if stem_counts[&base] > 1 {
+                    Path::new(input)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or(base)
+                } else {
+                    base
+                };
+                Path::new(output_dir)
+                    .join(format!("{base}.{target_ext}"))
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        if let Some(clash) = outputs.iter().find(|output| !seen.insert(*output)) {
+            anyhow::bail!("Several inputs would be converted to {clash}");
+        }
+
+        let outcomes: Vec<(String, Result<String>)> = inputs
+            .par_iter()
+            .zip(outputs)
+            .map(|(input, output)| {
+                let result = self.convert(input, &output, sheet_name).map(|_| output);
+                (input.clone(), result)
+            })
+            .collect();
+
+        let mut summary = ConvertDirSummary::default();
+        for (input, result) in outcomes {
+            match result {
+                Ok(output) => summary.converted.push(output),
+                Err(e) => summary.failed.push((input, format!("{e:#}"))),
+            }
+        }
+        Ok(summary)
+    }
+
     /// Read data from any supported format
     fn read_any(&self, path: &str, sheet_name: Option<&str>) -> Result<Vec<Vec<String>>> {
+        if path == STDIO_PATH {
+            return self.read_stdin(sheet_name);
+        }
         let format = self.format_detector.detect_format(path)?;
 
         match format.as_str() {
@@ -72,7 +279,6 @@ impl Converter {
                 Ok(self.parse_csv_data(&content))
             }
             "parquet" => {
-                use crate::columnar::ParquetHandler;
                 let handler = ParquetHandler::new();
                 handler.read_with_headers(path)
             }
@@ -81,12 +287,24 @@ impl Converter {
                 let handler = AvroHandler::new();
                 handler.read_with_headers(path)
             }
+            "csv" => DataReader::read(&self.csv_reader, path),
+            "tsv" => DataReader::read(&Self::tsv_handler(&self.csv_reader), path),
             _ => self.registry.read(path),
         }
     }
 
     /// Write data to any supported format
-    fn write_any(&self, path: &str, data: &[Vec<String>], sheet_name: Option<&str>) -> Result<()> {
+    fn write_any(
+        &self,
+        path: &str,
+        data: &[Vec<String>],
+        sheet_name: Option<&str>,
+        progress: &mut dyn ProgressCallback,
+    ) -> Result<()> {
+        // Stdout is always written as CSV
+        if path == STDIO_PATH {
+            return self.csv_writer.write_to(std::io::stdout(), data);
+        }
         let format = self.format_detector.detect_format(path)?;
 
         match format.as_str() {
@@ -101,8 +319,8 @@ impl Converter {
                     }
                 };
 
-                // Write to temp CSV
-                self.csv_handler.write_records(&temp_csv, data.to_vec())
+                // Write to temp CSV (always comma-separated for the Excel reader)
+                CsvHandler::new().write_records(&temp_csv, data.to_vec())
                     .with_context(|| format!("Failed to write temp CSV file: {}", temp_csv))?;
 
                 // Convert to Excel
@@ -117,6 +335,22 @@ impl Converter {
                     }
                 }
             }
+            "ods" => self.excel_handler.write_ods(path, data, sheet_name),
+            "csv" => write_csv_rows(path, *self.csv_writer.options(), data, progress),
+            "tsv" => {
+                let options = *Self::tsv_handler(&self.csv_writer).options();
+                write_csv_rows(path, options, data, progress)
+            }
+            // The first row names the columns so only data rows drive type inference
+            "parquet" => {
+                let (header, rows) = split_header(data);
+                ParquetHandler::new().write_with_options(path, rows, header, &self.parquet_options)
+            }
+            "avro" => {
+                use crate::columnar::AvroHandler;
+                let (header, rows) = split_header(data);
+                AvroHandler::new().write(path, rows, header)
+            }
             _ => {
                 // Use registry for other formats
                 let options = DataWriteOptions {
@@ -128,6 +362,45 @@ impl Converter {
         }
     }
 
+    /// Read all of stdin as `self.stdin_format`
+    fn read_stdin(&self, sheet_name: Option<&str>) -> Result<Vec<Vec<String>>> {
+        let format = self.stdin_format.as_str();
+        if !self.format_detector.is_supported(format) {
+            anyhow::bail!("Unsupported input format: {}", format);
+        }
+
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .context("Failed to read from stdin")?;
+
+        match format {
+            "csv" => self.csv_reader.read_from(Cursor::new(bytes)),
+            "tsv" => Self::tsv_handler(&self.csv_reader).read_from(Cursor::new(bytes)),
+            _ => {
+                // Other readers need a file, so stage the bytes in one
+                let temp = std::env::temp_dir().join(format!(
+                    "datacell-stdin-{}.{}",
+                    std::process::id(),
+                    format
+                ));
+                std::fs::write(&temp, &bytes)
+                    .with_context(|| format!("Failed to stage stdin in {}", temp.display()))?;
+                let result = self.read_any(&temp.to_string_lossy(), sheet_name);
+                std::fs::remove_file(&temp).ok();
+                result
+            }
+        }
+    }
+
+    /// `handler`'s dialect with a tab delimiter, used for `.tsv` files
+    fn tsv_handler(handler: &CsvHandler) -> CsvHandler {
+        CsvHandler::with_options(CsvOptions {
+            delimiter: b'\t',
+            ..*handler.options()
+        })
+    }
+
     fn parse_csv_data(&self, data: &str) -> Vec<Vec<String>> {
         let cursor = Cursor::new(data);
         let mut reader = ReaderBuilder::new()
@@ -153,3 +426,30 @@ impl Converter {
         result
     }
 }
+
+/// Split the header row off `data` for writers that store column names apart
+fn split_header(data: &[Vec<String>]) -> (Option<&[String]>, &[Vec<String>]) {
+    match data.split_first() {
+        Some((header, rows)) => (Some(header.as_slice()), rows),
+        None => (None, data),
+    }
+}
+
+/// Write `data` as delimited text, reporting every [`PROGRESS_INTERVAL`] rows
+fn write_csv_rows(
+    path: &str,
+    options: CsvOptions,
+    data: &[Vec<String>],
+    progress: &mut dyn ProgressCallback,
+) -> Result<()> {
+    let mut writer = StreamingCsvWriter::create_with_options(path, options)?;
+    for (row_idx, row) in data.iter().enumerate() {
+        // Row zero was already reported once the input had been read
+        if row_idx > 0 && row_idx % PROGRESS_INTERVAL == 0 {
+            progress.on_progress(row_idx, Some(data.len()), "Converting");
+        }
+        writer.write_row(row)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
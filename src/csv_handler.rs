@@ -1,10 +1,15 @@
+use crate::text_encoding::{EncodingWriter, decode_reader};
 use crate::traits::{
     CellRangeProvider, DataReader, DataWriteOptions, DataWriter, FileHandler, SchemaProvider,
 };
 use anyhow::{Context, Result};
 use csv::{ReaderBuilder, WriterBuilder};
+use encoding_rs::{Encoding, UTF_8};
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Read};
+use std::io::{self, BufReader, BufWriter, Read, Write};
 
 /// Represents a cell range like A1:B3
 #[derive(Debug, Clone)]
@@ -74,64 +79,305 @@ impl CellRange {
     }
 }
 
-pub struct CsvHandler;
+/// Byte order mark written at the start of UTF-8 files when requested
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Delimiters considered by [`CsvHandler::detect_delimiter`], in tie-break order
+const DELIMITER_CANDIDATES: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Bytes sampled from the start of a file when detecting the delimiter
+const DELIMITER_SAMPLE_BYTES: usize = 8 * 1024;
+
+/// Dialect settings for reading and writing CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Field separator, e.g. `b','`, `b';'` or `b'\t'`
+    pub delimiter: u8,
+    /// Character used to quote fields containing the delimiter
+    pub quote: u8,
+    /// Skip the first record when reading, as `csv::ReaderBuilder` does
+    pub has_headers: bool,
+    /// Allow records with differing field counts
+    pub flexible: bool,
+    /// Character encoding of the file; reads honor a UTF-8/UTF-16 BOM instead
+    pub encoding: &'static Encoding,
+    /// Start new UTF-8 files with a byte order mark, as Excel expects
+    pub write_bom: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            has_headers: false,
+            flexible: true,
+            encoding: UTF_8,
+            write_bom: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Parse a delimiter given on the command line
+    ///
+    /// Accepts a single ASCII character, `\t`, or the names `tab`, `comma`,
+    /// `semicolon` and `pipe`.
+    pub fn parse_delimiter(value: &str) -> Result<u8> {
+        match value.to_lowercase().as_str() {
+            "\\t" | "tab" => Ok(b'\t'),
+            "comma" => Ok(b','),
+            "semicolon" => Ok(b';'),
+            "pipe" => Ok(b'|'),
+            _ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+            _ => anyhow::bail!("Invalid delimiter '{value}': expected a single character"),
+        }
+    }
+
+    /// Open `path` for reading with this dialect, decoding it to UTF-8
+    fn open_reader(&self, path: &str) -> Result<csv::Reader<Box<dyn Read>>> {
+        let input = open_input(path)?;
+        Ok(self
+            .reader_builder()
+            .from_reader(decode_reader(input, self.encoding)))
+    }
+
+    /// Wrap `output` in a CSV writer that encodes with this dialect
+    ///
+    /// `at_start` says whether `output` is empty, so a BOM is only ever
+    /// written at the beginning of a file.
+    fn writer(
+        &self,
+        output: Box<dyn FinishWrite>,
+        at_start: bool,
+    ) -> Result<csv::Writer<Box<dyn FinishWrite>>> {
+        let mut output = if self.encoding == UTF_8 {
+            output
+        } else {
+            Box::new(EncodingWriter::new(output, self.encoding)?)
+        };
+        if self.write_bom && at_start && self.encoding == UTF_8 {
+            output.write_all(UTF8_BOM)?;
+        }
+        Ok(self.writer_builder().from_writer(output))
+    }
+
+    fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(self.has_headers)
+            .flexible(self.flexible);
+        builder
+    }
+
+    fn writer_builder(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(false)
+            .flexible(self.flexible);
+        builder
+    }
+}
+
+/// CSV reader/writer
+///
+/// Reads detect the delimiter from the file contents unless the dialect is
+/// fixed with [`CsvHandler::with_options`] or [`CsvHandler::with_delimiter`].
+/// Writes always use the configured delimiter (a comma by default) and
+/// encoding (UTF-8 by default).
+#[derive(Debug, Clone)]
+pub struct CsvHandler {
+    options: CsvOptions,
+    detect_delimiter: bool,
+}
+
+impl Default for CsvHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl CsvHandler {
     pub fn new() -> Self {
-        Self
+        Self {
+            options: CsvOptions::default(),
+            detect_delimiter: true,
+        }
+    }
+
+    /// Read and write with a fixed dialect instead of detecting the delimiter
+    pub fn with_options(options: CsvOptions) -> Self {
+        Self {
+            options,
+            detect_delimiter: false,
+        }
+    }
+
+    /// Always use `delimiter` instead of detecting it
+    pub fn with_delimiter(delimiter: u8) -> Self {
+        Self::with_options(CsvOptions {
+            delimiter,
+            ..CsvOptions::default()
+        })
+    }
+
+    /// Read and write `encoding` instead of UTF-8, keeping delimiter detection as configured
+    pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.options.encoding = encoding;
+        self
     }
 
+    pub fn options(&self) -> &CsvOptions {
+        &self.options
+    }
+
+    /// Guess the delimiter of a CSV file from its first few KB
+    ///
+    /// Each candidate (`,` `;` tab `|`) is scored by how many sampled lines
+    /// share the same field count (more than one field), then by that field
+    /// count. Falls back to a comma when no candidate splits any line.
+    pub fn detect_delimiter(path: &str) -> Result<u8> {
+        Self::detect_delimiter_with_encoding(path, UTF_8)
+    }
+
+    fn detect_delimiter_with_encoding(path: &str, encoding: &'static Encoding) -> Result<u8> {
+        let input = open_input(path)?;
+
+        let mut sample = Vec::with_capacity(DELIMITER_SAMPLE_BYTES);
+        input
+            .take(DELIMITER_SAMPLE_BYTES as u64)
+            .read_to_end(&mut sample)?;
+
+        let (text, _) = encoding.decode_with_bom_removal(&sample);
+        Ok(Self::detect_delimiter_in(
+            &text,
+            sample.len() == DELIMITER_SAMPLE_BYTES,
+        ))
+    }
+
+    fn detect_delimiter_in(text: &str, truncated: bool) -> u8 {
+        use std::collections::HashMap;
+
+        let mut lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        if truncated && lines.len() > 1 {
+            // The last sampled line is probably cut short
+            lines.pop();
+        }
+
+        let mut best = (b',', 0, 0);
+        for delimiter in DELIMITER_CANDIDATES {
+            let mut field_counts: HashMap<usize, usize> = HashMap::new();
+            for line in &lines {
+                let fields = count_fields(line, delimiter);
+                if fields > 1 {
+                    *field_counts.entry(fields).or_default() += 1;
+                }
+            }
+
+            if let Some((fields, consistent)) = field_counts
+                .into_iter()
+                .max_by_key(|&(fields, consistent)| (consistent, fields))
+                && (consistent, fields) > (best.1, best.2)
+            {
+                best = (delimiter, consistent, fields);
+            }
+        }
+
+        best.0
+    }
+
+    /// Open a CSV reader using the configured or detected delimiter
+    fn open_reader(&self, path: &str) -> Result<csv::Reader<Box<dyn Read>>> {
+        let mut options = self.options;
+        if self.detect_delimiter {
+            options.delimiter = Self::detect_delimiter_with_encoding(path, options.encoding)?;
+        }
+
+        options.open_reader(path)
+    }
+
+    /// Create (or truncate) `path` for writing with the configured dialect
+    fn create_writer(&self, path: &str) -> Result<csv::Writer<Box<dyn FinishWrite>>> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create CSV file: {path}"))?;
+        self.options.writer(compress_output(path, file), true)
+    }
+
+    /// Read the raw file text, decoded to UTF-8 without a leading BOM
     pub fn read(&self, path: &str) -> Result<String> {
-        let mut file =
-            File::open(path).with_context(|| format!("Failed to open CSV file: {path}"))?;
+        let input = open_input(path)?;
 
         let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
+        decode_reader(input, self.options.encoding)
+            .read_to_string(&mut contents)
+            .with_context(|| format!("Failed to read CSV file: {path}"))?;
 
         Ok(contents)
     }
 
+    /// Read every record from `input`, such as stdin, with the configured dialect
+    ///
+    /// There is no file to sample, so the delimiter is never detected: a
+    /// handler from [`CsvHandler::new`] reads commas.
+    pub fn read_from<R: Read + 'static>(&self, input: R) -> Result<Vec<Vec<String>>> {
+        let mut reader = self
+            .options
+            .reader_builder()
+            .from_reader(decode_reader(input, self.options.encoding));
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            rows.push(record?.iter().map(String::from).collect());
+        }
+        Ok(rows)
+    }
+
+    /// Write records to `output`, such as stdout, with the configured dialect
+    pub fn write_to<W: Write + 'static>(&self, output: W, records: &[Vec<String>]) -> Result<()> {
+        let mut writer = self.options.writer(Box::new(PlainOutput(output)), true)?;
+        for record in records {
+            writer.write_record(record)?;
+        }
+        finish_writer(writer)
+    }
+
+    /// Copy `input_path` to `output_path` in the configured dialect
+    ///
+    /// With `has_headers` set the header row is kept as the first record
+    /// rather than skipped.
     pub fn write_from_csv(&self, input_path: &str, output_path: &str) -> Result<()> {
-        let mut reader = ReaderBuilder::new()
-            .has_headers(false)
-            .from_path(input_path)
-            .with_context(|| format!("Failed to open CSV file: {input_path}"))?;
+        let mut reader = self.open_reader(input_path)?;
 
-        let mut writer = WriterBuilder::new()
-            .has_headers(false)
-            .from_path(output_path)
-            .with_context(|| format!("Failed to create CSV file: {}", output_path))?;
+        let mut writer = self.create_writer(output_path)?;
 
+        if reader.has_headers() {
+            writer.write_record(reader.headers()?)?;
+        }
         for result in reader.records() {
             let record = result?;
             writer.write_record(&record)?;
         }
 
-        writer.flush()?;
-        Ok(())
+        finish_writer(writer)
     }
 
     pub fn write_records(&self, path: &str, records: Vec<Vec<String>>) -> Result<()> {
-        let mut writer = WriterBuilder::new()
-            .has_headers(false)
-            .from_path(path)
-            .with_context(|| format!("Failed to create CSV file: {path}"))?;
+        let mut writer = self.create_writer(path)?;
 
         for record in records {
             writer.write_record(&record)?;
         }
 
-        writer.flush()?;
-        Ok(())
+        finish_writer(writer)
     }
 
     /// Read a specific range from CSV file
     pub fn read_range(&self, path: &str, range: &CellRange) -> Result<Vec<Vec<String>>> {
-        let mut reader = ReaderBuilder::new()
-            .has_headers(false)
-            .flexible(true)
-            .from_path(path)
-            .with_context(|| format!("Failed to open CSV file: {path}"))?;
+        let mut reader = self.open_reader(path)?;
 
         let estimated_rows = range.end_row.saturating_sub(range.start_row) + 1;
         let estimated_cols = range.end_col.saturating_sub(range.start_col) + 1;
@@ -162,11 +408,7 @@ impl CsvHandler {
 
     /// Read CSV and return as JSON array
     pub fn read_as_json(&self, path: &str) -> Result<String> {
-        let mut reader = ReaderBuilder::new()
-            .has_headers(false)
-            .flexible(true)
-            .from_path(path)
-            .with_context(|| format!("Failed to open CSV file: {path}"))?;
+        let mut reader = self.open_reader(path)?;
 
         let mut rows: Vec<Vec<String>> = Vec::with_capacity(1024);
         for record in reader.records() {
@@ -192,16 +434,15 @@ impl CsvHandler {
             .open(path)
             .with_context(|| format!("Failed to open CSV file for append: {path}"))?;
 
-        let mut writer = csv::WriterBuilder::new()
-            .has_headers(false)
-            .from_writer(file);
+        let at_start = file.metadata()?.len() == 0;
+        // A `.gz` file gains a new gzip member, which readers decode as one stream
+        let mut writer = self.options.writer(compress_output(path, file), at_start)?;
 
         for record in records {
             writer.write_record(record)?;
         }
 
-        writer.flush()?;
-        Ok(())
+        finish_writer(writer)
     }
 
     /// Write data to a specific cell range in CSV
@@ -214,10 +455,12 @@ impl CsvHandler {
     ) -> Result<()> {
         // Read existing data if file exists
         let mut existing: Vec<Vec<String>> = if std::path::Path::new(path).exists() {
-            let mut reader = ReaderBuilder::new()
-                .has_headers(false)
-                .flexible(true)
-                .from_path(path)?;
+            // Keep every row, reading with the dialect the file is rewritten in
+            let options = CsvOptions {
+                has_headers: false,
+                ..self.options
+            };
+            let mut reader = options.open_reader(path)?;
             reader
                 .records()
                 .map(|r| {
@@ -253,6 +496,107 @@ impl CsvHandler {
     }
 }
 
+/// Whether `path` names a gzip-compressed file
+pub fn is_gzip_path(path: &str) -> bool {
+    path.to_lowercase().ends_with(".gz")
+}
+
+/// Whether `path` names a plain or gzip-compressed CSV file
+fn is_csv_path(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".csv") || path.ends_with(".csv.gz")
+}
+
+/// Open `path` for reading, decompressing `.gz` files on the fly
+fn open_input(path: &str) -> Result<Box<dyn Read>> {
+    let file = File::open(path).with_context(|| format!("Failed to open CSV file: {path}"))?;
+    let input = BufReader::with_capacity(64 * 1024, file);
+    if is_gzip_path(path) {
+        Ok(Box::new(MultiGzDecoder::new(input)))
+    } else {
+        Ok(Box::new(input))
+    }
+}
+
+/// Wrap `output` so it is gzip-compressed when `path` ends in `.gz`
+fn compress_output<W: Write + 'static>(path: &str, output: W) -> Box<dyn FinishWrite> {
+    if is_gzip_path(path) {
+        Box::new(GzEncoder::new(output, Compression::default()))
+    } else {
+        Box::new(PlainOutput(output))
+    }
+}
+
+/// Byte stream under a CSV writer that may buffer a trailer
+///
+/// Encoders finish themselves on drop too, but any error there is lost, so
+/// writers call [`finish_writer`] once the last record is written.
+trait FinishWrite: Write {
+    /// Write anything still buffered or pending, then flush
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Output with nothing to finish beyond a flush
+struct PlainOutput<W>(W);
+
+impl<W: Write> Write for PlainOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: Write> FinishWrite for PlainOutput<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: Write> FinishWrite for GzEncoder<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        // Writes the gzip trailer, which `Drop` would do silently
+        self.try_finish()?;
+        self.get_mut().flush()
+    }
+}
+
+impl<W: FinishWrite> FinishWrite for EncodingWriter<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        EncodingWriter::finish(self)?;
+        self.get_mut().finish()
+    }
+}
+
+impl FinishWrite for Box<dyn FinishWrite> {
+    fn finish(&mut self) -> io::Result<()> {
+        (**self).finish()
+    }
+}
+
+/// Flush the last records of `writer` and finish its output stream
+fn finish_writer(writer: csv::Writer<Box<dyn FinishWrite>>) -> Result<()> {
+    let mut output = writer.into_inner().map_err(|e| e.into_error())?;
+    output.finish()?;
+    Ok(())
+}
+
+/// Count the fields `line` splits into, ignoring delimiters inside quotes
+fn count_fields(line: &str, delimiter: u8) -> usize {
+    let mut in_quotes = false;
+    let mut fields = 1;
+    for byte in line.bytes() {
+        if byte == b'"' {
+            in_quotes = !in_quotes;
+        } else if byte == delimiter && !in_quotes {
+            fields += 1;
+        }
+    }
+    fields
+}
+
 /// Characters that can trigger formula injection in spreadsheet applications
 const CSV_INJECTION_CHARS: &[char] = &['=', '+', '-', '@', '\t', '\r', '\n'];
 
@@ -298,19 +642,17 @@ impl CsvHandler {
 
 /// Streaming CSV reader for large files - processes rows one at a time
 pub struct StreamingCsvReader {
-    reader: csv::Reader<BufReader<File>>,
+    reader: csv::Reader<Box<dyn Read>>,
     current_row: usize,
 }
 
 impl StreamingCsvReader {
     pub fn open(path: &str) -> Result<Self> {
-        let file = File::open(path).with_context(|| format!("Failed to open CSV file: {path}"))?;
-        let buf_reader = BufReader::with_capacity(64 * 1024, file); // 64KB buffer
+        Self::open_with_options(path, CsvOptions::default())
+    }
 
-        let reader = ReaderBuilder::new()
-            .has_headers(false)
-            .flexible(true)
-            .from_reader(buf_reader);
+    pub fn open_with_options(path: &str, options: CsvOptions) -> Result<Self> {
+        let reader = options.open_reader(path)?;
 
         Ok(Self {
             reader,
@@ -344,20 +686,48 @@ impl Iterator for StreamingCsvReader {
 }
 
 /// Streaming CSV writer for large files
+///
+/// Call [`finish`](Self::finish) after the last row; dropping the writer
+/// flushes it but cannot report errors.
 pub struct StreamingCsvWriter {
-    writer: csv::Writer<BufWriter<File>>,
+    writer: csv::Writer<Box<dyn FinishWrite>>,
     rows_written: usize,
 }
 
 impl StreamingCsvWriter {
     pub fn create(path: &str) -> Result<Self> {
+        Self::create_with_options(path, CsvOptions::default())
+    }
+
+    pub fn create_with_options(path: &str, options: CsvOptions) -> Result<Self> {
         let file =
             File::create(path).with_context(|| format!("Failed to create CSV file: {path}"))?;
         let buf_writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let writer = WriterBuilder::new()
-            .has_headers(false)
-            .from_writer(buf_writer);
+        let writer = options.writer(compress_output(path, buf_writer), true)?;
+
+        Ok(Self {
+            writer,
+            rows_written: 0,
+        })
+    }
+
+    /// Open an existing file for appending (created if missing)
+    pub fn append(path: &str) -> Result<Self> {
+        Self::append_with_options(path, CsvOptions::default())
+    }
+
+    /// Open an existing file for appending with the given dialect
+    pub fn append_with_options(path: &str, options: CsvOptions) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open CSV file for append: {path}"))?;
+        let at_start = file.metadata()?.len() == 0;
+        let buf_writer = BufWriter::with_capacity(64 * 1024, file);
+
+        let writer = options.writer(compress_output(path, buf_writer), at_start)?;
 
         Ok(Self {
             writer,
@@ -379,11 +749,11 @@ impl StreamingCsvWriter {
         self.writer.flush()?;
         Ok(())
     }
-}
 
-impl Drop for StreamingCsvWriter {
-    fn drop(&mut self) {
-        let _ = self.writer.flush();
+    /// Flush and finish the output, returning the number of rows written
+    pub fn finish(self) -> Result<usize> {
+        finish_writer(self.writer)?;
+        Ok(self.rows_written)
     }
 }
 
@@ -391,11 +761,7 @@ impl Drop for StreamingCsvWriter {
 
 impl DataReader for CsvHandler {
     fn read(&self, path: &str) -> Result<Vec<Vec<String>>> {
-        let mut reader = ReaderBuilder::new()
-            .has_headers(false)
-            .flexible(true)
-            .from_path(path)
-            .with_context(|| format!("Failed to open CSV file: {path}"))?;
+        let mut reader = self.open_reader(path)?;
 
         // Pre-allocate with capacity hint for better performance
         let mut rows = Vec::with_capacity(1024);
@@ -424,7 +790,7 @@ impl DataReader for CsvHandler {
     }
 
     fn supports_format(&self, path: &str) -> bool {
-        path.to_lowercase().ends_with(".csv")
+        is_csv_path(path)
     }
 }
 
@@ -448,7 +814,7 @@ impl DataWriter for CsvHandler {
     }
 
     fn supports_format(&self, path: &str) -> bool {
-        path.to_lowercase().ends_with(".csv")
+        is_csv_path(path)
     }
 }
 
@@ -2,17 +2,41 @@
 //!
 //! Provides encryption and decryption capabilities for data files.
 
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
 use anyhow::Result;
+use argon2::Argon2;
 use std::fs;
 use std::io::{Read, Write};
 
 /// Encryption algorithm
 #[derive(Debug, Clone, Copy)]
 pub enum EncryptionAlgorithm {
+    /// Placeholder that currently applies XOR; offers no integrity protection
     Aes256,
+    /// AES-256-GCM with a random 96-bit nonce prepended to the ciphertext;
+    /// decryption verifies the authentication tag, so tampering is detected
+    Aes256Gcm,
     Xor, // Simple XOR for testing (not secure for production)
 }
 
+/// Key length required by AES-256-GCM
+pub const AES_GCM_KEY_LEN: usize = 32;
+
+/// Nonce length prepended to AES-256-GCM output
+const AES_GCM_NONCE_LEN: usize = 12;
+
+/// Header of password-encrypted output, followed by the algorithm id and
+/// the key derivation salt
+const PASSWORD_MAGIC: &[u8] = b"DCPWD1";
+
+/// Algorithm id stored after `PASSWORD_MAGIC` for AES-256-GCM
+const PASSWORD_AES_GCM: u8 = 1;
+
+/// Length of the random salt stored in password-encrypted output
+pub const SALT_LEN: usize = 16;
+
 /// Data encryptor/decryptor
 pub struct DataEncryptor {
     algorithm: EncryptionAlgorithm,
@@ -25,45 +49,173 @@ impl DataEncryptor {
 
     /// Encrypt a file
     pub fn encrypt_file(&self, input_path: &str, output_path: &str, key: &[u8]) -> Result<()> {
+        self.transform_file(input_path, output_path, |data| self.encrypt_data(data, key))
+    }
+
+    /// Decrypt a file
+    pub fn decrypt_file(&self, input_path: &str, output_path: &str, key: &[u8]) -> Result<()> {
+        self.transform_file(input_path, output_path, |data| self.decrypt_data(data, key))
+    }
+
+    /// Encrypt a file with a key derived from `password`
+    ///
+    /// See `encrypt_data_with_password` for the output layout.
+    pub fn encrypt_file_with_password(
+        &self,
+        input_path: &str,
+        output_path: &str,
+        password: &str,
+    ) -> Result<()> {
+        self.transform_file(input_path, output_path, |data| {
+            self.encrypt_data_with_password(data, password)
+        })
+    }
+
+    /// Decrypt a file written by `encrypt_file_with_password`
+    pub fn decrypt_file_with_password(
+        &self,
+        input_path: &str,
+        output_path: &str,
+        password: &str,
+    ) -> Result<()> {
+        self.transform_file(input_path, output_path, |data| {
+            self.decrypt_data_with_password(data, password)
+        })
+    }
+
+    fn transform_file(
+        &self,
+        input_path: &str,
+        output_path: &str,
+        transform: impl FnOnce(&[u8]) -> Result<Vec<u8>>,
+    ) -> Result<()> {
         let mut input_data = Vec::new();
         let mut file = std::fs::File::open(input_path)
             .with_context(|| format!("Failed to open input file: {}", input_path))?;
         file.read_to_end(&mut input_data)?;
 
-        let encrypted = match self.algorithm {
-            EncryptionAlgorithm::Aes256 => {
-                // For now, use simple XOR as placeholder
-                // In production, use proper AES-256-GCM
-                self.xor_encrypt(&input_data, key)
-            }
-            EncryptionAlgorithm::Xor => self.xor_encrypt(&input_data, key),
-        }?;
+        let output_data = transform(&input_data)?;
 
         let mut output_file = std::fs::File::create(output_path)
             .with_context(|| format!("Failed to create output file: {}", output_path))?;
-        output_file.write_all(&encrypted)?;
+        output_file.write_all(&output_data)?;
 
         Ok(())
     }
 
-    /// Decrypt a file
-    pub fn decrypt_file(&self, input_path: &str, output_path: &str, key: &[u8]) -> Result<()> {
-        // XOR encryption is symmetric
-        self.encrypt_file(input_path, output_path, key)
+    /// Derive a 32-byte key from a human password with Argon2id
+    ///
+    /// The same password and salt always give the same key; `salt` must be at
+    /// least 8 bytes.
+    pub fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32]> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+
+    /// Encrypt data with a key derived from `password`
+    ///
+    /// Only AES-256-GCM is accepted, so a wrong password is reported on
+    /// decryption rather than producing garbage. The output starts with a
+    /// `DCPWD1` header, the algorithm id and the random salt used for
+    /// derivation, so decryption needs only the password.
+    pub fn encrypt_data_with_password(&self, data: &[u8], password: &str) -> Result<Vec<u8>> {
+        if !matches!(self.algorithm, EncryptionAlgorithm::Aes256Gcm) {
+            anyhow::bail!(
+                "Password encryption requires AES-256-GCM, not {:?}",
+                self.algorithm
+            );
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = Self::derive_key(password, &salt)?;
+
+        let mut output = PASSWORD_MAGIC.to_vec();
+        output.push(PASSWORD_AES_GCM);
+        output.extend_from_slice(&salt);
+        output.extend(self.aes_gcm_encrypt(data, &key)?);
+        Ok(output)
+    }
+
+    /// Decrypt data written by `encrypt_data_with_password`
+    ///
+    /// The algorithm is read from the header, whatever this encryptor's
+    /// algorithm is.
+    pub fn decrypt_data_with_password(&self, data: &[u8], password: &str) -> Result<Vec<u8>> {
+        let Some(rest) = data.strip_prefix(PASSWORD_MAGIC) else {
+            anyhow::bail!("Data was not encrypted with a password (missing header)");
+        };
+        let Some((&algorithm, rest)) = rest.split_first() else {
+            anyhow::bail!("Encrypted data is too short to contain an algorithm id");
+        };
+        if algorithm != PASSWORD_AES_GCM {
+            anyhow::bail!("Unknown password encryption algorithm id {}", algorithm);
+        }
+        if rest.len() < SALT_LEN {
+            anyhow::bail!("Encrypted data is too short to contain a salt");
+        }
+        let (salt, payload) = rest.split_at(SALT_LEN);
+
+        let key = Self::derive_key(password, salt)?;
+        self.aes_gcm_decrypt(payload, &key)
     }
 
     /// Encrypt data in memory
     pub fn encrypt_data(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
         match self.algorithm {
             EncryptionAlgorithm::Aes256 => self.xor_encrypt(data, key),
+            EncryptionAlgorithm::Aes256Gcm => self.aes_gcm_encrypt(data, key),
             EncryptionAlgorithm::Xor => self.xor_encrypt(data, key),
         }
     }
 
     /// Decrypt data in memory
     pub fn decrypt_data(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
-        // XOR is symmetric
-        self.encrypt_data(data, key)
+        match self.algorithm {
+            EncryptionAlgorithm::Aes256Gcm => self.aes_gcm_decrypt(data, key),
+            // XOR is symmetric
+            EncryptionAlgorithm::Aes256 | EncryptionAlgorithm::Xor => self.encrypt_data(data, key),
+        }
+    }
+
+    fn aes_gcm_cipher(&self, key: &[u8]) -> Result<Aes256Gcm> {
+        if key.len() != AES_GCM_KEY_LEN {
+            anyhow::bail!(
+                "AES-256-GCM needs a {}-byte key, got {} bytes",
+                AES_GCM_KEY_LEN,
+                key.len()
+            );
+        }
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)))
+    }
+
+    fn aes_gcm_encrypt(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+        let cipher = self.aes_gcm_cipher(key)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, data)
+            .map_err(|_| anyhow::anyhow!("AES-256-GCM encryption failed"))?;
+
+        let mut output = nonce.to_vec();
+        output.extend(ciphertext);
+        Ok(output)
+    }
+
+    fn aes_gcm_decrypt(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+        let cipher = self.aes_gcm_cipher(key)?;
+        if data.len() < AES_GCM_NONCE_LEN {
+            anyhow::bail!("Encrypted data is too short to contain a nonce");
+        }
+        let (nonce, ciphertext) = data.split_at(AES_GCM_NONCE_LEN);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Decryption failed: authentication failed (wrong key or modified data)"
+                )
+            })
     }
 
     fn xor_encrypt(&self, data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
@@ -32,6 +32,18 @@ impl DataChartType {
     }
 }
 
+/// How series of a bar/column/line/area chart are laid out against each other
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ChartGrouping {
+    /// Side by side (bars) or overlapping (lines, areas)
+    #[default]
+    Clustered,
+    /// Stacked on top of each other
+    Stacked,
+    /// Stacked and scaled so each category totals 100%
+    PercentStacked,
+}
+
 /// Chart configuration
 #[derive(Debug, Clone)]
 pub struct ChartConfig {
@@ -45,6 +57,14 @@ pub struct ChartConfig {
     pub height: u32,
     pub show_legend: bool,
     pub colors: Option<Vec<String>>,
+    /// Top-left cell of the chart, e.g. `"E2"`; `None` places the first chart
+    /// at E2 and each later one below the previous chart on the sheet
+    pub start_cell: Option<String>,
+    pub grouping: ChartGrouping,
+    /// Per-series chart type, parallel to `value_columns`; a `Line` entry on a
+    /// bar/column/area chart draws that series as a line on a secondary axis.
+    /// Empty means every series uses `chart_type`.
+    pub series_types: Vec<DataChartType>,
 }
 
 impl Default for ChartConfig {
@@ -60,6 +80,9 @@ impl Default for ChartConfig {
             height: 400,
             show_legend: true,
             colors: None,
+            start_cell: None,
+            grouping: ChartGrouping::Clustered,
+            series_types: Vec::new(),
         }
     }
 }
@@ -0,0 +1,95 @@
+//! Sheet layout read straight from the XLSX package
+//!
+//! calamine exposes cell values but not presentation, so column widths are
+//! taken from each worksheet part's `<cols>` element. Sheet names are mapped
+//! to their parts through `xl/workbook.xml` and its relationships.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use zip::ZipArchive;
+
+/// Columns past this index are ignored; a `<col>` spanning to the last
+/// column (16384) is a whole-sheet default rather than a real width
+const MAX_WIDTH_COLUMNS: usize = 1024;
+
+/// Read custom column widths per sheet name, as `(column index, width)` pairs
+pub(crate) fn read_column_widths(path: &str) -> Result<HashMap<String, Vec<(usize, f64)>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open Excel file: {path}"))?;
+    let mut archive = ZipArchive::new(file).context("Not a valid XLSX package")?;
+
+    let workbook = read_part(&mut archive, "xl/workbook.xml")?;
+    let rels = read_part(&mut archive, "xl/_rels/workbook.xml.rels")?;
+
+    let targets: HashMap<String, String> = start_tags(&rels, "Relationship")
+        .into_iter()
+        .filter_map(|tag| Some((attr(tag, "Id")?, attr(tag, "Target")?)))
+        .collect();
+
+    let mut widths = HashMap::new();
+    for tag in start_tags(&workbook, "sheet") {
+        let (Some(name), Some(rel_id)) = (attr(tag, "name"), attr(tag, "r:id")) else {
+            continue;
+        };
+        let Some(target) = targets.get(&rel_id) else {
+            continue;
+        };
+        let part = match target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("xl/{target}"),
+        };
+        let Ok(sheet_xml) = read_part(&mut archive, &part) else {
+            continue;
+        };
+
+        let mut columns = Vec::new();
+        for col in start_tags(&sheet_xml, "col") {
+            let min = attr(col, "min").and_then(|v| v.parse::<usize>().ok());
+            let max = attr(col, "max").and_then(|v| v.parse::<usize>().ok());
+            let width = attr(col, "width").and_then(|v| v.parse::<f64>().ok());
+            if let (Some(min), Some(max), Some(width)) = (min, max, width)
+                && min >= 1
+            {
+                columns.extend((min - 1..max.min(MAX_WIDTH_COLUMNS)).map(|idx| (idx, width)));
+            }
+        }
+        widths.insert(unescape_xml(&name), columns);
+    }
+
+    Ok(widths)
+}
+
+fn read_part(archive: &mut ZipArchive<File>, name: &str) -> Result<String> {
+    let mut part = archive
+        .by_name(name)
+        .with_context(|| format!("Missing {name} in XLSX package"))?;
+    let mut xml = String::new();
+    part.read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+/// Attribute text of every `<name ...>` start tag in `xml`
+fn start_tags<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let open = format!("<{name}");
+    xml.match_indices(open.as_str())
+        .map(|(idx, _)| &xml[idx + open.len()..])
+        .filter(|rest| rest.starts_with([' ', '\t', '\r', '\n', '/', '>']))
+        .map(|rest| &rest[..rest.find('>').unwrap_or(rest.len())])
+        .collect()
+}
+
+fn attr(tag: &str, key: &str) -> Option<String> {
+    let needle = format!(" {key}=\"");
+    let start = tag.find(&needle)? + needle.len();
+    let end = start + tag[start..].find('"')?;
+    Some(tag[start..end].to_string())
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
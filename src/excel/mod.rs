@@ -1,18 +1,21 @@
 //! Excel file handling module
 
 mod chart;
+mod layout;
+mod ods_writer;
 mod reader;
 mod types;
 mod writer;
 pub mod xlsx_writer;
 
 #[allow(unused_imports)]
-pub use chart::{ChartConfig, DataChartType};
+pub use chart::{ChartConfig, ChartGrouping, DataChartType};
+pub(crate) use layout::read_column_widths;
 pub use reader::ExcelHandler;
 #[allow(unused_imports)]
 pub use types::{CellStyle, WriteOptions};
 pub use xlsx_writer::{
-    CellData, ConditionalFormat, ConditionalRule, RowData, Sparkline, SparklineGroup,
-    SparklineType, XlsxWriter,
+    CellData, Comment, ConditionalFormat, ConditionalRule, DataValidation, RowData, Sparkline,
+    SparklineGroup, SparklineType, XlsxWriter,
     streaming::StreamingXlsxWriter,
 };
@@ -0,0 +1,145 @@
+//! Minimal OpenDocument Spreadsheet (ODS) writer
+//!
+//! Like the XLSX writer, an `.ods` file is a ZIP archive of XML parts:
+//! an uncompressed `mimetype` entry first, then `content.xml` with the
+//! table, `meta.xml`, and `META-INF/manifest.xml` listing the parts.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use zip::ZipWriter;
+use zip::write::FileOptions;
+
+use super::reader::ExcelHandler;
+use super::xlsx_writer::escape_xml;
+
+const ODS_MIMETYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+
+impl ExcelHandler {
+    /// Write rows to a single-sheet ODS file
+    ///
+    /// Fields that parse as numbers are stored as floats, everything else as
+    /// text.
+    pub fn write_ods(
+        &self,
+        path: &str,
+        data: &[Vec<String>],
+        sheet_name: Option<&str>,
+    ) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create ODS file: {path}"))?;
+        let mut zip = ZipWriter::new(BufWriter::new(file));
+
+        // The mimetype entry must come first and be stored uncompressed
+        let opts = FileOptions::<()>::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("mimetype", opts)?;
+        zip.write_all(ODS_MIMETYPE.as_bytes())?;
+
+        let opts =
+            FileOptions::<()>::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("content.xml", opts)?;
+        zip.write_all(generate_content_xml(data, sheet_name.unwrap_or("Sheet1")).as_bytes())?;
+
+        let opts =
+            FileOptions::<()>::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("meta.xml", opts)?;
+        zip.write_all(generate_meta_xml().as_bytes())?;
+
+        let opts =
+            FileOptions::<()>::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("META-INF/manifest.xml", opts)?;
+        zip.write_all(generate_manifest_xml().as_bytes())?;
+
+        zip.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+/// Generate content.xml holding one table
+fn generate_content_xml(data: &[Vec<String>], sheet_name: &str) -> String {
+    let cell_count: usize = data.iter().map(Vec::len).sum();
+    let mut xml = String::with_capacity(cell_count * 64 + 1024);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2">"#);
+    xml.push_str(r#"<office:body><office:spreadsheet>"#);
+    xml.push_str(&format!(
+        r#"<table:table table:name="{}">"#,
+        escape_xml(sheet_name)
+    ));
+
+    let max_cols = data.iter().map(Vec::len).max().unwrap_or(0);
+    if max_cols > 0 {
+        xml.push_str(&format!(
+            r#"<table:table-column table:number-columns-repeated="{}"/>"#,
+            max_cols
+        ));
+    }
+
+    for row in data {
+        xml.push_str(r#"<table:table-row>"#);
+        for field in row {
+            if field.is_empty() {
+                xml.push_str(r#"<table:table-cell/>"#);
+            } else if let Some(num) = as_float(field) {
+                xml.push_str(&format!(
+                    r#"<table:table-cell office:value-type="float" office:value="{}"><text:p>{}</text:p></table:table-cell>"#,
+                    num,
+                    escape_xml(field)
+                ));
+            } else {
+                // Each line of a multi-line value is its own paragraph
+                xml.push_str(r#"<table:table-cell office:value-type="string">"#);
+                for line in field.split('\n') {
+                    xml.push_str(&format!(r#"<text:p>{}</text:p>"#, escape_xml(line)));
+                }
+                xml.push_str(r#"</table:table-cell>"#);
+            }
+        }
+        xml.push_str(r#"</table:table-row>"#);
+    }
+
+    xml.push_str(r#"</table:table>"#);
+    xml.push_str(r#"</office:spreadsheet></office:body>"#);
+    xml.push_str(r#"</office:document-content>"#);
+    xml
+}
+
+/// The value of `field` if it is written exactly as its `f64` prints
+///
+/// Zip codes ("02134"), exponents ("1e3"), explicit signs ("+5") and
+/// digit separators ("1_000") all parse, but would not read back as the
+/// same text, so they stay strings.
+fn as_float(field: &str) -> Option<f64> {
+    field
+        .parse::<f64>()
+        .ok()
+        .filter(|num| num.is_finite() && num.to_string() == field)
+}
+
+/// Generate meta.xml
+fn generate_meta_xml() -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<office:document-meta xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" office:version="1.2">"#,
+            r#"<office:meta><meta:generator>datacell/{}</meta:generator></office:meta>"#,
+            r#"</office:document-meta>"#,
+        ),
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+/// Generate META-INF/manifest.xml
+fn generate_manifest_xml() -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">"#,
+            r#"<manifest:file-entry manifest:full-path="/" manifest:version="1.2" manifest:media-type="{}"/>"#,
+            r#"<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>"#,
+            r#"<manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/>"#,
+            r#"</manifest:manifest>"#,
+        ),
+        ODS_MIMETYPE
+    )
+}
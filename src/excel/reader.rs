@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use calamine::{open_workbook, Ods, Reader, Xlsx};
+use calamine::{open_workbook, Data, Dimensions, Ods, Range, Reader, Xlsx};
+use chrono::NaiveTime;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
@@ -100,6 +101,20 @@ impl ExcelHandler {
     }
 
     pub fn read_with_sheet(&self, path: &str, sheet_name: Option<&str>) -> Result<String> {
+        self.read_with_sheet_typed(path, sheet_name, false)
+    }
+
+    /// Read a sheet as CSV text, rendering cells by their stored type
+    ///
+    /// With `date_as_iso`, date-formatted cells become `YYYY-MM-DD` (or
+    /// `YYYY-MM-DDTHH:MM:SS` when they carry a time) instead of the raw
+    /// Excel serial number. Booleans are always `true`/`false`.
+    pub fn read_with_sheet_typed(
+        &self,
+        path: &str,
+        sheet_name: Option<&str>,
+        date_as_iso: bool,
+    ) -> Result<String> {
         let mut workbook: Xlsx<_> =
             open_workbook(path).with_context(|| format!("Failed to open Excel file: {path}"))?;
 
@@ -112,15 +127,21 @@ impl ExcelHandler {
             .worksheet_range(sheet_name)
             .with_context(|| format!("Failed to read sheet: {}", sheet_name))?;
 
-        // Pre-allocate string capacity based on estimated size
-        let mut output = String::with_capacity(range.height() * range.width() * 10);
+        // Pre-allocate buffer capacity based on estimated size; the csv writer
+        // quotes cells containing commas, quotes or newlines
+        let buffer = Vec::with_capacity(range.height() * range.width() * 10);
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .terminator(csv::Terminator::Any(b'\n'))
+            .from_writer(buffer);
         for row in range.rows() {
-            let row_str: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
-            output.push_str(&row_str.join(","));
-            output.push('\n');
+            writer.write_record(row.iter().map(|cell| cell_to_string(cell, date_as_iso)))?;
         }
 
-        Ok(output)
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| anyhow::anyhow!("Failed to flush CSV buffer: {e}"))?;
+        String::from_utf8(bytes).context("Sheet content is not valid UTF-8")
     }
 
     pub fn parse_cell_reference(&self, cell: &str) -> Result<(u32, u16)> {
@@ -157,6 +178,21 @@ impl ExcelHandler {
         path: &str,
         range: &CellRange,
         sheet_name: Option<&str>,
+    ) -> Result<Vec<Vec<String>>> {
+        self.read_range_with_options(path, range, sheet_name, false)
+    }
+
+    /// Read a specific range, optionally filling merged cells
+    ///
+    /// Excel stores a merged region's value only in its top-left cell. With
+    /// `fill_merged`, every covered cell inside `range` gets that value too,
+    /// even when the top-left cell itself lies outside `range`.
+    pub fn read_range_with_options(
+        &self,
+        path: &str,
+        range: &CellRange,
+        sheet_name: Option<&str>,
+        fill_merged: bool,
     ) -> Result<Vec<Vec<String>>> {
         let mut workbook: Xlsx<_> =
             open_workbook(path).with_context(|| format!("Failed to open Excel file: {path}"))?;
@@ -191,6 +227,15 @@ impl ExcelHandler {
             result.push(row_data);
         }
 
+        if fill_merged {
+            let merged = workbook
+                .worksheet_merge_cells(sheet_name)
+                .transpose()
+                .with_context(|| format!("Failed to read merged cells: {sheet_name}"))?
+                .unwrap_or_default();
+            fill_merged_cells(&mut result, &ws_range, range, &merged);
+        }
+
         Ok(result)
     }
 
@@ -320,12 +365,7 @@ impl ExcelHandler {
                 return self.read_range(path, &cell_range, None);
             } else {
                 let csv_str = self.read_with_sheet(path, None)?;
-                let data = csv_str
-                    .lines()
-                    .filter(|l| !l.is_empty())
-                    .map(|l| l.split(',').map(|s| s.to_string()).collect())
-                    .collect();
-                return Ok(data);
+                return parse_csv_rows(&csv_str);
             }
         }
 
@@ -333,26 +373,98 @@ impl ExcelHandler {
     }
 }
 
+/// Render a cell as text, optionally converting date serials to ISO-8601
+fn cell_to_string(cell: &Data, date_as_iso: bool) -> String {
+    match cell {
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) if date_as_iso && dt.is_datetime() => match dt.as_datetime() {
+            Some(datetime) if datetime.time() == NaiveTime::MIN => {
+                datetime.format("%Y-%m-%d").to_string()
+            }
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => cell.to_string(),
+        },
+        _ => cell.to_string(),
+    }
+}
+
+/// Parse the CSV text produced by `read_with_sheet` into rows
+///
+/// Uses the csv reader so quoted commas and embedded newlines stay inside
+/// their cell.
+fn parse_csv_rows(csv_str: &str) -> Result<Vec<Vec<String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(csv_str.as_bytes());
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.context("Failed to parse sheet content")?;
+        rows.push(record.iter().map(|s| s.to_string()).collect());
+    }
+    Ok(rows)
+}
+
+/// Copy each merged region's top-left value into its covered cells in `rows`
+///
+/// `rows` holds the cells of `range`, which is relative to the start of
+/// `ws_range`; merged regions use absolute sheet coordinates.
+fn fill_merged_cells(
+    rows: &mut [Vec<String>],
+    ws_range: &Range<Data>,
+    range: &CellRange,
+    merged: &[Dimensions],
+) {
+    let (origin_row, origin_col) = ws_range.start().unwrap_or((0, 0));
+    let (origin_row, origin_col) = (origin_row as usize, origin_col as usize);
+
+    for region in merged {
+        // Regions entirely above or left of the data hold no values
+        if (region.end.0 as usize) < origin_row || (region.end.1 as usize) < origin_col {
+            continue;
+        }
+
+        let anchor = ws_range
+            .get_value(region.start)
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+
+        // Clip the region to the requested range, in sheet-relative indices
+        let first_row = (region.start.0 as usize)
+            .saturating_sub(origin_row)
+            .max(range.start_row);
+        let last_row = (region.end.0 as usize - origin_row).min(range.end_row);
+        let first_col = (region.start.1 as usize)
+            .saturating_sub(origin_col)
+            .max(range.start_col);
+        let last_col = (region.end.1 as usize - origin_col).min(range.end_col);
+
+        for row_idx in first_row..=last_row {
+            let Some(row) = rows.get_mut(row_idx - range.start_row) else {
+                break;
+            };
+            for col_idx in first_col..=last_col {
+                let cell = col_idx - range.start_col;
+                if row.len() <= cell {
+                    row.resize(cell + 1, String::new());
+                }
+                row[cell] = anchor.clone();
+            }
+        }
+    }
+}
+
 impl DataReader for ExcelHandler {
     fn read(&self, path: &str) -> Result<Vec<Vec<String>>> {
         let csv_str = self.read_with_sheet(path, None)?;
-        let result: Vec<Vec<String>> = csv_str
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(|l| l.split(',').map(|s| s.to_string()).collect())
-            .collect();
-        Ok(result)
+        parse_csv_rows(&csv_str)
     }
 
     fn read_with_headers(&self, path: &str) -> Result<Vec<Vec<String>>> {
         // Call the trait method explicitly to avoid conflict with inherent method
         let csv_str = self.read_with_sheet(path, None)?;
-        let result: Vec<Vec<String>> = csv_str
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(|l| l.split(',').map(|s| s.to_string()).collect())
-            .collect();
-        Ok(result)
+        parse_csv_rows(&csv_str)
     }
 
     fn read_range(&self, path: &str, range: &CellRange) -> Result<Vec<Vec<String>>> {
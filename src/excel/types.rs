@@ -59,6 +59,10 @@ pub struct WriteOptions {
     pub column_styles: Option<std::collections::HashMap<usize, CellStyle>>,
     /// Freeze first row
     pub freeze_header: bool,
+    /// Freeze the rows above and columns left of this zero-based
+    /// `(row, col)` cell, e.g. `(1, 2)` keeps the top row and first two
+    /// columns in view; takes precedence over `freeze_header`
+    pub freeze_panes: Option<(u32, u32)>,
     /// Enable auto-filter
     pub auto_filter: bool,
     /// Auto-fit column widths
@@ -73,6 +77,7 @@ impl Default for WriteOptions {
             header_style: CellStyle::header(),
             column_styles: None,
             freeze_header: false,
+            freeze_panes: None,
             auto_filter: false,
             auto_fit: true,
         }
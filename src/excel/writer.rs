@@ -5,7 +5,9 @@ use std::io::BufWriter;
 use super::reader::ExcelHandler;
 use super::types::WriteOptions;
 use super::xlsx_writer::{CellData, RowData, XlsxWriter};
+use crate::text_encoding::decode_reader;
 use crate::traits::{DataWriteOptions, DataWriter};
+use encoding_rs::UTF_8;
 
 impl ExcelHandler {
     pub fn write_from_csv(
@@ -14,10 +16,12 @@ impl ExcelHandler {
         excel_path: &str,
         sheet_name: Option<&str>,
     ) -> Result<()> {
+        let file =
+            File::open(csv_path).with_context(|| format!("Failed to open CSV file: {csv_path}"))?;
+        // Decoding drops a leading BOM so it doesn't end up in the first cell
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(false)
-            .from_path(csv_path)
-            .with_context(|| format!("Failed to open CSV file: {csv_path}"))?;
+            .from_reader(decode_reader(file, UTF_8));
 
         let mut writer = XlsxWriter::new();
         let name = sheet_name.unwrap_or("Sheet1");
@@ -50,6 +54,7 @@ impl ExcelHandler {
                                 .get(col_idx)
                                 .map(|c| match c {
                                     CellData::String(s) => s.len(),
+                                    CellData::Hyperlink { text, .. } => text.len(),
                                     CellData::Number(_) => 10,
                                     _ => 0,
                                 })
@@ -212,6 +217,10 @@ impl ExcelHandler {
 
 impl DataWriter for ExcelHandler {
     fn write(&self, path: &str, data: &[Vec<String>], options: DataWriteOptions) -> Result<()> {
+        if path.to_lowercase().ends_with(".ods") {
+            return self.write_ods(path, data, options.sheet_name.as_deref());
+        }
+
         let mut writer = XlsxWriter::new();
         let sheet_name = options.sheet_name.as_deref().unwrap_or("Sheet1");
         writer.add_sheet(sheet_name)?;
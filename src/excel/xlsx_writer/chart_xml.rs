@@ -8,7 +8,7 @@ use std::io::{Seek, Write};
 use zip::ZipWriter;
 use zip::write::FileOptions;
 
-use super::super::chart::{ChartConfig, DataChartType};
+use super::super::chart::{ChartConfig, ChartGrouping, DataChartType};
 use super::xml_gen::{col_num_to_letter, escape_xml};
 
 /// Default chart colors (Office theme palette)
@@ -58,7 +58,7 @@ pub fn generate_chart_xml(
             generate_scatter_chart(&mut xml, config, data, sheet_name, cat_col);
         }
         _ => {
-            generate_axis_chart(&mut xml, config, data, sheet_name, cat_col);
+            generate_axis_chart(&mut xml, config, data, sheet_name);
         }
     }
 
@@ -75,14 +75,74 @@ pub fn generate_chart_xml(
 }
 
 /// Generate bar/column/line/area chart XML
+///
+/// Series whose `series_types` entry is `Line` while the chart itself is not
+/// a line chart are drawn as a line over the main plot, on a secondary value
+/// axis (combo chart).
 fn generate_axis_chart(
     xml: &mut String,
     config: &ChartConfig,
     data: &[Vec<String>],
     sheet_name: &str,
-    cat_col: usize,
 ) {
-    let tag = match config.chart_type {
+    let (primary, overlay): (Vec<_>, Vec<_>) = config
+        .value_columns
+        .iter()
+        .copied()
+        .enumerate()
+        .partition(|&(ser_idx, _)| {
+            config.chart_type == DataChartType::Line
+                || config.series_types.get(ser_idx) != Some(&DataChartType::Line)
+        });
+
+    push_axis_plot(xml, config, config.chart_type, &primary, data, sheet_name, (1, 2));
+    if !overlay.is_empty() {
+        push_axis_plot(xml, config, DataChartType::Line, &overlay, data, sheet_name, (3, 4));
+    }
+
+    // Category axis
+    xml.push_str(r#"<c:catAx><c:axId val="1"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="b"/>"#);
+    if let Some(ref t) = config.x_axis_title {
+        xml.push_str(&format!(
+            r#"<c:title><c:tx><c:rich><a:bodyPr/><a:lstStyle/><a:p><a:r><a:t>{}</a:t></a:r></a:p></c:rich></c:tx></c:title>"#,
+            escape_xml(t)
+        ));
+    }
+    xml.push_str(r#"<c:crossAx val="2"/></c:catAx>"#);
+
+    // Value axis
+    xml.push_str(r#"<c:valAx><c:axId val="2"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="l"/>"#);
+    if let Some(ref t) = config.y_axis_title {
+        xml.push_str(&format!(
+            r#"<c:title><c:tx><c:rich><a:bodyPr/><a:lstStyle/><a:p><a:r><a:t>{}</a:t></a:r></a:p></c:rich></c:tx></c:title>"#,
+            escape_xml(t)
+        ));
+    }
+    xml.push_str(r#"<c:crossAx val="1"/></c:valAx>"#);
+
+    // Secondary axes for the line overlay: a hidden category axis and a
+    // value axis on the right
+    if !overlay.is_empty() {
+        xml.push_str(r#"<c:catAx><c:axId val="3"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="1"/><c:axPos val="b"/><c:crossAx val="4"/></c:catAx>"#);
+        xml.push_str(r#"<c:valAx><c:axId val="4"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="r"/><c:crossAx val="3"/><c:crosses val="max"/></c:valAx>"#);
+    }
+}
+
+/// Emit one `<c:barChart>`/`<c:lineChart>`/`<c:areaChart>` block
+///
+/// `series` holds `(series index, value column)` pairs; the series index keeps
+/// colors and ordering stable across the blocks of a combo chart.
+fn push_axis_plot(
+    xml: &mut String,
+    config: &ChartConfig,
+    chart_type: DataChartType,
+    series: &[(usize, usize)],
+    data: &[Vec<String>],
+    sheet_name: &str,
+    (cat_ax, val_ax): (u32, u32),
+) {
+    let cat_col = config.category_column;
+    let tag = match chart_type {
         DataChartType::Bar => "c:barChart",
         DataChartType::Column => "c:barChart",
         DataChartType::Line => "c:lineChart",
@@ -93,22 +153,35 @@ fn generate_axis_chart(
     xml.push_str(&format!("<{}>", tag));
 
     // Bar direction
-    if matches!(config.chart_type, DataChartType::Bar | DataChartType::Column) {
-        let dir = if config.chart_type == DataChartType::Bar {
+    let is_bar = !matches!(chart_type, DataChartType::Line | DataChartType::Area);
+    if is_bar {
+        let dir = if chart_type == DataChartType::Bar {
             "bar"
         } else {
             "col"
         };
         xml.push_str(&format!(r#"<c:barDir val="{}"/>"#, dir));
-        xml.push_str(r#"<c:grouping val="clustered"/>"#);
     }
-    if config.chart_type == DataChartType::Line {
-        xml.push_str(r#"<c:grouping val="standard"/>"#);
+    // The main plot's grouping; a line overlay is always standard
+    let grouping = if chart_type == config.chart_type {
+        config.grouping
+    } else {
+        ChartGrouping::Clustered
+    };
+    let grouping_val = match grouping {
+        ChartGrouping::Clustered if is_bar => "clustered",
+        ChartGrouping::Clustered => "standard",
+        ChartGrouping::Stacked => "stacked",
+        ChartGrouping::PercentStacked => "percentStacked",
+    };
+    // Area charts default to standard grouping, so only write it when stacking
+    if chart_type != DataChartType::Area || grouping != ChartGrouping::Clustered {
+        xml.push_str(&format!(r#"<c:grouping val="{}"/>"#, grouping_val));
     }
 
     let data_rows = if data.len() > 1 { data.len() - 1 } else { 0 };
 
-    for (ser_idx, &val_col) in config.value_columns.iter().enumerate() {
+    for &(ser_idx, val_col) in series {
         let color = series_color(config, ser_idx);
         xml.push_str(&format!(r#"<c:ser><c:idx val="{}"/><c:order val="{}"/>"#, ser_idx, ser_idx));
         xml.push_str(&format!(r#"<c:tx><c:strRef><c:f>'{}'!{}{}</c:f></c:strRef></c:tx>"#,
@@ -132,32 +205,17 @@ fn generate_axis_chart(
         xml.push_str(r#"</c:ser>"#);
     }
 
-    if matches!(config.chart_type, DataChartType::Line) {
+    if matches!(chart_type, DataChartType::Line) {
         xml.push_str(r#"<c:marker><c:symbol val="none"/></c:marker>"#);
     }
 
-    xml.push_str(r#"<c:axId val="1"/><c:axId val="2"/>"#);
-    xml.push_str(&format!("</{}>", tag));
-
-    // Category axis
-    xml.push_str(r#"<c:catAx><c:axId val="1"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="b"/>"#);
-    if let Some(ref t) = config.x_axis_title {
-        xml.push_str(&format!(
-            r#"<c:title><c:tx><c:rich><a:bodyPr/><a:lstStyle/><a:p><a:r><a:t>{}</a:t></a:r></a:p></c:rich></c:tx></c:title>"#,
-            escape_xml(t)
-        ));
+    // Stacked bars must fully overlap to sit on top of each other
+    if is_bar && grouping != ChartGrouping::Clustered {
+        xml.push_str(r#"<c:overlap val="100"/>"#);
     }
-    xml.push_str(r#"<c:crossAx val="2"/></c:catAx>"#);
 
-    // Value axis
-    xml.push_str(r#"<c:valAx><c:axId val="2"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="l"/>"#);
-    if let Some(ref t) = config.y_axis_title {
-        xml.push_str(&format!(
-            r#"<c:title><c:tx><c:rich><a:bodyPr/><a:lstStyle/><a:p><a:r><a:t>{}</a:t></a:r></a:p></c:rich></c:tx></c:title>"#,
-            escape_xml(t)
-        ));
-    }
-    xml.push_str(r#"<c:crossAx val="1"/></c:valAx>"#);
+    xml.push_str(&format!(r#"<c:axId val="{}"/><c:axId val="{}"/>"#, cat_ax, val_ax));
+    xml.push_str(&format!("</{}>", tag));
 }
 
 /// Generate pie/doughnut chart XML
@@ -334,88 +392,141 @@ fn generate_num_ref_inner(
     xml.push_str(r#"</c:numCache></c:numRef>"#);
 }
 
+/// Default chart position when no start cell is given: column E, row 2
+const DEFAULT_ANCHOR_COL: u32 = 4;
+const DEFAULT_ANCHOR_ROW: u32 = 1;
+
+/// Approximate default cell size in pixels, used to size chart anchors
+const CELL_WIDTH_PX: u32 = 64;
+const CELL_HEIGHT_PX: u32 = 20;
+
+/// Placement of one chart on a sheet's drawing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartAnchor {
+    /// Zero-based top-left row
+    pub row: u32,
+    /// Zero-based top-left column
+    pub col: u32,
+    /// Size in pixels
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ChartAnchor {
+    /// Zero-based (row, col) of the cell just past the chart's bottom-right corner
+    fn end(&self) -> (u32, u32) {
+        (
+            self.row + self.height.div_ceil(CELL_HEIGHT_PX).max(1),
+            self.col + self.width.div_ceil(CELL_WIDTH_PX).max(1),
+        )
+    }
+}
+
+/// Work out where each chart of a sheet goes
+///
+/// Charts with a `start_cell` are placed there; the rest are stacked below
+/// the previous chart, starting at E2, so they don't overlap.
+pub fn chart_anchors(charts: &[(ChartConfig, Vec<Vec<String>>)]) -> Result<Vec<ChartAnchor>> {
+    let mut next_row = DEFAULT_ANCHOR_ROW;
+    let mut anchors = Vec::with_capacity(charts.len());
+    for (config, _) in charts {
+        let (row, col) = match &config.start_cell {
+            Some(cell) => super::comments_xml::parse_cell_ref(cell)?,
+            None => (next_row, DEFAULT_ANCHOR_COL),
+        };
+        let anchor = ChartAnchor {
+            row,
+            col,
+            width: config.width,
+            height: config.height,
+        };
+        next_row = next_row.max(anchor.end().0 + 1);
+        anchors.push(anchor);
+    }
+    Ok(anchors)
+}
+
 /// Generate the drawing XML (xl/drawings/drawing{n}.xml)
-pub fn generate_drawing_xml(chart_rid: &str, width_emu: u64, height_emu: u64) -> String {
-    let mut xml = String::with_capacity(1024);
+///
+/// The chart at `anchors[i]` is referenced as `rId{i + 1}` in the drawing's
+/// relationships.
+pub fn generate_drawing_xml(anchors: &[ChartAnchor]) -> String {
+    let mut xml = String::with_capacity(256 + anchors.len() * 1024);
     xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
     xml.push_str(r#"<xdr:wsDr xmlns:xdr="http://schemas.openxmlformats.org/drawingml/2006/spreadsheetDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#);
-    xml.push_str(r#"<xdr:twoCellAnchor>"#);
-    // Position: start at E2, end based on size
-    xml.push_str(r#"<xdr:from><xdr:col>4</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>1</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from>"#);
-    xml.push_str(r#"<xdr:to><xdr:col>14</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>20</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:to>"#);
-    xml.push_str(r#"<xdr:graphicFrame macro="">"#);
-    xml.push_str(r#"<xdr:nvGraphicFramePr><xdr:cNvPr id="2" name="Chart 1"/><xdr:cNvGraphicFramePr/></xdr:nvGraphicFramePr>"#);
-    xml.push_str(r#"<xdr:xfrm><a:off x="0" y="0"/>"#);
-    xml.push_str(&format!(r#"<a:ext cx="{}" cy="{}"/>"#, width_emu, height_emu));
-    xml.push_str(r#"</xdr:xfrm>"#);
-    xml.push_str(r#"<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/chart">"#);
-    xml.push_str(&format!(r#"<c:chart xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" r:id="{}"/>"#, chart_rid));
-    xml.push_str(r#"</a:graphicData></a:graphic>"#);
-    xml.push_str(r#"</xdr:graphicFrame>"#);
-    xml.push_str(r#"<xdr:clientData/>"#);
-    xml.push_str(r#"</xdr:twoCellAnchor>"#);
+    for (i, anchor) in anchors.iter().enumerate() {
+        let (end_row, end_col) = anchor.end();
+        // Pixel to EMU conversion (1 pixel = 9525 EMU)
+        let width_emu = anchor.width as u64 * 9525;
+        let height_emu = anchor.height as u64 * 9525;
+
+        xml.push_str(r#"<xdr:twoCellAnchor>"#);
+        xml.push_str(&format!(r#"<xdr:from><xdr:col>{}</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>{}</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:from>"#, anchor.col, anchor.row));
+        xml.push_str(&format!(r#"<xdr:to><xdr:col>{}</xdr:col><xdr:colOff>0</xdr:colOff><xdr:row>{}</xdr:row><xdr:rowOff>0</xdr:rowOff></xdr:to>"#, end_col, end_row));
+        xml.push_str(r#"<xdr:graphicFrame macro="">"#);
+        xml.push_str(&format!(r#"<xdr:nvGraphicFramePr><xdr:cNvPr id="{}" name="Chart {}"/><xdr:cNvGraphicFramePr/></xdr:nvGraphicFramePr>"#, i + 2, i + 1));
+        xml.push_str(r#"<xdr:xfrm><a:off x="0" y="0"/>"#);
+        xml.push_str(&format!(r#"<a:ext cx="{}" cy="{}"/>"#, width_emu, height_emu));
+        xml.push_str(r#"</xdr:xfrm>"#);
+        xml.push_str(r#"<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/chart">"#);
+        xml.push_str(&format!(r#"<c:chart xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" r:id="rId{}"/>"#, i + 1));
+        xml.push_str(r#"</a:graphicData></a:graphic>"#);
+        xml.push_str(r#"</xdr:graphicFrame>"#);
+        xml.push_str(r#"<xdr:clientData/>"#);
+        xml.push_str(r#"</xdr:twoCellAnchor>"#);
+    }
     xml.push_str(r#"</xdr:wsDr>"#);
     xml
 }
 
 /// Add chart-related files to the ZIP archive for a specific sheet
+///
+/// Every chart of the sheet shares one drawing (`drawing{sheet_idx + 1}.xml`);
+/// charts are numbered workbook-wide starting at `first_chart_num`. The
+/// worksheet's own relationship to the drawing (`rId1`) is written by
+/// `xml_gen::add_worksheet_rels`.
 pub fn add_chart_to_zip<W: Write + Seek>(
     zip: &mut ZipWriter<W>,
     sheet_idx: usize,
-    config: &ChartConfig,
-    data: &[Vec<String>],
+    first_chart_num: usize,
+    charts: &[(ChartConfig, Vec<Vec<String>>)],
     sheet_name: &str,
 ) -> Result<()> {
-    let chart_idx = sheet_idx + 1;
-    let opts = FileOptions::<()>::default()
-        .compression_method(zip::CompressionMethod::Deflated);
-
-    // Pixel to EMU conversion (1 pixel = 9525 EMU)
-    let width_emu = config.width as u64 * 9525;
-    let height_emu = config.height as u64 * 9525;
+    let anchors = chart_anchors(charts)?;
 
-    // 1. xl/charts/chart{n}.xml
-    let chart_xml = generate_chart_xml(config, data, sheet_name);
-    zip.start_file(format!("xl/charts/chart{}.xml", chart_idx), opts)?;
-    zip.write_all(chart_xml.as_bytes())?;
+    // 1. xl/charts/chart{n}.xml, one per chart
+    for (i, (config, data)) in charts.iter().enumerate() {
+        let chart_xml = generate_chart_xml(config, data, sheet_name);
+        let opts = FileOptions::<()>::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file(format!("xl/charts/chart{}.xml", first_chart_num + i), opts)?;
+        zip.write_all(chart_xml.as_bytes())?;
+    }
 
     // 2. xl/drawings/drawing{n}.xml
-    let drawing_xml = generate_drawing_xml("rId1", width_emu, height_emu);
+    let drawing_xml = generate_drawing_xml(&anchors);
     let opts = FileOptions::<()>::default()
         .compression_method(zip::CompressionMethod::Deflated);
-    zip.start_file(format!("xl/drawings/drawing{}.xml", chart_idx), opts)?;
+    zip.start_file(format!("xl/drawings/drawing{}.xml", sheet_idx + 1), opts)?;
     zip.write_all(drawing_xml.as_bytes())?;
 
     // 3. xl/drawings/_rels/drawing{n}.xml.rels
-    let drawing_rels = format!(
-        concat!(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
-            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
-            r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart{}.xml"/>"#,
-            r#"</Relationships>"#,
-        ),
-        chart_idx
-    );
+    let mut drawing_rels = String::with_capacity(256 + charts.len() * 160);
+    drawing_rels.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    drawing_rels.push_str(r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#);
+    for i in 0..charts.len() {
+        drawing_rels.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart{}.xml"/>"#,
+            i + 1,
+            first_chart_num + i
+        ));
+    }
+    drawing_rels.push_str(r#"</Relationships>"#);
     let opts = FileOptions::<()>::default()
         .compression_method(zip::CompressionMethod::Deflated);
-    zip.start_file(format!("xl/drawings/_rels/drawing{}.xml.rels", chart_idx), opts)?;
+    zip.start_file(format!("xl/drawings/_rels/drawing{}.xml.rels", sheet_idx + 1), opts)?;
     zip.write_all(drawing_rels.as_bytes())?;
 
-    // 4. xl/worksheets/_rels/sheet{n}.xml.rels
-    let sheet_rels = format!(
-        concat!(
-            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
-            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
-            r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing" Target="../drawings/drawing{}.xml"/>"#,
-            r#"</Relationships>"#,
-        ),
-        chart_idx
-    );
-    let opts = FileOptions::<()>::default()
-        .compression_method(zip::CompressionMethod::Deflated);
-    zip.start_file(format!("xl/worksheets/_rels/sheet{}.xml.rels", chart_idx), opts)?;
-    zip.write_all(sheet_rels.as_bytes())?;
-
     Ok(())
 }
 
@@ -513,6 +624,91 @@ mod tests {
         assert!(xml.contains("c:areaChart"));
     }
 
+    #[test]
+    fn test_generate_combo_chart_xml() {
+        let data = vec![
+            vec!["Month".into(), "Revenue".into(), "Costs".into(), "Margin".into()],
+            vec!["Jan".into(), "100".into(), "60".into(), "0.4".into()],
+            vec!["Feb".into(), "120".into(), "70".into(), "0.42".into()],
+        ];
+        let config = ChartConfig {
+            value_columns: vec![1, 2, 3],
+            series_types: vec![DataChartType::Column, DataChartType::Column, DataChartType::Line],
+            ..Default::default()
+        };
+        let xml = generate_chart_xml(&config, &data, "Dashboard");
+
+        let bar = &xml[xml.find("<c:barChart>").unwrap()..xml.find("</c:barChart>").unwrap()];
+        assert_eq!(bar.matches("<c:ser>").count(), 2);
+        assert!(bar.contains(r#"<c:axId val="1"/><c:axId val="2"/>"#));
+
+        let line = &xml[xml.find("<c:lineChart>").unwrap()..xml.find("</c:lineChart>").unwrap()];
+        assert_eq!(line.matches("<c:ser>").count(), 1);
+        assert!(line.contains(r#"<c:idx val="2"/>"#));
+        assert!(line.contains("'Dashboard'!$D$2:$D$3"));
+        assert!(line.contains(r#"<c:axId val="3"/><c:axId val="4"/>"#));
+
+        // Secondary value axis on the right, crossing a hidden category axis
+        assert!(xml.contains(r#"<c:valAx><c:axId val="4"/>"#));
+        assert!(xml.contains(r#"<c:axPos val="r"/><c:crossAx val="3"/>"#));
+        assert!(xml.contains(r#"<c:catAx><c:axId val="3"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="1"/>"#));
+    }
+
+    #[test]
+    fn test_line_chart_ignores_line_series_types() {
+        let config = ChartConfig {
+            chart_type: DataChartType::Line,
+            value_columns: vec![1, 2],
+            series_types: vec![DataChartType::Line, DataChartType::Line],
+            ..Default::default()
+        };
+        let xml = generate_chart_xml(&config, &multi_series_data(), "Sheet1");
+        assert_eq!(xml.matches("<c:lineChart>").count(), 1);
+        assert!(!xml.contains(r#"<c:axId val="4"/>"#));
+    }
+
+    #[test]
+    fn test_clustered_column_chart_has_no_overlap() {
+        let xml = generate_chart_xml(&ChartConfig::default(), &multi_series_data(), "Sheet1");
+        assert!(xml.contains(r#"<c:grouping val="clustered"/>"#));
+        assert!(!xml.contains("c:overlap"));
+    }
+
+    #[test]
+    fn test_generate_stacked_column_chart_xml() {
+        let config = ChartConfig {
+            value_columns: vec![1, 2],
+            grouping: ChartGrouping::Stacked,
+            ..Default::default()
+        };
+        let xml = generate_chart_xml(&config, &multi_series_data(), "Budget");
+        assert!(xml.contains(r#"<c:barDir val="col"/><c:grouping val="stacked"/>"#));
+        assert!(xml.contains(r#"</c:ser><c:overlap val="100"/><c:axId val="1"/>"#));
+    }
+
+    #[test]
+    fn test_generate_percent_stacked_charts_xml() {
+        let config = ChartConfig {
+            chart_type: DataChartType::Bar,
+            value_columns: vec![1, 2],
+            grouping: ChartGrouping::PercentStacked,
+            ..Default::default()
+        };
+        let xml = generate_chart_xml(&config, &multi_series_data(), "Budget");
+        assert!(xml.contains(r#"<c:grouping val="percentStacked"/>"#));
+        assert!(xml.contains(r#"<c:overlap val="100"/>"#));
+
+        // Line and area charts stack without an overlap element
+        let config = ChartConfig {
+            chart_type: DataChartType::Area,
+            grouping: ChartGrouping::PercentStacked,
+            ..config
+        };
+        let xml = generate_chart_xml(&config, &multi_series_data(), "Budget");
+        assert!(xml.contains(r#"<c:areaChart><c:grouping val="percentStacked"/>"#));
+        assert!(!xml.contains("c:overlap"));
+    }
+
     #[test]
     fn test_generate_pie_chart_xml() {
         let config = ChartConfig {
@@ -627,19 +823,65 @@ mod tests {
         assert!(xml.contains("$B$2:$B$4"));
     }
 
+    fn anchor(row: u32, col: u32, width: u32, height: u32) -> ChartAnchor {
+        ChartAnchor {
+            row,
+            col,
+            width,
+            height,
+        }
+    }
+
     #[test]
     fn test_generate_drawing_xml() {
-        let xml = generate_drawing_xml("rId1", 5715000, 3810000);
+        let xml = generate_drawing_xml(&[anchor(1, 4, 600, 400)]);
         assert!(xml.contains("xdr:wsDr"));
         assert!(xml.contains("xdr:twoCellAnchor"));
         assert!(xml.contains(r#"r:id="rId1""#));
         assert!(xml.contains(r#"cx="5715000""#));
         assert!(xml.contains(r#"cy="3810000""#));
+        assert!(xml.contains("<xdr:from><xdr:col>4</xdr:col>"));
+    }
+
+    #[test]
+    fn test_drawing_xml_multiple_anchors() {
+        let xml = generate_drawing_xml(&[anchor(1, 4, 600, 400), anchor(1, 15, 300, 300)]);
+        assert_eq!(xml.matches("<xdr:twoCellAnchor>").count(), 2);
+        assert!(xml.contains(r#"r:id="rId2""#));
+        assert!(xml.contains(r#"<xdr:cNvPr id="3" name="Chart 2"/>"#));
+        assert!(xml.contains("<xdr:from><xdr:col>15</xdr:col>"));
+    }
+
+    #[test]
+    fn test_chart_anchors_stack_without_start_cell() {
+        let explicit = ChartConfig {
+            start_cell: Some("P2".to_string()),
+            ..Default::default()
+        };
+        let charts = vec![
+            (ChartConfig::default(), sample_data()),
+            (ChartConfig::default(), sample_data()),
+            (explicit, sample_data()),
+        ];
+        let anchors = chart_anchors(&charts).unwrap();
+        assert_eq!(anchors[0], anchor(1, 4, 600, 400));
+        // 400px tall is 20 rows; the next chart starts one row below
+        assert_eq!(anchors[1], anchor(22, 4, 600, 400));
+        assert_eq!(anchors[2], anchor(1, 15, 600, 400));
+    }
+
+    #[test]
+    fn test_chart_anchors_reject_bad_start_cell() {
+        let config = ChartConfig {
+            start_cell: Some("nowhere".to_string()),
+            ..Default::default()
+        };
+        assert!(chart_anchors(&[(config, sample_data())]).is_err());
     }
 
     #[test]
     fn test_drawing_xml_structure() {
-        let xml = generate_drawing_xml("rId1", 100, 200);
+        let xml = generate_drawing_xml(&[anchor(0, 0, 100, 200)]);
         assert!(xml.starts_with(r#"<?xml version="1.0""#));
         assert!(xml.contains("xdr:graphicFrame"));
         assert!(xml.contains("xdr:clientData"));
@@ -0,0 +1,167 @@
+//! Cell comment (note) XML generation for XLSX files
+//!
+//! A sheet with comments needs two parts: `xl/comments{n}.xml` holding the
+//! text, and a legacy VML drawing that positions each note box.
+
+use anyhow::Result;
+
+use super::xml_gen::escape_xml;
+
+/// A note attached to a single cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// Cell reference such as `B2`
+    pub cell: String,
+    pub author: String,
+    pub text: String,
+}
+
+/// Parse a cell reference like `B2` into zero-based (row, column)
+pub fn parse_cell_ref(cell: &str) -> Result<(u32, u32)> {
+    let cell = cell.trim().to_ascii_uppercase();
+    let split = cell
+        .find(|c: char| c.is_ascii_digit())
+        .filter(|&i| i > 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid cell reference: '{}'", cell))?;
+    let (letters, digits) = cell.split_at(split);
+
+    if !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        anyhow::bail!("Invalid cell reference: '{}'", cell);
+    }
+    let col = letters
+        .bytes()
+        .try_fold(0u32, |acc, b| acc.checked_mul(26)?.checked_add((b - b'A' + 1) as u32))
+        .ok_or_else(|| anyhow::anyhow!("Invalid cell reference: '{}'", cell))?;
+    let row: u32 = digits
+        .parse()
+        .ok()
+        .filter(|&r| r > 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid cell reference: '{}'", cell))?;
+
+    Ok((row - 1, col - 1))
+}
+
+/// Generate `xl/comments{n}.xml` for a sheet's comments
+pub fn generate_comments_xml(comments: &[Comment]) -> String {
+    let mut authors: Vec<&str> = Vec::new();
+    for comment in comments {
+        if !authors.contains(&comment.author.as_str()) {
+            authors.push(&comment.author);
+        }
+    }
+
+    let mut xml = String::with_capacity(256 + comments.len() * 128);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    xml.push_str(r#"<comments xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#);
+    xml.push_str(r#"<authors>"#);
+    for author in &authors {
+        xml.push_str(&format!(r#"<author>{}</author>"#, escape_xml(author)));
+    }
+    xml.push_str(r#"</authors>"#);
+
+    xml.push_str(r#"<commentList>"#);
+    for comment in comments {
+        let author_id = authors
+            .iter()
+            .position(|a| *a == comment.author)
+            .unwrap_or(0);
+        xml.push_str(&format!(
+            r#"<comment ref="{}" authorId="{}"><text><r><t xml:space="preserve">{}</t></r></text></comment>"#,
+            escape_xml(&comment.cell.to_ascii_uppercase()),
+            author_id,
+            escape_xml(&comment.text)
+        ));
+    }
+    xml.push_str(r#"</commentList>"#);
+    xml.push_str(r#"</comments>"#);
+
+    xml
+}
+
+/// Generate the VML drawing that anchors each note box next to its cell
+///
+/// `sheet_idx` keeps shape ids unique across the workbook.
+pub fn generate_vml_drawing(comments: &[Comment], sheet_idx: usize) -> Result<String> {
+    let mut xml = String::with_capacity(1024 + comments.len() * 768);
+    xml.push_str(r#"<xml xmlns:v="urn:schemas-microsoft-com:vml" xmlns:o="urn:schemas-microsoft-com:office:office" xmlns:x="urn:schemas-microsoft-com:office:excel">"#);
+    xml.push_str(&format!(
+        r#"<o:shapelayout v:ext="edit"><o:idmap v:ext="edit" data="{}"/></o:shapelayout>"#,
+        sheet_idx + 1
+    ));
+    xml.push_str(r#"<v:shapetype id="_x0000_t202" coordsize="21600,21600" o:spt="202" path="m,l,21600r21600,l21600,xe">"#);
+    xml.push_str(r#"<v:stroke joinstyle="miter"/><v:path gradientshapeok="t" o:connecttype="rect"/>"#);
+    xml.push_str(r#"</v:shapetype>"#);
+
+    for (n, comment) in comments.iter().enumerate() {
+        let (row, col) = parse_cell_ref(&comment.cell)?;
+        let shape_id = 1024 * (sheet_idx + 1) + n + 1;
+        xml.push_str(&format!(
+            r##"<v:shape id="_x0000_s{}" type="#_x0000_t202" style="position:absolute;margin-left:59.25pt;margin-top:1.5pt;width:108pt;height:59.25pt;z-index:{};visibility:hidden" fillcolor="#ffffe1" o:insetmode="auto">"##,
+            shape_id,
+            n + 1
+        ));
+        xml.push_str(r##"<v:fill color2="#ffffe1"/><v:shadow on="t" color="black" obscured="t"/><v:path o:connecttype="none"/>"##);
+        xml.push_str(r#"<v:textbox style="mso-direction-alt:auto"><div style="text-align:left"></div></v:textbox>"#);
+        // Anchor: box spans from one column right of the cell, three columns wide
+        xml.push_str(&format!(
+            r#"<x:ClientData ObjectType="Note"><x:MoveWithCells/><x:SizeWithCells/><x:Anchor>{}, 15, {}, 10, {}, 15, {}, 4</x:Anchor><x:AutoFill>False</x:AutoFill><x:Row>{}</x:Row><x:Column>{}</x:Column></x:ClientData>"#,
+            col + 1,
+            row,
+            col + 3,
+            row + 4,
+            row,
+            col
+        ));
+        xml.push_str(r#"</v:shape>"#);
+    }
+    xml.push_str(r#"</xml>"#);
+
+    Ok(xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(cell: &str, author: &str, text: &str) -> Comment {
+        Comment {
+            cell: cell.to_string(),
+            author: author.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_cell_ref() {
+        assert_eq!(parse_cell_ref("A1").unwrap(), (0, 0));
+        assert_eq!(parse_cell_ref("b3").unwrap(), (2, 1));
+        assert_eq!(parse_cell_ref("AA10").unwrap(), (9, 26));
+        assert!(parse_cell_ref("A0").is_err());
+        assert!(parse_cell_ref("12").is_err());
+        assert!(parse_cell_ref("A1B").is_err());
+        assert!(parse_cell_ref("").is_err());
+    }
+
+    #[test]
+    fn test_comments_xml_dedupes_authors() {
+        let comments = vec![
+            comment("A1", "Ann", "Check <this>"),
+            comment("B2", "Bob", "Looks fine"),
+            comment("C3", "Ann", "Again"),
+        ];
+        let xml = generate_comments_xml(&comments);
+        assert_eq!(xml.matches("<author>").count(), 2);
+        assert!(xml.contains(r#"<comment ref="A1" authorId="0">"#));
+        assert!(xml.contains(r#"<comment ref="B2" authorId="1">"#));
+        assert!(xml.contains(r#"<comment ref="C3" authorId="0">"#));
+        assert!(xml.contains("Check &lt;this&gt;"));
+    }
+
+    #[test]
+    fn test_vml_anchors_to_cell() {
+        let xml = generate_vml_drawing(&[comment("C5", "Ann", "Note")], 1).unwrap();
+        assert!(xml.contains("<x:Row>4</x:Row><x:Column>2</x:Column>"));
+        assert!(xml.contains(r#"data="2""#));
+        assert!(xml.contains(r#"id="_x0000_s2049""#));
+    }
+}
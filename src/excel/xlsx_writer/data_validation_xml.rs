@@ -0,0 +1,192 @@
+//! Data validation XML generation for XLSX files
+//!
+//! Supports: dropdown lists, whole number ranges, and decimal ranges.
+
+use anyhow::Result;
+
+use super::xml_gen::escape_xml;
+
+/// Excel's limit on the length of an inline list formula
+const MAX_LIST_FORMULA_LEN: usize = 255;
+
+/// Constraint applied to the cells of a range
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataValidation {
+    /// Dropdown list of allowed values
+    List(Vec<String>),
+    /// Integer between `min` and `max` (inclusive)
+    WholeNumber { min: i64, max: i64 },
+    /// Decimal between `min` and `max` (inclusive)
+    Decimal { min: f64, max: f64 },
+}
+
+impl DataValidation {
+    /// Check the constraint can be stored in a worksheet
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            DataValidation::List(values) => {
+                if values.is_empty() {
+                    anyhow::bail!("List validation needs at least one value");
+                }
+                if let Some(value) = values.iter().find(|v| v.contains(',')) {
+                    anyhow::bail!("List validation values cannot contain commas: '{}'", value);
+                }
+                if list_formula(values).len() > MAX_LIST_FORMULA_LEN {
+                    anyhow::bail!(
+                        "List validation values exceed {} characters",
+                        MAX_LIST_FORMULA_LEN
+                    );
+                }
+            }
+            DataValidation::WholeNumber { min, max } => {
+                if min > max {
+                    anyhow::bail!("Validation minimum {} exceeds maximum {}", min, max);
+                }
+            }
+            DataValidation::Decimal { min, max } => {
+                if !min.is_finite() || !max.is_finite() || min > max {
+                    anyhow::bail!("Invalid decimal validation range {}..{}", min, max);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Inline list formula: values joined by commas inside double quotes, with
+/// embedded quotes doubled
+fn list_formula(values: &[String]) -> String {
+    format!("\"{}\"", values.join(",").replace('"', "\"\""))
+}
+
+/// Generate the `<dataValidations>` block for a worksheet
+///
+/// Each entry pairs an `sqref` range such as `B2:B100` with its constraint.
+/// Returns an empty string when there are no validations.
+pub fn generate_data_validations_xml(validations: &[(String, DataValidation)]) -> String {
+    if validations.is_empty() {
+        return String::new();
+    }
+
+    let mut xml = format!(r#"<dataValidations count="{}">"#, validations.len());
+    for (range, validation) in validations {
+        let (kind, formula1, formula2) = match validation {
+            DataValidation::List(values) => ("list", list_formula(values), None),
+            DataValidation::WholeNumber { min, max } => {
+                ("whole", min.to_string(), Some(max.to_string()))
+            }
+            DataValidation::Decimal { min, max } => {
+                ("decimal", min.to_string(), Some(max.to_string()))
+            }
+        };
+
+        let operator = if formula2.is_some() {
+            r#" operator="between""#
+        } else {
+            ""
+        };
+        xml.push_str(&format!(
+            r#"<dataValidation type="{}"{} allowBlank="1" showInputMessage="1" showErrorMessage="1" sqref="{}">"#,
+            kind,
+            operator,
+            escape_xml(range)
+        ));
+        xml.push_str(&format!(
+            r#"<formula1>{}</formula1>"#,
+            escape_xml(&formula1)
+        ));
+        if let Some(formula2) = formula2 {
+            xml.push_str(&format!(
+                r#"<formula2>{}</formula2>"#,
+                escape_xml(&formula2)
+            ));
+        }
+        xml.push_str(r#"</dataValidation>"#);
+    }
+    xml.push_str(r#"</dataValidations>"#);
+
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_validation() {
+        let validations = vec![(
+            "B2:B100".to_string(),
+            DataValidation::List(vec!["Open".to_string(), "Closed".to_string()]),
+        )];
+        let xml = generate_data_validations_xml(&validations);
+        assert!(xml.contains(r#"<dataValidations count="1">"#));
+        assert!(xml.contains(r#"type="list""#));
+        assert!(xml.contains(r#"sqref="B2:B100""#));
+        assert!(xml.contains("<formula1>&quot;Open,Closed&quot;</formula1>"));
+        assert!(!xml.contains("formula2"));
+    }
+
+    #[test]
+    fn test_list_validation_quotes_are_doubled() {
+        let values = vec![r#"6" pipe"#.to_string(), "tube".to_string()];
+        assert_eq!(list_formula(&values), r#""6"" pipe,tube""#);
+    }
+
+    #[test]
+    fn test_number_validations() {
+        let validations = vec![
+            (
+                "C2:C10".to_string(),
+                DataValidation::WholeNumber { min: 1, max: 10 },
+            ),
+            (
+                "D2:D10".to_string(),
+                DataValidation::Decimal { min: 0.0, max: 0.5 },
+            ),
+        ];
+        let xml = generate_data_validations_xml(&validations);
+        assert!(xml.contains(r#"count="2""#));
+        assert!(xml.contains(r#"type="whole" operator="between""#));
+        assert!(xml.contains("<formula1>1</formula1><formula2>10</formula2>"));
+        assert!(xml.contains(r#"type="decimal" operator="between""#));
+        assert!(xml.contains("<formula1>0</formula1><formula2>0.5</formula2>"));
+    }
+
+    #[test]
+    fn test_empty_validations() {
+        assert_eq!(generate_data_validations_xml(&[]), "");
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_constraints() {
+        assert!(DataValidation::List(vec![]).validate().is_err());
+        assert!(
+            DataValidation::List(vec!["a,b".to_string()])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataValidation::List(vec!["x".repeat(300)])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataValidation::WholeNumber { min: 5, max: 1 }
+                .validate()
+                .is_err()
+        );
+        assert!(
+            DataValidation::Decimal {
+                min: f64::NAN,
+                max: 1.0
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            DataValidation::Decimal { min: 0.0, max: 1.0 }
+                .validate()
+                .is_ok()
+        );
+    }
+}
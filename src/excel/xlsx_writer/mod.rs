@@ -5,11 +5,15 @@
 //!
 //! # Supported Features
 //! - Multiple sheets with validation (max 31 char name, invalid characters)
-//! - Cell data types: String, Number, Formula, Empty
+//! - Cell data types: String, Number, NumberFmt, Formula, Hyperlink, Empty
 //! - Column width configuration (auto-fit and manual)
-//! - Freeze headers (freeze top row)
+//! - Freeze panes (top row, or any leading rows and columns)
 //! - Auto-filter for tables
 //! - Basic styling (bold, alignment, borders, fills)
+//! - Number formats (built-in ids or custom codes such as `#,##0.00`)
+//! - Data validation (dropdown lists, number ranges)
+//! - Hyperlinks (external URLs and links to other sheets)
+//! - Cell comments (notes)
 //! - XML escaping for special characters
 //!
 //! # Current Limitations
@@ -19,7 +23,6 @@
 //! - **Conditional formatting**: Not implemented - requires conditional formatting XML
 //! - **Advanced Excel features**: Some features require additional XML namespaces
 //! - **Merged cells**: Not implemented
-//! - **Data validation**: Not implemented
 //! - **Pivot tables**: Not implemented
 
 use anyhow::Result;
@@ -29,16 +32,21 @@ use zip::ZipWriter;
 mod types;
 mod xml_gen;
 pub mod chart_xml;
+pub mod comments_xml;
 pub mod cond_fmt_xml;
+pub mod data_validation_xml;
 pub mod sparkline_xml;
 pub mod streaming;
 
 pub use types::{CellData, RowData};
+pub use comments_xml::Comment;
 pub use cond_fmt_xml::{ConditionalFormat, ConditionalRule};
+pub use data_validation_xml::DataValidation;
 pub use sparkline_xml::{Sparkline, SparklineGroup, SparklineType};
+pub(crate) use xml_gen::escape_xml;
 
 use super::types::WriteOptions;
-use types::SheetData;
+use types::{FIRST_CUSTOM_NUM_FMT_ID, NumberStyles, SheetData};
 use xml_gen::*;
 
 use super::chart::{ChartConfig};
@@ -47,8 +55,10 @@ use super::chart::{ChartConfig};
 pub struct XlsxWriter {
     pub sheets: Vec<SheetData>,
     options: WriteOptions,
-    /// Chart config per sheet index (None = no chart for that sheet)
-    chart_configs: Vec<Option<(ChartConfig, Vec<Vec<String>>)>>,
+    /// Charts per sheet index (empty = no chart for that sheet)
+    chart_configs: Vec<Vec<(ChartConfig, Vec<Vec<String>>)>>,
+    /// Custom number format codes registered with `add_number_format`
+    number_formats: Vec<String>,
 }
 
 impl XlsxWriter {
@@ -57,6 +67,7 @@ impl XlsxWriter {
             sheets: Vec::new(),
             options: WriteOptions::default(),
             chart_configs: Vec::new(),
+            number_formats: Vec::new(),
         }
     }
 
@@ -65,16 +76,32 @@ impl XlsxWriter {
             sheets: Vec::new(),
             options,
             chart_configs: Vec::new(),
+            number_formats: Vec::new(),
         }
     }
 
-    /// Set a chart for the current (last added) sheet
+    /// Set a chart for the current (last added) sheet, replacing any charts
+    /// already added to it
     pub fn set_chart(&mut self, config: ChartConfig, data: Vec<Vec<String>>) {
+        let charts = self.current_sheet_charts();
+        charts.clear();
+        charts.push((config, data));
+    }
+
+    /// Add another chart to the current (last added) sheet
+    ///
+    /// Use `ChartConfig::start_cell` to position it; charts without one are
+    /// stacked below each other.
+    pub fn add_chart(&mut self, config: ChartConfig, data: Vec<Vec<String>>) {
+        self.current_sheet_charts().push((config, data));
+    }
+
+    fn current_sheet_charts(&mut self) -> &mut Vec<(ChartConfig, Vec<Vec<String>>)> {
         let sheet_idx = if self.sheets.is_empty() { 0 } else { self.sheets.len() - 1 };
         while self.chart_configs.len() <= sheet_idx {
-            self.chart_configs.push(None);
+            self.chart_configs.push(Vec::new());
         }
-        self.chart_configs[sheet_idx] = Some((config, data));
+        &mut self.chart_configs[sheet_idx]
     }
 
     /// Add a new sheet to the workbook
@@ -96,10 +123,60 @@ impl XlsxWriter {
             column_widths: Vec::new(),
             conditional_formats: Vec::new(),
             sparkline_groups: Vec::new(),
+            data_validations: Vec::new(),
+            comments: Vec::new(),
         });
         Ok(())
     }
 
+    /// Register a custom number format code such as `"0.0%"` and return the
+    /// id to use in `CellData::NumberFmt`
+    pub fn add_number_format(&mut self, code: &str) -> usize {
+        custom_format_id(&mut self.number_formats, code)
+    }
+
+    /// Collect the number formats used by cells and column styles
+    fn number_styles(&self) -> Result<NumberStyles> {
+        let mut styles = NumberStyles {
+            custom_formats: self.number_formats.clone(),
+            ..Default::default()
+        };
+
+        if let Some(column_styles) = &self.options.column_styles {
+            for (&col_idx, style) in column_styles {
+                let Some(code) = &style.number_format else {
+                    continue;
+                };
+                let fmt_id = custom_format_id(&mut styles.custom_formats, code);
+                styles.column_fmt_ids.insert(col_idx, fmt_id);
+            }
+        }
+
+        let cell_fmt_ids = self
+            .sheets
+            .iter()
+            .flat_map(|sheet| &sheet.rows)
+            .flat_map(|row| &row.cells)
+            .filter_map(|cell| match cell {
+                CellData::NumberFmt { fmt_id, .. } => Some(*fmt_id),
+                _ => None,
+            });
+        let mut fmt_ids: Vec<usize> = cell_fmt_ids
+            .chain(styles.column_fmt_ids.values().copied())
+            .collect();
+        fmt_ids.sort_unstable();
+        fmt_ids.dedup();
+        if let Some(&id) = fmt_ids
+            .iter()
+            .find(|&&id| id >= FIRST_CUSTOM_NUM_FMT_ID + styles.custom_formats.len())
+        {
+            anyhow::bail!("Unknown number format id {}", id);
+        }
+        styles.fmt_ids = fmt_ids;
+
+        Ok(styles)
+    }
+
     /// Add conditional formatting to the current sheet
     pub fn add_conditional_format(&mut self, format: ConditionalFormat) {
         if let Some(sheet) = self.sheets.last_mut() {
@@ -107,6 +184,32 @@ impl XlsxWriter {
         }
     }
 
+    /// Add data validation to a range (e.g. `B2:B100`) of the current sheet
+    pub fn add_data_validation(&mut self, range: &str, validation: DataValidation) -> Result<()> {
+        validation.validate()?;
+        let sheet = self
+            .sheets
+            .last_mut()
+            .ok_or_else(|| anyhow::anyhow!("Add a sheet before adding data validation"))?;
+        sheet.data_validations.push((range.to_string(), validation));
+        Ok(())
+    }
+
+    /// Attach a comment (note) to a cell, e.g. `B2`, of the current sheet
+    pub fn add_comment(&mut self, cell: &str, author: &str, text: &str) -> Result<()> {
+        comments_xml::parse_cell_ref(cell)?;
+        let sheet = self
+            .sheets
+            .last_mut()
+            .ok_or_else(|| anyhow::anyhow!("Add a sheet before adding comments"))?;
+        sheet.comments.push(Comment {
+            cell: cell.trim().to_ascii_uppercase(),
+            author: author.to_string(),
+            text: text.to_string(),
+        });
+        Ok(())
+    }
+
     /// Add a sparkline group to the current sheet
     pub fn add_sparkline_group(&mut self, group: SparklineGroup) {
         if let Some(sheet) = self.sheets.last_mut() {
@@ -157,16 +260,20 @@ impl XlsxWriter {
 
     /// Save the workbook to a writer
     pub fn save<W: Write + Seek>(&self, mut writer: W) -> Result<()> {
+        let number_styles = self.number_styles()?;
         let mut zip = ZipWriter::new(&mut writer);
 
         // Determine which sheets have charts
-        let chart_flags: Vec<bool> = (0..self.sheets.len())
-            .map(|i| self.chart_configs.get(i).and_then(|c| c.as_ref()).is_some())
+        let chart_counts: Vec<usize> = (0..self.sheets.len())
+            .map(|i| self.chart_configs.get(i).map_or(0, Vec::len))
             .collect();
+        let chart_flags: Vec<bool> = chart_counts.iter().map(|&n| n > 0).collect();
         let _has_any_chart = chart_flags.iter().any(|&f| f);
 
-        // Add [Content_Types].xml (with chart content types if needed)
-        add_content_types_ext(&mut zip, self.sheets.len(), &chart_flags)?;
+        let comment_flags: Vec<bool> = self.sheets.iter().map(|s| !s.comments.is_empty()).collect();
+
+        // Add [Content_Types].xml (with chart and comment content types if needed)
+        add_content_types_ext(&mut zip, self.sheets.len(), &chart_counts, &comment_flags)?;
 
         // Add _rels/.rels
         add_rels(&mut zip)?;
@@ -178,17 +285,21 @@ impl XlsxWriter {
         add_workbook_rels(&mut zip, self.sheets.len())?;
 
         // Add xl/styles.xml
-        add_styles(&mut zip)?;
+        add_styles(&mut zip, &number_styles)?;
 
         // Add worksheets
         for (idx, sheet) in self.sheets.iter().enumerate() {
-            add_worksheet(&mut zip, idx, sheet, &self.options, chart_flags[idx])?;
+            add_worksheet(&mut zip, idx, sheet, &self.options, &number_styles, chart_flags[idx])?;
+            add_worksheet_rels(&mut zip, idx, sheet, chart_flags[idx])?;
+            add_comments(&mut zip, idx, sheet)?;
         }
 
         // Add chart files for sheets that have charts
+        let mut next_chart_num = 1;
         for (idx, sheet) in self.sheets.iter().enumerate() {
-            if let Some(Some((config, data))) = self.chart_configs.get(idx) {
-                chart_xml::add_chart_to_zip(&mut zip, idx, config, data, &sheet.name)?;
+            if let Some(charts) = self.chart_configs.get(idx).filter(|c| !c.is_empty()) {
+                chart_xml::add_chart_to_zip(&mut zip, idx, next_chart_num, charts, &sheet.name)?;
+                next_chart_num += charts.len();
             }
         }
 
@@ -200,6 +311,18 @@ impl XlsxWriter {
     }
 }
 
+/// numFmtId of `code`, appending it to `formats` if it is new
+fn custom_format_id(formats: &mut Vec<String>, code: &str) -> usize {
+    let pos = match formats.iter().position(|c| c == code) {
+        Some(pos) => pos,
+        None => {
+            formats.push(code.to_string());
+            formats.len() - 1
+        }
+    };
+    FIRST_CUSTOM_NUM_FMT_ID + pos
+}
+
 impl Default for XlsxWriter {
     fn default() -> Self {
         Self::new()
@@ -316,6 +439,7 @@ mod tests {
             header_style: CellStyle::header(),
             column_styles: None,
             freeze_header: true,
+            freeze_panes: None,
             auto_filter: true,
             auto_fit: true,
         };
@@ -489,6 +613,7 @@ mod tests {
             header_style: CellStyle::default(),
             column_styles: None,
             freeze_header: true,
+            freeze_panes: None,
             auto_filter: false,
             auto_fit: false,
         };
@@ -511,6 +636,43 @@ mod tests {
         assert_eq!(&output[0..4], b"PK\x03\x04");
     }
 
+    #[test]
+    fn test_freeze_panes_rows_and_columns() {
+        let options = WriteOptions {
+            freeze_header: true,
+            freeze_panes: Some((1, 2)),
+            ..Default::default()
+        };
+        let mut writer = XlsxWriter::with_options(options);
+        writer.add_sheet("Wide").unwrap();
+        writer.add_data(&[vec!["id".to_string(), "name".to_string(), "q1".to_string()]]);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        // freeze_panes wins over freeze_header
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<pane xSplit="2" ySplit="1" topLeftCell="C2" activePane="bottomRight" state="frozen"/>"#));
+        assert!(sheet_xml.contains(r#"<selection pane="bottomRight" activeCell="C2" sqref="C2"/>"#));
+    }
+
+    #[test]
+    fn test_freeze_panes_columns_only() {
+        let options = WriteOptions {
+            freeze_panes: Some((0, 1)),
+            ..Default::default()
+        };
+        let mut writer = XlsxWriter::with_options(options);
+        writer.add_sheet("Sheet1").unwrap();
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<pane xSplit="1" topLeftCell="B1" activePane="topRight" state="frozen"/>"#));
+        assert!(!sheet_xml.contains("ySplit"));
+    }
+
     #[test]
     fn test_save_workbook_with_auto_filter() {
         let options = WriteOptions {
@@ -519,6 +681,7 @@ mod tests {
             header_style: CellStyle::default(),
             column_styles: None,
             freeze_header: false,
+            freeze_panes: None,
             auto_filter: true,
             auto_fit: false,
         };
@@ -543,6 +706,310 @@ mod tests {
         assert_eq!(&output[0..4], b"PK\x03\x04");
     }
 
+    #[test]
+    fn test_save_workbook_with_data_validation() {
+        use std::io::Read;
+
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Tasks").unwrap();
+        writer.add_data(&[
+            vec!["Task".to_string(), "Status".to_string()],
+            vec!["Write docs".to_string(), "Open".to_string()],
+        ]);
+        writer
+            .add_data_validation(
+                "B2:B100",
+                DataValidation::List(vec![
+                    "Open".to_string(),
+                    "In Progress".to_string(),
+                    "Done".to_string(),
+                ]),
+            )
+            .unwrap();
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        let mut sheet_xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet_xml)
+            .unwrap();
+
+        assert!(sheet_xml.contains(r#"sqref="B2:B100""#));
+        assert!(sheet_xml.contains("<formula1>&quot;Open,In Progress,Done&quot;</formula1>"));
+        // dataValidations must precede pageMargins in the worksheet schema
+        assert!(
+            sheet_xml.find("<dataValidations").unwrap() < sheet_xml.find("<pageMargins").unwrap()
+        );
+    }
+
+    fn read_zip_entry(buffer: Cursor<Vec<u8>>, name: &str) -> String {
+        use std::io::Read;
+
+        let mut archive = zip::ZipArchive::new(buffer).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_save_workbook_with_hyperlinks() {
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Report").unwrap();
+
+        let mut row = RowData::new();
+        row.add_string("Docs");
+        row.add_hyperlink("Homepage", "https://example.com/?a=1&b=2");
+        row.add_hyperlink("Totals", "#Summary!A1");
+        writer.add_row(row);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let rels = read_zip_entry(buffer.clone(), "xl/worksheets/_rels/sheet1.xml.rels");
+        assert!(
+            rels.contains(r#"Target="https://example.com/?a=1&amp;b=2" TargetMode="External""#)
+        );
+        assert!(rels.contains(r#"Id="rId1""#));
+        // Internal links don't get a relationship
+        assert!(!rels.contains("Summary"));
+
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<hyperlink ref="B1" r:id="rId1"/>"#));
+        assert!(sheet_xml.contains(r#"<hyperlink ref="C1" location="Summary!A1"/>"#));
+        assert!(sheet_xml.contains("<t>Homepage</t>"));
+        assert!(sheet_xml.contains("xmlns:r="));
+    }
+
+    #[test]
+    fn test_hyperlink_relationships_follow_chart_drawing() {
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Sales").unwrap();
+        let data = vec![
+            vec!["Month".to_string(), "Total".to_string()],
+            vec!["Jan".to_string(), "10".to_string()],
+        ];
+        writer.add_data(&data);
+        let mut row = RowData::new();
+        row.add_hyperlink("Source", "https://example.com/sales");
+        writer.add_row(row);
+        writer.set_chart(ChartConfig::default(), data);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let rels = read_zip_entry(buffer.clone(), "xl/worksheets/_rels/sheet1.xml.rels");
+        assert!(rels.contains(r#"Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing""#));
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink""#));
+
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<hyperlink ref="A3" r:id="rId2"/>"#));
+    }
+
+    #[test]
+    fn test_save_workbook_with_comments() {
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Report").unwrap();
+        let mut row = RowData::new();
+        row.add_string("Total");
+        row.add_hyperlink("Source", "https://example.com");
+        writer.add_row(row);
+        writer.add_comment("a1", "Ann", "Checked & signed off").unwrap();
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let comments = read_zip_entry(buffer.clone(), "xl/comments1.xml");
+        assert!(comments.contains(r#"<comment ref="A1" authorId="0">"#));
+        assert!(comments.contains("Checked &amp; signed off"));
+
+        let vml = read_zip_entry(buffer.clone(), "xl/drawings/vmlDrawing1.vml");
+        assert!(vml.contains(r#"ObjectType="Note""#));
+
+        let content_types = read_zip_entry(buffer.clone(), "[Content_Types].xml");
+        assert!(content_types.contains(r#"<Default Extension="vml""#));
+        assert!(content_types.contains(r#"PartName="/xl/comments1.xml""#));
+
+        // Comment parts come before the external hyperlink
+        let rels = read_zip_entry(buffer.clone(), "xl/worksheets/_rels/sheet1.xml.rels");
+        assert!(rels.contains(r#"Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/vmlDrawing" Target="../drawings/vmlDrawing1.vml""#));
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments1.xml""#));
+        assert!(rels.contains(r#"Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink""#));
+
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<legacyDrawing r:id="rId1"/>"#));
+        assert!(sheet_xml.contains(r#"<hyperlink ref="B1" r:id="rId3"/>"#));
+    }
+
+    #[test]
+    fn test_save_workbook_with_number_formats() {
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Prices").unwrap();
+        let currency = writer.add_number_format("#,##0.00");
+        let percent = writer.add_number_format("0.0%");
+        assert_eq!(currency, 164);
+        assert_eq!(percent, 165);
+        assert_eq!(writer.add_number_format("#,##0.00"), currency);
+
+        let mut row = RowData::new();
+        row.add_number_fmt(1234.5, currency);
+        row.add_number_fmt(0.25, percent);
+        row.add_number_fmt(0.5, 9); // built-in "0%"
+        row.add_number(7.0);
+        writer.add_row(row);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let styles = read_zip_entry(buffer.clone(), "xl/styles.xml");
+        assert!(styles.contains(r##"<numFmts count="2"><numFmt numFmtId="164" formatCode="#,##0.00"/><numFmt numFmtId="165" formatCode="0.0%"/></numFmts>"##));
+        // Base styles 0-2, then one per format in id order: 9, 164, 165
+        assert!(styles.contains(r#"<cellXfs count="6">"#));
+        assert!(styles.contains(r#"<xf numFmtId="9" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#));
+
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<c r="A1" s="4" t="n"><v>1234.5</v></c>"#));
+        assert!(sheet_xml.contains(r#"<c r="B1" s="5" t="n"><v>0.25</v></c>"#));
+        assert!(sheet_xml.contains(r#"<c r="C1" s="3" t="n"><v>0.5</v></c>"#));
+        assert!(sheet_xml.contains(r#"<c r="D1" t="n"><v>7</v></c>"#));
+    }
+
+    #[test]
+    fn test_column_style_number_format() {
+        let mut column_styles = std::collections::HashMap::new();
+        column_styles.insert(
+            1,
+            CellStyle {
+                number_format: Some("$#,##0.00".to_string()),
+                ..Default::default()
+            },
+        );
+        let options = WriteOptions {
+            column_styles: Some(column_styles),
+            ..Default::default()
+        };
+        let mut writer = XlsxWriter::with_options(options);
+        writer.add_sheet("Sheet1").unwrap();
+        writer.add_data(&[
+            vec!["Item".to_string(), "Price".to_string()],
+            vec!["Tea".to_string(), "3.5".to_string()],
+        ]);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let styles = read_zip_entry(buffer.clone(), "xl/styles.xml");
+        assert!(styles.contains(r#"<numFmt numFmtId="164" formatCode="$#,##0.00"/>"#));
+
+        let sheet_xml = read_zip_entry(buffer, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<c r="B2" s="3" t="n"><v>3.5</v></c>"#));
+        // The text header in the same column is left alone
+        assert!(sheet_xml.contains(r#"<c r="B1" t="inlineStr">"#));
+    }
+
+    #[test]
+    fn test_unknown_number_format_id() {
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Sheet1").unwrap();
+        let mut row = RowData::new();
+        row.add_number_fmt(1.0, 170);
+        writer.add_row(row);
+        assert!(writer.save(Cursor::new(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn test_add_comment_rejects_bad_input() {
+        let mut writer = XlsxWriter::new();
+        assert!(writer.add_comment("A1", "Ann", "Note").is_err());
+        writer.add_sheet("Sheet1").unwrap();
+        assert!(writer.add_comment("1A", "Ann", "Note").is_err());
+    }
+
+    #[test]
+    fn test_multiple_charts_on_one_sheet() {
+        use super::super::chart::DataChartType;
+
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("Sales").unwrap();
+        let data = vec![
+            vec!["Region".to_string(), "Total".to_string()],
+            vec!["North".to_string(), "10".to_string()],
+            vec!["South".to_string(), "20".to_string()],
+        ];
+        writer.add_data(&data);
+        writer.add_chart(ChartConfig::default(), data.clone());
+        let pie = ChartConfig {
+            chart_type: DataChartType::Pie,
+            start_cell: Some("P2".to_string()),
+            ..Default::default()
+        };
+        writer.add_chart(pie, data);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let archive = zip::ZipArchive::new(buffer.clone()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert!(names.contains(&"xl/charts/chart1.xml"));
+        assert!(names.contains(&"xl/charts/chart2.xml"));
+        assert!(names.contains(&"xl/drawings/drawing1.xml"));
+
+        let chart2 = read_zip_entry(buffer.clone(), "xl/charts/chart2.xml");
+        assert!(chart2.contains("c:pieChart"));
+
+        let drawing = read_zip_entry(buffer.clone(), "xl/drawings/drawing1.xml");
+        assert_eq!(drawing.matches("<xdr:twoCellAnchor>").count(), 2);
+        assert!(drawing.contains("<xdr:from><xdr:col>15</xdr:col>"));
+
+        let rels = read_zip_entry(buffer.clone(), "xl/drawings/_rels/drawing1.xml.rels");
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart2.xml""#));
+
+        let content_types = read_zip_entry(buffer, "[Content_Types].xml");
+        assert!(content_types.contains(r#"PartName="/xl/charts/chart2.xml""#));
+    }
+
+    #[test]
+    fn test_charts_are_numbered_across_sheets() {
+        let data = vec![
+            vec!["Month".to_string(), "Total".to_string()],
+            vec!["Jan".to_string(), "10".to_string()],
+        ];
+        let mut writer = XlsxWriter::new();
+        writer.add_sheet("First").unwrap();
+        writer.set_chart(ChartConfig::default(), data.clone());
+        // set_chart replaces rather than appends
+        writer.set_chart(ChartConfig::default(), data.clone());
+        writer.add_sheet("Second").unwrap();
+        writer.add_chart(ChartConfig::default(), data);
+
+        let mut buffer = Cursor::new(Vec::new());
+        writer.save(&mut buffer).unwrap();
+
+        let archive = zip::ZipArchive::new(buffer.clone()).unwrap();
+        let charts = archive
+            .file_names()
+            .filter(|n| n.starts_with("xl/charts/"))
+            .count();
+        assert_eq!(charts, 2);
+
+        let rels = read_zip_entry(buffer, "xl/drawings/_rels/drawing2.xml.rels");
+        assert!(rels.contains(r#"Target="../charts/chart2.xml""#));
+    }
+
+    #[test]
+    fn test_add_data_validation_without_sheet() {
+        let mut writer = XlsxWriter::new();
+        let validation = DataValidation::WholeNumber { min: 0, max: 10 };
+        assert!(writer.add_data_validation("A1:A10", validation).is_err());
+    }
+
     #[test]
     fn test_empty_cells_handling() {
         let mut writer = XlsxWriter::new();
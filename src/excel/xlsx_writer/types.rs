@@ -1,11 +1,32 @@
 //! Data types for XLSX writer
 
+use std::collections::HashMap;
+
+/// First numFmtId available for custom number formats; lower ids are Excel
+/// built-ins such as 4 (`#,##0.00`) and 10 (`0.00%`)
+pub const FIRST_CUSTOM_NUM_FMT_ID: usize = 164;
+
+/// Number of fixed entries in styles.xml `cellXfs` (normal, header, centered)
+pub const BASE_CELL_XFS: usize = 3;
+
 /// Cell data type for writing
 #[derive(Debug, Clone)]
 pub enum CellData {
     String(String),
     Number(f64),
+    /// Number displayed with the number format `fmt_id`, either a built-in id
+    /// or one returned by `XlsxWriter::add_number_format`
+    NumberFmt {
+        value: f64,
+        fmt_id: usize,
+    },
     Formula(String),
+    /// Text linking to `url`; a leading `#` marks a location in the
+    /// workbook, e.g. `#Summary!A1`
+    Hyperlink {
+        text: String,
+        url: String,
+    },
     Empty,
 }
 
@@ -30,10 +51,21 @@ impl RowData {
         self.cells.push(CellData::Number(value));
     }
 
+    pub fn add_number_fmt(&mut self, value: f64, fmt_id: usize) {
+        self.cells.push(CellData::NumberFmt { value, fmt_id });
+    }
+
     pub fn add_formula(&mut self, formula: &str) {
         self.cells.push(CellData::Formula(formula.to_string()));
     }
 
+    pub fn add_hyperlink(&mut self, text: &str, url: &str) {
+        self.cells.push(CellData::Hyperlink {
+            text: text.to_string(),
+            url: url.to_string(),
+        });
+    }
+
     pub fn add_empty(&mut self) {
         self.cells.push(CellData::Empty);
     }
@@ -46,4 +78,35 @@ pub struct SheetData {
     pub column_widths: Vec<f64>,
     pub conditional_formats: Vec<super::cond_fmt_xml::ConditionalFormat>,
     pub sparkline_groups: Vec<super::sparkline_xml::SparklineGroup>,
+    /// `(sqref, constraint)` pairs
+    pub data_validations: Vec<(String, super::data_validation_xml::DataValidation)>,
+    pub comments: Vec<super::comments_xml::Comment>,
+}
+
+/// Number formats used by a workbook and the cell styles that apply them
+#[derive(Debug, Default)]
+pub struct NumberStyles {
+    /// Custom format codes; entry `i` has numFmtId `FIRST_CUSTOM_NUM_FMT_ID + i`
+    pub custom_formats: Vec<String>,
+    /// numFmtIds applied to cells; entry `i` is cell style `BASE_CELL_XFS + i`
+    pub fmt_ids: Vec<usize>,
+    /// numFmtId applied to plain numbers in a column (by index)
+    pub column_fmt_ids: HashMap<usize, usize>,
+}
+
+impl NumberStyles {
+    /// Cell style index (`s` attribute) that applies `fmt_id`
+    pub fn style_index(&self, fmt_id: usize) -> Option<usize> {
+        self.fmt_ids
+            .iter()
+            .position(|&id| id == fmt_id)
+            .map(|pos| BASE_CELL_XFS + pos)
+    }
+
+    /// Cell style index for a plain number in column `col_idx`
+    pub fn column_style_index(&self, col_idx: usize) -> Option<usize> {
+        self.column_fmt_ids
+            .get(&col_idx)
+            .and_then(|&fmt_id| self.style_index(fmt_id))
+    }
 }
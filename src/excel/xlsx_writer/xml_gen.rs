@@ -8,7 +8,7 @@ use std::io::{Seek, Write};
 use zip::ZipWriter;
 use zip::write::FileOptions;
 
-use super::types::{CellData, SheetData};
+use super::types::{CellData, FIRST_CUSTOM_NUM_FMT_ID, NumberStyles, SheetData};
 use super::WriteOptions;
 
 /// Escape special XML characters
@@ -45,21 +45,26 @@ pub fn add_content_types<W: Write + Seek>(
     zip: &mut ZipWriter<W>,
     sheet_count: usize,
 ) -> Result<()> {
-    let no_charts = vec![false; sheet_count];
-    add_content_types_ext(zip, sheet_count, &no_charts)
+    let no_charts = vec![0; sheet_count];
+    let no_comments = vec![false; sheet_count];
+    add_content_types_ext(zip, sheet_count, &no_charts, &no_comments)
 }
 
-/// Add [Content_Types].xml with optional chart/drawing content types
+/// Add [Content_Types].xml with optional chart/drawing and comment content types
 pub fn add_content_types_ext<W: Write + Seek>(
     zip: &mut ZipWriter<W>,
     sheet_count: usize,
-    chart_flags: &[bool],
+    chart_counts: &[usize],
+    comment_flags: &[bool],
 ) -> Result<()> {
     let mut xml = String::with_capacity(1024);
     xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
     xml.push_str(r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#);
     xml.push_str(r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#);
     xml.push_str(r#"<Default Extension="xml" ContentType="application/xml"/>"#);
+    if comment_flags.iter().any(|&f| f) {
+        xml.push_str(r#"<Default Extension="vml" ContentType="application/vnd.openxmlformats-officedocument.vmlDrawing"/>"#);
+    }
     xml.push_str(r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#);
     for idx in 0..sheet_count {
         xml.push_str(&format!(
@@ -71,7 +76,17 @@ pub fn add_content_types_ext<W: Write + Seek>(
     xml.push_str(r#"<Override PartName="/xl/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#);
 
     // Chart and drawing content types
-    add_chart_content_types(&mut xml, sheet_count, chart_flags);
+    add_chart_content_types(&mut xml, sheet_count, chart_counts);
+
+    // Comment parts (their VML drawings are covered by the vml default)
+    for (idx, has_comments) in comment_flags.iter().enumerate() {
+        if *has_comments {
+            xml.push_str(&format!(
+                r#"<Override PartName="/xl/comments{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.comments+xml"/>"#,
+                idx + 1
+            ));
+        }
+    }
 
     xml.push_str(r#"</Types>"#);
 
@@ -159,11 +174,15 @@ pub fn add_workbook_rels<W: Write + Seek>(
 }
 
 /// Add xl/styles.xml
-pub fn add_styles<W: Write + Seek>(zip: &mut ZipWriter<W>) -> Result<()> {
-    let xml = concat!(
+pub fn add_styles<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    number_styles: &NumberStyles,
+) -> Result<()> {
+    let head = concat!(
         r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
         r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
-        r#"<numFmts count="0"/>"#,
+    );
+    let fonts_to_style_xfs = concat!(
         // Font 0: normal, Font 1: bold
         r#"<fonts count="2">"#,
         r#"<font><name val="Calibri"/><family val="2"/><color theme="1"/><sz val="11"/><scheme val="minor"/></font>"#,
@@ -181,17 +200,50 @@ pub fn add_styles<W: Write + Seek>(zip: &mut ZipWriter<W>) -> Result<()> {
         r#"<border><left style="thin"><color auto="1"/></left><right style="thin"><color auto="1"/></right><top style="thin"><color auto="1"/></top><bottom style="thin"><color auto="1"/></bottom><diagonal/></border>"#,
         r#"</borders>"#,
         r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
-        // xf 0: normal, xf 1: bold+fill+border (header), xf 2: centered
-        r#"<cellXfs count="3">"#,
+    );
+    // xf 0: normal, xf 1: bold+fill+border (header), xf 2: centered
+    let base_xfs = concat!(
         r#"<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#,
         r#"<xf numFmtId="0" fontId="1" fillId="2" borderId="1" xfId="0" applyFont="1" applyFill="1" applyBorder="1"><alignment horizontal="center"/></xf>"#,
         r#"<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"><alignment horizontal="center"/></xf>"#,
-        r#"</cellXfs>"#,
+    );
+    let tail = concat!(
         r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#,
         r#"<tableStyles count="0" defaultTableStyle="TableStyleMedium9" defaultPivotStyle="PivotStyleLight16"/>"#,
         r#"</styleSheet>"#,
     );
 
+    let mut xml = String::with_capacity(4096);
+    xml.push_str(head);
+    if number_styles.custom_formats.is_empty() {
+        xml.push_str(r#"<numFmts count="0"/>"#);
+    } else {
+        xml.push_str(&format!(r#"<numFmts count="{}">"#, number_styles.custom_formats.len()));
+        for (i, code) in number_styles.custom_formats.iter().enumerate() {
+            xml.push_str(&format!(
+                r#"<numFmt numFmtId="{}" formatCode="{}"/>"#,
+                FIRST_CUSTOM_NUM_FMT_ID + i,
+                escape_xml(code)
+            ));
+        }
+        xml.push_str(r#"</numFmts>"#);
+    }
+    xml.push_str(fonts_to_style_xfs);
+    // One extra xf per number format applied to cells, after the base styles
+    xml.push_str(&format!(
+        r#"<cellXfs count="{}">"#,
+        super::types::BASE_CELL_XFS + number_styles.fmt_ids.len()
+    ));
+    xml.push_str(base_xfs);
+    for fmt_id in &number_styles.fmt_ids {
+        xml.push_str(&format!(
+            r#"<xf numFmtId="{}" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/>"#,
+            fmt_id
+        ));
+    }
+    xml.push_str(r#"</cellXfs>"#);
+    xml.push_str(tail);
+
     let opts = FileOptions::<()>::default()
         .compression_method(zip::CompressionMethod::Deflated);
     zip.start_file("xl/styles.xml", opts)?;
@@ -247,6 +299,7 @@ pub fn add_worksheet<W: Write + Seek>(
     idx: usize,
     sheet: &SheetData,
     options: &WriteOptions,
+    number_styles: &NumberStyles,
     has_chart: bool,
 ) -> Result<()> {
     let max_row = sheet.rows.len();
@@ -272,10 +325,33 @@ pub fn add_worksheet<W: Write + Seek>(
 
     // Sheet views
     xml.push_str(r#"<sheetViews>"#);
-    if options.freeze_header {
+    let freeze = options
+        .freeze_panes
+        .or(options.freeze_header.then_some((1, 0)))
+        .filter(|&(row, col)| row > 0 || col > 0);
+    if let Some((row, col)) = freeze {
+        let top_left = format!("{}{}", col_num_to_letter(col as usize + 1), row + 1);
+        let pane = match (row > 0, col > 0) {
+            (true, true) => "bottomRight",
+            (true, false) => "bottomLeft",
+            _ => "topRight",
+        };
+        let mut splits = String::new();
+        if col > 0 {
+            splits.push_str(&format!(r#" xSplit="{}""#, col));
+        }
+        if row > 0 {
+            splits.push_str(&format!(r#" ySplit="{}""#, row));
+        }
         xml.push_str(r#"<sheetView workbookViewId="0">"#);
-        xml.push_str(r#"<pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/>"#);
-        xml.push_str(r#"<selection pane="bottomLeft" activeCell="A2" sqref="A2"/>"#);
+        xml.push_str(&format!(
+            r#"<pane{} topLeftCell="{}" activePane="{}" state="frozen"/>"#,
+            splits, top_left, pane
+        ));
+        xml.push_str(&format!(
+            r#"<selection pane="{}" activeCell="{}" sqref="{}"/>"#,
+            pane, top_left, top_left
+        ));
         xml.push_str(r#"</sheetView>"#);
     } else {
         xml.push_str(r#"<sheetView workbookViewId="0">"#);
@@ -317,9 +393,22 @@ pub fn add_worksheet<W: Write + Seek>(
                     ));
                 }
                 CellData::Number(n) => {
+                    let style = number_styles
+                        .column_style_index(col_idx)
+                        .map(|s| format!(r#" s="{}""#, s))
+                        .unwrap_or_default();
+                    xml.push_str(&format!(
+                        r#"<c r="{}"{} t="n"><v>{}</v></c>"#,
+                        cell_ref, style, n
+                    ));
+                }
+                CellData::NumberFmt { value, fmt_id } => {
+                    let style = number_styles.style_index(*fmt_id).ok_or_else(|| {
+                        anyhow::anyhow!("Unknown number format id {} in cell {}", fmt_id, cell_ref)
+                    })?;
                     xml.push_str(&format!(
-                        r#"<c r="{}" t="n"><v>{}</v></c>"#,
-                        cell_ref, n
+                        r#"<c r="{}" s="{}" t="n"><v>{}</v></c>"#,
+                        cell_ref, style, value
                     ));
                 }
                 CellData::Formula(f) => {
@@ -330,6 +419,13 @@ pub fn add_worksheet<W: Write + Seek>(
                         escape_xml(formula)
                     ));
                 }
+                CellData::Hyperlink { text, .. } => {
+                    xml.push_str(&format!(
+                        r#"<c r="{}" t="inlineStr"><is><t>{}</t></is></c>"#,
+                        cell_ref,
+                        escape_xml(text)
+                    ));
+                }
                 CellData::Empty => {}
             }
         }
@@ -353,12 +449,48 @@ pub fn add_worksheet<W: Write + Seek>(
         xml.push_str(&cf_xml);
     }
 
+    // Data validation
+    xml.push_str(&super::data_validation_xml::generate_data_validations_xml(
+        &sheet.data_validations,
+    ));
+
+    // Hyperlinks: external URLs go through the sheet's relationships,
+    // links within the workbook use `location`
+    let rel_ids = SheetRelIds::new(sheet, has_chart);
+    let hyperlinks = sheet_hyperlinks(sheet);
+    let has_external_links = hyperlinks.iter().any(|(_, url)| !url.starts_with('#'));
+    if !hyperlinks.is_empty() {
+        xml.push_str(r#"<hyperlinks>"#);
+        let mut rel_id = rel_ids.first_hyperlink;
+        for (cell_ref, url) in &hyperlinks {
+            if let Some(location) = url.strip_prefix('#') {
+                xml.push_str(&format!(
+                    r#"<hyperlink ref="{}" location="{}"/>"#,
+                    cell_ref,
+                    escape_xml(location)
+                ));
+            } else {
+                xml.push_str(&format!(
+                    r#"<hyperlink ref="{}" r:id="rId{}"/>"#,
+                    cell_ref, rel_id
+                ));
+                rel_id += 1;
+            }
+        }
+        xml.push_str(r#"</hyperlinks>"#);
+    }
+
     // Page margins (required by Excel/Numbers)
     xml.push_str(r#"<pageMargins left="0.75" right="0.75" top="1" bottom="1" header="0.5" footer="0.5"/>"#);
 
     // Drawing reference (for charts)
-    if has_chart {
-        xml.push_str(r#"<drawing r:id="rId1"/>"#);
+    if let Some(rel_id) = rel_ids.drawing {
+        xml.push_str(&format!(r#"<drawing r:id="rId{}"/>"#, rel_id));
+    }
+
+    // Comment boxes are positioned by a legacy VML drawing
+    if let Some(rel_id) = rel_ids.vml_drawing {
+        xml.push_str(&format!(r#"<legacyDrawing r:id="rId{}"/>"#, rel_id));
     }
 
     // Sparklines (must come after pageMargins, before closing worksheet)
@@ -370,8 +502,8 @@ pub fn add_worksheet<W: Write + Seek>(
 
     xml.push_str(r#"</worksheet>"#);
 
-    // If we have a drawing reference, we need the r: namespace
-    if has_chart {
+    // Drawing, comment and external hyperlink references need the r: namespace
+    if has_chart || has_external_links || !sheet.comments.is_empty() {
         // Replace the worksheet opening tag to include the r: namespace
         xml = xml.replacen(
             r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
@@ -387,19 +519,153 @@ pub fn add_worksheet<W: Write + Seek>(
     Ok(())
 }
 
+/// Hyperlink cells of a sheet as (cell reference, url), in row-major order
+fn sheet_hyperlinks(sheet: &SheetData) -> Vec<(String, &str)> {
+    let mut links = Vec::new();
+    for (row_idx, row) in sheet.rows.iter().enumerate() {
+        for (col_idx, cell) in row.cells.iter().enumerate() {
+            if let CellData::Hyperlink { url, .. } = cell {
+                let cell_ref = format!("{}{}", col_num_to_letter(col_idx + 1), row_idx + 1);
+                links.push((cell_ref, url.as_str()));
+            }
+        }
+    }
+    links
+}
+
+/// Relationship ids of a worksheet's parts, in the order they appear in its
+/// .rels file: chart drawing, comment VML drawing, comments, then one per
+/// external hyperlink
+struct SheetRelIds {
+    drawing: Option<usize>,
+    vml_drawing: Option<usize>,
+    comments: Option<usize>,
+    first_hyperlink: usize,
+}
+
+impl SheetRelIds {
+    fn new(sheet: &SheetData, has_chart: bool) -> Self {
+        let mut next = 1;
+        let mut take = |present: bool| {
+            present.then(|| {
+                next += 1;
+                next - 1
+            })
+        };
+        let drawing = take(has_chart);
+        let vml_drawing = take(!sheet.comments.is_empty());
+        let comments = take(!sheet.comments.is_empty());
+        Self {
+            drawing,
+            vml_drawing,
+            comments,
+            first_hyperlink: next,
+        }
+    }
+}
+
+/// Add xl/worksheets/_rels/sheet{n}.xml.rels for the chart drawing, comments
+/// and external hyperlinks, if the sheet has any
+pub fn add_worksheet_rels<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    idx: usize,
+    sheet: &SheetData,
+    has_chart: bool,
+) -> Result<()> {
+    let rel_ids = SheetRelIds::new(sheet, has_chart);
+    let external_urls: Vec<&str> = sheet_hyperlinks(sheet)
+        .into_iter()
+        .map(|(_, url)| url)
+        .filter(|url| !url.starts_with('#'))
+        .collect();
+    if rel_ids.first_hyperlink == 1 && external_urls.is_empty() {
+        return Ok(());
+    }
+
+    let mut xml = String::with_capacity(512);
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    xml.push_str(r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#);
+    if let Some(rel_id) = rel_ids.drawing {
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing" Target="../drawings/drawing{}.xml"/>"#,
+            rel_id,
+            idx + 1
+        ));
+    }
+    if let Some(rel_id) = rel_ids.vml_drawing {
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/vmlDrawing" Target="../drawings/vmlDrawing{}.vml"/>"#,
+            rel_id,
+            idx + 1
+        ));
+    }
+    if let Some(rel_id) = rel_ids.comments {
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="../comments{}.xml"/>"#,
+            rel_id,
+            idx + 1
+        ));
+    }
+    for (offset, url) in external_urls.iter().enumerate() {
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="{}" TargetMode="External"/>"#,
+            rel_ids.first_hyperlink + offset,
+            escape_xml(url)
+        ));
+    }
+    xml.push_str(r#"</Relationships>"#);
+
+    let opts = FileOptions::<()>::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(format!("xl/worksheets/_rels/sheet{}.xml.rels", idx + 1), opts)?;
+    zip.write_all(xml.as_bytes())?;
+    Ok(())
+}
+
+/// Add xl/comments{n}.xml and its VML drawing for a sheet with comments
+pub fn add_comments<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    idx: usize,
+    sheet: &SheetData,
+) -> Result<()> {
+    if sheet.comments.is_empty() {
+        return Ok(());
+    }
+
+    let xml = super::comments_xml::generate_comments_xml(&sheet.comments);
+    let opts = FileOptions::<()>::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(format!("xl/comments{}.xml", idx + 1), opts)?;
+    zip.write_all(xml.as_bytes())?;
+
+    let vml = super::comments_xml::generate_vml_drawing(&sheet.comments, idx)?;
+    let opts = FileOptions::<()>::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(format!("xl/drawings/vmlDrawing{}.vml", idx + 1), opts)?;
+    zip.write_all(vml.as_bytes())?;
+    Ok(())
+}
+
 /// Add content types for chart/drawing parts
-pub fn add_chart_content_types(xml: &mut String, _sheet_count: usize, charts: &[bool]) {
-    for (idx, has_chart) in charts.iter().enumerate() {
-        if *has_chart {
-            let n = idx + 1;
+///
+/// `charts[i]` is the number of charts on sheet `i`; charts are numbered
+/// across the workbook and each sheet with charts has one drawing.
+pub fn add_chart_content_types(xml: &mut String, _sheet_count: usize, charts: &[usize]) {
+    let mut chart_num = 1;
+    for (idx, &count) in charts.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        for _ in 0..count {
             xml.push_str(&format!(
                 r#"<Override PartName="/xl/charts/chart{}.xml" ContentType="application/vnd.openxmlformats-officedocument.drawingml.chart+xml"/>"#,
-                n
-            ));
-            xml.push_str(&format!(
-                r#"<Override PartName="/xl/drawings/drawing{}.xml" ContentType="application/vnd.openxmlformats-officedocument.drawing+xml"/>"#,
-                n
+                chart_num
             ));
+            chart_num += 1;
         }
+        xml.push_str(&format!(
+            r#"<Override PartName="/xl/drawings/drawing{}.xml" ContentType="application/vnd.openxmlformats-officedocument.drawing+xml"/>"#,
+            idx + 1
+        ));
     }
 }
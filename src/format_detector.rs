@@ -25,6 +25,11 @@ impl FormatDetector for DefaultFormatDetector {
             return Ok("gsheet".to_string());
         }
 
+        // Gzip-compressed CSV is read and written transparently
+        if path.to_lowercase().ends_with(".csv.gz") {
+            return Ok("csv".to_string());
+        }
+
         // Fall back to file extension detection
         path.split('.')
             .last()
@@ -35,18 +40,30 @@ impl FormatDetector for DefaultFormatDetector {
     fn is_supported(&self, format: &str) -> bool {
         matches!(
             format.to_lowercase().as_str(),
-            "csv" | "xlsx" | "xls" | "ods" | "parquet" | "avro" | "gsheet"
+            "csv"
+                | "tsv"
+                | "xlsx"
+                | "xls"
+                | "ods"
+                | "parquet"
+                | "avro"
+                | "ndjson"
+                | "jsonl"
+                | "gsheet"
         )
     }
 
     fn supported_formats(&self) -> Vec<String> {
         vec![
             "csv".to_string(),
+            "tsv".to_string(),
             "xlsx".to_string(),
             "xls".to_string(),
             "ods".to_string(),
             "parquet".to_string(),
             "avro".to_string(),
+            "ndjson".to_string(),
+            "jsonl".to_string(),
             "gsheet".to_string(),
         ]
     }
@@ -1,10 +1,14 @@
 //! Formula evaluator
 
-use super::types::{CellRange, FormulaResult};
-use crate::excel::ExcelHandler;
+use super::parser;
+use super::types::{CellRange, FormulaError, FormulaResult};
+use crate::excel::xlsx_writer::XlsxWriter;
+use crate::excel::{CellData, ExcelHandler, RowData, read_column_widths};
+use crate::text_encoding::decode_reader;
 use anyhow::{Context, Result};
-use calamine::{Reader, Xlsx, open_workbook};
+use calamine::{Data, Range, Reader, Xlsx, open_workbook};
 use csv::{ReaderBuilder, WriterBuilder};
+use encoding_rs::UTF_8;
 
 pub struct FormulaEvaluator {
     excel_handler: ExcelHandler,
@@ -17,59 +21,99 @@ impl FormulaEvaluator {
         }
     }
 
+    /// Evaluate `formula` against a sheet and write the result into `cell`
+    ///
+    /// `cell` may also be a range such as `C2:C100`; see
+    /// [`apply_formula_to_range`](Self::apply_formula_to_range).
+    ///
+    /// Every sheet is copied to `output` with its cells at their original
+    /// positions: numbers stay numbers, text stays text (even when it looks
+    /// numeric), dates keep a date format, and custom column widths are kept.
+    /// Other styling is not carried over.
     pub fn apply_to_excel(
         &self,
         input: &str,
         output: &str,
-        _formula: &str,
-        _cell: &str,
+        formula: &str,
+        cell: &str,
         sheet_name: Option<&str>,
     ) -> Result<()> {
         let mut workbook: Xlsx<_> = open_workbook(input)
             .with_context(|| format!("Failed to open Excel file: {}", input))?;
 
         let sheet_names = workbook.sheet_names();
-        let sheet_name = sheet_name
+        let target_sheet = sheet_name
             .or_else(|| sheet_names.first().map(|s| s.as_str()))
-            .ok_or_else(|| anyhow::anyhow!("No sheets found in workbook"))?;
-
-        let range = workbook
-            .worksheet_range(sheet_name)
-            .with_context(|| format!("Failed to read sheet: {}", sheet_name))?;
+            .ok_or_else(|| anyhow::anyhow!("No sheets found in workbook"))?
+            .to_string();
+        if !sheet_names.contains(&target_sheet) {
+            anyhow::bail!("Sheet '{}' not found in {}", target_sheet, input);
+        }
 
-        use crate::excel::xlsx_writer::XlsxWriter;
+        let targets = self.formula_targets(formula, cell)?;
+        // Widths are best effort; a package we cannot inspect still copies its cells
+        let column_widths = read_column_widths(input).unwrap_or_default();
 
         let mut writer = XlsxWriter::new();
-        writer.add_sheet(sheet_name)?;
+        for name in &sheet_names {
+            let range = workbook
+                .worksheet_range(name)
+                .with_context(|| format!("Failed to read sheet: {}", name))?;
+            let mut rows = sheet_cells(&range);
+            // Formulas are evaluated against their cached values
+            let mut text: Vec<Vec<String>> = rows
+                .iter()
+                .map(|row| row.iter().map(cell_text).collect())
+                .collect();
+            let formulas = workbook
+                .worksheet_formula(name)
+                .with_context(|| format!("Failed to read formulas of sheet: {}", name))?;
+            keep_formulas(&mut rows, &formulas);
+
+            if *name == target_sheet {
+                for (row, col, formula) in &targets {
+                    let result = self.evaluate(formula, &text)?;
+                    let (row, col) = (*row as usize, *col as usize);
+                    set_cell(&mut text, row, col, result.to_string(), String::new());
+                    let result = match result {
+                        FormulaResult::Number(num) => CellData::Number(num),
+                        FormulaResult::Text(text) | FormulaResult::Error(text) => {
+                            CellData::String(text)
+                        }
+                    };
+                    set_cell(&mut rows, row, col, result, CellData::Empty);
+                }
+            }
 
-        // Read all data from the existing workbook
-        let mut all_data: Vec<Vec<String>> = Vec::new();
-        for row in range.rows() {
-            all_data.push(row.iter().map(|c| c.to_string()).collect());
+            writer.add_sheet(name)?;
+            for cells in rows {
+                writer.add_row(RowData { cells });
+            }
+            for &(col, width) in column_widths.get(name).into_iter().flatten() {
+                writer.set_column_width(col, width);
+            }
         }
 
-        // Add all data to the writer
-        writer.add_data(&all_data);
-
-        // Note: Excel formulas require special cell type with formula attribute
-        // The custom XLSX writer supports formulas via CellData::Formula
-        // However, we need to add the formula to a specific cell
-        // For now, this is a limitation - formulas require modifying existing cells
-        // which is complex with the current architecture
-
-        let file = std::fs::File::create(output)?;
+        let file = std::fs::File::create(output)
+            .with_context(|| format!("Failed to create Excel file: {}", output))?;
         let mut buf_writer = std::io::BufWriter::new(file);
         writer.save(&mut buf_writer)?;
 
         Ok(())
     }
 
+    /// Evaluate `formula` against a CSV file and write the result into `cell`
+    ///
+    /// `cell` may also be a range such as `C2:C100`; see
+    /// [`apply_formula_to_range`](Self::apply_formula_to_range).
     pub fn apply_to_csv(&self, input: &str, output: &str, formula: &str, cell: &str) -> Result<()> {
+        let file = std::fs::File::open(input)
+            .with_context(|| format!("Failed to open CSV file: {}", input))?;
+        // Decoding drops a leading BOM so `A1` doesn't carry it
         let mut reader = ReaderBuilder::new()
             .has_headers(false)
             .flexible(true)
-            .from_path(input)
-            .with_context(|| format!("Failed to open CSV file: {}", input))?;
+            .from_reader(decode_reader(file, UTF_8));
 
         let mut records: Vec<Vec<String>> = Vec::new();
         for result in reader.records() {
@@ -77,35 +121,27 @@ impl FormulaEvaluator {
             records.push(record.iter().map(|s| s.to_string()).collect());
         }
 
-        let (row, col) = self.parse_cell_reference(cell)?;
-        let value = self.evaluate_formula_full(formula, &records)?;
-
-        while records.len() <= row as usize {
-            records.push(Vec::new());
+        // Earlier targets are visible to later ones, so a filled column can build on itself
+        for (row, col, formula) in self.formula_targets(formula, cell)? {
+            let value = self.evaluate(&formula, &records)?;
+            set_cell(
+                &mut records,
+                row as usize,
+                col as usize,
+                value.to_string(),
+                String::new(),
+            );
         }
 
-        let max_cols = records
-            .iter()
-            .map(|r| r.len())
-            .max()
-            .unwrap_or(0)
-            .max((col as usize) + 1);
-
+        let max_cols = records.iter().map(|r| r.len()).max().unwrap_or(0);
         for record in &mut records {
             while record.len() < max_cols {
                 record.push(String::new());
             }
         }
 
-        while records[row as usize].len() <= col as usize {
-            records[row as usize].push(String::new());
-        }
-
-        records[row as usize][col as usize] = value.to_string();
-
         // Check output format based on extension
         if output.ends_with(".xlsx") {
-            use crate::excel::xlsx_writer::XlsxWriter;
             let mut writer = XlsxWriter::new();
             writer.add_sheet("Sheet1")?;
             writer.add_data(&records);
@@ -130,6 +166,61 @@ impl FormulaEvaluator {
         Ok(())
     }
 
+    /// Apply `formula` to every cell of `range`, e.g. `C2:C100`
+    ///
+    /// The formula is written for the first cell of the range; for each other
+    /// cell its relative references shift by the same rows and columns, so
+    /// `A2*B2` becomes `A3*B3` one row down. `$` anchors a column or row, and
+    /// `{row}` stands for the target's own row number, as in `A{row}*B{row}`.
+    /// Cells are filled in order and each sees the results written before it.
+    pub fn apply_formula_to_range(
+        &self,
+        input: &str,
+        output: &str,
+        formula: &str,
+        range: &str,
+        sheet_name: Option<&str>,
+    ) -> Result<()> {
+        if !range.contains(':') {
+            anyhow::bail!("Expected a cell range like C2:C100, got '{}'", range);
+        }
+
+        if input.ends_with(".csv") {
+            self.apply_to_csv(input, output, formula, range)
+        } else if input.ends_with(".xls") || input.ends_with(".xlsx") {
+            self.apply_to_excel(input, output, formula, range, sheet_name)
+        } else {
+            anyhow::bail!("Unsupported file format for formula. Use .csv, .xls, or .xlsx");
+        }
+    }
+
+    /// Target cells of a cell or range, each with the formula shifted to it
+    fn formula_targets(&self, formula: &str, cell: &str) -> Result<Vec<(u32, u16, String)>> {
+        let (start, end) = match cell.split_once(':') {
+            Some((first, last)) => (
+                self.parse_cell_reference(first)?,
+                self.parse_cell_reference(last)?,
+            ),
+            None => {
+                let target = self.parse_cell_reference(cell)?;
+                (target, target)
+            }
+        };
+        if end.0 < start.0 || end.1 < start.1 {
+            anyhow::bail!("Invalid target range: {}", cell);
+        }
+
+        let mut targets = Vec::new();
+        for row in start.0..=end.0 {
+            for col in start.1..=end.1 {
+                let shifted = parser::shift_references(formula, row - start.0, col - start.1);
+                let shifted = shifted.replace("{row}", &(row + 1).to_string());
+                targets.push((row, col, shifted));
+            }
+        }
+        Ok(targets)
+    }
+
     pub(crate) fn parse_cell_reference(&self, cell: &str) -> Result<(u32, u16)> {
         let mut col_str = String::new();
         let mut row_str = String::new();
@@ -158,17 +249,59 @@ impl FormulaEvaluator {
         Ok(index - 1)
     }
 
+    /// Evaluate a formula for writing into a cell
+    ///
+    /// Spreadsheet errors such as division by zero come back as
+    /// [`FormulaResult::Error`]; only malformed formulas fail.
+    pub(crate) fn evaluate(&self, formula: &str, data: &[Vec<String>]) -> Result<FormulaResult> {
+        match self.evaluate_formula_full(formula, data) {
+            Err(err) => match err.downcast_ref::<FormulaError>() {
+                Some(error) => Ok(FormulaResult::Error(error.code().to_string())),
+                None => Err(err),
+            },
+            result => result,
+        }
+    }
+
+    /// Evaluate a formula for one data row, as `DataOperations::mutate` does
+    ///
+    /// Header names and bare column letters in `formula` refer to the cells of
+    /// `data[row]`; see [`parser::bind_row_names`].
+    pub(crate) fn evaluate_row(
+        &self,
+        formula: &str,
+        data: &[Vec<String>],
+        row: usize,
+    ) -> Result<FormulaResult> {
+        let header = data.first().map(Vec::as_slice).unwrap_or(&[]);
+        let bound = parser::bind_row_names(formula, header, row as u32 + 1);
+        self.evaluate(&bound, data)
+    }
+
     pub(crate) fn evaluate_formula_full(
         &self,
         formula: &str,
         data: &[Vec<String>],
     ) -> Result<FormulaResult> {
-        let formula_upper = formula.trim().to_uppercase();
+        let formula = formula.trim();
+        let formula = formula.strip_prefix('=').unwrap_or(formula);
+        let formula_upper = formula.to_uppercase();
 
         if formula_upper.starts_with("IF(") {
             self.evaluate_if(formula, data)
         } else if formula_upper.starts_with("CONCAT(") {
             self.evaluate_concat(formula, data)
+        } else if formula_upper.starts_with("INDEX(") {
+            self.evaluate_index(formula, data)
+        } else if formula_upper.starts_with("TODAY(") {
+            self.evaluate_today(formula)
+        } else if formula_upper.starts_with("DATE(") {
+            self.evaluate_date(formula, data)
+        } else if let Some(name) = ["LEFT(", "RIGHT(", "MID(", "TRIM(", "UPPER(", "LOWER("]
+            .iter()
+            .find(|name| formula_upper.starts_with(**name))
+        {
+            self.evaluate_text_function(name.trim_end_matches('('), formula, data)
         } else {
             let num = self.evaluate_formula(&formula_upper, data)?;
             Ok(FormulaResult::Number(num))
@@ -200,6 +333,22 @@ impl FormulaEvaluator {
             self.evaluate_sumif(&formula, data)
         } else if formula.starts_with("COUNTIF(") {
             self.evaluate_countif(&formula, data)
+        } else if formula.starts_with("AVERAGEIF(") {
+            self.evaluate_averageif(&formula, data)
+        } else if formula.starts_with("DATEDIF(") {
+            self.evaluate_datedif(&formula, data)
+        } else if let Some(name) = ["YEAR(", "MONTH(", "DAY("]
+            .iter()
+            .find(|name| formula.starts_with(**name))
+        {
+            self.evaluate_date_part(name.trim_end_matches('('), &formula, data)
+        } else if formula.starts_with("MATCH(") {
+            self.evaluate_match(&formula, data)
+        } else if formula.starts_with("INDEX(") {
+            match self.evaluate_index(&formula, data)? {
+                FormulaResult::Number(num) => Ok(num),
+                _ => Err(FormulaError::Value.into()),
+            }
         } else if formula.contains('+')
             || formula.contains('-')
             || formula.contains('*')
@@ -224,16 +373,60 @@ impl FormulaEvaluator {
         let condition = self.evaluate_condition(&args[0], data)?;
         let result_expr = if condition { &args[1] } else { &args[2] };
 
-        if let Ok(num) = self.evaluate_formula(result_expr, data) {
-            Ok(FormulaResult::Number(num))
-        } else {
-            Ok(FormulaResult::Text(
+        match self.evaluate_formula(result_expr, data) {
+            Ok(num) => Ok(FormulaResult::Number(num)),
+            // Spreadsheet errors in the chosen branch propagate rather than becoming text
+            Err(err) if err.is::<FormulaError>() => Err(err),
+            Err(_) => Ok(FormulaResult::Text(
                 result_expr.trim().trim_matches('"').to_string(),
-            ))
+            )),
         }
     }
 
     fn evaluate_condition(&self, condition: &str, data: &[Vec<String>]) -> Result<bool> {
+        let condition = condition.trim();
+        let upper = condition.to_uppercase();
+
+        for name in ["AND", "OR", "NOT"] {
+            let is_call = upper.starts_with(name)
+                && upper[name.len()..].trim_start().starts_with('(')
+                && upper.ends_with(')');
+            if !is_call {
+                continue;
+            }
+
+            let inner = self.extract_function_args(condition)?;
+            let args = self.split_args(&inner)?;
+            let args: Vec<_> = args.into_iter().filter(|a| !a.is_empty()).collect();
+
+            return match name {
+                "NOT" => {
+                    if args.len() != 1 {
+                        anyhow::bail!("NOT requires exactly 1 argument: NOT(condition)");
+                    }
+                    Ok(!self.evaluate_condition(&args[0], data)?)
+                }
+                _ => {
+                    if args.len() < 2 {
+                        anyhow::bail!(
+                            "{} requires at least 2 arguments: {}(condition1, condition2, ...)",
+                            name,
+                            name
+                        );
+                    }
+                    let mut results = Vec::with_capacity(args.len());
+                    for arg in &args {
+                        results.push(self.evaluate_condition(arg, data)?);
+                    }
+                    Ok(if name == "AND" {
+                        results.iter().all(|r| *r)
+                    } else {
+                        results.iter().any(|r| *r)
+                    })
+                }
+            };
+        }
+
         let ops = [">=", "<=", "<>", "!=", "=", ">", "<"];
 
         for op in ops {
@@ -245,15 +438,7 @@ impl FormulaEvaluator {
                 let right_val = self.evaluate_formula(right, data).ok();
 
                 return Ok(match (left_val, right_val) {
-                    (Some(l), Some(r)) => match op {
-                        ">=" => l >= r,
-                        "<=" => l <= r,
-                        "<>" | "!=" => (l - r).abs() > f64::EPSILON,
-                        "=" => (l - r).abs() < f64::EPSILON,
-                        ">" => l > r,
-                        "<" => l < r,
-                        _ => false,
-                    },
+                    (Some(l), Some(r)) => Self::compare_numbers(op, l, r),
                     _ => {
                         let left_str = left.trim_matches('"');
                         let right_str = right.trim_matches('"');
@@ -270,6 +455,19 @@ impl FormulaEvaluator {
         anyhow::bail!("Invalid condition: {}", condition)
     }
 
+    /// Apply a comparison operator (`>=`, `<=`, `<>`, `!=`, `=`, `>`, `<`) to two numbers
+    pub(crate) fn compare_numbers(op: &str, l: f64, r: f64) -> bool {
+        match op {
+            ">=" => l >= r,
+            "<=" => l <= r,
+            "<>" | "!=" => (l - r).abs() > f64::EPSILON,
+            "=" => (l - r).abs() < f64::EPSILON,
+            ">" => l > r,
+            "<" => l < r,
+            _ => false,
+        }
+    }
+
     fn evaluate_concat(&self, formula: &str, data: &[Vec<String>]) -> Result<FormulaResult> {
         let inner = self.extract_function_args(formula)?;
         let args = self.split_args(&inner)?;
@@ -309,8 +507,12 @@ impl FormulaEvaluator {
 
     pub(crate) fn get_cell_value(&self, cell_ref: &str, data: &[Vec<String>]) -> Result<f64> {
         let (row, col) = self.parse_cell_reference(cell_ref)?;
+        let width = data.iter().map(Vec::len).max().unwrap_or(0);
+        if row as usize >= data.len() || col as usize >= width {
+            return Err(FormulaError::Ref.into());
+        }
         self.get_cell_value_by_index(row, col, data)
-            .ok_or_else(|| anyhow::anyhow!("Cell {} is empty or invalid", cell_ref))
+            .ok_or_else(|| FormulaError::Value.into())
     }
 
     pub(crate) fn get_cell_value_by_index(
@@ -407,3 +609,69 @@ impl FormulaEvaluator {
         Ok(result)
     }
 }
+
+/// Built-in Excel number format for dates (`m/d/yyyy`)
+const DATE_NUM_FMT_ID: usize = 14;
+
+/// Cells of a sheet at their absolute positions, keeping each value's type
+fn sheet_cells(range: &Range<Data>) -> Vec<Vec<CellData>> {
+    let (origin_row, origin_col) = range.start().unwrap_or((0, 0));
+    let mut rows = vec![Vec::new(); origin_row as usize];
+
+    for row in range.rows() {
+        let mut cells = vec![CellData::Empty; origin_col as usize];
+        cells.extend(row.iter().map(|value| match value {
+            Data::Empty => CellData::Empty,
+            Data::Int(n) => CellData::Number(*n as f64),
+            Data::Float(n) => CellData::Number(*n),
+            Data::DateTime(dt) if dt.is_datetime() => CellData::NumberFmt {
+                value: dt.as_f64(),
+                fmt_id: DATE_NUM_FMT_ID,
+            },
+            Data::DateTime(dt) => CellData::Number(dt.as_f64()),
+            other => CellData::String(other.to_string()),
+        }));
+        rows.push(cells);
+    }
+    rows
+}
+
+/// Replace the cached values in `rows` with the formulas that produced them
+fn keep_formulas(rows: &mut Vec<Vec<CellData>>, formulas: &Range<String>) {
+    let (origin_row, origin_col) = formulas.start().unwrap_or((0, 0));
+    for (r, row) in formulas.rows().enumerate() {
+        for (c, formula) in row.iter().enumerate() {
+            if !formula.is_empty() {
+                let (row, col) = (origin_row as usize + r, origin_col as usize + c);
+                set_cell(
+                    rows,
+                    row,
+                    col,
+                    CellData::Formula(format!("={}", formula)),
+                    CellData::Empty,
+                );
+            }
+        }
+    }
+}
+
+/// A cell as the formula functions see it
+fn cell_text(cell: &CellData) -> String {
+    match cell {
+        CellData::String(s) | CellData::Formula(s) => s.clone(),
+        CellData::Number(n) | CellData::NumberFmt { value: n, .. } => n.to_string(),
+        CellData::Hyperlink { text, .. } => text.clone(),
+        CellData::Empty => String::new(),
+    }
+}
+
+/// Store `value` at `row`/`col`, growing the grid with `blank` cells as needed
+fn set_cell<T: Clone>(grid: &mut Vec<Vec<T>>, row: usize, col: usize, value: T, blank: T) {
+    if grid.len() <= row {
+        grid.resize(row + 1, Vec::new());
+    }
+    if grid[row].len() <= col {
+        grid[row].resize(col + 1, blank);
+    }
+    grid[row][col] = value;
+}
@@ -1,7 +1,13 @@
 //! Formula function implementations
 
 use super::evaluator::FormulaEvaluator;
+use super::parser;
+use super::types::{FormulaError, FormulaResult};
 use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate};
+
+/// Cell date formats accepted by the date functions, tried in order
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y"];
 
 impl FormulaEvaluator {
     pub(crate) fn evaluate_sum(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
@@ -124,6 +130,93 @@ impl FormulaEvaluator {
         Ok(text.len() as f64)
     }
 
+    /// Evaluate LEFT/RIGHT/MID/TRIM/UPPER/LOWER, which operate on raw cell text.
+    /// Lengths past the end of the string are clamped rather than rejected.
+    pub(crate) fn evaluate_text_function(
+        &self,
+        name: &str,
+        formula: &str,
+        data: &[Vec<String>],
+    ) -> Result<FormulaResult> {
+        let inner = self.extract_function_args(formula)?;
+        let args = self.split_args(&inner)?;
+
+        let (arity, usage) = match name {
+            "LEFT" => (1..=2, "LEFT(text, [num_chars])"),
+            "RIGHT" => (1..=2, "RIGHT(text, [num_chars])"),
+            "MID" => (3..=3, "MID(text, start, num_chars)"),
+            "TRIM" => (1..=1, "TRIM(text)"),
+            "UPPER" => (1..=1, "UPPER(text)"),
+            "LOWER" => (1..=1, "LOWER(text)"),
+            _ => anyhow::bail!("Unknown text function: {}", name),
+        };
+        if !arity.contains(&args.len()) {
+            anyhow::bail!("Invalid arguments for {}: expected {}", name, usage);
+        }
+
+        let text = self.evaluate_text_arg(&args[0], data)?;
+        let chars: Vec<char> = text.chars().collect();
+
+        let result = match name {
+            "LEFT" => {
+                let n = match args.get(1) {
+                    Some(arg) => self.evaluate_count_arg(name, arg, data)?,
+                    None => 1,
+                };
+                chars.iter().take(n).collect()
+            }
+            "RIGHT" => {
+                let n = match args.get(1) {
+                    Some(arg) => self.evaluate_count_arg(name, arg, data)?,
+                    None => 1,
+                };
+                chars[chars.len().saturating_sub(n)..].iter().collect()
+            }
+            "MID" => {
+                let start = self.evaluate_count_arg(name, &args[1], data)?;
+                if start < 1 {
+                    anyhow::bail!("MID start position must be at least 1");
+                }
+                let len = self.evaluate_count_arg(name, &args[2], data)?;
+                chars.iter().skip(start - 1).take(len).collect()
+            }
+            "TRIM" => text.split_whitespace().collect::<Vec<_>>().join(" "),
+            "UPPER" => text.to_uppercase(),
+            _ => text.to_lowercase(),
+        };
+
+        Ok(FormulaResult::Text(result))
+    }
+
+    /// Resolve a text argument: a quoted literal, a nested function, or a cell's raw text
+    fn evaluate_text_arg(&self, arg: &str, data: &[Vec<String>]) -> Result<String> {
+        let arg = arg.trim();
+
+        if arg.len() >= 2 && arg.starts_with('"') && arg.ends_with('"') {
+            return Ok(arg[1..arg.len() - 1].to_string());
+        }
+        if arg.contains('(') {
+            return Ok(self.evaluate_formula_full(arg, data)?.to_string());
+        }
+        if let Ok(num) = arg.parse::<f64>() {
+            return Ok(num.to_string());
+        }
+
+        let (row, col) = self.parse_cell_reference(arg)?;
+        Ok(self
+            .get_cell_text_by_index(row, col, data)
+            .unwrap_or_default())
+    }
+
+    /// Evaluate a character count or position argument as a non-negative integer
+    fn evaluate_count_arg(&self, name: &str, arg: &str, data: &[Vec<String>]) -> Result<usize> {
+        let value = self.evaluate_formula(arg, data)?;
+        if value < 0.0 {
+            anyhow::bail!("{}: character count cannot be negative", name);
+        }
+        Ok(value as usize)
+    }
+
     pub(crate) fn evaluate_vlookup(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
         let inner = self.extract_function_args(formula)?;
         let args = self.split_args(&inner)?;
@@ -145,6 +238,9 @@ impl FormulaEvaluator {
         if col_index < 1 {
             anyhow::bail!("VLOOKUP col_index must be >= 1");
         }
+        if col_index > (range.end_col - range.start_col + 1) as usize {
+            return Err(FormulaError::Ref.into());
+        }
 
         for row in range.start_row..=range.end_row {
             if let Some(cell_text) = self.get_cell_text_by_index(row, range.start_col, data) {
@@ -165,56 +261,283 @@ impl FormulaEvaluator {
                             return Ok(num);
                         }
                     }
-                    anyhow::bail!("VLOOKUP: value at result column is not numeric");
+                    return Err(FormulaError::Value.into());
                 }
             }
         }
 
-        anyhow::bail!("VLOOKUP: no match found for '{}'", lookup_value)
+        Err(FormulaError::NotAvailable.into())
+    }
+
+    /// MATCH(lookup_value, range, [match_type]) - 1-based position within a one-row or one-column range
+    ///
+    /// Match type 0 finds the first exact match, 1 (the default) the largest
+    /// value <= lookup_value in an ascending range, and -1 the smallest value
+    /// >= lookup_value in a descending range.
+    pub(crate) fn evaluate_match(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
+        let inner = self.extract_function_args(formula)?;
+        let args = self.split_args(&inner)?;
+
+        if args.len() < 2 || args.len() > 3 {
+            anyhow::bail!("MATCH requires 2-3 arguments: MATCH(lookup_value, range, [match_type])");
+        }
+
+        let lookup_value = self.evaluate_text_arg(&args[0], data)?;
+        let range = self.extract_range(&format!("X({})", args[1]))?;
+        let match_type = match args.get(2) {
+            Some(arg) => self.evaluate_formula(arg, data)?,
+            None => 1.0,
+        };
+
+        let cells: Vec<String> = if range.start_row == range.end_row {
+            (range.start_col..=range.end_col)
+                .map(|col| {
+                    self.get_cell_text_by_index(range.start_row, col, data)
+                        .unwrap_or_default()
+                })
+                .collect()
+        } else if range.start_col == range.end_col {
+            (range.start_row..=range.end_row)
+                .map(|row| {
+                    self.get_cell_text_by_index(row, range.start_col, data)
+                        .unwrap_or_default()
+                })
+                .collect()
+        } else {
+            anyhow::bail!("MATCH range must be a single row or column");
+        };
+
+        let mut found = None;
+        for (idx, cell) in cells.iter().enumerate() {
+            let ordering = compare_lookup(cell, &lookup_value);
+            if match_type == 0.0 {
+                if ordering == std::cmp::Ordering::Equal {
+                    found = Some(idx);
+                    break;
+                }
+            } else if match_type > 0.0 {
+                // Ascending data: keep the last value not past the lookup
+                if ordering == std::cmp::Ordering::Greater {
+                    break;
+                }
+                found = Some(idx);
+            } else {
+                if ordering == std::cmp::Ordering::Less {
+                    break;
+                }
+                found = Some(idx);
+            }
+        }
+
+        match found {
+            Some(idx) => Ok((idx + 1) as f64),
+            None => Err(FormulaError::NotAvailable.into()),
+        }
+    }
+
+    /// TODAY() - the current local date as `YYYY-MM-DD`
+    pub(crate) fn evaluate_today(&self, formula: &str) -> Result<FormulaResult> {
+        let inner = self.extract_function_args(formula)?;
+        if !inner.trim().is_empty() {
+            anyhow::bail!("TODAY takes no arguments");
+        }
+        Ok(FormulaResult::Text(
+            Local::now().date_naive().format("%Y-%m-%d").to_string(),
+        ))
+    }
+
+    /// DATE(year, month, day) - a date as `YYYY-MM-DD`
+    pub(crate) fn evaluate_date(
+        &self,
+        formula: &str,
+        data: &[Vec<String>],
+    ) -> Result<FormulaResult> {
+        let inner = self.extract_function_args(formula)?;
+        let args = self.split_args(&inner)?;
+
+        if args.len() != 3 {
+            anyhow::bail!("DATE requires 3 arguments: DATE(year, month, day)");
+        }
+
+        let year = self.evaluate_formula(&args[0], data)? as i32;
+        let month = self.evaluate_formula(&args[1], data)? as u32;
+        let day = self.evaluate_formula(&args[2], data)? as u32;
+        let date = NaiveDate::from_ymd_opt(year, month, day).ok_or(FormulaError::Num)?;
+
+        Ok(FormulaResult::Text(date.format("%Y-%m-%d").to_string()))
+    }
+
+    /// YEAR/MONTH/DAY(date) - one component of a date
+    pub(crate) fn evaluate_date_part(
+        &self,
+        name: &str,
+        formula: &str,
+        data: &[Vec<String>],
+    ) -> Result<f64> {
+        let inner = self.extract_function_args(formula)?;
+        let args = self.split_args(&inner)?;
+
+        if args.len() != 1 {
+            anyhow::bail!("{} requires 1 argument: {}(date)", name, name);
+        }
+
+        let date = self.evaluate_date_arg(&args[0], data)?;
+        let part = match name {
+            "YEAR" => date.year() as f64,
+            "MONTH" => date.month() as f64,
+            _ => date.day() as f64,
+        };
+        Ok(part)
+    }
+
+    /// DATEDIF(start, end, unit) - whole days ("D"), months ("M"), or years ("Y") between two dates
+    pub(crate) fn evaluate_datedif(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
+        let inner = self.extract_function_args(formula)?;
+        let args = self.split_args(&inner)?;
+
+        if args.len() != 3 {
+            anyhow::bail!("DATEDIF requires 3 arguments: DATEDIF(start_date, end_date, unit)");
+        }
+
+        let start = self.evaluate_date_arg(&args[0], data)?;
+        let end = self.evaluate_date_arg(&args[1], data)?;
+        if end < start {
+            return Err(FormulaError::Num.into());
+        }
+
+        // A month only counts once the end day reaches the start day
+        let mut months =
+            (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+        if end.day() < start.day() {
+            months -= 1;
+        }
+
+        let unit = self.evaluate_text_arg(&args[2], data)?;
+        match unit.trim().to_uppercase().as_str() {
+            "D" => Ok((end - start).num_days() as f64),
+            "M" => Ok(months as f64),
+            "Y" => Ok((months / 12) as f64),
+            _ => Err(FormulaError::Num.into()),
+        }
+    }
+
+    /// Resolve a date argument from a quoted literal, a nested function, or a cell
+    fn evaluate_date_arg(&self, arg: &str, data: &[Vec<String>]) -> Result<NaiveDate> {
+        let text = self.evaluate_text_arg(arg, data)?;
+        let text = text.trim();
+        DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+            .ok_or_else(|| FormulaError::Value.into())
+    }
+
+    /// INDEX(range, row, [col]) - the cell at a 1-based row/col within a range
+    ///
+    /// With a single-row range and no col, the second argument picks the column.
+    pub(crate) fn evaluate_index(
+        &self,
+        formula: &str,
+        data: &[Vec<String>],
+    ) -> Result<FormulaResult> {
+        let inner = self.extract_function_args(formula)?;
+        let args = self.split_args(&inner)?;
+
+        if args.len() < 2 || args.len() > 3 {
+            anyhow::bail!("INDEX requires 2-3 arguments: INDEX(range, row, [col])");
+        }
+
+        let range = self.extract_range(&format!("X({})", args[0]))?;
+        let first = self.evaluate_formula(&args[1], data)?;
+        let (row_num, col_num) = match args.get(2) {
+            Some(arg) => (first, self.evaluate_formula(arg, data)?),
+            None if range.start_row == range.end_row => (1.0, first),
+            None => (first, 1.0),
+        };
+
+        let height = (range.end_row - range.start_row + 1) as f64;
+        let width = (range.end_col - range.start_col + 1) as f64;
+        if row_num < 1.0 || row_num > height || col_num < 1.0 || col_num > width {
+            return Err(FormulaError::Ref.into());
+        }
+
+        let row = range.start_row + row_num as u32 - 1;
+        let col = range.start_col + col_num as u16 - 1;
+        let text = self
+            .get_cell_text_by_index(row, col, data)
+            .unwrap_or_default();
+
+        Ok(match text.parse::<f64>() {
+            Ok(num) => FormulaResult::Number(num),
+            Err(_) => FormulaResult::Text(text),
+        })
     }
 
     pub(crate) fn evaluate_sumif(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
+        let values = self.collect_conditional_values("SUMIF", formula, data)?;
+        Ok(values.iter().sum())
+    }
+
+    pub(crate) fn evaluate_averageif(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
+        let values = self.collect_conditional_values("AVERAGEIF", formula, data)?;
+        if values.is_empty() {
+            return Err(FormulaError::DivZero.into());
+        }
+        Ok(values.iter().sum::<f64>() / values.len() as f64)
+    }
+
+    /// Collect numeric cells for `FUNC(range, criteria, [value_range])`
+    ///
+    /// When `value_range` is omitted the criteria range itself supplies the
+    /// values. The value range is aligned to the criteria range by offset.
+    fn collect_conditional_values(
+        &self,
+        name: &str,
+        formula: &str,
+        data: &[Vec<String>],
+    ) -> Result<Vec<f64>> {
         let inner = self.extract_function_args(formula)?;
         let args = self.split_args(&inner)?;
 
         if args.len() < 2 || args.len() > 3 {
-            anyhow::bail!("SUMIF requires 2-3 arguments: SUMIF(range, criteria, [sum_range])");
+            anyhow::bail!(
+                "{} requires 2-3 arguments: {}(range, criteria, [value_range])",
+                name,
+                name
+            );
         }
 
         let criteria_range = self.extract_range(&format!("X({})", args[0]))?;
         let criteria = args[1].trim().trim_matches('"').to_string();
 
-        let sum_range = if args.len() == 3 {
+        let value_range = if args.len() == 3 {
             self.extract_range(&format!("X({})", args[2]))?
         } else {
             criteria_range.clone()
         };
 
-        let mut sum = 0.0;
+        let mut values = Vec::new();
 
         for row_offset in 0..=(criteria_range.end_row - criteria_range.start_row) {
             let criteria_row = criteria_range.start_row + row_offset;
-            let sum_row = sum_range.start_row + row_offset;
+            let value_row = value_range.start_row + row_offset;
 
             for col_offset in 0..=(criteria_range.end_col - criteria_range.start_col) {
                 let criteria_col = criteria_range.start_col + col_offset;
-                let sum_col = sum_range.start_col + col_offset;
+                let value_col = value_range.start_col + col_offset;
 
-                if let Some(cell_text) =
-                    self.get_cell_text_by_index(criteria_row, criteria_col, data)
-                {
-                    let matches = self.matches_criteria(&cell_text, &criteria);
+                let matches = self
+                    .get_cell_text_by_index(criteria_row, criteria_col, data)
+                    .is_some_and(|text| self.matches_criteria(&text, &criteria));
 
-                    if matches {
-                        if let Some(value) = self.get_cell_value_by_index(sum_row, sum_col, data) {
-                            sum += value;
-                        }
-                    }
+                if matches
+                    && let Some(value) = self.get_cell_value_by_index(value_row, value_col, data)
+                {
+                    values.push(value);
                 }
             }
         }
 
-        Ok(sum)
+        Ok(values)
     }
 
     pub(crate) fn evaluate_countif(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
@@ -243,107 +566,48 @@ impl FormulaEvaluator {
         Ok(count as f64)
     }
 
+    /// Match a cell against a SUMIF-style criteria such as `>5`, `<=10`,
+    /// `=foo`, `<>bar` or bare text (equality)
+    ///
+    /// Relational operators compare numerically and never match text.
+    /// Equality compares numerically when both sides are numbers and
+    /// otherwise as case-insensitive text, since formulas are uppercased
+    /// before evaluation.
     pub(crate) fn matches_criteria(&self, value: &str, criteria: &str) -> bool {
         let criteria = criteria.trim();
+        let value = value.trim();
 
-        if criteria.starts_with(">=") {
-            if let (Ok(v), Ok(c)) = (value.parse::<f64>(), criteria[2..].trim().parse::<f64>()) {
-                return v >= c;
-            }
-        } else if criteria.starts_with("<=") {
-            if let (Ok(v), Ok(c)) = (value.parse::<f64>(), criteria[2..].trim().parse::<f64>()) {
-                return v <= c;
-            }
-        } else if criteria.starts_with("<>") || criteria.starts_with("!=") {
-            let c = criteria[2..].trim();
-            return value != c;
-        } else if criteria.starts_with('>') {
-            if let (Ok(v), Ok(c)) = (value.parse::<f64>(), criteria[1..].trim().parse::<f64>()) {
-                return v > c;
-            }
-        } else if criteria.starts_with('<') {
-            if let (Ok(v), Ok(c)) = (value.parse::<f64>(), criteria[1..].trim().parse::<f64>()) {
-                return v < c;
-            }
-        } else if criteria.starts_with('=') {
-            let c = criteria[1..].trim();
-            return value == c;
-        }
-
-        // Exact match
-        value.to_uppercase() == criteria.to_uppercase()
-    }
-
-    pub(crate) fn evaluate_arithmetic(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
-        let cell_ref_regex = regex::Regex::new(r"([A-Z]+[0-9]+)")?;
-
-        let mut expr = formula.to_string();
-        for cap in cell_ref_regex.captures_iter(formula) {
-            let cell_ref = &cap[1];
-            let value = self.get_cell_value(cell_ref, data)?;
-            expr = expr.replace(cell_ref, &value.to_string());
-        }
-
-        self.evaluate_simple_arithmetic(&expr)
-    }
+        let (op, operand) = [">=", "<=", "<>", "!=", ">", "<", "="]
+            .iter()
+            .find_map(|op| criteria.strip_prefix(op).map(|rest| (*op, rest.trim())))
+            .unwrap_or(("=", criteria));
 
-    fn evaluate_simple_arithmetic(&self, expr: &str) -> Result<f64> {
-        let expr = expr.replace(" ", "");
-
-        if let Ok(num) = expr.parse::<f64>() {
-            return Ok(num);
+        if let (Ok(v), Ok(c)) = (value.parse::<f64>(), operand.parse::<f64>()) {
+            return Self::compare_numbers(op, v, c);
         }
 
-        // Handle + and - (left to right, lowest precedence)
-        let mut depth = 0;
-        for (i, c) in expr.chars().rev().enumerate() {
-            let pos = expr.len() - 1 - i;
-            match c {
-                '(' => depth += 1,
-                ')' => depth -= 1,
-                '+' if depth == 0 && pos > 0 => {
-                    let left = self.evaluate_simple_arithmetic(&expr[..pos])?;
-                    let right = self.evaluate_simple_arithmetic(&expr[pos + 1..])?;
-                    return Ok(left + right);
-                }
-                '-' if depth == 0 && pos > 0 => {
-                    let left = self.evaluate_simple_arithmetic(&expr[..pos])?;
-                    let right = self.evaluate_simple_arithmetic(&expr[pos + 1..])?;
-                    return Ok(left - right);
-                }
-                _ => {}
-            }
+        match op {
+            "=" => value.eq_ignore_ascii_case(operand),
+            "<>" | "!=" => !value.eq_ignore_ascii_case(operand),
+            _ => false,
         }
+    }
 
-        // Handle * and /
-        depth = 0;
-        for (i, c) in expr.chars().rev().enumerate() {
-            let pos = expr.len() - 1 - i;
-            match c {
-                '(' => depth += 1,
-                ')' => depth -= 1,
-                '*' if depth == 0 => {
-                    let left = self.evaluate_simple_arithmetic(&expr[..pos])?;
-                    let right = self.evaluate_simple_arithmetic(&expr[pos + 1..])?;
-                    return Ok(left * right);
-                }
-                '/' if depth == 0 => {
-                    let left = self.evaluate_simple_arithmetic(&expr[..pos])?;
-                    let right = self.evaluate_simple_arithmetic(&expr[pos + 1..])?;
-                    if right == 0.0 {
-                        anyhow::bail!("Division by zero");
-                    }
-                    return Ok(left / right);
-                }
-                _ => {}
+    pub(crate) fn evaluate_arithmetic(&self, formula: &str, data: &[Vec<String>]) -> Result<f64> {
+        parser::evaluate_arithmetic(formula, &|operand| {
+            if operand.contains('(') {
+                self.evaluate_formula(operand, data)
+            } else {
+                self.get_cell_value(operand, data)
             }
-        }
-
-        // Handle parentheses
-        if expr.starts_with('(') && expr.ends_with(')') {
-            return self.evaluate_simple_arithmetic(&expr[1..expr.len() - 1]);
-        }
+        })
+    }
+}
 
-        anyhow::bail!("Cannot evaluate expression: {}", expr)
+/// Order a cell against a lookup value: numerically when both parse, else case-insensitively
+fn compare_lookup(cell: &str, lookup: &str) -> std::cmp::Ordering {
+    match (cell.parse::<f64>(), lookup.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+        _ => cell.to_uppercase().cmp(&lookup.to_uppercase()),
     }
 }
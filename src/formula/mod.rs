@@ -1,6 +1,8 @@
 //! Formula evaluation module
 //!
-//! Supports Excel-like formulas: SUM, AVERAGE, MIN, MAX, COUNT, IF, CONCAT, VLOOKUP, etc.
+//! Supports Excel-like formulas: SUM, AVERAGE, MIN, MAX, COUNT, IF, CONCAT, VLOOKUP,
+//! INDEX/MATCH, text functions (LEFT, RIGHT, MID, TRIM, UPPER, LOWER), date functions
+//! (TODAY, DATE, YEAR, MONTH, DAY, DATEDIF), etc.
 
 mod evaluator;
 mod functions;
@@ -9,4 +11,4 @@ mod types;
 
 pub use evaluator::FormulaEvaluator;
 #[allow(unused_imports)]
-pub use types::FormulaResult;
+pub use types::{FormulaError, FormulaResult};
@@ -1,6 +1,6 @@
 //! Formula parsing utilities
 
-use super::types::CellRange;
+use super::types::{CellRange, FormulaError};
 use anyhow::{Context, Result};
 
 /// Parse cell reference like "A1" to (row, col)
@@ -34,6 +34,127 @@ pub fn column_to_index(col: &str) -> Result<u16> {
     Ok(index - 1)
 }
 
+/// Convert a column index to its letters (0=A, 25=Z, 26=AA, ...)
+pub fn index_to_column(index: u16) -> String {
+    let mut index = index as u32 + 1;
+    let mut letters = Vec::new();
+    while index > 0 {
+        index -= 1;
+        letters.push((b'A' + (index % 26) as u8) as char);
+        index /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Shift relative cell references by whole rows and columns, as when a formula is filled
+///
+/// Parts anchored with `$` (as in `$A1` or `A$1`) stay fixed, and quoted text
+/// and function names are left alone.
+pub fn shift_references(formula: &str, rows: u32, cols: u16) -> String {
+    map_names(formula, |word| {
+        let (col_fixed, col, row_fixed, row) = split_reference(word)?;
+        let col = if col_fixed {
+            col
+        } else {
+            col.saturating_add(cols)
+        };
+        let row = if row_fixed {
+            row
+        } else {
+            row.saturating_add(rows)
+        };
+        Some(format!(
+            "{}{}{}{}",
+            if col_fixed { "$" } else { "" },
+            index_to_column(col),
+            if row_fixed { "$" } else { "" },
+            row
+        ))
+    })
+}
+
+/// Bind column names and bare column letters to cells of one sheet row
+///
+/// A word matching a header name, or letters like `B` within the header's
+/// width, becomes a reference into `row` (1-based), so `price*qty` on row 3
+/// reads `A3*B3`. Existing cell references such as `C1` are kept.
+pub fn bind_row_names(formula: &str, header: &[String], row: u32) -> String {
+    map_names(formula, |word| {
+        let col = match header.iter().position(|name| name == word) {
+            Some(col) => col,
+            None if word.chars().all(|c| c.is_ascii_alphabetic()) && word.len() <= 3 => {
+                let col = column_to_index(&word.to_ascii_uppercase()).ok()? as usize;
+                (col < header.len()).then_some(col)?
+            }
+            None => return None,
+        };
+        Some(format!("{}{}", index_to_column(col as u16), row))
+    })
+}
+
+/// Rewrite each name in a formula that `rewrite` maps, outside quoted text
+///
+/// Names are whole words of letters, digits, `$` and `_` starting with a
+/// letter or `$`; function names (followed by `(`) are never rewritten.
+fn map_names(formula: &str, rewrite: impl Fn(&str) -> Option<String>) -> String {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut result = String::with_capacity(formula.len());
+    let mut in_quotes = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        if in_quotes || !(c.is_ascii_alphabetic() || c == '$') {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+
+        // Take the whole word so references are never matched mid-name
+        let start = i;
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '$' || chars[i] == '_')
+        {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect();
+        let is_call = chars.get(i) == Some(&'(');
+
+        match rewrite(&word) {
+            Some(replacement) if !is_call => result.push_str(&replacement),
+            _ => result.push_str(&word),
+        }
+    }
+
+    result
+}
+
+/// Split a reference like `$A1` into (column anchored, column index, row anchored, row number)
+fn split_reference(word: &str) -> Option<(bool, u16, bool, u32)> {
+    let (col_fixed, rest) = match word.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+    let letters_end = rest.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, rest) = rest.split_at(letters_end);
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+    let (row_fixed, digits) = match rest.strip_prefix('$') {
+        Some(digits) => (true, digits),
+        None => (false, rest),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let col = column_to_index(&letters.to_ascii_uppercase()).ok()?;
+    let row = digits.parse::<u32>().ok().filter(|&row| row > 0)?;
+    Some((col_fixed, col, row_fixed, row))
+}
+
 /// Parse a cell range like "A1:C10"
 pub fn parse_range(range_str: &str) -> Result<CellRange> {
     let parts: Vec<&str> = range_str.split(':').collect();
@@ -143,3 +264,172 @@ pub fn get_range_values(range: &CellRange, data: &[Vec<String>]) -> Vec<f64> {
 
     values
 }
+
+/// Token of an arithmetic expression
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    /// Cell reference or function call, resolved by the caller
+    Operand(String),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+/// Evaluate an arithmetic expression with `+ - * /`, unary signs and parentheses.
+///
+/// `*` and `/` bind tighter than `+` and `-`, and operators of equal precedence
+/// associate to the left. Operands that are not numeric literals (cell references
+/// such as `A1` or function calls such as `SUM(A1:A3)`) are passed to `resolve`.
+pub fn evaluate_arithmetic(expr: &str, resolve: &dyn Fn(&str) -> Result<f64>) -> Result<f64> {
+    let tokens = tokenize(expr)?;
+    let mut parser = ArithmeticParser {
+        tokens: &tokens,
+        pos: 0,
+        resolve,
+    };
+
+    let value = parser.parse_expression()?;
+    if parser.pos < tokens.len() {
+        anyhow::bail!("Unexpected token in expression: {}", expr);
+    }
+
+    Ok(value)
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '+' | '-' | '*' | '/' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let num = text
+                    .parse::<f64>()
+                    .with_context(|| format!("Invalid number in expression: {}", text))?;
+                tokens.push(Token::Number(num));
+            }
+            c if c.is_alphabetic() || c == '$' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '$') {
+                    i += 1;
+                }
+
+                // A name followed by `(` is a function call; keep its balanced arguments
+                if chars.get(i) == Some(&'(') {
+                    let mut depth = 0;
+                    while i < chars.len() {
+                        match chars[i] {
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                        i += 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    if depth != 0 {
+                        anyhow::bail!("Unbalanced parentheses in expression: {}", expr);
+                    }
+                }
+
+                tokens.push(Token::Operand(chars[start..i].iter().collect()));
+            }
+            _ => anyhow::bail!("Unexpected character '{}' in expression: {}", c, expr),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over arithmetic tokens
+struct ArithmeticParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    resolve: &'a dyn Fn(&str) -> Result<f64>,
+}
+
+impl ArithmeticParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// expression := term (('+' | '-') term)*
+    fn parse_expression(&mut self) -> Result<f64> {
+        let mut value = self.parse_term()?;
+
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.parse_term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+
+        Ok(value)
+    }
+
+    /// term := factor (('*' | '/') factor)*
+    fn parse_term(&mut self) -> Result<f64> {
+        let mut value = self.parse_factor()?;
+
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.parse_factor()?;
+            if op == '*' {
+                value *= rhs;
+            } else {
+                if rhs == 0.0 {
+                    return Err(FormulaError::DivZero.into());
+                }
+                value /= rhs;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// factor := ('+' | '-') factor | number | operand | '(' expression ')'
+    fn parse_factor(&mut self) -> Result<f64> {
+        match self.next() {
+            Some(Token::Op('-')) => Ok(-self.parse_factor()?),
+            Some(Token::Op('+')) => self.parse_factor(),
+            Some(Token::Number(num)) => Ok(num),
+            Some(Token::Operand(operand)) => (self.resolve)(&operand),
+            Some(Token::LParen) => {
+                let value = self.parse_expression()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(value),
+                    _ => anyhow::bail!("Missing closing parenthesis in expression"),
+                }
+            }
+            Some(token) => anyhow::bail!("Unexpected token in expression: {:?}", token),
+            None => anyhow::bail!("Unexpected end of expression"),
+        }
+    }
+}
@@ -1,10 +1,17 @@
 //! Formula types
 
-/// Result of formula evaluation - can be number or string
-#[derive(Debug, Clone)]
+use serde::Serialize;
+
+/// Result of formula evaluation - can be number, string, or an Excel error value
+///
+/// Serializes as `{"type": "number" | "text" | "error", "value": ...}`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
 pub enum FormulaResult {
     Number(f64),
     Text(String),
+    /// Error value such as `#DIV/0!`, written into the cell in place of a result
+    Error(String),
 }
 
 impl std::fmt::Display for FormulaResult {
@@ -12,6 +19,7 @@ impl std::fmt::Display for FormulaResult {
         match self {
             FormulaResult::Number(n) => write!(f, "{}", n),
             FormulaResult::Text(s) => write!(f, "{}", s),
+            FormulaResult::Error(code) => write!(f, "{}", code),
         }
     }
 }
@@ -21,10 +29,51 @@ impl FormulaResult {
         match self {
             FormulaResult::Number(n) => Some(*n),
             FormulaResult::Text(s) => s.parse().ok(),
+            FormulaResult::Error(_) => None,
         }
     }
 }
 
+/// Spreadsheet error raised while evaluating a formula
+///
+/// Evaluators return these through `anyhow` so they propagate out of nested
+/// calls like any other error; the top-level evaluation turns them into
+/// [`FormulaResult::Error`] instead of failing the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormulaError {
+    /// `#DIV/0!` - division by zero
+    DivZero,
+    /// `#REF!` - reference outside the data
+    Ref,
+    /// `#VALUE!` - operand of the wrong type
+    Value,
+    /// `#N/A` - lookup found no match
+    NotAvailable,
+    /// `#NUM!` - invalid numeric result, such as an impossible date
+    Num,
+}
+
+impl FormulaError {
+    /// The error value as Excel displays it
+    pub fn code(&self) -> &'static str {
+        match self {
+            FormulaError::DivZero => "#DIV/0!",
+            FormulaError::Ref => "#REF!",
+            FormulaError::Value => "#VALUE!",
+            FormulaError::NotAvailable => "#N/A",
+            FormulaError::Num => "#NUM!",
+        }
+    }
+}
+
+impl std::fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
 /// Internal cell range representation
 #[derive(Clone)]
 pub(crate) struct CellRange {
@@ -12,17 +12,96 @@ pub struct Coordinate {
     pub longitude: f64,
 }
 
+/// Formula used for distances
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum DistanceFormula {
+    /// Great-circle distance on a sphere; fast, within about 0.5%
+    #[default]
+    Haversine,
+    /// Iterative solution on the WGS-84 ellipsoid; accurate to millimetres
+    Vincenty,
+}
+
+/// WGS-84 semi-major axis in kilometers
+const WGS84_A: f64 = 6378.137;
+/// WGS-84 flattening
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+/// Iteration limit before Vincenty gives up, e.g. for nearly antipodal points
+const VINCENTY_MAX_ITERATIONS: usize = 200;
+
 /// Geospatial calculator
-pub struct GeospatialCalculator;
+pub struct GeospatialCalculator {
+    formula: DistanceFormula,
+}
 
 impl GeospatialCalculator {
     pub fn new() -> Self {
-        Self
+        Self {
+            formula: DistanceFormula::default(),
+        }
     }
 
-    /// Calculate distance between two coordinates using Haversine formula
+    /// Use `formula` for distances
+    pub fn with_formula(mut self, formula: DistanceFormula) -> Self {
+        self.formula = formula;
+        self
+    }
+
+    /// Calculate distance between two coordinates with the configured formula
     /// Returns distance in kilometers
+    ///
+    /// Vincenty falls back to Haversine when it does not converge.
     pub fn distance(&self, from: &Coordinate, to: &Coordinate) -> f64 {
+        match self.formula {
+            DistanceFormula::Haversine => self.haversine(from, to),
+            DistanceFormula::Vincenty => self
+                .vincenty(from, to)
+                .unwrap_or_else(|| self.haversine(from, to)),
+        }
+    }
+
+    /// Distance from each row's coordinates to `reference`, appended as a
+    /// `distance_km` column
+    ///
+    /// Short rows are padded to the header width, and rows whose latitude or
+    /// longitude is missing or not a number get an empty distance.
+    pub fn distance_column(
+        &self,
+        data: &[Vec<String>],
+        lat_col: usize,
+        lon_col: usize,
+        reference: &Coordinate,
+    ) -> Vec<Vec<String>> {
+        let width = data.first().map_or(0, Vec::len);
+        data.iter()
+            .enumerate()
+            .map(|(idx, row)| {
+                let distance = if idx == 0 {
+                    "distance_km".to_string()
+                } else {
+                    let coordinate = |col: usize| row.get(col)?.trim().parse::<f64>().ok();
+                    match (coordinate(lat_col), coordinate(lon_col)) {
+                        (Some(latitude), Some(longitude)) => {
+                            let point = Coordinate {
+                                latitude,
+                                longitude,
+                            };
+                            format!("{:.3}", self.distance(&point, reference))
+                        }
+                        _ => String::new(),
+                    }
+                };
+
+                let mut row = row.clone();
+                row.resize(width.max(row.len()), String::new());
+                row.push(distance);
+                row
+            })
+            .collect()
+    }
+
+    /// Great-circle distance in kilometers on a sphere of the mean Earth radius
+    fn haversine(&self, from: &Coordinate, to: &Coordinate) -> f64 {
         const EARTH_RADIUS_KM: f64 = 6371.0;
 
         let lat1_rad = from.latitude.to_radians();
@@ -37,6 +116,68 @@ impl GeospatialCalculator {
         EARTH_RADIUS_KM * c
     }
 
+    /// Vincenty's inverse formula on the WGS-84 ellipsoid, in kilometers
+    ///
+    /// Returns `None` if the iteration does not converge.
+    fn vincenty(&self, from: &Coordinate, to: &Coordinate) -> Option<f64> {
+        let b = WGS84_A * (1.0 - WGS84_F);
+        let l = (to.longitude - from.longitude).to_radians();
+        let u1 = ((1.0 - WGS84_F) * from.latitude.to_radians().tan()).atan();
+        let u2 = ((1.0 - WGS84_F) * to.latitude.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..VINCENTY_MAX_ITERATIONS {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                return Some(0.0); // Coincident points
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha.powi(2);
+            // Both points on the equator
+            let cos_2sigma_m = if cos_sq_alpha == 0.0 {
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            };
+            let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+            let previous = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * WGS84_F
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+            if (lambda - previous).abs() < 1e-12 {
+                let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+                let big_a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+                let delta_sigma = big_b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + big_b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                                - big_b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                    * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+                return Some(b * big_a * (sigma - delta_sigma));
+            }
+        }
+
+        None
+    }
+
     /// Calculate bearing (direction) from one point to another
     /// Returns bearing in degrees (0-360)
     pub fn bearing(&self, from: &Coordinate, to: &Coordinate) -> f64 {
@@ -53,6 +194,27 @@ impl GeospatialCalculator {
         (bearing_deg + 360.0) % 360.0
     }
 
+    /// Point halfway along the great circle between two coordinates
+    pub fn midpoint(&self, from: &Coordinate, to: &Coordinate) -> Coordinate {
+        let lat1_rad = from.latitude.to_radians();
+        let lat2_rad = to.latitude.to_radians();
+        let lon1_rad = from.longitude.to_radians();
+        let delta_lon = (to.longitude - from.longitude).to_radians();
+
+        let bx = lat2_rad.cos() * delta_lon.cos();
+        let by = lat2_rad.cos() * delta_lon.sin();
+
+        let latitude = (lat1_rad.sin() + lat2_rad.sin())
+            .atan2(((lat1_rad.cos() + bx).powi(2) + by.powi(2)).sqrt());
+        let longitude = lon1_rad + by.atan2(lat1_rad.cos() + bx);
+
+        Coordinate {
+            latitude: latitude.to_degrees(),
+            // Normalize to -180..180
+            longitude: (longitude.to_degrees() + 540.0) % 360.0 - 180.0,
+        }
+    }
+
     /// Parse coordinate from string (format: "lat,lon" or "lat, lon")
     pub fn parse_coordinate(&self, coord_str: &str) -> Result<Coordinate> {
         let parts: Vec<&str> = coord_str.split(',').map(|s| s.trim()).collect();
@@ -89,4 +251,18 @@ impl GeospatialCalculator {
         let to = self.parse_coordinate(to_str)?;
         Ok(self.distance(&from, &to))
     }
+
+    /// Calculate bearing between two coordinate strings
+    pub fn bearing_from_strings(&self, from_str: &str, to_str: &str) -> Result<f64> {
+        let from = self.parse_coordinate(from_str)?;
+        let to = self.parse_coordinate(to_str)?;
+        Ok(self.bearing(&from, &to))
+    }
+
+    /// Calculate midpoint between two coordinate strings
+    pub fn midpoint_from_strings(&self, from_str: &str, to_str: &str) -> Result<Coordinate> {
+        let from = self.parse_coordinate(from_str)?;
+        let to = self.parse_coordinate(to_str)?;
+        Ok(self.midpoint(&from, &to))
+    }
 }
@@ -5,6 +5,7 @@ use crate::csv_handler::CsvHandler;
 use crate::excel::ExcelHandler;
 use crate::format_detector::DefaultFormatDetector;
 use crate::google_sheets::GoogleSheetsHandler;
+use crate::json::JsonLinesHandler;
 use crate::traits::FormatDetector;
 use crate::traits::{DataReader, DataWriteOptions, DataWriter, FileHandler};
 use anyhow::Result;
@@ -30,6 +31,7 @@ impl HandlerRegistry {
             "xlsx" | "xls" | "ods" => Ok(Box::new(ExcelHandler::new())),
             "parquet" => Ok(Box::new(ParquetHandler::new())),
             "avro" => Ok(Box::new(AvroHandler::new())),
+            "ndjson" | "jsonl" => Ok(Box::new(JsonLinesHandler::new())),
             "gsheet" => Ok(Box::new(GoogleSheetsHandler::new())),
             _ => anyhow::bail!("Unsupported format: {format}"),
         }
@@ -44,6 +46,7 @@ impl HandlerRegistry {
             "xlsx" | "xls" | "ods" => Ok(Box::new(ExcelHandler::new())),
             "parquet" => Ok(Box::new(ParquetHandler::new())),
             "avro" => Ok(Box::new(AvroHandler::new())),
+            "ndjson" | "jsonl" => Ok(Box::new(JsonLinesHandler::new())),
             "gsheet" => Ok(Box::new(GoogleSheetsHandler::new())),
             _ => anyhow::bail!("Unsupported format: {format}"),
         }
@@ -57,6 +60,7 @@ impl HandlerRegistry {
             "csv" => Ok(Box::new(CsvHandler::new())),
             "parquet" => Ok(Box::new(ParquetHandler::new())),
             "avro" => Ok(Box::new(AvroHandler::new())),
+            "ndjson" | "jsonl" => Ok(Box::new(JsonLinesHandler::new())),
             "gsheet" => Ok(Box::new(GoogleSheetsHandler::new())),
             _ => anyhow::bail!("Unsupported format: {format}"),
         }
@@ -163,3 +163,80 @@ pub fn validate_column_index(data: &[Vec<String>], col: usize) -> Result<()> {
     Ok(())
 }
 
+
+/// Storage type inferred for a column of string cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Int64,
+    Float64,
+    Boolean,
+    Utf8,
+}
+
+impl ColumnType {
+    /// The narrowest type that holds values of both `self` and `other`
+    pub fn widen(self, other: ColumnType) -> ColumnType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::Int64, ColumnType::Float64) | (ColumnType::Float64, ColumnType::Int64) => {
+                ColumnType::Float64
+            }
+            _ => ColumnType::Utf8,
+        }
+    }
+}
+
+/// Infer the narrowest type that every non-empty cell in `col` parses as
+///
+/// Empty and missing cells are ignored. Integers with leading zeros or a `+`
+/// sign (zip codes, phone numbers) stay strings so they round-trip unchanged.
+/// Columns with no non-empty cells are `Utf8`.
+pub fn infer_column_type(data: &[Vec<String>], col: usize) -> ColumnType {
+    let mut values = data
+        .iter()
+        .filter_map(|row| row.get(col))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .peekable();
+
+    if values.peek().is_none() {
+        return ColumnType::Utf8;
+    }
+
+    let (mut int, mut float, mut boolean) = (true, true, true);
+    for value in values {
+        let canonical = !value.starts_with('+') && !has_leading_zero(value);
+        int &= canonical && value.parse::<i64>().is_ok();
+        float &= canonical && value.parse::<f64>().is_ok_and(|f| f.is_finite());
+        boolean &= parse_bool(value).is_some();
+        if !(int || float || boolean) {
+            return ColumnType::Utf8;
+        }
+    }
+
+    if int {
+        ColumnType::Int64
+    } else if float {
+        ColumnType::Float64
+    } else if boolean {
+        ColumnType::Boolean
+    } else {
+        ColumnType::Utf8
+    }
+}
+
+/// Parse `true`/`false` case-insensitively
+pub fn parse_bool(value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn has_leading_zero(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value).as_bytes();
+    digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit()
+}
@@ -0,0 +1,238 @@
+//! JSON-lines (NDJSON) file handling
+//!
+//! Each non-blank line holds one JSON object. Reading turns the union of the
+//! top-level keys into columns; writing emits one object per row keyed by
+//! the header.
+
+use anyhow::{Context, Result};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use crate::csv_handler::CellRange;
+use crate::helpers::filter_by_range;
+use crate::traits::{DataReader, DataWriteOptions, DataWriter, FileHandler};
+
+/// Handler for `.ndjson` / `.jsonl` files
+#[derive(Default)]
+pub struct JsonLinesHandler;
+
+impl JsonLinesHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Read a JSON-lines file with column names as the first row
+    ///
+    /// Columns are the union of all keys, in the order they are first seen.
+    /// Cells are blank where an object lacks the key or holds `null`; nested
+    /// arrays and objects are kept as compact JSON text.
+    pub fn read(&self, path: &str) -> Result<Vec<Vec<String>>> {
+        let file = File::open(path).with_context(|| format!("Failed to open JSON file: {path}"))?;
+
+        let mut header: Vec<String> = Vec::new();
+        let mut columns: HashMap<String, usize> = HashMap::new();
+        let mut rows: Vec<Vec<String>> = Vec::new();
+
+        for (line_idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let OrderedObject(entries) = serde_json::from_str(&line)
+                .with_context(|| format!("Line {} of {path} is not a JSON object", line_idx + 1))?;
+
+            let mut row = vec![String::new(); header.len()];
+            for (key, value) in entries {
+                let col = *columns.entry(key.clone()).or_insert_with(|| {
+                    header.push(key);
+                    header.len() - 1
+                });
+                if col >= row.len() {
+                    row.resize(col + 1, String::new());
+                }
+                row[col] = value_to_cell(&value);
+            }
+            rows.push(row);
+        }
+
+        // Rows read before a key first appeared are padded to the full width
+        for row in &mut rows {
+            row.resize(header.len(), String::new());
+        }
+
+        let mut data = Vec::with_capacity(rows.len() + 1);
+        data.push(header);
+        data.extend(rows);
+        Ok(data)
+    }
+
+    /// Write rows as JSON lines, using the first row as the keys
+    ///
+    /// Values are written as JSON strings. Cells past the end of a short row
+    /// are omitted from its object.
+    pub fn write(&self, path: &str, data: &[Vec<String>]) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create JSON file: {path}"))?;
+        let mut writer = BufWriter::new(file);
+        Self::write_rows(&mut writer, data)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Append rows, using the first row of `data` as the keys
+    pub fn append(&self, path: &str, data: &[Vec<String>]) -> Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open JSON file: {path}"))?;
+        let mut writer = BufWriter::new(file);
+        Self::write_rows(&mut writer, data)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_rows(writer: &mut impl Write, data: &[Vec<String>]) -> Result<()> {
+        let Some((header, rows)) = data.split_first() else {
+            return Ok(());
+        };
+        for row in rows {
+            serde_json::to_writer(&mut *writer, &row_object(header, row))?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// JSON object mapping each header name to the row's value, as a string
+///
+/// Keys are written in header order.
+pub(crate) fn row_object<'a>(header: &'a [String], row: &'a [String]) -> RowObject<'a> {
+    RowObject { header, row }
+}
+
+/// Serializes as the object built by [`row_object`]
+pub(crate) struct RowObject<'a> {
+    header: &'a [String],
+    row: &'a [String],
+}
+
+impl Serialize for RowObject<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let len = self.header.len().min(self.row.len());
+        let mut map = serializer.serialize_map(Some(len))?;
+        for (key, value) in self.header.iter().zip(self.row) {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+fn value_to_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Top-level entries of one JSON object, in document order
+///
+/// `serde_json::Map` sorts its keys, which would lose first-seen order.
+struct OrderedObject(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct ObjectVisitor;
+
+        impl<'de> Visitor<'de> for ObjectVisitor {
+            type Value = OrderedObject;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry::<String, Value>()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedObject(entries))
+            }
+        }
+
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+fn is_json_lines(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".ndjson") || lower.ends_with(".jsonl")
+}
+
+impl DataReader for JsonLinesHandler {
+    fn read(&self, path: &str) -> Result<Vec<Vec<String>>> {
+        self.read(path)
+    }
+
+    fn read_with_headers(&self, path: &str) -> Result<Vec<Vec<String>>> {
+        self.read(path)
+    }
+
+    fn read_range(&self, path: &str, range: &CellRange) -> Result<Vec<Vec<String>>> {
+        let all_data = self.read(path)?;
+        Ok(filter_by_range(&all_data, range))
+    }
+
+    fn read_as_json(&self, path: &str) -> Result<String> {
+        let data = self.read(path)?;
+        serde_json::to_string_pretty(&data).with_context(|| "Failed to serialize to JSON")
+    }
+
+    fn supports_format(&self, path: &str) -> bool {
+        is_json_lines(path)
+    }
+}
+
+impl DataWriter for JsonLinesHandler {
+    fn write(&self, path: &str, data: &[Vec<String>], _options: DataWriteOptions) -> Result<()> {
+        self.write(path, data)
+    }
+
+    fn write_range(
+        &self,
+        path: &str,
+        data: &[Vec<String>],
+        _start_row: usize,
+        _start_col: usize,
+    ) -> Result<()> {
+        // Lines have no cell positions, so the whole dataset is written
+        self.write(path, data)
+    }
+
+    fn append(&self, path: &str, data: &[Vec<String>]) -> Result<()> {
+        self.append(path, data)
+    }
+
+    fn supports_format(&self, path: &str) -> bool {
+        is_json_lines(path)
+    }
+}
+
+impl FileHandler for JsonLinesHandler {
+    fn format_name(&self) -> &'static str {
+        "ndjson"
+    }
+
+    fn supported_extensions(&self) -> &'static [&'static str] {
+        &["ndjson", "jsonl"]
+    }
+}
@@ -0,0 +1,6 @@
+//! JSON file handling module
+
+mod lines;
+
+pub use lines::JsonLinesHandler;
+pub(crate) use lines::row_object;
@@ -22,6 +22,7 @@ pub mod geospatial;
 pub mod google_sheets;
 pub mod handler_registry;
 pub mod helpers;
+pub mod json;
 pub mod lineage;
 pub mod mcp;
 #[cfg(test)]
@@ -36,6 +37,7 @@ pub mod streaming;
 pub mod string_utils;
 pub mod text_analysis;
 pub mod text_analysis_handler;
+pub mod text_encoding;
 pub mod timeseries;
 pub mod traits;
 pub mod types;
@@ -43,12 +45,14 @@ pub mod validation;
 pub mod workflow;
 
 pub use anomaly::{Anomaly, AnomalyDetector, AnomalyMethod, AnomalyResult};
-pub use api::{ApiConfig, ApiRequest, ApiResponse, ApiServer};
-pub use columnar::{AvroHandler, ParquetHandler};
+pub use api::{ApiConfig, ApiResponse, ApiServer};
+pub use columnar::{
+    AvroHandler, ParquetCompression, ParquetHandler, ParquetStreamWriter, ParquetWriteOptions,
+};
 pub use config::Config;
-pub use converter::Converter;
+pub use converter::{ConvertDirSummary, Converter, STDIO_PATH};
 pub use csv_handler::{
-    CellRange, CellRangeHelper, CsvHandler, StreamingCsvReader, StreamingCsvWriter,
+    CellRange, CellRangeHelper, CsvHandler, CsvOptions, StreamingCsvReader, StreamingCsvWriter,
     sanitize_csv_value, sanitize_csv_row,
 };
 pub use encryption::{DataEncryptor, EncryptionAlgorithm};
@@ -58,13 +62,13 @@ pub use error_traits::{
     ToTraitBasedError, TraitBasedError, UserFriendlyError,
 };
 pub use excel::{
-    CellData, CellStyle, ChartConfig, ConditionalFormat, ConditionalRule, DataChartType,
-    ExcelHandler, RowData, Sparkline, SparklineGroup, SparklineType, StreamingXlsxWriter,
-    WriteOptions, XlsxWriter,
+    CellData, CellStyle, ChartConfig, ChartGrouping, Comment, ConditionalFormat, ConditionalRule,
+    DataChartType, DataValidation, ExcelHandler, RowData, Sparkline, SparklineGroup, SparklineType,
+    StreamingXlsxWriter, WriteOptions, XlsxWriter,
 };
 pub use format_detector::DefaultFormatDetector;
-pub use formula::{FormulaEvaluator, FormulaResult};
-pub use geospatial::{Coordinate, GeospatialCalculator};
+pub use formula::{FormulaError, FormulaEvaluator, FormulaResult};
+pub use geospatial::{Coordinate, DistanceFormula, GeospatialCalculator};
 pub use google_sheets::GoogleSheetsHandler;
 pub use handler_registry::HandlerRegistry;
 pub use helpers::{
@@ -73,16 +77,20 @@ pub use helpers::{
     with_cell_context, with_file_context, with_full_context,
     validate_row_index, validate_column_index,
 };
+pub use json::JsonLinesHandler;
 pub use lineage::{LineageNode, LineageTracker};
 pub use mcp::DatacellMcpServer;
 pub use operations::{
-    AggFunc, DataOperations, JoinType, NoProgress, ProgressCallback, SortOrder, StderrProgress,
+    AggFunc, ColumnType, CumOp, DataOperations, FillMethod, JoinType, NoProgress, NormalizeMethod,
+    PROGRESS_INTERVAL, ProgressCallback, RankMethod, SortOrder, StderrProgress,
 };
 pub use plugins::{FunctionMetadata, PluginFunction, PluginMetadata, PluginRegistry};
-pub use profiling::{ColumnProfile, DataProfile, DataProfiler};
+pub use profiling::{ColumnProfile, CorrelationMatrix, DataProfile, DataProfiler};
 pub use quality::{IssueSeverity, QualityIssue, QualityReport, QualityReportGenerator};
 pub use streaming::{
-    DataChunk, StreamingChannel, StreamingDataReader, StreamingDataWriter, StreamingProcessor,
+    BloomFilter, ConversionCheckpoint, DataChunk, DedupeMode, DedupeStats, StreamingChannel,
+    StreamingConvertStats, StreamingDataReader, StreamingDataWriter, StreamingProcessor,
+    convert_csv_to_parquet_streaming, convert_parquet_streaming, convert_streaming,
 };
 pub use string_utils::{
     join_cell_reference, join_with_capacity, string_with_capacity, StringBuilder,
@@ -90,7 +98,8 @@ pub use string_utils::{
 };
 pub use text_analysis::{KeywordResult, LanguageResult, SentimentResult, TextAnalyzer, TextStats};
 pub use timeseries::{
-    ResampleInterval, RollingWindow, TimeSeriesAgg, TimeSeriesPoint, TimeSeriesProcessor,
+    Decomposition, GapFill, ResampleInterval, RollingWindow, TimeSeriesAgg, TimeSeriesPoint,
+    TimeSeriesProcessor,
 };
 pub use traits::{
     CellRangeProvider, DataOperator, DataReader, DataWriteOptions, DataWriter, FileHandler,
@@ -99,4 +108,4 @@ pub use traits::{
 };
 pub use types::{CellValue, DataSet, DataType, DataRow};
 pub use validation::{DataValidator, ValidationConfig, ValidationResult, ValidationRule};
-pub use workflow::{WorkflowConfig, WorkflowExecutor, WorkflowStep};
+pub use workflow::{Aggregation, StepAction, WorkflowConfig, WorkflowExecutor, WorkflowStep};
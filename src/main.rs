@@ -10,7 +10,10 @@ use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let handler = DefaultCommandHandler::new();
+    let handler = match cli.input_format.as_deref() {
+        Some(format) => DefaultCommandHandler::new().with_stdin_format(format),
+        None => DefaultCommandHandler::new(),
+    };
 
     handler.handle(cli.command)
 }
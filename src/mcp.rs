@@ -1,8 +1,10 @@
 use rmcp::{
-    ServerHandler,
+    RoleServer, ServerHandler,
     handler::server::wrapper::Parameters,
     model::{ErrorData as McpError, *},
-    schemars, tool, tool_handler, tool_router,
+    schemars,
+    service::RequestContext,
+    tool, tool_handler, tool_router,
 };
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -11,6 +13,8 @@ use crate::converter::Converter;
 use crate::csv_handler::CsvHandler;
 use crate::excel::ExcelHandler;
 use crate::formula::FormulaEvaluator;
+use crate::operations::{DataOperations, SortOrder};
+use crate::profiling::DataProfiler;
 
 use rmcp::handler::server::tool::ToolRouter;
 
@@ -61,6 +65,50 @@ pub struct FormulaRequest {
     pub sheet: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct EvaluateRequest {
+    #[schemars(description = "Path to the file whose cells the formula reads")]
+    pub path: String,
+    #[schemars(
+        description = "Formula to evaluate, e.g. 'SUM(A1:A3)' or 'VLOOKUP(\"x\", A1:B9, 2)'; A1 is the first row of the file"
+    )]
+    pub formula: String,
+    #[schemars(description = "Sheet name for Excel files (optional, defaults to first sheet)")]
+    pub sheet: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ProfileRequest {
+    #[schemars(description = "Path to the file to profile (any format datacell reads)")]
+    pub path: String,
+    #[schemars(
+        description = "Profile an evenly spaced sample of this many rows (optional, defaults to all rows)"
+    )]
+    pub sample_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TransformRequest {
+    #[schemars(description = "Path to the input file (any format datacell reads)")]
+    pub input: String,
+    #[schemars(description = "Path to the output file; the format follows its extension")]
+    pub output: String,
+    #[schemars(
+        description = "Operations applied in order, each an object naming its op: \
+        {op: 'filter', col, operator (= != > < >= <= contains starts_with ends_with), value}, \
+        {op: 'where', clause} such as age > 30 AND city = NYC, \
+        {op: 'sort', col, descending?}, {op: 'select', cols}, {op: 'drop', cols}, \
+        {op: 'rename', from, to}, {op: 'dedupe'}, {op: 'fillna', value}, \
+        {op: 'mutate', col, formula} such as price*qty"
+    )]
+    pub operations: Vec<serde_json::Value>,
+}
+
+/// `op` names accepted by the transform tool
+pub(crate) const TRANSFORM_OPS: [&str; 9] = [
+    "filter", "where", "sort", "select", "drop", "rename", "dedupe", "fillna", "mutate",
+];
+
 fn make_error(msg: String) -> McpError {
     McpError {
         code: ErrorCode::INTERNAL_ERROR,
@@ -186,6 +234,134 @@ impl DatacellMcpServer {
             Err(e) => Err(make_error(format!("Failed to apply formula: {}", e))),
         }
     }
+
+    #[tool(
+        description = "Evaluate a formula against a file's cells without writing anything; returns {type: number|text|error, value}"
+    )]
+    pub async fn evaluate_formula(
+        &self,
+        request: Parameters<EvaluateRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let data = Converter::new()
+            .read_any_data(&request.0.path, request.0.sheet.as_deref())
+            .map_err(|e| make_error(format!("Failed to read file: {}", e)))?;
+
+        match FormulaEvaluator::new().evaluate(&request.0.formula, &data) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::json(result)?])),
+            Err(e) => Err(make_error(format!("Failed to evaluate formula: {}", e))),
+        }
+    }
+
+    #[tool(
+        description = "Profile a data file: per-column types, null and distinct counts, statistics, a data quality score and recommendations, returned as JSON"
+    )]
+    pub async fn profile_file(
+        &self,
+        request: Parameters<ProfileRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let data = Converter::new()
+            .read_any_data(&request.0.path, None)
+            .map_err(|e| make_error(format!("Failed to read file: {}", e)))?;
+
+        let mut profiler = DataProfiler::new();
+        if let Some(size) = request.0.sample_size {
+            profiler = profiler.with_sample_size(size);
+        }
+
+        match profiler.profile(&data, &request.0.path) {
+            Ok(profile) => Ok(CallToolResult::success(vec![Content::json(profile)?])),
+            Err(e) => Err(make_error(format!("Failed to profile file: {}", e))),
+        }
+    }
+
+    #[tool(
+        description = "Apply an ordered chain of operations (filter, where, sort, select, drop, rename, dedupe, fillna, mutate) to a file and write the result"
+    )]
+    pub async fn transform(
+        &self,
+        request: Parameters<TransformRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let converter = Converter::new();
+        let mut data = converter
+            .read_any_data(&request.0.input, None)
+            .map_err(|e| make_error(format!("Failed to read file: {}", e)))?;
+
+        for (step, operation) in request.0.operations.iter().enumerate() {
+            data = apply_operation(&data, operation).map_err(|e| step_error(step, operation, e))?;
+        }
+
+        converter
+            .write_any_data(&request.0.output, &data, None)
+            .map_err(|e| make_error(format!("Failed to write file: {}", e)))?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Applied {} operation(s); wrote {} rows to {}",
+            request.0.operations.len(),
+            data.len().saturating_sub(1),
+            request.0.output
+        ))]))
+    }
+}
+
+/// URI template of the schema resource, resolved by [`DatacellMcpServer::read_schema`]
+const SCHEMA_URI_TEMPLATE: &str = "file:///{path}";
+
+impl DatacellMcpServer {
+    /// Resource templates advertised to clients
+    pub fn resource_templates(&self) -> Vec<ResourceTemplate> {
+        vec![
+            RawResourceTemplate {
+                uri_template: SCHEMA_URI_TEMPLATE.to_string(),
+                name: "file_schema".to_string(),
+                title: Some("Sheets and column types".to_string()),
+                description: Some(
+                    "Sheet names (for Excel and ODS workbooks) and the inferred type of each \
+                    column of the first sheet, as {sheets: [...], columns: [{name, type}]}"
+                        .to_string(),
+                ),
+                mime_type: Some("application/json".to_string()),
+                icons: None,
+            }
+            .no_annotation(),
+        ]
+    }
+
+    /// Sheet names and inferred column types of the file at a `file://` URI
+    pub fn read_schema(&self, uri: &str) -> Result<ReadResourceResult, McpError> {
+        let path = uri.strip_prefix("file://").ok_or_else(|| {
+            McpError::resource_not_found(format!("Expected a file:// URI, got {uri}"), None)
+        })?;
+
+        let lower = path.to_lowercase();
+        let handler = ExcelHandler::new();
+        let sheets = if lower.ends_with(".xlsx") || lower.ends_with(".xls") {
+            handler.list_sheets(path)
+        } else if lower.ends_with(".ods") {
+            handler.list_ods_sheets(path)
+        } else {
+            Ok(Vec::new())
+        }
+        .map_err(|e| make_error(format!("Failed to list sheets: {}", e)))?;
+
+        let data = Converter::new()
+            .read_any_data(path, None)
+            .map_err(|e| make_error(format!("Failed to read file: {}", e)))?;
+        let columns: Vec<serde_json::Value> = DataOperations::new()
+            .infer_dtypes(&data)
+            .into_iter()
+            .map(|(name, dtype)| serde_json::json!({ "name": name, "type": dtype }))
+            .collect();
+
+        let schema = serde_json::json!({ "sheets": sheets, "columns": columns });
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some("application/json".to_string()),
+                text: schema.to_string(),
+                meta: None,
+            }],
+        })
+    }
 }
 
 #[tool_handler]
@@ -193,14 +369,146 @@ impl ServerHandler for DatacellMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(
                 "A spreadsheet tool for reading, writing, converting CSV and Excel files with formula support. \
                 Use read_file to read data, write_file to write data, convert_file to convert between formats, \
-                and apply_formula to apply formulas to spreadsheets."
+                apply_formula to apply formulas to spreadsheets, evaluate_formula to compute a formula \
+                without writing, profile_file to check data quality, \
+                and transform to filter, sort, select and derive columns in one call. \
+                Read a file:// URI as a resource for its sheets and column types."
                     .to_string(),
             ),
         }
     }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            self.resource_templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.read_schema(&request.uri)
+    }
+}
+
+/// Why a transform step failed
+pub(crate) enum StepError {
+    /// The descriptor names no `op`, or one the tool doesn't know
+    UnknownOp,
+    /// The op's arguments are missing or the operation itself failed
+    Failed(anyhow::Error),
+}
+
+impl From<anyhow::Error> for StepError {
+    fn from(e: anyhow::Error) -> Self {
+        StepError::Failed(e)
+    }
+}
+
+/// An invalid-params error whose data names the failing step and op
+fn step_error(step: usize, operation: &serde_json::Value, error: StepError) -> McpError {
+    let op = operation
+        .get("op")
+        .cloned()
+        .unwrap_or(serde_json::Value::Null);
+    let (message, data) = match error {
+        StepError::UnknownOp => (
+            format!("Unknown operation {op} at step {step}"),
+            serde_json::json!({ "step": step, "op": op, "supported": TRANSFORM_OPS }),
+        ),
+        StepError::Failed(e) => (
+            format!("Operation {op} at step {step} failed: {e}"),
+            serde_json::json!({ "step": step, "op": op, "error": e.to_string() }),
+        ),
+    };
+    McpError {
+        code: ErrorCode::INVALID_PARAMS,
+        message: Cow::from(message),
+        data: Some(data),
+    }
+}
+
+/// Apply one transform descriptor, like `{"op": "sort", "col": "name"}`
+pub(crate) fn apply_operation(
+    data: &[Vec<String>],
+    operation: &serde_json::Value,
+) -> Result<Vec<Vec<String>>, StepError> {
+    let ops = DataOperations::new();
+    let str_arg = |key: &str| -> anyhow::Result<&str> {
+        operation
+            .get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("missing string argument '{key}'"))
+    };
+    let column = |key: &str| -> anyhow::Result<usize> {
+        let name = str_arg(key)?;
+        data.first()
+            .and_then(|header| header.iter().position(|h| h == name))
+            .ok_or_else(|| anyhow::anyhow!("Column '{name}' not found"))
+    };
+    let columns = |key: &str| -> anyhow::Result<Vec<usize>> {
+        let names = operation
+            .get(key)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| anyhow::anyhow!("missing array argument '{key}'"))?;
+        names
+            .iter()
+            .map(|name| {
+                let name = name
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("'{key}' must list column names"))?;
+                data.first()
+                    .and_then(|header| header.iter().position(|h| h == name))
+                    .ok_or_else(|| anyhow::anyhow!("Column '{name}' not found"))
+            })
+            .collect()
+    };
+
+    let op = operation.get("op").and_then(|v| v.as_str());
+    let mut result = data.to_vec();
+    match op {
+        Some("filter") => {
+            let col = column("col")?;
+            let Some((header, rows)) = result.split_first() else {
+                return Ok(result);
+            };
+            let mut filtered = vec![header.clone()];
+            filtered.extend(ops.filter_rows(rows, col, str_arg("operator")?, str_arg("value")?)?);
+            result = filtered;
+        }
+        Some("where") => result = ops.filter_where(&result, str_arg("clause")?)?,
+        Some("sort") => {
+            let descending = operation.get("descending").and_then(|v| v.as_bool());
+            let order = if descending.unwrap_or(false) {
+                SortOrder::Descending
+            } else {
+                SortOrder::Ascending
+            };
+            ops.sort_by_column(&mut result, column("col")?, order, true)?;
+        }
+        Some("select") => result = ops.select_columns(&result, &columns("cols")?),
+        Some("drop") => result = ops.drop_columns(&result, &columns("cols")?),
+        Some("rename") => ops.rename_columns(&mut result, &[(str_arg("from")?, str_arg("to")?)])?,
+        Some("dedupe") => {
+            ops.deduplicate_mut(&mut result);
+        }
+        Some("fillna") => ops.fillna(&mut result, str_arg("value")?),
+        Some("mutate") => ops.mutate(&mut result, str_arg("col")?, str_arg("formula")?)?,
+        _ => return Err(StepError::UnknownOp),
+    }
+    Ok(result)
 }
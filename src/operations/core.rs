@@ -17,6 +17,54 @@ impl DataOperations {
     }
 }
 
+/// Compare two strings in natural order (see `sort_by_column_natural`)
+///
+/// This is a total order: only identical strings compare equal.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    // Numbers form their own class ahead of text, so mixing numeric and
+    // run-by-run comparisons can't make the order intransitive
+    let number = |s: &str| s.parse::<f64>().ok().filter(|n| n.is_finite());
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => return x.total_cmp(&y).then_with(|| a.cmp(b)),
+        (Some(_), None) => return Ordering::Less,
+        (None, Some(_)) => return Ordering::Greater,
+        (None, None) => {}
+    }
+
+    let is_digit_run = |s: &str| s.starts_with(|c: char| c.is_ascii_digit());
+    let chunks = |s: &str| -> Vec<String> {
+        let mut chunks: Vec<String> = Vec::new();
+        for ch in s.chars() {
+            match chunks.last_mut() {
+                Some(last) if is_digit_run(last) == ch.is_ascii_digit() => last.push(ch),
+                _ => chunks.push(ch.to_string()),
+            }
+        }
+        chunks
+    };
+
+    let (chunks_a, chunks_b) = (chunks(a), chunks(b));
+    for (x, y) in chunks_a.iter().zip(&chunks_b) {
+        let cmp = if is_digit_run(x) && is_digit_run(y) {
+            // Compare by magnitude without overflowing on long digit runs
+            let (tx, ty) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+            tx.len()
+                .cmp(&ty.len())
+                .then_with(|| tx.cmp(ty))
+                .then_with(|| x.len().cmp(&y.len()))
+        } else {
+            x.cmp(y)
+        };
+        if cmp != Ordering::Equal {
+            return cmp;
+        }
+    }
+
+    chunks_a.len().cmp(&chunks_b.len()).then_with(|| a.cmp(b))
+}
+
 // Trait implementations for better SOC
 impl SortOperator for DataOperations {
     fn sort(&self, data: &mut Vec<Vec<String>>, column: usize, ascending: bool) -> Result<()> {
@@ -25,17 +73,21 @@ impl SortOperator for DataOperations {
         } else {
             SortOrder::Descending
         };
-        self.sort_by_column(data, column, order)
+        self.sort_by_column(data, column, order, true)
     }
 }
 
 impl DataOperations {
     /// Sort rows by a specific column (public for backward compatibility)
+    ///
+    /// Row 0 stays in place when `has_header` is set; otherwise every row is
+    /// sorted. See [`DataOperations::sort_by_columns`] for comparison rules.
     pub fn sort_by_column(
         &self,
-        data: &mut Vec<Vec<String>>,
+        data: &mut [Vec<String>],
         column: usize,
         order: SortOrder,
+        has_header: bool,
     ) -> Result<()> {
         if data.is_empty() {
             return Ok(());
@@ -50,26 +102,92 @@ impl DataOperations {
             );
         }
 
-        // Use parallel sort for better performance on large datasets
-        data.par_sort_by(|a, b| {
-            let val_a = a.get(column).map(|s| s.as_str()).unwrap_or("");
-            let val_b = b.get(column).map(|s| s.as_str()).unwrap_or("");
+        self.sort_by_columns(data, &[(column, order)], has_header)
+    }
 
-            let cmp = match (val_a.parse::<f64>(), val_b.parse::<f64>()) {
-                (Ok(num_a), Ok(num_b)) => num_a
-                    .partial_cmp(&num_b)
-                    .unwrap_or(std::cmp::Ordering::Equal),
-                _ => val_a.cmp(val_b),
-            };
+    /// Sort rows in natural (alphanumeric) order, so `item2` comes before `item10`
+    ///
+    /// Cells that parse as finite numbers (including negatives) compare
+    /// numerically and sort before all other cells. Other cells are split into
+    /// digit and non-digit runs and digit runs compare by value, with fewer
+    /// leading zeros first on ties. Empty cells always sort last, regardless
+    /// of `order`.
+    pub fn sort_by_column_natural(
+        &self,
+        data: &mut [Vec<String>],
+        column: usize,
+        order: SortOrder,
+        has_header: bool,
+    ) -> Result<()> {
+        let rows = if has_header && !data.is_empty() {
+            &mut data[1..]
+        } else {
+            data
+        };
 
-            match order {
-                SortOrder::Ascending => cmp,
-                SortOrder::Descending => cmp.reverse(),
+        rows.par_sort_by(|a, b| {
+            let val_a = a.get(column).map(|s| s.trim()).unwrap_or("");
+            let val_b = b.get(column).map(|s| s.trim()).unwrap_or("");
+
+            match (val_a.is_empty(), val_b.is_empty()) {
+                (true, true) => std::cmp::Ordering::Equal,
+                (true, false) => std::cmp::Ordering::Greater,
+                (false, true) => std::cmp::Ordering::Less,
+                (false, false) => {
+                    let cmp = natural_cmp(val_a, val_b);
+                    match order {
+                        SortOrder::Ascending => cmp,
+                        SortOrder::Descending => cmp.reverse(),
+                    }
+                }
             }
         });
 
         Ok(())
     }
+
+    /// Sort rows chronologically by a date column parsed with a chrono format
+    ///
+    /// The header row stays in place. Cells that fail to parse (including
+    /// empty ones) are kept in their original order after all parsed rows,
+    /// regardless of `order`.
+    pub fn sort_by_date(
+        &self,
+        data: &mut [Vec<String>],
+        column: usize,
+        format: &str,
+        order: SortOrder,
+    ) -> Result<()> {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        if data.len() < 2 {
+            return Ok(());
+        }
+
+        let parse = |row: &Vec<String>| {
+            let cell = row.get(column).map(|s| s.trim()).unwrap_or("");
+            NaiveDateTime::parse_from_str(cell, format)
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(cell, format)
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                })
+        };
+
+        // Stable sort keeps ties and unparseable rows in input order
+        data[1..].sort_by_cached_key(|row| {
+            let key = parse(row);
+            let rank = match (key, order) {
+                (Some(dt), SortOrder::Ascending) => Some(dt.and_utc().timestamp()),
+                (Some(dt), SortOrder::Descending) => Some(-dt.and_utc().timestamp()),
+                (None, _) => None,
+            };
+            (rank.is_none(), rank)
+        });
+
+        Ok(())
+    }
 }
 
 impl FilterOperator for DataOperations {
@@ -106,7 +224,11 @@ impl DataOperations {
         <Self as FilterOperator>::filter(self, data, column, condition)
     }
 
-    fn parse_filter_condition(&self, operator: &str, value: &str) -> Result<FilterCondition> {
+    pub(super) fn parse_filter_condition(
+        &self,
+        operator: &str,
+        value: &str,
+    ) -> Result<FilterCondition> {
         Ok(match operator {
             "=" | "==" => FilterCondition::Equals(value.to_string()),
             "!=" | "<>" => FilterCondition::NotEquals(value.to_string()),
@@ -121,7 +243,11 @@ impl DataOperations {
         })
     }
 
-    fn evaluate_condition(&self, cell_value: &str, condition: &FilterCondition) -> Result<bool> {
+    pub(super) fn evaluate_condition(
+        &self,
+        cell_value: &str,
+        condition: &FilterCondition,
+    ) -> Result<bool> {
         Ok(match condition {
             FilterCondition::Equals(v) => cell_value == v,
             FilterCondition::NotEquals(v) => cell_value != v,
@@ -276,6 +402,63 @@ impl DataOperations {
         output
     }
 
+    /// Format data as tab-separated values
+    ///
+    /// Cells containing a tab, newline or double quote are quoted with
+    /// embedded quotes doubled, so a TSV reader gets the original text back.
+    pub fn to_tsv(&self, data: &[Vec<String>]) -> String {
+        let mut output = String::new();
+        for row in data {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|cell| {
+                    if cell.contains(['\t', '\n', '\r', '"']) {
+                        format!("\"{}\"", cell.replace('"', "\"\""))
+                    } else {
+                        cell.clone()
+                    }
+                })
+                .collect();
+            output.push_str(&fields.join("\t"));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Format data as an HTML table
+    ///
+    /// Row 0 becomes the `<thead>`, the rest the `<tbody>`. Cell values are
+    /// HTML-escaped.
+    pub fn to_html(&self, data: &[Vec<String>]) -> String {
+        if data.is_empty() {
+            return String::new();
+        }
+
+        let mut output = String::from("<table>\n");
+
+        // Header row
+        if let Some(header) = data.first() {
+            output.push_str("  <thead>\n    <tr>");
+            for cell in header {
+                output.push_str(&format!("<th>{}</th>", escape_html(cell)));
+            }
+            output.push_str("</tr>\n  </thead>\n");
+        }
+
+        // Data rows
+        output.push_str("  <tbody>\n");
+        for row in data.iter().skip(1) {
+            output.push_str("    <tr>");
+            for cell in row {
+                output.push_str(&format!("<td>{}</td>", escape_html(cell)));
+            }
+            output.push_str("</tr>\n");
+        }
+        output.push_str("  </tbody>\n</table>\n");
+
+        output
+    }
+
     /// Insert a row at a specific index
     pub fn insert_row(&self, data: &mut Vec<Vec<String>>, index: usize, row: Vec<String>) {
         if index <= data.len() {
@@ -352,11 +535,12 @@ impl TransformOperator for DataOperations {
                             // e.g., A1 -> A{row_idx+1}, B2 -> B{row_idx+1}
                             let row_formula = adjust_cell_references_for_row(&formula_str, row_idx);
 
-                            match evaluator.evaluate_formula_full(&row_formula, &data_clone) {
+                            match evaluator.evaluate(&row_formula, &data_clone) {
                                 Ok(result) => {
                                     let value = match result {
                                         crate::formula::FormulaResult::Number(n) => n.to_string(),
-                                        crate::formula::FormulaResult::Text(s) => s,
+                                        crate::formula::FormulaResult::Text(s)
+                                        | crate::formula::FormulaResult::Error(s) => s,
                                     };
                                     row.push(value);
                                 }
@@ -367,11 +551,12 @@ impl TransformOperator for DataOperations {
                         }
                     } else {
                         // Aggregate formula: evaluate once for all rows (SUM, AVERAGE, etc.)
-                        match evaluator.evaluate_formula_full(&formula_str, data) {
+                        match evaluator.evaluate(&formula_str, data) {
                             Ok(result) => {
                                 let value = match result {
                                     crate::formula::FormulaResult::Number(n) => n.to_string(),
-                                    crate::formula::FormulaResult::Text(s) => s,
+                                    crate::formula::FormulaResult::Text(s)
+                                    | crate::formula::FormulaResult::Error(s) => s,
                                 };
                                 for row in data.iter_mut() {
                                     row.push(value.clone());
@@ -431,6 +616,21 @@ fn adjust_cell_references_for_row(formula: &str, row_idx: usize) -> String {
     result.to_string()
 }
 
+/// Escape `&`, `<`, `>` and `"` for use in HTML text and attributes
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -458,4 +658,39 @@ mod tests {
         // Mixed case
         assert_eq!(adjust_cell_references_for_row("a1+b2", 0), "a1+b1");
     }
+
+    #[test]
+    fn test_natural_cmp_is_a_total_order() {
+        use std::cmp::Ordering;
+
+        // Deterministic pseudo-random strings over digits, signs, dots and letters
+        let alphabet: Vec<char> = "0012789-.eEaZ _".chars().collect();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+        let mut values: Vec<String> = ["1", "01", "1.0", "1e3", "NaN", "inf", "-0", "0", "a1b"]
+            .map(String::from)
+            .to_vec();
+        while values.len() < 120 {
+            let len = 1 + next(6);
+            values.push((0..len).map(|_| alphabet[next(alphabet.len())]).collect());
+        }
+
+        for a in &values {
+            for b in &values {
+                let ab = natural_cmp(a, b);
+                assert_eq!(ab, natural_cmp(b, a).reverse(), "{a:?} vs {b:?}");
+                assert_eq!(ab == Ordering::Equal, a == b, "{a:?} vs {b:?}");
+                for c in &values {
+                    if ab != Ordering::Greater && natural_cmp(b, c) != Ordering::Greater {
+                        assert_ne!(natural_cmp(a, c), Ordering::Greater, "{a:?} {b:?} {c:?}");
+                    }
+                }
+            }
+        }
+    }
 }
@@ -4,11 +4,15 @@
 
 mod core;
 mod pandas;
+mod predicate;
 mod stats;
 mod transform;
 pub mod types;
 
 pub use core::DataOperations;
-pub use types::{AggFunc, JoinType, SortOrder};
+pub use types::{
+    AggFunc, CROSS_JOIN_MAX_ROWS, ColumnType, CumOp, FillMethod, JoinType, NormalizeMethod,
+    RankMethod, SortOrder,
+};
 #[allow(unused_imports)]
-pub use types::{NoProgress, ProgressCallback, StderrProgress};
+pub use types::{NoProgress, PROGRESS_INTERVAL, ProgressCallback, StderrProgress};
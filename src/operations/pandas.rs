@@ -1,7 +1,9 @@
 //! Pandas-inspired data operations
 
 use super::core::DataOperations;
-use super::types::{AggFunc, JoinType};
+use super::types::{
+    AggFunc, CROSS_JOIN_MAX_ROWS, JoinType, NoProgress, PROGRESS_INTERVAL, ProgressCallback,
+};
 use anyhow::Result;
 
 impl DataOperations {
@@ -41,6 +43,39 @@ impl DataOperations {
         Ok(self.select_columns(data, &indices))
     }
 
+    /// Insert an integer row-index column named `name` in front of the data
+    ///
+    /// The header gets `name`; data rows are numbered from `start`.
+    pub fn add_index(&self, data: &[Vec<String>], name: &str, start: usize) -> Vec<Vec<String>> {
+        data.iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let label = if i == 0 {
+                    name.to_string()
+                } else {
+                    (start + i - 1).to_string()
+                };
+                let mut new_row = Vec::with_capacity(row.len() + 1);
+                new_row.push(label);
+                new_row.extend(row.iter().cloned());
+                new_row
+            })
+            .collect()
+    }
+
+    /// Remove a leading index column named `name`, as added by `add_index`
+    ///
+    /// Data whose first header cell is not `name` is returned unchanged.
+    pub fn reset_index(&self, data: &[Vec<String>], name: &str) -> Vec<Vec<String>> {
+        match data.first() {
+            Some(header) if header.first().is_some_and(|h| h == name) => data
+                .iter()
+                .map(|row| row.iter().skip(1).cloned().collect())
+                .collect(),
+            _ => data.to_vec(),
+        }
+    }
+
     /// Get first n rows (head)
     pub fn head(&self, data: &[Vec<String>], n: usize) -> Vec<Vec<String>> {
         data.iter().take(n).cloned().collect()
@@ -125,6 +160,90 @@ impl DataOperations {
         }
     }
 
+    /// Fill empty cells in `columns` with the last non-empty value above them
+    ///
+    /// The header row is skipped, and empties before a column's first value
+    /// stay empty. Returns the number of cells filled.
+    pub fn fill_forward(&self, data: &mut [Vec<String>], columns: &[usize]) -> usize {
+        let mut filled = 0;
+        for &col in columns {
+            let mut last: Option<String> = None;
+            for row in data.iter_mut().skip(1) {
+                let Some(cell) = row.get_mut(col) else {
+                    continue;
+                };
+                if !cell.is_empty() {
+                    last = Some(cell.clone());
+                } else if let Some(value) = &last {
+                    *cell = value.clone();
+                    filled += 1;
+                }
+            }
+        }
+        filled
+    }
+
+    /// Fill empty cells in `columns` with the next non-empty value below them
+    ///
+    /// The header row is skipped, and empties after a column's last value
+    /// stay empty. Returns the number of cells filled.
+    pub fn fill_backward(&self, data: &mut [Vec<String>], columns: &[usize]) -> usize {
+        let mut filled = 0;
+        for &col in columns {
+            let mut next: Option<String> = None;
+            for row in data.iter_mut().skip(1).rev() {
+                let Some(cell) = row.get_mut(col) else {
+                    continue;
+                };
+                if !cell.is_empty() {
+                    next = Some(cell.clone());
+                } else if let Some(value) = &next {
+                    *cell = value.clone();
+                    filled += 1;
+                }
+            }
+        }
+        filled
+    }
+
+    /// Fill runs of empty cells in `column` by linear interpolation
+    ///
+    /// Only gaps with a number on both sides are filled, each cell getting
+    /// its evenly spaced point on the line between them. Gaps at the start or
+    /// end of the column, or next to a non-numeric value, are left empty. The
+    /// header row is skipped. Returns the number of cells filled.
+    pub fn interpolate_linear(&self, data: &mut [Vec<String>], column: usize) -> usize {
+        let mut filled = 0;
+        // Row index and value of the last number seen, cleared by non-numeric text
+        let mut anchor: Option<(usize, f64)> = None;
+
+        for idx in 1..data.len() {
+            let Some(cell) = data[idx].get(column) else {
+                continue;
+            };
+            if cell.is_empty() {
+                continue;
+            }
+            let Ok(value) = cell.trim().parse::<f64>() else {
+                anchor = None;
+                continue;
+            };
+
+            if let Some((start, start_value)) = anchor {
+                let span = (idx - start) as f64;
+                for (step, row) in data[start + 1..idx].iter_mut().enumerate() {
+                    if let Some(cell) = row.get_mut(column) {
+                        let t = (step + 1) as f64 / span;
+                        *cell = (start_value + (value - start_value) * t).to_string();
+                        filled += 1;
+                    }
+                }
+            }
+            anchor = Some((idx, value));
+        }
+        filled
+    }
+
     /// Drop rows with any empty values
     pub fn dropna(&self, data: &[Vec<String>]) -> Vec<Vec<String>> {
         data.iter()
@@ -143,6 +262,9 @@ impl DataOperations {
     }
 
     /// Join two datasets on a column
+    ///
+    /// `JoinType::Cross` ignores the join columns and is limited to
+    /// [`CROSS_JOIN_MAX_ROWS`]; use [`DataOperations::cross_join`] to change it.
     pub fn join(
         &self,
         left: &[Vec<String>],
@@ -151,46 +273,118 @@ impl DataOperations {
         right_col: usize,
         how: JoinType,
     ) -> Result<Vec<Vec<String>>> {
-        use std::collections::HashMap;
+        self.join_multi(left, right, &[left_col], &[right_col], how)
+    }
+
+    /// [`DataOperations::join`] reporting the number of left rows matched
+    pub fn join_with_progress(
+        &self,
+        left: &[Vec<String>],
+        right: &[Vec<String>],
+        left_col: usize,
+        right_col: usize,
+        how: JoinType,
+        progress: &mut dyn ProgressCallback,
+    ) -> Result<Vec<Vec<String>>> {
+        self.join_multi_with_progress(left, right, &[left_col], &[right_col], how, progress)
+    }
+
+    /// Join two datasets on one or more key columns
+    ///
+    /// `left_cols[i]` is matched against `right_cols[i]`. Every right key
+    /// column is dropped from the appended right-hand cells; unmatched right
+    /// rows in right/outer joins carry their key values in the left key columns.
+    pub fn join_multi(
+        &self,
+        left: &[Vec<String>],
+        right: &[Vec<String>],
+        left_cols: &[usize],
+        right_cols: &[usize],
+        how: JoinType,
+    ) -> Result<Vec<Vec<String>>> {
+        self.join_multi_with_progress(left, right, left_cols, right_cols, how, &mut NoProgress)
+    }
+
+    /// [`DataOperations::join_multi`] reporting the number of left rows matched
+    ///
+    /// `progress` is called every [`PROGRESS_INTERVAL`] rows and once at the
+    /// end, with the left row count as the total.
+    pub fn join_multi_with_progress(
+        &self,
+        left: &[Vec<String>],
+        right: &[Vec<String>],
+        left_cols: &[usize],
+        right_cols: &[usize],
+        how: JoinType,
+        progress: &mut dyn ProgressCallback,
+    ) -> Result<Vec<Vec<String>>> {
+        use std::collections::{HashMap, HashSet};
+
+        if how == JoinType::Cross {
+            return self.cross_join(left, right, Some(CROSS_JOIN_MAX_ROWS));
+        }
+
+        if left_cols.is_empty() || left_cols.len() != right_cols.len() {
+            anyhow::bail!(
+                "Join needs the same, non-zero number of key columns on each side (got {} and {})",
+                left_cols.len(),
+                right_cols.len()
+            );
+        }
 
         if left.is_empty() || right.is_empty() {
             return Ok(Vec::new());
         }
 
+        // Composite key; the null byte cannot appear in parsed cell text
+        let left_key = |row: &[String]| {
+            left_cols
+                .iter()
+                .map(|col| row.get(*col).map(|s| s.as_str()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("\0")
+        };
+
         let mut right_index: HashMap<String, Vec<usize>> = HashMap::new();
         for (idx, row) in right.iter().enumerate() {
-            if let Some(key) = row.get(right_col) {
-                right_index.entry(key.clone()).or_default().push(idx);
+            let key: Option<Vec<&str>> = right_cols
+                .iter()
+                .map(|col| row.get(*col).map(|s| s.as_str()))
+                .collect();
+            if let Some(key) = key {
+                right_index.entry(key.join("\0")).or_default().push(idx);
             }
         }
 
+        // Right-hand cells appended to each output row, minus the key columns
+        let right_values = |row: &[String]| {
+            row.iter()
+                .enumerate()
+                .filter(|(idx, _)| !right_cols.contains(idx))
+                .map(|(_, val)| val.clone())
+                .collect::<Vec<_>>()
+        };
+
         let right_width = right.iter().map(|r| r.len()).max().unwrap_or(0);
         let empty_right: Vec<String> = vec![String::new(); right_width];
 
         let mut result = Vec::new();
-        let mut matched_right: std::collections::HashSet<usize> = std::collections::HashSet::new();
-
-        for left_row in left {
-            let key = left_row.get(left_col).cloned().unwrap_or_default();
+        let mut matched_right: HashSet<usize> = HashSet::new();
 
-            if let Some(right_indices) = right_index.get(&key) {
+        for (row_idx, left_row) in left.iter().enumerate() {
+            if row_idx % PROGRESS_INTERVAL == 0 {
+                progress.on_progress(row_idx, Some(left.len()), "Joining");
+            }
+            if let Some(right_indices) = right_index.get(&left_key(left_row)) {
                 for &right_idx in right_indices {
                     matched_right.insert(right_idx);
                     let mut new_row = left_row.clone();
-                    for (idx, val) in right[right_idx].iter().enumerate() {
-                        if idx != right_col {
-                            new_row.push(val.clone());
-                        }
-                    }
+                    new_row.extend(right_values(&right[right_idx]));
                     result.push(new_row);
                 }
             } else if matches!(how, JoinType::Left | JoinType::Outer) {
                 let mut new_row = left_row.clone();
-                for (idx, val) in empty_right.iter().enumerate() {
-                    if idx != right_col {
-                        new_row.push(val.clone());
-                    }
-                }
+                new_row.extend(right_values(&empty_right));
                 result.push(new_row);
             }
         }
@@ -202,21 +396,65 @@ impl DataOperations {
             for (idx, right_row) in right.iter().enumerate() {
                 if !matched_right.contains(&idx) {
                     let mut new_row = empty_left.clone();
-                    if let Some(key) = right_row.get(right_col) {
-                        if left_col < new_row.len() {
-                            new_row[left_col] = key.clone();
-                        }
-                    }
-                    for (i, val) in right_row.iter().enumerate() {
-                        if i != right_col {
-                            new_row.push(val.clone());
+                    for (left_col, right_col) in left_cols.iter().zip(right_cols) {
+                        if let (Some(slot), Some(key)) =
+                            (new_row.get_mut(*left_col), right_row.get(*right_col))
+                        {
+                            *slot = key.clone();
                         }
                     }
+                    new_row.extend(right_values(right_row));
                     result.push(new_row);
                 }
             }
         }
 
+        progress.on_progress(left.len(), Some(left.len()), "Joining");
+        Ok(result)
+    }
+
+    /// Cartesian product of two datasets
+    ///
+    /// The header is the left header followed by the right header, and every
+    /// left data row is paired with every right data row. Fails when the
+    /// result would exceed `max_rows` data rows; pass `None` to disable the cap.
+    pub fn cross_join(
+        &self,
+        left: &[Vec<String>],
+        right: &[Vec<String>],
+        max_rows: Option<usize>,
+    ) -> Result<Vec<Vec<String>>> {
+        if left.is_empty() || right.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let left_rows = &left[1..];
+        let right_rows = &right[1..];
+        let total = left_rows.len().saturating_mul(right_rows.len());
+        if let Some(max_rows) = max_rows.filter(|&max| total > max) {
+            anyhow::bail!(
+                "Cross join would produce {} rows ({} x {}), above the limit of {}",
+                total,
+                left_rows.len(),
+                right_rows.len(),
+                max_rows
+            );
+        }
+
+        let mut result = Vec::with_capacity(total + 1);
+        let mut header = left[0].clone();
+        header.extend(right[0].iter().cloned());
+        result.push(header);
+
+        for left_row in left_rows {
+            for right_row in right_rows {
+                let mut new_row = Vec::with_capacity(left_row.len() + right_row.len());
+                new_row.extend(left_row.iter().cloned());
+                new_row.extend(right_row.iter().cloned());
+                result.push(new_row);
+            }
+        }
+
         Ok(result)
     }
 
@@ -226,25 +464,76 @@ impl DataOperations {
         data: &[Vec<String>],
         group_col: usize,
         aggregations: &[(usize, AggFunc)],
+    ) -> Result<Vec<Vec<String>>> {
+        self.groupby_multi(data, &[group_col], aggregations)
+    }
+
+    /// [`DataOperations::groupby`] reporting the number of rows grouped
+    pub fn groupby_with_progress(
+        &self,
+        data: &[Vec<String>],
+        group_col: usize,
+        aggregations: &[(usize, AggFunc)],
+        progress: &mut dyn ProgressCallback,
+    ) -> Result<Vec<Vec<String>>> {
+        self.groupby_multi_with_progress(data, &[group_col], aggregations, progress)
+    }
+
+    /// Group by one or more columns with aggregations
+    ///
+    /// The output has one leading column per grouping key, followed by one
+    /// column per aggregation. Groups are sorted by their keys in column order.
+    pub fn groupby_multi(
+        &self,
+        data: &[Vec<String>],
+        group_cols: &[usize],
+        aggregations: &[(usize, AggFunc)],
+    ) -> Result<Vec<Vec<String>>> {
+        self.groupby_multi_with_progress(data, group_cols, aggregations, &mut NoProgress)
+    }
+
+    /// [`DataOperations::groupby_multi`] reporting the number of rows grouped
+    ///
+    /// `progress` is called every [`PROGRESS_INTERVAL`] data rows and once at
+    /// the end, with the data row count as the total.
+    pub fn groupby_multi_with_progress(
+        &self,
+        data: &[Vec<String>],
+        group_cols: &[usize],
+        aggregations: &[(usize, AggFunc)],
+        progress: &mut dyn ProgressCallback,
     ) -> Result<Vec<Vec<String>>> {
         use std::collections::HashMap;
 
         if data.is_empty() {
             return Ok(Vec::new());
         }
+        if group_cols.is_empty() {
+            anyhow::bail!("groupby requires at least one grouping column");
+        }
 
         let header = &data[0];
-        let mut groups: HashMap<String, Vec<Vec<f64>>> = HashMap::new();
+        let mut groups: HashMap<String, Vec<Vec<String>>> = HashMap::new();
 
-        for row in data.iter().skip(1) {
-            let key = row.get(group_col).cloned().unwrap_or_default();
+        let total_rows = data.len() - 1;
+        for (row_idx, row) in data.iter().skip(1).enumerate() {
+            if row_idx % PROGRESS_INTERVAL == 0 {
+                progress.on_progress(row_idx, Some(total_rows), "Grouping");
+            }
+            // Composite key; the null byte cannot appear in parsed cell text
+            let key = group_cols
+                .iter()
+                .map(|col| row.get(*col).map(|s| s.as_str()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("\0");
             let entry = groups
                 .entry(key)
                 .or_insert_with(|| vec![Vec::new(); aggregations.len()]);
 
+            // Keep raw cells; each aggregation decides how to interpret them
             for (i, (col, _)) in aggregations.iter().enumerate() {
-                if let Some(val) = row.get(*col).and_then(|v| v.parse::<f64>().ok()) {
-                    entry[i].push(val);
+                if let Some(val) = row.get(*col) {
+                    entry[i].push(val.clone());
                 }
             }
         }
@@ -252,12 +541,18 @@ impl DataOperations {
         let mut result = Vec::new();
 
         // Header
-        let mut result_header = vec![
-            header
-                .get(group_col)
-                .cloned()
-                .unwrap_or_else(|| "group".to_string()),
-        ];
+        let mut result_header: Vec<String> = group_cols
+            .iter()
+            .map(|col| {
+                header.get(*col).cloned().unwrap_or_else(|| {
+                    if group_cols.len() == 1 {
+                        "group".to_string()
+                    } else {
+                        format!("group_{}", col)
+                    }
+                })
+            })
+            .collect();
         for (col, agg) in aggregations {
             let col_name = header
                 .get(*col)
@@ -273,14 +568,14 @@ impl DataOperations {
 
         for key in keys {
             let values = &groups[&key];
-            let mut row = vec![key];
+            let mut row: Vec<String> = key.split('\0').map(|s| s.to_string()).collect();
             for (i, (_, agg)) in aggregations.iter().enumerate() {
-                let agg_val = agg.apply(&values[i]);
-                row.push(format!("{:.2}", agg_val));
+                row.push(agg.aggregate(&values[i]));
             }
             result.push(row);
         }
 
+        progress.on_progress(total_rows, Some(total_rows), "Grouping");
         Ok(result)
     }
 }
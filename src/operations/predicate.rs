@@ -0,0 +1,256 @@
+//! WHERE-clause predicates for `filter` and `query`
+//!
+//! A clause is one or more `column OP value` comparisons joined with `AND`
+//! and `OR`, optionally grouped with parentheses. `AND` binds tighter than
+//! `OR`, and the keywords are case-insensitive. `OP` is one of
+//! `= == != <> > < >= <=` or `contains`, `starts_with`, `ends_with`.
+//!
+//! Values may be quoted with `'` or `"` (a backslash escapes the quote);
+//! unquoted values run up to the next keyword or parenthesis, so
+//! `city = New York` compares against `New York`. Column names are matched
+//! against the header and may also be quoted.
+
+use anyhow::{Result, bail};
+
+use super::core::DataOperations;
+use crate::traits::FilterCondition;
+
+/// A parsed WHERE clause with columns resolved to indices
+#[derive(Debug)]
+pub(crate) enum Predicate {
+    Compare {
+        column: usize,
+        condition: FilterCondition,
+    },
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+}
+
+impl Predicate {
+    /// Parse `clause`, resolving column names against `header`
+    pub(crate) fn parse(ops: &DataOperations, clause: &str, header: &[String]) -> Result<Self> {
+        let tokens = tokenize(clause)?;
+        if tokens.is_empty() {
+            bail!("Empty WHERE clause");
+        }
+
+        let mut parser = Parser {
+            ops,
+            header,
+            tokens,
+            pos: 0,
+        };
+        let predicate = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            bail!(
+                "Unexpected {} in WHERE clause: '{clause}'",
+                token.describe()
+            );
+        }
+        Ok(predicate)
+    }
+
+    /// Whether `row` satisfies the predicate; missing cells compare as blank
+    pub(crate) fn matches(&self, ops: &DataOperations, row: &[String]) -> bool {
+        match self {
+            Predicate::Compare { column, condition } => {
+                let cell = row.get(*column).map(String::as_str).unwrap_or("");
+                ops.evaluate_condition(cell, condition).unwrap_or(false)
+            }
+            Predicate::And(lhs, rhs) => lhs.matches(ops, row) && rhs.matches(ops, row),
+            Predicate::Or(lhs, rhs) => lhs.matches(ops, row) || rhs.matches(ops, row),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(w) => format!("'{w}'"),
+            Token::Quoted(q) => format!("\"{q}\""),
+            Token::Op(op) => format!("'{op}'"),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+}
+
+/// Symbolic operators, longest first so `>=` wins over `>`
+const SYMBOL_OPS: [&str; 8] = [">=", "<=", "!=", "<>", "==", "=", ">", "<"];
+
+/// Word operators, matched case-insensitively
+const WORD_OPS: [&str; 3] = ["contains", "starts_with", "ends_with"];
+
+fn tokenize(clause: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = clause.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail!("Unterminated quoted string in WHERE clause: '{clause}'"),
+                    Some('\\') if chars.get(i + 1) == Some(&c) => {
+                        text.push(c);
+                        i += 2;
+                    }
+                    Some(&ch) if ch == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&ch) => {
+                        text.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Token::Quoted(text));
+        } else if let Some(op) = SYMBOL_OPS.iter().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(k, o)| chars.get(i + k) == Some(&o))
+        }) {
+            tokens.push(Token::Op(op));
+            i += op.len();
+        } else {
+            // A `!` only ends a word when it starts `!=`, so `hi!` stays one value
+            let start = i;
+            while i < chars.len()
+                && !chars[i].is_whitespace()
+                && !matches!(chars[i], '(' | ')' | '"' | '\'' | '=' | '<' | '>')
+                && (chars[i] != '!' || chars.get(i + 1) != Some(&'='))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    ops: &'a DataOperations,
+    header: &'a [String],
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Predicate> {
+        let mut lhs = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.is_keyword("or")) {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            lhs = Predicate::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Predicate> {
+        let mut lhs = self.parse_term()?;
+        while self.peek().is_some_and(|t| t.is_keyword("and")) {
+            self.pos += 1;
+            let rhs = self.parse_term()?;
+            lhs = Predicate::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_term(&mut self) -> Result<Predicate> {
+        if self.peek() == Some(&Token::Open) {
+            self.pos += 1;
+            let inner = self.parse_or()?;
+            if self.next() != Some(Token::Close) {
+                bail!("Missing ')' in WHERE clause");
+            }
+            return Ok(inner);
+        }
+
+        let column = match self.next() {
+            Some(Token::Word(name) | Token::Quoted(name)) => self.resolve_column(&name)?,
+            Some(other) => bail!("Expected a column name, found {}", other.describe()),
+            None => bail!("Expected a column name at end of WHERE clause"),
+        };
+
+        let operator = match self.next() {
+            Some(Token::Op(op)) => op.to_string(),
+            Some(Token::Word(w)) if WORD_OPS.iter().any(|op| w.eq_ignore_ascii_case(op)) => {
+                w.to_ascii_lowercase()
+            }
+            Some(other) => bail!("Expected an operator, found {}", other.describe()),
+            None => bail!("Expected an operator at end of WHERE clause"),
+        };
+
+        let value = self.parse_value()?;
+        let condition = self.ops.parse_filter_condition(&operator, &value)?;
+        Ok(Predicate::Compare { column, condition })
+    }
+
+    /// A quoted value, or bare words up to the next keyword or parenthesis
+    fn parse_value(&mut self) -> Result<String> {
+        if let Some(Token::Quoted(_)) = self.peek()
+            && let Some(Token::Quoted(value)) = self.next()
+        {
+            return Ok(value);
+        }
+
+        let mut words = Vec::new();
+        while let Some(Token::Word(w)) = self.peek() {
+            if w.eq_ignore_ascii_case("and") || w.eq_ignore_ascii_case("or") {
+                break;
+            }
+            words.push(w.clone());
+            self.pos += 1;
+        }
+        if words.is_empty() {
+            match self.peek() {
+                Some(other) => bail!("Expected a value, found {}", other.describe()),
+                None => bail!("Expected a value at end of WHERE clause"),
+            }
+        }
+        Ok(words.join(" "))
+    }
+
+    fn resolve_column(&self, name: &str) -> Result<usize> {
+        self.header
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| anyhow::anyhow!("Column '{name}' not found"))
+    }
+}
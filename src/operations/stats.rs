@@ -1,7 +1,8 @@
 //! Statistical operations
 
 use super::core::DataOperations;
-use super::types::AggFunc;
+use super::types::{AggFunc, CumOp, RankMethod, SortOrder};
+use crate::profiling::analysis::infer_data_type;
 use anyhow::Result;
 
 impl DataOperations {
@@ -80,7 +81,64 @@ impl DataOperations {
         Ok(result)
     }
 
-    /// Count unique values in a column
+    /// Focused statistics for a single column
+    ///
+    /// Returns `stat,value` rows: count, nulls and unique for every column,
+    /// min/max/mean/median/std when the profiler infers a numeric type, and
+    /// the five most frequent values.
+    pub fn column_stats(&self, data: &[Vec<String>], column: usize) -> Result<Vec<Vec<String>>> {
+        use crate::profiling::DataProfiler;
+
+        if data.is_empty() {
+            anyhow::bail!("Data is empty");
+        }
+
+        let name = data[0]
+            .get(column)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Column index {} out of range", column))?;
+        let values: Vec<String> = data
+            .iter()
+            .skip(1)
+            .map(|row| row.get(column).cloned().unwrap_or_default())
+            .collect();
+
+        let profile = DataProfiler::new().profile_column(&name, &values, values.len().max(1))?;
+
+        let mut result = vec![
+            vec!["stat".to_string(), name],
+            vec!["dtype".to_string(), format!("{:?}", profile.data_type)],
+            vec![
+                "count".to_string(),
+                (values.len() - profile.null_count).to_string(),
+            ],
+            vec!["nulls".to_string(), profile.null_count.to_string()],
+            vec!["unique".to_string(), profile.unique_count.to_string()],
+        ];
+
+        // The profiler only computes numeric stats for Integer/Float columns
+        if let Some(stats) = &profile.numeric_stats {
+            result.push(vec!["min".to_string(), format!("{:.2}", stats.min)]);
+            result.push(vec!["max".to_string(), format!("{:.2}", stats.max)]);
+            result.push(vec!["mean".to_string(), format!("{:.2}", stats.mean)]);
+            result.push(vec!["median".to_string(), format!("{:.2}", stats.median)]);
+            result.push(vec!["std".to_string(), format!("{:.2}", stats.std_dev)]);
+        }
+
+        for (rank, freq) in profile.top_values.iter().take(5).enumerate() {
+            result.push(vec![
+                format!("top{}", rank + 1),
+                format!("{} ({})", freq.value, freq.count),
+            ]);
+        }
+
+        Ok(result)
+    }
+
+    /// Count each distinct value in a column
+    ///
+    /// Rows are `value, count, percentage`, most frequent first with ties
+    /// broken by value. Percentages are of all data rows, to 2 decimals.
     pub fn value_counts(&self, data: &[Vec<String>], column: usize) -> Vec<Vec<String>> {
         use std::collections::HashMap;
 
@@ -90,18 +148,28 @@ impl DataOperations {
                 *counts.entry(val.clone()).or_insert(0) += 1;
             }
         }
+        let total: usize = counts.values().sum();
 
         let mut result: Vec<(String, usize)> = counts.into_iter().collect();
-        result.sort_by(|a, b| b.1.cmp(&a.1));
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
-        let mut output = vec![vec!["value".to_string(), "count".to_string()]];
+        let mut output = vec![vec![
+            "value".to_string(),
+            "count".to_string(),
+            "percentage".to_string(),
+        ]];
         for (val, count) in result {
-            output.push(vec![val, count.to_string()]);
+            let percentage = count as f64 / total as f64 * 100.0;
+            output.push(vec![val, count.to_string(), format!("{percentage:.2}")]);
         }
         output
     }
 
     /// Pivot table
+    ///
+    /// Rows are the distinct `index_col` values and columns the distinct
+    /// `columns_col` values, both sorted. Each cell aggregates `values_col`
+    /// for that combination; combinations with no rows are left blank.
     pub fn pivot(
         &self,
         data: &[Vec<String>],
@@ -118,24 +186,18 @@ impl DataOperations {
 
         let mut col_values: BTreeSet<String> = BTreeSet::new();
         let mut index_values: BTreeSet<String> = BTreeSet::new();
-        let mut groups: HashMap<(String, String), Vec<f64>> = HashMap::new();
+        let mut groups: HashMap<(String, String), Vec<String>> = HashMap::new();
 
         for row in data.iter().skip(1) {
             let idx = row.get(index_col).cloned().unwrap_or_default();
             let col = row.get(columns_col).cloned().unwrap_or_default();
-            let val = row
-                .get(values_col)
-                .and_then(|v| v.parse::<f64>().ok())
-                .unwrap_or(0.0);
+            let val = row.get(values_col).cloned().unwrap_or_default();
 
             index_values.insert(idx.clone());
             col_values.insert(col.clone());
             groups.entry((idx, col)).or_default().push(val);
         }
 
-        let col_values: Vec<String> = col_values.into_iter().collect();
-        let index_values: Vec<String> = index_values.into_iter().collect();
-
         let mut result = Vec::new();
 
         let index_name = data[0]
@@ -149,12 +211,11 @@ impl DataOperations {
         for idx in &index_values {
             let mut row = vec![idx.clone()];
             for col in &col_values {
-                let values = groups.get(&(idx.clone(), col.clone()));
-                let agg_val = match values {
-                    Some(vals) => agg.apply(vals),
-                    None => 0.0,
-                };
-                row.push(format!("{:.2}", agg_val));
+                let cell = groups
+                    .get(&(idx.clone(), col.clone()))
+                    .map(|vals| agg.aggregate(vals))
+                    .unwrap_or_default();
+                row.push(cell);
             }
             result.push(row);
         }
@@ -162,57 +223,384 @@ impl DataOperations {
         Ok(result)
     }
 
-    /// Correlation matrix
-    pub fn correlation(&self, data: &[Vec<String>], columns: &[usize]) -> Result<Vec<Vec<String>>> {
-        if data.is_empty() || columns.is_empty() {
-            return Ok(Vec::new());
+    /// Melt (unpivot) wide data into long form
+    ///
+    /// Keeps `id_cols` and emits one `variable`/`value` row per value column
+    /// per input row, with `variable` holding the original column name.
+    /// Cells missing from ragged rows are treated as empty.
+    pub fn melt(
+        &self,
+        data: &[Vec<String>],
+        id_cols: &[usize],
+        value_cols: &[usize],
+    ) -> Vec<Vec<String>> {
+        if data.is_empty() {
+            return Vec::new();
         }
 
         let header = &data[0];
+        let column_name = |col: usize| {
+            header
+                .get(col)
+                .cloned()
+                .unwrap_or_else(|| format!("col_{}", col))
+        };
 
-        let mut col_data: Vec<Vec<f64>> = vec![Vec::new(); columns.len()];
+        let mut result_header: Vec<String> = id_cols.iter().map(|&col| column_name(col)).collect();
+        result_header.push("variable".to_string());
+        result_header.push("value".to_string());
+
+        let mut result = vec![result_header];
         for row in data.iter().skip(1) {
-            for (i, &col_idx) in columns.iter().enumerate() {
-                if let Some(val) = row.get(col_idx).and_then(|v| v.parse::<f64>().ok()) {
-                    col_data[i].push(val);
-                }
+            let ids: Vec<String> = id_cols
+                .iter()
+                .map(|&col| row.get(col).cloned().unwrap_or_default())
+                .collect();
+
+            for &col in value_cols {
+                let mut new_row = ids.clone();
+                new_row.push(column_name(col));
+                new_row.push(row.get(col).cloned().unwrap_or_default());
+                result.push(new_row);
             }
         }
 
-        let mut result = Vec::new();
+        result
+    }
 
-        let mut corr_header = vec!["".to_string()];
-        for &col_idx in columns {
-            corr_header.push(
-                header
-                    .get(col_idx)
-                    .cloned()
-                    .unwrap_or_else(|| format!("col_{}", col_idx)),
-            );
+    /// Rolling-window aggregate over a column, appended as a new column
+    ///
+    /// Each data row aggregates the `window` rows ending at it. The first
+    /// `window - 1` rows get empty cells because their window is incomplete.
+    pub fn rolling(
+        &self,
+        data: &[Vec<String>],
+        value_col: usize,
+        window: usize,
+        agg: AggFunc,
+    ) -> Result<Vec<Vec<String>>> {
+        if window == 0 {
+            anyhow::bail!("Rolling window must be at least 1");
+        }
+        if data.is_empty() {
+            return Ok(Vec::new());
         }
-        result.push(corr_header);
 
-        for (i, &col_i) in columns.iter().enumerate() {
-            let col_name = header
-                .get(col_i)
-                .cloned()
-                .unwrap_or_else(|| format!("col_{}", col_i));
-            let mut row = vec![col_name];
+        let values: Vec<String> = data
+            .iter()
+            .skip(1)
+            .map(|row| row.get(value_col).cloned().unwrap_or_default())
+            .collect();
+
+        let col_name = data[0]
+            .get(value_col)
+            .cloned()
+            .unwrap_or_else(|| format!("col_{}", value_col));
+
+        let mut result = Vec::with_capacity(data.len());
+        let mut header = data[0].clone();
+        header.push(format!("rolling_{}_{}", agg.name(), col_name));
+        result.push(header);
+
+        for (i, row) in data.iter().skip(1).enumerate() {
+            let mut new_row = row.clone();
+            if i + 1 >= window {
+                new_row.push(agg.aggregate(&values[i + 1 - window..=i]));
+            } else {
+                new_row.push(String::new());
+            }
+            result.push(new_row);
+        }
+
+        Ok(result)
+    }
+
+    /// Running total (or max/min/product) of a column, appended as a new column
+    ///
+    /// Computed top to bottom below the header. Non-numeric cells act as the
+    /// op's identity, so they repeat the running value; rows before the first
+    /// numeric cell stay empty.
+    pub fn cumulative(
+        &self,
+        data: &[Vec<String>],
+        value_col: usize,
+        op: CumOp,
+    ) -> Vec<Vec<String>> {
+        if data.is_empty() {
+            return Vec::new();
+        }
 
-            for (j, _) in columns.iter().enumerate() {
-                let corr = self.pearson_correlation(&col_data[i], &col_data[j]);
-                row.push(format!("{:.4}", corr));
+        let col_name = data[0]
+            .get(value_col)
+            .cloned()
+            .unwrap_or_else(|| format!("col_{}", value_col));
+
+        let mut result = Vec::with_capacity(data.len());
+        let mut header = data[0].clone();
+        header.push(format!("{}_{}", op.name(), col_name));
+        result.push(header);
+
+        let mut acc = op.identity();
+        let mut seen_number = false;
+        for row in data.iter().skip(1) {
+            if let Some(value) = row
+                .get(value_col)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+            {
+                acc = op.combine(acc, value);
+                seen_number = true;
+            }
+
+            let mut new_row = row.clone();
+            new_row.push(if seen_number {
+                format!("{:.2}", acc)
+            } else {
+                String::new()
+            });
+            result.push(new_row);
+        }
+
+        result
+    }
+
+    /// Rank rows by a numeric column, appending a `rank` column
+    ///
+    /// Rank 1 is the smallest value for ascending order and the largest for
+    /// descending. Ties are resolved by `method`; non-numeric cells, `NaN`
+    /// included, get an empty rank and do not take up a position.
+    pub fn rank(
+        &self,
+        data: &[Vec<String>],
+        column: usize,
+        order: SortOrder,
+        method: RankMethod,
+    ) -> Vec<Vec<String>> {
+        if data.is_empty() {
+            return Vec::new();
+        }
+
+        // (row index, value) for numeric cells, in rank order; the sort is
+        // stable so `First` keeps ties in their original order
+        let mut values: Vec<(usize, f64)> = data
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(i, row)| {
+                row.get(column)
+                    .and_then(|v| v.trim().parse::<f64>().ok())
+                    .filter(|v| !v.is_nan())
+                    .map(|v| (i, v))
+            })
+            .collect();
+        values.sort_by(|a, b| {
+            let cmp = a.1.total_cmp(&b.1);
+            match order {
+                SortOrder::Ascending => cmp,
+                SortOrder::Descending => cmp.reverse(),
             }
+        });
+
+        let mut ranks = vec![String::new(); data.len()];
+        let mut start = 0;
+        let mut dense = 0;
+        while start < values.len() {
+            let end = start
+                + values[start..]
+                    .iter()
+                    .take_while(|(_, v)| *v == values[start].1)
+                    .count();
+            dense += 1;
+
+            for (offset, (row_idx, _)) in values[start..end].iter().enumerate() {
+                let rank = match method {
+                    RankMethod::Average => (start + 1 + end) as f64 / 2.0,
+                    RankMethod::Min => (start + 1) as f64,
+                    RankMethod::Max => end as f64,
+                    RankMethod::Dense => dense as f64,
+                    RankMethod::First => (start + 1 + offset) as f64,
+                };
+                ranks[*row_idx] = rank.to_string();
+            }
+
+            start = end;
+        }
+
+        let mut result = Vec::with_capacity(data.len());
+        let mut header = data[0].clone();
+        header.push("rank".to_string());
+        result.push(header);
+
+        for (row, rank) in data.iter().zip(ranks).skip(1) {
+            let mut new_row = row.clone();
+            new_row.push(rank);
+            result.push(new_row);
+        }
+
+        result
+    }
+
+    /// Bucket a numeric column into `bins` equal-width intervals
+    ///
+    /// Appends a `<column>_bin` column labelled like `[0,10)`; the last bin
+    /// is closed so it includes the maximum. Non-numeric cells get an empty
+    /// label.
+    pub fn cut(
+        &self,
+        data: &[Vec<String>],
+        column: usize,
+        bins: usize,
+    ) -> Result<Vec<Vec<String>>> {
+        if bins == 0 {
+            anyhow::bail!("cut needs at least one bin");
+        }
+        let values = numeric_cells(data, column)?;
+
+        let min = values.iter().map(|(_, v)| *v).fold(f64::INFINITY, f64::min);
+        let max = values
+            .iter()
+            .map(|(_, v)| *v)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let width = (max - min) / bins as f64;
+        let edges: Vec<f64> = if width == 0.0 {
+            vec![min, max]
+        } else {
+            (0..=bins).map(|i| min + width * i as f64).collect()
+        };
+
+        Ok(append_bin_labels(data, column, &values, &edges))
+    }
+
+    /// Bucket a numeric column into `q` quantile bins of roughly equal size
+    ///
+    /// Edges are the 0, 1/q, ..., 1 quantiles; edges repeated by tied values
+    /// are merged, which leaves fewer bins. Labels follow [`cut`](Self::cut).
+    pub fn qcut(&self, data: &[Vec<String>], column: usize, q: usize) -> Result<Vec<Vec<String>>> {
+        if q == 0 {
+            anyhow::bail!("qcut needs at least one quantile");
+        }
+        let values = numeric_cells(data, column)?;
+
+        let numbers: Vec<f64> = values.iter().map(|(_, v)| *v).collect();
+        let mut edges: Vec<f64> = (0..=q)
+            .map(|i| AggFunc::Percentile(100.0 * i as f64 / q as f64).apply(&numbers))
+            .collect();
+        edges.dedup();
+        if edges.len() == 1 {
+            edges.push(edges[0]);
+        }
+
+        Ok(append_bin_labels(data, column, &values, &edges))
+    }
+
+    /// Pearson correlation matrix of numeric columns
+    ///
+    /// Uses `columns`, or every column when `None`, skipping any that are not
+    /// numeric (see [`is_numeric_column`](Self::is_numeric_column)). Each pair
+    /// is computed over the rows where both cells are numbers. The result has
+    /// a header row and a label column; pairs involving a column with no
+    /// variance are `NaN`.
+    pub fn correlation(
+        &self,
+        data: &[Vec<String>],
+        columns: Option<&[usize]>,
+    ) -> Result<Vec<Vec<String>>> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let header = &data[0];
+        let all_columns: Vec<usize> = (0..header.len()).collect();
+        let numeric: Vec<usize> = columns
+            .unwrap_or(&all_columns)
+            .iter()
+            .copied()
+            .filter(|&col| self.is_numeric_column(data, col))
+            .collect();
+        if numeric.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let matrix = self.correlation_matrix(data, &numeric);
+
+        let name = |col: usize| {
+            header
+                .get(col)
+                .cloned()
+                .unwrap_or_else(|| format!("col_{}", col))
+        };
+
+        let mut result = Vec::with_capacity(numeric.len() + 1);
+        let mut corr_header = vec!["".to_string()];
+        corr_header.extend(numeric.iter().map(|&col| name(col)));
+        result.push(corr_header);
+
+        for (i, &col_i) in numeric.iter().enumerate() {
+            let mut row = vec![name(col_i)];
+            row.extend(matrix[i].iter().map(|corr| match corr {
+                Some(corr) => format!("{:.4}", corr),
+                None => "NaN".to_string(),
+            }));
             result.push(row);
         }
 
         Ok(result)
     }
 
+    /// Whether every non-empty cell of `column` below the header is a number,
+    /// with at least one number present
+    pub fn is_numeric_column(&self, data: &[Vec<String>], column: usize) -> bool {
+        let mut cells = data
+            .iter()
+            .skip(1)
+            .filter_map(|row| row.get(column))
+            .filter(|cell| !cell.trim().is_empty())
+            .peekable();
+        cells.peek().is_some() && cells.all(|cell| cell.trim().parse::<f64>().is_ok())
+    }
+
+    /// Pairwise Pearson correlations between `columns`
+    ///
+    /// Each pair is computed over the rows where both cells parse as numbers.
+    /// Entry `[i][j]` pairs `columns[i]` with `columns[j]`, and is `None` when
+    /// either side has no variance over those rows. Only the upper triangle
+    /// is computed, then mirrored.
+    pub fn correlation_matrix(
+        &self,
+        data: &[Vec<String>],
+        columns: &[usize],
+    ) -> Vec<Vec<Option<f64>>> {
+        let parsed: Vec<Vec<Option<f64>>> = columns
+            .iter()
+            .map(|&col| {
+                data.iter()
+                    .skip(1)
+                    .map(|row| row.get(col).and_then(|v| v.trim().parse::<f64>().ok()))
+                    .collect()
+            })
+            .collect();
+
+        let mut matrix = vec![vec![None; columns.len()]; columns.len()];
+        for i in 0..parsed.len() {
+            for j in i..parsed.len() {
+                let (x, y): (Vec<f64>, Vec<f64>) = parsed[i]
+                    .iter()
+                    .zip(&parsed[j])
+                    .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+                    .unzip();
+                let corr = self.pearson_correlation(&x, &y);
+                let corr = (!corr.is_nan()).then_some(corr);
+                matrix[i][j] = corr;
+                matrix[j][i] = corr;
+            }
+        }
+        matrix
+    }
+
+    /// Pearson correlation of paired samples; `NaN` when either has no variance
     pub(crate) fn pearson_correlation(&self, x: &[f64], y: &[f64]) -> f64 {
         let n = x.len().min(y.len());
         if n == 0 {
-            return 0.0;
+            return f64::NAN;
         }
 
         let mean_x = x.iter().take(n).sum::<f64>() / n as f64;
@@ -231,12 +619,49 @@ impl DataOperations {
         }
 
         if var_x == 0.0 || var_y == 0.0 {
-            return 0.0;
+            return f64::NAN;
         }
 
         cov / (var_x.sqrt() * var_y.sqrt())
     }
 
+    /// Inferred type of each column, as `(column name, type)` pairs
+    ///
+    /// Uses the same inference as the data profiler, so types are names like
+    /// `Integer`, `Float`, `Date` or `Email`; all-blank columns are `Unknown`.
+    pub fn infer_dtypes(&self, data: &[Vec<String>]) -> Vec<(String, String)> {
+        let Some(header) = data.first() else {
+            return Vec::new();
+        };
+
+        header
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let values: Vec<String> = data
+                    .iter()
+                    .skip(1)
+                    .map(|row| row.get(idx).cloned().unwrap_or_default())
+                    .collect();
+                (name.clone(), format!("{:?}", infer_data_type(&values)))
+            })
+            .collect()
+    }
+
+    /// Rough in-memory size of the data in bytes, counting cell text and the
+    /// per-cell and per-row overhead of the `Vec<Vec<String>>`
+    pub fn memory_usage(&self, data: &[Vec<String>]) -> usize {
+        data.iter()
+            .map(|row| {
+                size_of::<Vec<String>>()
+                    + row
+                        .iter()
+                        .map(|cell| size_of::<String>() + cell.len())
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
     /// Infer column types
     pub fn dtypes(&self, data: &[Vec<String>]) -> Vec<Vec<String>> {
         if data.is_empty() {
@@ -297,22 +722,17 @@ impl DataOperations {
         result
     }
 
-    /// Get unique values in a column
-    pub fn unique(&self, data: &[Vec<String>], column: usize) -> Vec<Vec<String>> {
+    /// Distinct values in a column, in the order they first appear
+    pub fn unique(&self, data: &[Vec<String>], column: usize) -> Vec<String> {
         use std::collections::HashSet;
 
-        let mut seen: HashSet<String> = HashSet::new();
-        let mut result = vec![vec!["value".to_string()]];
-
-        for row in data.iter().skip(1) {
-            if let Some(val) = row.get(column) {
-                if seen.insert(val.clone()) {
-                    result.push(vec![val.clone()]);
-                }
-            }
-        }
-
-        result
+        let mut seen: HashSet<&str> = HashSet::new();
+        data.iter()
+            .skip(1)
+            .filter_map(|row| row.get(column))
+            .filter(|val| seen.insert(val.as_str()))
+            .cloned()
+            .collect()
     }
 
     /// Count unique values in a column
@@ -344,12 +764,10 @@ impl DataOperations {
             vec!["columns".to_string(), num_cols.to_string()],
         ];
 
-        let total_chars: usize = data
-            .iter()
-            .flat_map(|row| row.iter())
-            .map(|s| s.len())
-            .sum();
-        result.push(vec!["memory_bytes".to_string(), total_chars.to_string()]);
+        result.push(vec![
+            "memory_bytes".to_string(),
+            self.memory_usage(data).to_string(),
+        ]);
 
         for (idx, col_name) in header.iter().enumerate() {
             let non_null: usize = data
@@ -372,3 +790,69 @@ impl DataOperations {
         result
     }
 }
+
+/// (row index, value) of each numeric cell in `column`, skipping the header
+fn numeric_cells(data: &[Vec<String>], column: usize) -> Result<Vec<(usize, f64)>> {
+    let values: Vec<(usize, f64)> = data
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(i, row)| {
+            row.get(column)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite())
+                .map(|v| (i, v))
+        })
+        .collect();
+    if values.is_empty() {
+        anyhow::bail!("Column {} has no numeric values to bin", column);
+    }
+    Ok(values)
+}
+
+/// Copy `data` with a `<column>_bin` column labelling each value's interval
+///
+/// `edges` are ascending; every interval is half-open except the last.
+fn append_bin_labels(
+    data: &[Vec<String>],
+    column: usize,
+    values: &[(usize, f64)],
+    edges: &[f64],
+) -> Vec<Vec<String>> {
+    let last = edges.len() - 2;
+    let labels: Vec<String> = (0..=last)
+        .map(|i| {
+            let close = if i == last { ']' } else { ')' };
+            format!(
+                "[{},{}{}",
+                format_edge(edges[i]),
+                format_edge(edges[i + 1]),
+                close
+            )
+        })
+        .collect();
+
+    let mut bins = vec![String::new(); data.len()];
+    for &(row_idx, value) in values {
+        let bin = edges[1..=last].partition_point(|&edge| edge <= value);
+        bins[row_idx] = labels[bin].clone();
+    }
+
+    let mut result = Vec::with_capacity(data.len());
+    let mut header = data[0].clone();
+    let name = header.get(column).cloned().unwrap_or_default();
+    header.push(format!("{}_bin", name));
+    result.push(header);
+
+    for (row, bin) in data.iter().zip(bins).skip(1) {
+        let mut new_row = row.clone();
+        new_row.push(bin);
+        result.push(new_row);
+    }
+    result
+}
+
+/// Round an edge so float noise like `3.3000000000000003` does not reach labels
+fn format_edge(edge: f64) -> String {
+    ((edge * 1e6).round() / 1e6).to_string()
+}
@@ -1,79 +1,52 @@
 //! Data transformation operations
 
 use super::core::DataOperations;
-use super::types::SortOrder;
-use crate::regex_cache::where_clause_regex;
+use super::predicate::Predicate;
+use super::types::{ColumnType, NormalizeMethod, SortOrder};
+use crate::formula::FormulaEvaluator;
 use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
 use rayon::prelude::*;
+use std::fmt::Write;
 
-struct QueryCondition {
-    column: usize,
-    operator: String,
-    value: String,
-}
+/// Failing cells named in an `astype` or `parse_date` error before the rest
+/// are just counted
+const MAX_REPORTED_FAILURES: usize = 10;
 
 impl DataOperations {
-    /// Query with SQL-like WHERE clause
-    pub fn query(&self, data: &[Vec<String>], where_clause: &str) -> Result<Vec<Vec<String>>> {
-        if data.is_empty() {
+    /// Keep the rows matching a WHERE clause, with the header
+    ///
+    /// The clause joins `column OP value` comparisons with `AND`/`OR` and
+    /// parentheses, e.g. `age > 30 AND city = "NYC"`; see the `predicate`
+    /// module for the full syntax. Numeric values compare as numbers.
+    pub fn filter_where(&self, data: &[Vec<String>], clause: &str) -> Result<Vec<Vec<String>>> {
+        let Some((header, rows)) = data.split_first() else {
             return Ok(Vec::new());
-        }
+        };
+        let predicate = Predicate::parse(self, clause, header)?;
 
-        let header = &data[0];
         let mut result = Vec::with_capacity(data.len());
         result.push(header.clone());
-
-        let conditions = self.parse_where_clause(where_clause, header)?;
-
-        for row in data.iter().skip(1) {
-            if self.evaluate_conditions(row, &conditions, header)? {
-                result.push(row.clone());
-            }
-        }
-
+        result.extend(
+            rows.iter()
+                .filter(|row| predicate.matches(self, row))
+                .cloned(),
+        );
         Ok(result)
     }
 
-    fn parse_where_clause(&self, clause: &str, header: &[String]) -> Result<Vec<QueryCondition>> {
-        let mut conditions = Vec::new();
-        let re_pattern = where_clause_regex();
-
-        for cap in re_pattern.captures_iter(clause) {
-            let col_name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-            let op = cap.get(2).map(|m| m.as_str()).unwrap_or("=");
-            let value = cap.get(3).map(|m| m.as_str().trim()).unwrap_or("");
-
-            let col_idx = header
-                .iter()
-                .position(|h| h == col_name)
-                .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", col_name))?;
-
-            conditions.push(QueryCondition {
-                column: col_idx,
-                operator: op.to_string(),
-                value: value.to_string(),
-            });
-        }
-
-        Ok(conditions)
-    }
-
-    fn evaluate_conditions(
-        &self,
-        row: &[String],
-        conditions: &[QueryCondition],
-        _header: &[String],
-    ) -> Result<bool> {
-        for cond in conditions {
-            let cell_value = row.get(cond.column).map(|s| s.as_str()).unwrap_or("");
-            if !self.evaluate_filter_condition(cell_value, &cond.operator, &cond.value)? {
-                return Ok(false);
-            }
-        }
-        Ok(true)
+    /// Query with SQL-like WHERE clause; the same as [`Self::filter_where`]
+    pub fn query(&self, data: &[Vec<String>], where_clause: &str) -> Result<Vec<Vec<String>>> {
+        self.filter_where(data, where_clause)
     }
 
-    /// Add computed column using formula
+    /// Add a computed column using a formula evaluated per row
+    ///
+    /// Column names and bare column letters in `formula` refer to the current
+    /// row, so `price*qty` or `A*B` multiplies that row's cells; functions
+    /// such as `ROUND` or `IF` work as in the formula engine. Rows where the
+    /// formula fails get an Excel error value like `#VALUE!`; a malformed
+    /// formula is an error.
     pub fn mutate(
         &self,
         data: &mut Vec<Vec<String>>,
@@ -84,151 +57,82 @@ impl DataOperations {
             return Ok(());
         }
 
-        data[0].push(new_col_name.to_string());
-        let header = data[0].clone();
+        let evaluator = FormulaEvaluator::new();
+        let values = (1..data.len())
+            .map(|row| Ok(evaluator.evaluate_row(formula, data, row)?.to_string()))
+            .collect::<Result<Vec<_>>>()?;
 
-        for row_idx in 1..data.len() {
-            let value = self.evaluate_row_formula(formula, &data[row_idx], &header)?;
-            data[row_idx].push(value);
+        data[0].push(new_col_name.to_string());
+        for (row, value) in data.iter_mut().skip(1).zip(values) {
+            row.push(value);
         }
 
         Ok(())
     }
 
-    fn evaluate_row_formula(
+    /// Cast a column to `target`, rewriting each cell in its canonical form
+    ///
+    /// Empty cells are left empty. If any cell can't be cast the data is left
+    /// untouched and the error lists the offending rows, numbered as in the
+    /// sheet (the header is row 1). Returns the number of cells cast.
+    pub fn astype(
         &self,
-        formula: &str,
-        row: &[String],
-        header: &[String],
-    ) -> Result<String> {
-        let mut expr = formula.to_string();
-
-        for (idx, col_name) in header.iter().enumerate() {
-            if expr.contains(col_name) {
-                let val = row.get(idx).cloned().unwrap_or_default();
-                expr = expr.replace(col_name, &val);
-            }
-        }
-
-        for idx in 0..row.len() {
-            let letter = (b'A' + idx as u8) as char;
-            let pattern = format!("{}", letter);
-            if expr.contains(&pattern) {
-                let val = row.get(idx).cloned().unwrap_or_default();
-                expr = expr.replace(&pattern, &val);
-            }
-        }
-
-        if let Ok(result) = self.eval_arithmetic(&expr) {
-            return Ok(format!("{:.2}", result));
-        }
-
-        Ok(expr)
-    }
-
-    pub(crate) fn eval_arithmetic(&self, expr: &str) -> Result<f64> {
-        let expr = expr.replace(" ", "");
-
-        if let Ok(n) = expr.parse::<f64>() {
-            return Ok(n);
-        }
-
-        if let Some(pos) = expr.rfind('*') {
-            let left = self.eval_arithmetic(&expr[..pos])?;
-            let right = self.eval_arithmetic(&expr[pos + 1..])?;
-            return Ok(left * right);
-        }
-        if let Some(pos) = expr.rfind('/') {
-            let left = self.eval_arithmetic(&expr[..pos])?;
-            let right = self.eval_arithmetic(&expr[pos + 1..])?;
-            if right == 0.0 {
-                anyhow::bail!("Division by zero");
+        data: &mut [Vec<String>],
+        column: usize,
+        target: ColumnType,
+    ) -> Result<usize> {
+        let mut cast = Vec::new();
+        let mut failures = Vec::new();
+        for (idx, row) in data.iter().enumerate().skip(1) {
+            let Some(cell) = row.get(column).filter(|cell| !cell.is_empty()) else {
+                continue;
+            };
+            match cast_cell(cell, &target) {
+                Some(value) => cast.push((idx, value)),
+                None => failures.push(format!("row {} ('{}')", idx + 1, cell)),
             }
-            return Ok(left / right);
         }
 
-        let bytes = expr.as_bytes();
-        for i in (1..bytes.len()).rev() {
-            if bytes[i] == b'+' {
-                let left = self.eval_arithmetic(&expr[..i])?;
-                let right = self.eval_arithmetic(&expr[i + 1..])?;
-                return Ok(left + right);
-            }
-            if bytes[i] == b'-' {
-                let left = self.eval_arithmetic(&expr[..i])?;
-                let right = self.eval_arithmetic(&expr[i + 1..])?;
-                return Ok(left - right);
-            }
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "Cannot cast {} cell(s) to {}: {}",
+                failures.len(),
+                target,
+                list_failures(&failures)
+            );
         }
 
-        anyhow::bail!("Cannot evaluate: {}", expr)
-    }
-
-    /// Cast column to specified type
-    pub fn astype(&self, data: &mut Vec<Vec<String>>, column: usize, dtype: &str) -> Result<usize> {
-        if data.is_empty() {
-            return Ok(0);
+        let converted = cast.len();
+        for (idx, value) in cast {
+            data[idx][column] = value;
         }
-
-        let mut converted = 0;
-        for row in data.iter_mut().skip(1) {
-            if let Some(cell) = row.get_mut(column) {
-                let new_val = match dtype.to_lowercase().as_str() {
-                    "int" | "integer" => {
-                        if let Ok(f) = cell.parse::<f64>() {
-                            converted += 1;
-                            (f as i64).to_string()
-                        } else {
-                            cell.clone()
-                        }
-                    }
-                    "float" | "double" => {
-                        if let Ok(f) = cell.parse::<f64>() {
-                            converted += 1;
-                            format!("{:.2}", f)
-                        } else {
-                            cell.clone()
-                        }
-                    }
-                    "string" | "str" => {
-                        converted += 1;
-                        cell.clone()
-                    }
-                    "bool" | "boolean" => {
-                        let lower = cell.to_lowercase();
-                        converted += 1;
-                        if lower == "true" || lower == "1" || lower == "yes" {
-                            "true".to_string()
-                        } else if lower == "false" || lower == "0" || lower == "no" {
-                            "false".to_string()
-                        } else {
-                            cell.clone()
-                        }
-                    }
-                    _ => anyhow::bail!("Unknown type: {}. Use: int, float, string, bool", dtype),
-                };
-                *cell = new_val;
-            }
-        }
-
         Ok(converted)
     }
 
-    /// Sort by multiple columns
+    /// Sort by multiple columns, each with its own direction
+    ///
+    /// Keys are compared in order, so later keys only break ties left by
+    /// earlier ones. Cells that both parse as numbers compare numerically,
+    /// otherwise as strings. The sort is stable, and row 0 stays in place when
+    /// `has_header` is set.
     pub fn sort_by_columns(
         &self,
-        data: &mut Vec<Vec<String>>,
-        columns: &[(usize, SortOrder)],
+        data: &mut [Vec<String>],
+        keys: &[(usize, SortOrder)],
+        has_header: bool,
     ) -> Result<()> {
-        if data.len() <= 1 || columns.is_empty() {
+        let rows = if has_header && !data.is_empty() {
+            &mut data[1..]
+        } else {
+            data
+        };
+        if rows.len() <= 1 || keys.is_empty() {
             return Ok(());
         }
 
-        let header = data.remove(0);
-
         // Use parallel sort for better performance on large datasets
-        data.par_sort_by(|a, b| {
-            for (col, order) in columns {
+        rows.par_sort_by(|a, b| {
+            for (col, order) in keys {
                 let val_a = a.get(*col).map(|s| s.as_str()).unwrap_or("");
                 let val_b = b.get(*col).map(|s| s.as_str()).unwrap_or("");
 
@@ -251,7 +155,6 @@ impl DataOperations {
             std::cmp::Ordering::Equal
         });
 
-        data.insert(0, header);
         Ok(())
     }
 
@@ -269,64 +172,74 @@ impl DataOperations {
     }
 
     /// Clip values to a range
+    ///
+    /// A missing bound leaves that side open. See [`clip_range`](Self::clip_range).
     pub fn clip(
         &self,
-        data: &mut Vec<Vec<String>>,
+        data: &mut [Vec<String>],
         column: usize,
         min: Option<f64>,
         max: Option<f64>,
     ) -> Result<usize> {
-        let mut clipped = 0;
+        self.clip_range(
+            data,
+            column,
+            min.unwrap_or(f64::NEG_INFINITY),
+            max.unwrap_or(f64::INFINITY),
+        )
+    }
 
+    /// Clamp numeric cells in a column into `[min, max]`
+    ///
+    /// Non-numeric and empty cells are left untouched. Returns the number of
+    /// cells that were changed.
+    pub fn clip_range(
+        &self,
+        data: &mut [Vec<String>],
+        column: usize,
+        min: f64,
+        max: f64,
+    ) -> Result<usize> {
+        if min.is_nan() || max.is_nan() || min > max {
+            anyhow::bail!("Invalid clip range: min {min} is greater than max {max}");
+        }
+
+        let mut clipped = 0;
         for row in data.iter_mut().skip(1) {
-            if let Some(cell) = row.get_mut(column) {
-                if let Ok(val) = cell.parse::<f64>() {
-                    let mut new_val = val;
-                    if let Some(min_val) = min {
-                        if val < min_val {
-                            new_val = min_val;
-                            clipped += 1;
-                        }
-                    }
-                    if let Some(max_val) = max {
-                        if val > max_val {
-                            new_val = max_val;
-                            clipped += 1;
-                        }
-                    }
-                    if new_val != val {
-                        *cell = format!("{:.2}", new_val);
-                    }
-                }
+            if let Some(cell) = row.get_mut(column)
+                && let Ok(val) = cell.trim().parse::<f64>()
+                && (val < min || val > max)
+            {
+                *cell = val.clamp(min, max).to_string();
+                clipped += 1;
             }
         }
 
         Ok(clipped)
     }
 
-    /// Normalize column values (0-1 range)
-    pub fn normalize(&self, data: &mut Vec<Vec<String>>, column: usize) -> Result<()> {
-        let values: Vec<f64> = data
-            .par_iter()
-            .skip(1)
-            .filter_map(|row| row.get(column))
-            .filter_map(|s| s.parse::<f64>().ok())
-            .collect();
-
+    /// Scale a numeric column into the 0-1 range using its min and max
+    ///
+    /// Non-numeric cells are left untouched. A column whose values are all
+    /// equal maps to 0.
+    pub fn normalize_minmax(&self, data: &mut [Vec<String>], column: usize) -> Result<()> {
+        let values = column_values(data, column);
         if values.is_empty() {
             return Ok(());
         }
 
         // Use parallel reduce for min/max calculation on large datasets
         let (min_val, max_val) = if values.len() > 1000 {
-            let (min, max) = values.par_iter().fold(
-                || (f64::INFINITY, f64::NEG_INFINITY),
-                |(acc_min, acc_max), &val| (acc_min.min(val), acc_max.max(val)),
-            ).reduce(
-                || (f64::INFINITY, f64::NEG_INFINITY),
-                |(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)),
-            );
-            (min, max)
+            values
+                .par_iter()
+                .fold(
+                    || (f64::INFINITY, f64::NEG_INFINITY),
+                    |(acc_min, acc_max), &val| (acc_min.min(val), acc_max.max(val)),
+                )
+                .reduce(
+                    || (f64::INFINITY, f64::NEG_INFINITY),
+                    |(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)),
+                )
         } else {
             let min_val = values.iter().cloned().fold(f64::INFINITY, f64::min);
             let max_val = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
@@ -334,47 +247,114 @@ impl DataOperations {
         };
 
         let range = max_val - min_val;
+        rescale_column(data, column, |val| {
+            if range == 0.0 {
+                0.0
+            } else {
+                (val - min_val) / range
+            }
+        });
+        Ok(())
+    }
 
-        if range == 0.0 {
+    /// Standardize a column to z-scores, `(x - mean) / std`
+    ///
+    /// Uses the population standard deviation, like `describe`, and a column
+    /// with no spread maps to 0. With `append` the scores go to a new
+    /// `<column>_zscore` column, blank where the source isn't numeric;
+    /// otherwise numeric cells are replaced in place.
+    pub fn standardize(&self, data: &mut [Vec<String>], column: usize, append: bool) -> Result<()> {
+        let values = column_values(data, column);
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let std = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let zscore = |val: f64| if std == 0.0 { 0.0 } else { (val - mean) / std };
+
+        if !append {
+            rescale_column(data, column, zscore);
             return Ok(());
         }
 
-        for row in data.iter_mut().skip(1) {
-            if let Some(cell) = row.get_mut(column) {
-                if let Ok(val) = cell.parse::<f64>() {
-                    let normalized = (val - min_val) / range;
-                    *cell = format!("{:.4}", normalized);
-                }
-            }
+        let Some((header, rows)) = data.split_first_mut() else {
+            return Ok(());
+        };
+        let name = header.get(column).cloned().unwrap_or_default();
+        let width = header.len();
+        header.push(format!("{name}_zscore"));
+        for row in rows {
+            let score = row
+                .get(column)
+                .and_then(|cell| cell.trim().parse::<f64>().ok())
+                .map(|val| format!("{:.4}", zscore(val)))
+                .unwrap_or_default();
+            row.resize(width, String::new());
+            row.push(score);
         }
-
         Ok(())
     }
 
-    /// Parse and reformat date column
+    /// Rescale a column with min-max scaling or z-scores
+    pub fn normalize(
+        &self,
+        data: &mut [Vec<String>],
+        column: usize,
+        method: NormalizeMethod,
+    ) -> Result<()> {
+        match method {
+            NormalizeMethod::MinMax => self.normalize_minmax(data, column),
+            NormalizeMethod::ZScore => self.standardize(data, column, false),
+        }
+    }
+
+    /// Reformat a date column, parsing with `from_format` and writing with
+    /// `to_format` (chrono `strftime` syntax)
+    ///
+    /// Formats with a time part like `%H:%M` are read as date-times, others
+    /// as plain dates. Empty cells stay empty. If any cell doesn't match
+    /// `from_format` nothing is converted and the error lists the offending
+    /// rows, numbered as in the sheet.
     pub fn parse_date(
         &self,
-        data: &mut Vec<Vec<String>>,
+        data: &[Vec<String>],
         column: usize,
         from_format: &str,
         to_format: &str,
-    ) -> Result<usize> {
-        use chrono::NaiveDate;
+    ) -> Result<Vec<Vec<String>>> {
+        let with_time = has_time_component(from_format);
+        let mut result = data.to_vec();
+        let mut failures = Vec::new();
 
-        let mut converted = 0;
-        for row in data.iter_mut().skip(1) {
-            if let Some(cell) = row.get_mut(column) {
-                if cell.is_empty() {
-                    continue;
-                }
-                if let Ok(date) = NaiveDate::parse_from_str(cell, from_format) {
-                    *cell = date.format(to_format).to_string();
-                    converted += 1;
-                }
+        for (idx, row) in result.iter_mut().enumerate().skip(1) {
+            let Some(cell) = row.get_mut(column).filter(|cell| !cell.trim().is_empty()) else {
+                continue;
+            };
+            let text = cell.trim();
+            let mut rendered = String::new();
+            let written = if with_time {
+                NaiveDateTime::parse_from_str(text, from_format)
+                    .map(|dt| write!(rendered, "{}", dt.format(to_format)))
+            } else {
+                NaiveDate::parse_from_str(text, from_format)
+                    .map(|date| write!(rendered, "{}", date.format(to_format)))
+            };
+            match written {
+                Ok(Ok(())) => *cell = rendered,
+                Ok(Err(_)) => anyhow::bail!(
+                    "Cannot write dates with format '{to_format}'; it is invalid or needs fields '{from_format}' doesn't provide"
+                ),
+                Err(_) => failures.push(format!("row {} ('{}')", idx + 1, cell)),
             }
         }
 
-        Ok(converted)
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "Cannot parse {} cell(s) with format '{}': {}",
+                failures.len(),
+                from_format,
+                list_failures(&failures)
+            );
+        }
+        Ok(result)
     }
 
     /// Filter rows by regex pattern
@@ -465,3 +445,81 @@ impl DataOperations {
         Ok(())
     }
 }
+
+/// One cell in the canonical form of `target`, or `None` if it doesn't fit
+fn cast_cell(cell: &str, target: &ColumnType) -> Option<String> {
+    let text = cell.trim();
+    match target {
+        ColumnType::Int => {
+            let value = text.parse::<f64>().ok()?;
+            (value.is_finite() && value.fract() == 0.0).then(|| format!("{value:.0}"))
+        }
+        ColumnType::Float => {
+            let value = text.parse::<f64>().ok().filter(|v| v.is_finite())?;
+            // Keep a decimal point so whole numbers still read as floats
+            Some(if value.fract() == 0.0 && value.abs() < 1e15 {
+                format!("{value:.1}")
+            } else {
+                value.to_string()
+            })
+        }
+        ColumnType::String => Some(cell.to_string()),
+        ColumnType::Bool => match text.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => Some("true".to_string()),
+            "false" | "f" | "no" | "n" | "0" => Some("false".to_string()),
+            _ => None,
+        },
+        ColumnType::Date(format) => NaiveDate::parse_from_str(text, format)
+            .ok()
+            .map(|date| date.format("%Y-%m-%d").to_string()),
+    }
+}
+
+/// Numeric cells of a column, skipping the header
+fn column_values(data: &[Vec<String>], column: usize) -> Vec<f64> {
+    data.iter()
+        .skip(1)
+        .filter_map(|row| row.get(column))
+        .filter_map(|s| s.trim().parse::<f64>().ok())
+        .collect()
+}
+
+/// Rewrite each numeric cell of a column through `scale`, to 4 decimals
+fn rescale_column(data: &mut [Vec<String>], column: usize, scale: impl Fn(f64) -> f64) {
+    for row in data.iter_mut().skip(1) {
+        if let Some(cell) = row.get_mut(column)
+            && let Ok(val) = cell.trim().parse::<f64>()
+        {
+            *cell = format!("{:.4}", scale(val));
+        }
+    }
+}
+
+/// The first few failures joined for an error message, then a count of the rest
+fn list_failures(failures: &[String]) -> String {
+    let shown = failures.len().min(MAX_REPORTED_FAILURES);
+    let mut list = failures[..shown].join(", ");
+    if failures.len() > shown {
+        list.push_str(&format!(" and {} more", failures.len() - shown));
+    }
+    list
+}
+
+/// chrono specifiers for hours, minutes, seconds and whole timestamps
+const TIME_SPECIFIERS: &str = "HkIlMSTRXrpPfsc+";
+
+/// Whether a chrono format string has a time-of-day specifier like `%H`
+fn has_time_component(format: &str) -> bool {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        // Skip padding and width modifiers such as `%-H` or `%.3f`
+        let spec = chars.find(|c| !matches!(c, '-' | '_' | '0'..='9' | '.' | ':' | '#'));
+        if spec.is_some_and(|c| TIME_SPECIFIERS.contains(c)) {
+            return true;
+        }
+    }
+    false
+}
@@ -7,14 +7,23 @@ pub trait ProgressCallback: Send {
     fn on_progress(&mut self, current: usize, total: Option<usize>, message: &str);
 }
 
+/// Rows processed between progress reports in row-by-row operations
+pub const PROGRESS_INTERVAL: usize = 1024;
+
+/// Width of the bar drawn by [`StderrProgress`]
+const PROGRESS_BAR_WIDTH: usize = 30;
+
 /// Simple progress reporter that prints to stderr
+///
+/// With a known total it redraws a single-line bar whenever the percentage
+/// changes and ends the line at 100%; otherwise it prints the running count.
 pub struct StderrProgress {
-    last_percent: usize,
+    last_percent: Option<usize>,
 }
 
 impl StderrProgress {
     pub fn new() -> Self {
-        Self { last_percent: 0 }
+        Self { last_percent: None }
     }
 }
 
@@ -22,13 +31,25 @@ impl ProgressCallback for StderrProgress {
     fn on_progress(&mut self, current: usize, total: Option<usize>, message: &str) {
         if let Some(total) = total {
             let percent = if total > 0 {
-                (current * 100) / total
+                (current.min(total) * 100) / total
             } else {
-                0
+                100
             };
-            if percent != self.last_percent {
-                eprintln!("\r{}: {}% ({}/{})", message, percent, current, total);
-                self.last_percent = percent;
+            if self.last_percent != Some(percent) {
+                let filled = percent * PROGRESS_BAR_WIDTH / 100;
+                eprint!(
+                    "\r{}: [{}{}] {}% ({}/{})",
+                    message,
+                    "#".repeat(filled),
+                    " ".repeat(PROGRESS_BAR_WIDTH - filled),
+                    percent,
+                    current,
+                    total
+                );
+                if percent == 100 {
+                    eprintln!();
+                }
+                self.last_percent = Some(percent);
             }
         } else {
             eprintln!("\r{}: {} processed", message, current);
@@ -50,6 +71,9 @@ pub enum SortOrder {
     Descending,
 }
 
+/// Default cap on the number of data rows a cross join may produce
+pub const CROSS_JOIN_MAX_ROWS: usize = 10_000_000;
+
 /// Join type for merge operations
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum JoinType {
@@ -57,6 +81,8 @@ pub enum JoinType {
     Left,
     Right,
     Outer,
+    /// Cartesian product; join columns are ignored
+    Cross,
 }
 
 impl JoinType {
@@ -66,19 +92,225 @@ impl JoinType {
             "left" => Ok(JoinType::Left),
             "right" => Ok(JoinType::Right),
             "outer" | "full" => Ok(JoinType::Outer),
-            _ => anyhow::bail!("Unknown join type: {}. Use: inner, left, right, outer", s),
+            "cross" => Ok(JoinType::Cross),
+            _ => anyhow::bail!(
+                "Unknown join type: {}. Use: inner, left, right, outer, cross",
+                s
+            ),
+        }
+    }
+}
+
+/// Tie-breaking method for ranking, mirroring pandas `rank(method=...)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankMethod {
+    /// Tied values share the mean of their positions (1, 2.5, 2.5, 4)
+    Average,
+    /// Tied values share the lowest position (1, 2, 2, 4)
+    Min,
+    /// Tied values share the highest position (1, 3, 3, 4)
+    Max,
+    /// Like `Min`, but ranks stay consecutive (1, 2, 2, 3)
+    Dense,
+    /// Ties are ranked in the order they appear (1, 2, 3, 4)
+    First,
+}
+
+impl std::str::FromStr for RankMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "average" | "avg" | "mean" => Ok(RankMethod::Average),
+            "min" => Ok(RankMethod::Min),
+            "max" => Ok(RankMethod::Max),
+            "dense" => Ok(RankMethod::Dense),
+            "first" | "ordinal" => Ok(RankMethod::First),
+            _ => anyhow::bail!(
+                "Unknown rank method: {}. Use: average, min, max, dense, first",
+                s
+            ),
+        }
+    }
+}
+
+/// How `fillna` fills empty cells
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillMethod {
+    /// Replace empties with a constant value
+    Value,
+    /// Carry the last non-empty value down the column
+    Forward,
+    /// Carry the next non-empty value up the column
+    Backward,
+    /// Estimate empties on the straight line between the numbers around them
+    Linear,
+}
+
+impl std::str::FromStr for FillMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "value" | "constant" => Ok(FillMethod::Value),
+            "ffill" | "pad" | "forward" => Ok(FillMethod::Forward),
+            "bfill" | "backfill" | "backward" => Ok(FillMethod::Backward),
+            "linear" | "interpolate" => Ok(FillMethod::Linear),
+            _ => anyhow::bail!(
+                "Unknown fill method: {}. Use: value, ffill, bfill, linear",
+                s
+            ),
+        }
+    }
+}
+
+/// Target type for `astype`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnType {
+    /// Whole numbers; `3.0` becomes `3`, `3.5` is rejected
+    Int,
+    Float,
+    /// Left as-is
+    String,
+    /// `true`/`false`, also accepting `yes`/`no`, `y`/`n`, `t`/`f` and `1`/`0`
+    Bool,
+    /// Dates read with a chrono format like `%d/%m/%Y`, written as `YYYY-MM-DD`
+    Date(String),
+}
+
+impl std::str::FromStr for ColumnType {
+    type Err = anyhow::Error;
+
+    /// Dates take their input format after a colon, as in `date:%d/%m/%Y`;
+    /// plain `date` expects ISO dates
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((kind, format)) = s.split_once(':')
+            && kind.eq_ignore_ascii_case("date")
+        {
+            return Ok(ColumnType::Date(format.to_string()));
+        }
+        match s.to_lowercase().as_str() {
+            "int" | "integer" => Ok(ColumnType::Int),
+            "float" | "double" => Ok(ColumnType::Float),
+            "string" | "str" => Ok(ColumnType::String),
+            "bool" | "boolean" => Ok(ColumnType::Bool),
+            "date" => Ok(ColumnType::Date("%Y-%m-%d".to_string())),
+            _ => anyhow::bail!(
+                "Unknown type: {}. Use: int, float, string, bool, date[:format]",
+                s
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnType::Int => f.write_str("int"),
+            ColumnType::Float => f.write_str("float"),
+            ColumnType::String => f.write_str("string"),
+            ColumnType::Bool => f.write_str("bool"),
+            ColumnType::Date(format) => write!(f, "date:{format}"),
+        }
+    }
+}
+
+/// How `normalize` rescales a numeric column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalizeMethod {
+    /// Scale into 0-1 using the column min and max
+    MinMax,
+    /// Subtract the mean and divide by the standard deviation
+    ZScore,
+}
+
+impl std::str::FromStr for NormalizeMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "minmax" | "min-max" => Ok(NormalizeMethod::MinMax),
+            "zscore" | "z-score" | "standard" => Ok(NormalizeMethod::ZScore),
+            _ => anyhow::bail!("Unknown normalize method: {}. Use: minmax, zscore", s),
+        }
+    }
+}
+
+/// Running operations for cumulative columns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CumOp {
+    Sum,
+    Max,
+    Min,
+    Product,
+}
+
+impl std::str::FromStr for CumOp {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "sum" | "cumsum" => Ok(CumOp::Sum),
+            "max" | "cummax" => Ok(CumOp::Max),
+            "min" | "cummin" => Ok(CumOp::Min),
+            "product" | "prod" | "cumprod" => Ok(CumOp::Product),
+            _ => anyhow::bail!("Unknown cumulative op: {}. Use: sum, max, min, product", s),
+        }
+    }
+}
+
+impl CumOp {
+    /// Pandas-style label used for the output column prefix
+    pub fn name(&self) -> &'static str {
+        match self {
+            CumOp::Sum => "cumsum",
+            CumOp::Max => "cummax",
+            CumOp::Min => "cummin",
+            CumOp::Product => "cumprod",
+        }
+    }
+
+    /// Starting value; non-numeric cells leave the running value unchanged
+    pub fn identity(&self) -> f64 {
+        match self {
+            CumOp::Sum => 0.0,
+            CumOp::Max => f64::NEG_INFINITY,
+            CumOp::Min => f64::INFINITY,
+            CumOp::Product => 1.0,
+        }
+    }
+
+    pub fn combine(&self, acc: f64, value: f64) -> f64 {
+        match self {
+            CumOp::Sum => acc + value,
+            CumOp::Max => acc.max(value),
+            CumOp::Min => acc.min(value),
+            CumOp::Product => acc * value,
         }
     }
 }
 
 /// Aggregation functions for groupby
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum AggFunc {
     Sum,
     Count,
     Mean,
     Min,
     Max,
+    Median,
+    /// Sample standard deviation (n - 1 denominator)
+    Std,
+    /// Sample variance (n - 1 denominator)
+    Var,
+    /// Linearly interpolated percentile, from 0 to 100 (`p90` is `Percentile(90.0)`)
+    Percentile(f64),
+    /// Number of unique non-empty values, compared as strings
+    CountDistinct,
+    /// Non-empty values joined with `sep`, like SQL `GROUP_CONCAT`
+    Concat {
+        sep: String,
+    },
 }
 
 impl AggFunc {
@@ -89,20 +321,44 @@ impl AggFunc {
             "mean" | "avg" | "average" => Ok(AggFunc::Mean),
             "min" => Ok(AggFunc::Min),
             "max" => Ok(AggFunc::Max),
-            _ => anyhow::bail!(
-                "Unknown aggregation: {}. Use: sum, count, mean, min, max",
-                s
-            ),
+            "median" => Ok(AggFunc::Median),
+            "std" | "stddev" => Ok(AggFunc::Std),
+            "var" | "variance" => Ok(AggFunc::Var),
+            "count_distinct" | "ndistinct" | "nunique" => Ok(AggFunc::CountDistinct),
+            "concat" | "group_concat" => Ok(AggFunc::Concat {
+                sep: ",".to_string(),
+            }),
+            other => {
+                if let Some(p) = other
+                    .strip_prefix('p')
+                    .and_then(|p| p.parse::<f64>().ok())
+                    .filter(|p| (0.0..=100.0).contains(p))
+                {
+                    return Ok(AggFunc::Percentile(p));
+                }
+                anyhow::bail!(
+                    "Unknown aggregation: {}. Use: sum, count, mean, min, max, median, std, var, \
+                     p<0-100> (e.g. p90), count_distinct, concat",
+                    s
+                )
+            }
         }
     }
 
-    pub fn name(&self) -> &'static str {
+    /// Label used as the output column prefix, e.g. `sum` or `p90`
+    pub fn name(&self) -> String {
         match self {
-            AggFunc::Sum => "sum",
-            AggFunc::Count => "count",
-            AggFunc::Mean => "mean",
-            AggFunc::Min => "min",
-            AggFunc::Max => "max",
+            AggFunc::Sum => "sum".to_string(),
+            AggFunc::Count => "count".to_string(),
+            AggFunc::Mean => "mean".to_string(),
+            AggFunc::Min => "min".to_string(),
+            AggFunc::Max => "max".to_string(),
+            AggFunc::Median => "median".to_string(),
+            AggFunc::Std => "std".to_string(),
+            AggFunc::Var => "var".to_string(),
+            AggFunc::Percentile(p) => format!("p{}", p),
+            AggFunc::CountDistinct => "count_distinct".to_string(),
+            AggFunc::Concat { .. } => "concat".to_string(),
         }
     }
 
@@ -116,6 +372,63 @@ impl AggFunc {
             AggFunc::Mean => values.iter().sum::<f64>() / values.len() as f64,
             AggFunc::Min => values.iter().cloned().fold(f64::INFINITY, f64::min),
             AggFunc::Max => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            AggFunc::Median => Self::percentile(values, 50.0),
+            AggFunc::Std => Self::variance(values).sqrt(),
+            AggFunc::Var => Self::variance(values),
+            AggFunc::Percentile(p) => Self::percentile(values, *p),
+            AggFunc::CountDistinct => {
+                let distinct: std::collections::HashSet<u64> =
+                    values.iter().map(|v| v.to_bits()).collect();
+                distinct.len() as f64
+            }
+            // Concatenation only makes sense on raw strings; see `aggregate`
+            AggFunc::Concat { .. } => f64::NAN,
+        }
+    }
+
+    fn variance(values: &[f64]) -> f64 {
+        if values.len() < 2 {
+            return 0.0;
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+    }
+
+    /// Percentile with linear interpolation between the closest ranks
+    fn percentile(values: &[f64], p: f64) -> f64 {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let rank = (sorted.len() - 1) as f64 * p / 100.0;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+    }
+
+    /// Aggregate raw cell values into an output cell
+    ///
+    /// Numeric functions use the cells that parse as `f64` and format the
+    /// result with two decimals; `CountDistinct` compares the raw strings.
+    pub fn aggregate(&self, values: &[String]) -> String {
+        match self {
+            AggFunc::CountDistinct => {
+                let distinct: std::collections::HashSet<&str> = values
+                    .iter()
+                    .map(|v| v.as_str())
+                    .filter(|v| !v.trim().is_empty())
+                    .collect();
+                distinct.len().to_string()
+            }
+            AggFunc::Concat { sep } => values
+                .iter()
+                .filter(|v| !v.trim().is_empty())
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(sep),
+            _ => {
+                let numbers: Vec<f64> = values.iter().filter_map(|v| v.parse().ok()).collect();
+                format!("{:.2}", self.apply(&numbers))
+            }
         }
     }
 }
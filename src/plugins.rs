@@ -1,10 +1,42 @@
 //! Plugin system for custom functions
 //!
 //! Provides a trait-based plugin system for extending datacell with custom operations.
+//!
+//! [`PluginRegistry::default`] registers the built-in functions named in
+//! [`BUILTIN_FUNCTIONS`]: `uppercase` and `prefix` transform one column, while
+//! `trim_whitespace`, `to_lowercase_headers`, `drop_empty_columns` and
+//! `dedupe_headers` clean up a whole table and take no arguments.
+//!
+//! With the `wasm` feature, functions can also come from WebAssembly modules
+//! loaded with [`PluginRegistry::register_wasm`]. Such a module exports its
+//! `memory`, an `alloc(len: i32) -> i32` that reserves `len` bytes and a
+//! `transform(ptr: i32, len: i32) -> i64`. The rows are passed to `transform`
+//! as a JSON array of string arrays; it returns the transformed rows in the
+//! same encoding, packed as `(ptr << 32) | len`. Every call runs on a fuel
+//! budget, [`DEFAULT_WASM_FUEL`] unless registered with
+//! [`PluginRegistry::register_wasm_with_fuel`], so a module that never
+//! returns fails instead of hanging, and its memory may grow to at most
+//! [`DEFAULT_WASM_MEMORY`] bytes.
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Names of the functions registered by [`PluginRegistry::default`]
+pub const BUILTIN_FUNCTIONS: [&str; 6] = [
+    "uppercase",
+    "prefix",
+    "trim_whitespace",
+    "to_lowercase_headers",
+    "drop_empty_columns",
+    "dedupe_headers",
+];
+
+/// Fuel a WebAssembly plugin may burn per call, roughly one unit per instruction
+pub const DEFAULT_WASM_FUEL: u64 = 1_000_000_000;
+
+/// Bytes of linear memory a WebAssembly plugin may grow to (256 MiB)
+pub const DEFAULT_WASM_MEMORY: usize = 256 * 1024 * 1024;
 
 /// Plugin metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +112,29 @@ impl PluginRegistry {
         self.plugins.insert(name, Box::new(function));
     }
 
+    /// Register the `transform` export of a WebAssembly module as `name`
+    ///
+    /// `path` may be a binary `.wasm` module or its `.wat` text form. Each
+    /// call may burn [`DEFAULT_WASM_FUEL`].
+    pub fn register_wasm(&mut self, name: &str, path: &str) -> Result<()> {
+        self.register_wasm_with_fuel(name, path, DEFAULT_WASM_FUEL)
+    }
+
+    /// [`register_wasm`](Self::register_wasm) with a fuel budget of `fuel` per call
+    #[cfg(feature = "wasm")]
+    pub fn register_wasm_with_fuel(&mut self, name: &str, path: &str, fuel: u64) -> Result<()> {
+        self.register(WasmPlugin::load(name, path)?.with_fuel(fuel));
+        Ok(())
+    }
+
+    /// Register a WebAssembly function (fallback when "wasm" feature is not enabled)
+    #[cfg(not(feature = "wasm"))]
+    pub fn register_wasm_with_fuel(&mut self, _name: &str, _path: &str, _fuel: u64) -> Result<()> {
+        anyhow::bail!(
+            "WASM plugins are not enabled. Please rebuild with the 'wasm' feature: cargo build --features wasm"
+        )
+    }
+
     /// Execute a plugin function
     pub fn execute(
         &self,
@@ -106,6 +161,128 @@ impl PluginRegistry {
     }
 }
 
+/// A function backed by the `transform` export of a WebAssembly module
+///
+/// The module is compiled once; every call runs in a fresh instance, so
+/// functions keep no state between calls and take no arguments. A call
+/// that burns more than its fuel budget is stopped with an error, and its
+/// memory cannot grow past the memory limit.
+#[cfg(feature = "wasm")]
+pub struct WasmPlugin {
+    name: String,
+    path: String,
+    engine: wasmtime::Engine,
+    module: wasmtime::Module,
+    fuel: u64,
+    memory_limit: usize,
+}
+
+#[cfg(feature = "wasm")]
+impl WasmPlugin {
+    /// Compile the module at `path`
+    pub fn load(name: &str, path: &str) -> Result<Self> {
+        use anyhow::Context;
+
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        let engine = wasmtime::Engine::new(&config)?;
+        let module = wasmtime::Module::from_file(&engine, path)
+            .with_context(|| format!("Failed to load WASM module: {}", path))?;
+        Ok(Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            engine,
+            module,
+            fuel: DEFAULT_WASM_FUEL,
+            memory_limit: DEFAULT_WASM_MEMORY,
+        })
+    }
+
+    /// Set the fuel each call may burn (default [`DEFAULT_WASM_FUEL`])
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Set the bytes of memory each call may grow to (default
+    /// [`DEFAULT_WASM_MEMORY`])
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl PluginFunction for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn execute(&self, args: &[String], data: &[Vec<String>]) -> Result<Vec<Vec<String>>> {
+        use anyhow::Context;
+
+        if !args.is_empty() {
+            anyhow::bail!("WASM plugin '{}' takes no arguments", self.name);
+        }
+
+        let limits = wasmtime::StoreLimitsBuilder::new()
+            .memory_size(self.memory_limit)
+            .build();
+        let mut store = wasmtime::Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(self.fuel)?;
+        let instance = wasmtime::Instance::new(&mut store, &self.module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow::anyhow!("WASM module {} exports no 'memory'", self.path))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let transform = instance.get_typed_func::<(i32, i32), i64>(&mut store, "transform")?;
+
+        let input = serde_json::to_vec(data)?;
+        let len = i32::try_from(input.len()).context("Data is too large for a WASM plugin")?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, &input)?;
+
+        let packed = transform.call(&mut store, (ptr, len)).map_err(|err| {
+            if err.downcast_ref() == Some(&wasmtime::Trap::OutOfFuel) {
+                anyhow::anyhow!(
+                    "WASM plugin '{}' ran out of fuel ({} units)",
+                    self.name,
+                    self.fuel
+                )
+            } else {
+                err
+            }
+        })? as u64;
+
+        // Check the returned range before allocating a buffer of its length
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if out_ptr
+            .checked_add(out_len)
+            .is_none_or(|end| end > memory.data_size(&store))
+        {
+            anyhow::bail!(
+                "WASM plugin '{}' returned rows outside its memory",
+                self.name
+            );
+        }
+        let mut output = vec![0; out_len];
+        memory.read(&store, out_ptr, &mut output)?;
+
+        serde_json::from_slice(&output)
+            .with_context(|| format!("WASM plugin '{}' returned invalid JSON rows", self.name))
+    }
+
+    fn metadata(&self) -> FunctionMetadata {
+        FunctionMetadata {
+            name: self.name.clone(),
+            description: format!("WASM transform from {}", self.path),
+            parameters: Vec::new(),
+            return_type: "Vec<Vec<String>>".to_string(),
+        }
+    }
+}
+
 /// Example plugin: Uppercase transformation
 pub struct UppercasePlugin;
 
@@ -206,6 +383,136 @@ impl PluginFunction for PrefixPlugin {
     }
 }
 
+/// Metadata for a built-in function that takes no arguments
+fn table_function_metadata(name: &str, description: &str) -> FunctionMetadata {
+    FunctionMetadata {
+        name: name.to_string(),
+        description: description.to_string(),
+        parameters: Vec::new(),
+        return_type: "Vec<Vec<String>>".to_string(),
+    }
+}
+
+/// Built-in plugin: trim leading and trailing whitespace from every cell
+pub struct TrimWhitespacePlugin;
+
+impl PluginFunction for TrimWhitespacePlugin {
+    fn name(&self) -> &str {
+        "trim_whitespace"
+    }
+
+    fn execute(&self, _args: &[String], data: &[Vec<String>]) -> Result<Vec<Vec<String>>> {
+        Ok(data
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.trim().to_string()).collect())
+            .collect())
+    }
+
+    fn metadata(&self) -> FunctionMetadata {
+        table_function_metadata(
+            "trim_whitespace",
+            "Trim leading and trailing whitespace from every cell",
+        )
+    }
+}
+
+/// Built-in plugin: lowercase the header row
+pub struct LowercaseHeadersPlugin;
+
+impl PluginFunction for LowercaseHeadersPlugin {
+    fn name(&self) -> &str {
+        "to_lowercase_headers"
+    }
+
+    fn execute(&self, _args: &[String], data: &[Vec<String>]) -> Result<Vec<Vec<String>>> {
+        let mut result = data.to_vec();
+        if let Some(header) = result.first_mut() {
+            for name in header.iter_mut() {
+                *name = name.to_lowercase();
+            }
+        }
+        Ok(result)
+    }
+
+    fn metadata(&self) -> FunctionMetadata {
+        table_function_metadata("to_lowercase_headers", "Convert header names to lowercase")
+    }
+}
+
+/// Built-in plugin: remove columns with no non-blank value below the header
+pub struct DropEmptyColumnsPlugin;
+
+impl PluginFunction for DropEmptyColumnsPlugin {
+    fn name(&self) -> &str {
+        "drop_empty_columns"
+    }
+
+    fn execute(&self, _args: &[String], data: &[Vec<String>]) -> Result<Vec<Vec<String>>> {
+        let width = data.iter().map(Vec::len).max().unwrap_or(0);
+        let keep: Vec<usize> = (0..width)
+            .filter(|&col| {
+                data.iter()
+                    .skip(1)
+                    .any(|row| row.get(col).is_some_and(|cell| !cell.trim().is_empty()))
+            })
+            .collect();
+
+        Ok(data
+            .iter()
+            .map(|row| {
+                keep.iter()
+                    .map(|&col| row.get(col).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect())
+    }
+
+    fn metadata(&self) -> FunctionMetadata {
+        table_function_metadata(
+            "drop_empty_columns",
+            "Remove columns whose values are all empty or whitespace",
+        )
+    }
+}
+
+/// Built-in plugin: make header names unique by suffixing repeats with `_2`, `_3`, ...
+pub struct DedupeHeadersPlugin;
+
+impl PluginFunction for DedupeHeadersPlugin {
+    fn name(&self) -> &str {
+        "dedupe_headers"
+    }
+
+    fn execute(&self, _args: &[String], data: &[Vec<String>]) -> Result<Vec<Vec<String>>> {
+        let mut result = data.to_vec();
+        if let Some(header) = result.first_mut() {
+            // Suffixed names must not clash with a header that comes later
+            let original: HashSet<String> = header.iter().cloned().collect();
+            let mut seen = HashSet::new();
+            for name in header.iter_mut() {
+                if !seen.insert(name.clone()) {
+                    let unique = (2..)
+                        .map(|n| format!("{}_{}", name, n))
+                        .find(|candidate| {
+                            !seen.contains(candidate) && !original.contains(candidate)
+                        })
+                        .expect("unbounded suffixes always find a free name");
+                    seen.insert(unique.clone());
+                    *name = unique;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    fn metadata(&self) -> FunctionMetadata {
+        table_function_metadata(
+            "dedupe_headers",
+            "Rename repeated header names to name_2, name_3, ...",
+        )
+    }
+}
+
 impl Default for PluginRegistry {
     fn default() -> Self {
         let mut registry = Self::new();
@@ -213,6 +520,10 @@ impl Default for PluginRegistry {
         // Register built-in plugins
         registry.register(UppercasePlugin);
         registry.register(PrefixPlugin);
+        registry.register(TrimWhitespacePlugin);
+        registry.register(LowercaseHeadersPlugin);
+        registry.register(DropEmptyColumnsPlugin);
+        registry.register(DedupeHeadersPlugin);
 
         registry
     }
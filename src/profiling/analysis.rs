@@ -1,7 +1,11 @@
 //! Statistical analysis methods for data profiling
 
 use crate::common::string;
-use chrono::{Datelike, NaiveDate};
+use crate::operations::DataOperations;
+use crate::regex_cache::{email_regex, url_regex};
+use anyhow::Result;
+use chrono::format::ParseErrorKind;
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use std::collections::HashMap;
 
 use super::types::*;
@@ -9,101 +13,7 @@ use super::types::*;
 impl super::profiler::DataProfiler {
     /// Infer data type from sample values
     pub fn infer_data_type(&self, data: &[String]) -> DataType {
-        let non_null_values: Vec<&str> = data
-            .iter()
-            .filter(|v| !string::is_empty_or_whitespace(v))
-            .map(|v| v.as_str())
-            .collect();
-
-        if non_null_values.is_empty() {
-            return DataType::Unknown;
-        }
-
-        let sample_size = non_null_values.len().min(100);
-        let sample = &non_null_values[..sample_size];
-
-        // Check for boolean
-        let boolean_count = sample
-            .iter()
-            .filter(|v| {
-                matches!(
-                    v.to_lowercase().as_str(),
-                    "true" | "false" | "1" | "0" | "yes" | "no"
-                )
-            })
-            .count();
-
-        if boolean_count as f64 / sample_size as f64 > 0.8 {
-            return DataType::Boolean;
-        }
-
-        // Check for email
-        let email_regex =
-            regex::Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
-        let email_count = sample.iter().filter(|v| email_regex.is_match(v)).count();
-
-        if email_count as f64 / sample_size as f64 > 0.8 {
-            return DataType::Email;
-        }
-
-        // Check for URL
-        let url_regex = regex::Regex::new(r"^https?://[^\s/$.?#].[^\s]*$").unwrap();
-        let url_count = sample.iter().filter(|v| url_regex.is_match(v)).count();
-
-        if url_count as f64 / sample_size as f64 > 0.8 {
-            return DataType::Url;
-        }
-
-        // Check for phone
-        let phone_regex = regex::Regex::new(r"^\+?[\d\s\-\(\)]{10,}$").unwrap();
-        let phone_count = sample.iter().filter(|v| phone_regex.is_match(v)).count();
-
-        if phone_count as f64 / sample_size as f64 > 0.8 {
-            return DataType::Phone;
-        }
-
-        // Check for date/datetime
-        let date_formats = vec![
-            "%Y-%m-%d",
-            "%d/%m/%Y",
-            "%m/%d/%Y",
-            "%Y-%m-%d %H:%M:%S",
-            "%d/%m/%Y %H:%M:%S",
-        ];
-
-        for format in &date_formats {
-            let date_count = sample
-                .iter()
-                .filter(|v| {
-                    chrono::NaiveDate::parse_from_str(v, format).is_ok()
-                        || chrono::NaiveDateTime::parse_from_str(v, format).is_ok()
-                })
-                .count();
-
-            if date_count as f64 / sample_size as f64 > 0.8 {
-                return if format.contains("%H") {
-                    DataType::DateTime
-                } else {
-                    DataType::Date
-                };
-            }
-        }
-
-        // Check for numeric
-        let numeric_count = sample.iter().filter(|v| string::is_numeric(v)).count();
-
-        if numeric_count as f64 / sample_size as f64 > 0.8 {
-            // Check if all are integers
-            let int_count = sample.iter().filter(|v| v.parse::<i64>().is_ok()).count();
-
-            return if int_count as f64 / numeric_count as f64 > 0.8 {
-                DataType::Integer
-            } else {
-                DataType::Float
-            };
-        }
-
-        DataType::String
+        infer_data_type_with_formats(data, &self.date_formats)
     }
 
     /// Get value frequencies
@@ -254,6 +164,9 @@ impl super::profiler::DataProfiler {
             0.0
         };
 
+        let histogram =
+            (self.histogram_bins > 0).then(|| histogram(&numbers, min, max, self.histogram_bins));
+
         Some(NumericStats {
             min,
             max,
@@ -267,6 +180,7 @@ impl super::profiler::DataProfiler {
             iqr,
             skewness,
             kurtosis,
+            histogram,
         })
     }
 
@@ -276,16 +190,9 @@ impl super::profiler::DataProfiler {
             .iter()
             .filter(|v| !string::is_empty_or_whitespace(v))
             .filter_map(|v| {
-                // Try different date formats
-                if let Ok(date) = NaiveDate::parse_from_str(v, "%Y-%m-%d") {
-                    Some(date)
-                } else if let Ok(date) = NaiveDate::parse_from_str(v, "%d/%m/%Y") {
-                    Some(date)
-                } else if let Ok(date) = NaiveDate::parse_from_str(v, "%m/%d/%Y") {
-                    Some(date)
-                } else {
-                    None
-                }
+                self.date_formats
+                    .iter()
+                    .find_map(|format| parse_date(v.trim(), format))
             })
             .collect();
 
@@ -423,4 +330,205 @@ impl super::profiler::DataProfiler {
             mixed_case,
         }
     }
+
+    /// Correlate the columns profiled as integer or float
+    ///
+    /// Returns `None` when fewer than two such columns remain after dropping
+    /// any that are not fully numeric (see
+    /// [`DataOperations::is_numeric_column`]). Each pair is correlated over
+    /// the rows where both have a value, via
+    /// [`DataOperations::correlation_matrix`].
+    pub fn calculate_correlations(
+        &self,
+        data: &[Vec<String>],
+        columns: &[ColumnProfile],
+    ) -> Result<Option<CorrelationMatrix>> {
+        let ops = DataOperations::new();
+        let numeric: Vec<usize> = columns
+            .iter()
+            .enumerate()
+            .filter(|(idx, column)| {
+                matches!(column.data_type, DataType::Integer | DataType::Float)
+                    && ops.is_numeric_column(data, *idx)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        if numeric.len() < 2 {
+            return Ok(None);
+        }
+
+        let values = ops.correlation_matrix(data, &numeric);
+
+        Ok(Some(CorrelationMatrix {
+            columns: numeric
+                .iter()
+                .map(|&idx| columns[idx].name.clone())
+                .collect(),
+            values,
+        }))
+    }
+}
+
+/// Date formats recognized by the profiler unless overridden
+pub const DEFAULT_DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%Y-%m-%d %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+/// Parse the date part of `value` with a chrono `format`
+///
+/// Formats that stop short of a full date are pinned to its start: `%b %Y`
+/// gets day 1 and `%G-W%V` gets Monday.
+fn parse_date(value: &str, format: &str) -> Option<NaiveDate> {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+        return Some(datetime.date());
+    }
+    match NaiveDate::parse_from_str(value, format) {
+        Ok(date) => Some(date),
+        Err(e) if e.kind() == ParseErrorKind::NotEnough => {
+            NaiveDate::parse_from_str(&format!("1 {value}"), &format!("%d {format}"))
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(&format!("{value} 1"), &format!("{format} %u"))
+                })
+                .ok()
+        }
+        Err(_) => None,
+    }
+}
+
+/// Count `numbers` into `bins` equal-width bins spanning `min..=max`
+///
+/// Bins are labelled `[lo, hi)`, except the last, which includes `max`. A
+/// column with a single distinct value gets one bin.
+fn histogram(numbers: &[f64], min: f64, max: f64, bins: usize) -> Vec<(String, usize)> {
+    if max <= min {
+        return vec![(format!("[{min}, {max}]"), numbers.len())];
+    }
+
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0; bins];
+    for &number in numbers {
+        let bin = ((number - min) / width) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let lo = min + width * i as f64;
+            let label = if i == bins - 1 {
+                format!("[{lo:.2}, {max:.2}]")
+            } else {
+                format!("[{lo:.2}, {:.2})", lo + width)
+            };
+            (label, count)
+        })
+        .collect()
+}
+
+/// Infer a column's data type from its values
+///
+/// Looks at up to 100 non-blank values and returns the first type that more
+/// than 80% of them fit, trying booleans, emails, URLs, phone numbers, dates
+/// and numbers in that order, else `String`. Blank columns are `Unknown`.
+/// Dates are recognized using [`DEFAULT_DATE_FORMATS`].
+pub fn infer_data_type(data: &[String]) -> DataType {
+    infer_data_type_with_formats(data, DEFAULT_DATE_FORMATS)
+}
+
+/// [`infer_data_type`] recognizing dates in any of `date_formats`
+///
+/// A format containing `%H` marks the column as `DateTime` rather than `Date`.
+pub fn infer_data_type_with_formats<S: AsRef<str>>(
+    data: &[String],
+    date_formats: &[S],
+) -> DataType {
+    let non_null_values: Vec<&str> = data
+        .iter()
+        .filter(|v| !string::is_empty_or_whitespace(v))
+        .map(|v| v.as_str())
+        .collect();
+
+    if non_null_values.is_empty() {
+        return DataType::Unknown;
+    }
+
+    let sample_size = non_null_values.len().min(100);
+    let sample = &non_null_values[..sample_size];
+
+    // Check for boolean
+    let boolean_count = sample
+        .iter()
+        .filter(|v| {
+            matches!(
+                v.to_lowercase().as_str(),
+                "true" | "false" | "1" | "0" | "yes" | "no"
+            )
+        })
+        .count();
+
+    if boolean_count as f64 / sample_size as f64 > 0.8 {
+        return DataType::Boolean;
+    }
+
+    // Check for email
+    let email_regex = email_regex();
+    let email_count = sample.iter().filter(|v| email_regex.is_match(v)).count();
+
+    if email_count as f64 / sample_size as f64 > 0.8 {
+        return DataType::Email;
+    }
+
+    // Check for URL
+    let url_regex = url_regex();
+    let url_count = sample.iter().filter(|v| url_regex.is_match(v)).count();
+
+    if url_count as f64 / sample_size as f64 > 0.8 {
+        return DataType::Url;
+    }
+
+    // Check for phone
+    let phone_regex = regex::Regex::new(r"^\+?[\d\s\-\(\)]{10,}$").unwrap();
+    let phone_count = sample.iter().filter(|v| phone_regex.is_match(v)).count();
+
+    if phone_count as f64 / sample_size as f64 > 0.8 {
+        return DataType::Phone;
+    }
+
+    // Check for date/datetime
+    for format in date_formats {
+        let format = format.as_ref();
+        let date_count = sample
+            .iter()
+            .filter(|v| parse_date(v, format).is_some())
+            .count();
+
+        if date_count as f64 / sample_size as f64 > 0.8 {
+            return if format.contains("%H") {
+                DataType::DateTime
+            } else {
+                DataType::Date
+            };
+        }
+    }
+
+    // Check for numeric
+    let numeric_count = sample.iter().filter(|v| string::is_numeric(v)).count();
+
+    if numeric_count as f64 / sample_size as f64 > 0.8 {
+        // Check if all are integers
+        let int_count = sample.iter().filter(|v| v.parse::<i64>().is_ok()).count();
+
+        return if int_count as f64 / numeric_count as f64 > 0.8 {
+            DataType::Integer
+        } else {
+            DataType::Float
+        };
+    }
+
+    DataType::String
 }
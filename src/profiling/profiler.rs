@@ -4,12 +4,16 @@ use crate::common::{collection, string};
 use anyhow::Result;
 use std::collections::HashSet;
 
+use super::analysis::DEFAULT_DATE_FORMATS;
 use super::types::*;
 
 /// Data profiler
 pub struct DataProfiler {
     max_distinct_values: usize,
     sample_size: Option<usize>,
+    pub(crate) histogram_bins: usize,
+    pub(crate) date_formats: Vec<String>,
+    correlations: bool,
 }
 
 impl DataProfiler {
@@ -18,6 +22,9 @@ impl DataProfiler {
         Self {
             max_distinct_values: 100,
             sample_size: None,
+            histogram_bins: 10,
+            date_formats: DEFAULT_DATE_FORMATS.iter().map(|f| f.to_string()).collect(),
+            correlations: true,
         }
     }
 
@@ -27,6 +34,31 @@ impl DataProfiler {
         self
     }
 
+    /// Set the number of histogram bins for numeric columns; 0 disables histograms
+    pub fn with_histogram_bins(mut self, bins: usize) -> Self {
+        self.histogram_bins = bins;
+        self
+    }
+
+    /// Set the chrono formats tried when detecting and parsing dates
+    ///
+    /// Replaces [`DEFAULT_DATE_FORMATS`]; include them to extend the defaults
+    /// instead. Formats without a day, such as `%b %Y`, resolve to the first
+    /// day of the month.
+    pub fn with_date_formats(mut self, formats: Vec<String>) -> Self {
+        self.date_formats = formats;
+        self
+    }
+
+    /// Set whether to correlate numeric columns; on by default
+    ///
+    /// The matrix grows with the square of the numeric column count, so
+    /// turn this off when profiling very wide tables.
+    pub fn with_correlations(mut self, enabled: bool) -> Self {
+        self.correlations = enabled;
+        self
+    }
+
     /// Set sample size for large datasets
     pub fn with_sample_size(mut self, size: usize) -> Self {
         self.sample_size = Some(size);
@@ -46,6 +78,8 @@ impl DataProfiler {
                 duplicate_rows: 0,
                 duplicate_percentage: 0.0,
                 columns: Vec::new(),
+                correlations: None,
+                candidate_keys: Vec::new(),
                 data_quality_score: 0.0,
                 recommendations: Vec::new(),
                 profiling_timestamp: chrono::Utc::now().to_rfc3339(),
@@ -96,13 +130,24 @@ impl DataProfiler {
         let duplicate_percentage = (duplicate_rows as f64 / total_rows as f64) * 100.0;
         let null_percentage = (null_cells as f64 / total_cells as f64) * 100.0;
 
+        let correlations = if self.correlations {
+            self.calculate_correlations(&data_to_profile, &columns)?
+        } else {
+            None
+        };
+        let candidate_keys = self.find_candidate_keys(&data_to_profile[1..], &columns);
+
         // Calculate overall quality score
         let data_quality_score =
             self.calculate_overall_quality_score(&columns, null_percentage, duplicate_percentage);
 
         // Generate recommendations
-        let recommendations =
-            self.generate_recommendations(&columns, null_percentage, duplicate_percentage);
+        let recommendations = self.generate_recommendations(
+            &columns,
+            correlations.as_ref(),
+            null_percentage,
+            duplicate_percentage,
+        );
 
         Ok(DataProfile {
             file_path: file_path.to_string(),
@@ -114,6 +159,8 @@ impl DataProfiler {
             duplicate_rows,
             duplicate_percentage,
             columns,
+            correlations,
+            candidate_keys,
             data_quality_score,
             recommendations,
             profiling_timestamp: chrono::Utc::now().to_rfc3339(),
@@ -121,7 +168,7 @@ impl DataProfiler {
     }
 
     /// Profile a single column
-    fn profile_column(
+    pub(crate) fn profile_column(
         &self,
         name: &str,
         data: &[String],
@@ -224,8 +271,59 @@ impl DataProfiler {
 
         duplicates
     }
+
+    /// Find columns that uniquely identify `rows`, then pairs of the remaining
+    /// columns that do so together
+    ///
+    /// `rows` are the sampled rows when a sample size is set. Columns with any
+    /// empty cell never take part in a key, and pairs are only searched when
+    /// at most [`MAX_KEY_PAIR_COLUMNS`] columns remain.
+    fn find_candidate_keys(&self, rows: &[Vec<String>], columns: &[ColumnProfile]) -> Vec<String> {
+        if rows.is_empty() {
+            return Vec::new();
+        }
+
+        let non_null: Vec<usize> = (0..columns.len())
+            .filter(|&col| {
+                rows.iter().all(|row| {
+                    row.get(col)
+                        .is_some_and(|v| !string::is_empty_or_whitespace(v))
+                })
+            })
+            .collect();
+
+        let (single, rest): (Vec<usize>, Vec<usize>) = non_null.into_iter().partition(|&col| {
+            let mut seen = HashSet::with_capacity(rows.len());
+            rows.iter().all(|row| seen.insert(row[col].as_str()))
+        });
+        let mut keys: Vec<String> = single
+            .iter()
+            .map(|&col| columns[col].name.clone())
+            .collect();
+        if rest.len() > MAX_KEY_PAIR_COLUMNS {
+            return keys;
+        }
+
+        let mut seen = HashSet::with_capacity(rows.len());
+        for (i, &first) in rest.iter().enumerate() {
+            for &second in &rest[i + 1..] {
+                seen.clear();
+                if rows
+                    .iter()
+                    .all(|row| seen.insert((row[first].as_str(), row[second].as_str())))
+                {
+                    keys.push(format!("{},{}", columns[first].name, columns[second].name));
+                }
+            }
+        }
+
+        keys
+    }
 }
 
+/// Most non-unique columns searched pairwise for composite candidate keys
+pub const MAX_KEY_PAIR_COLUMNS: usize = 20;
+
 impl Default for DataProfiler {
     fn default() -> Self {
         Self::new()
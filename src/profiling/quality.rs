@@ -80,6 +80,7 @@ impl super::profiler::DataProfiler {
     pub fn generate_recommendations(
         &self,
         columns: &[ColumnProfile],
+        correlations: Option<&CorrelationMatrix>,
         null_percentage: f64,
         duplicate_percentage: f64,
     ) -> Vec<String> {
@@ -146,6 +147,20 @@ impl super::profiler::DataProfiler {
             }
         }
 
+        // Cross-column recommendations
+        if let Some(matrix) = correlations {
+            for (i, row) in matrix.values.iter().enumerate() {
+                for (j, r) in row.iter().enumerate().skip(i + 1) {
+                    if let Some(r) = r.filter(|r| r.abs() > HIGH_CORRELATION) {
+                        recommendations.push(format!(
+                            "Columns '{}' and '{}' are highly correlated (r = {:.2}); consider dropping one.",
+                            matrix.columns[i], matrix.columns[j], r
+                        ));
+                    }
+                }
+            }
+        }
+
         recommendations
     }
 
@@ -173,6 +188,14 @@ impl super::profiler::DataProfiler {
             profile.data_quality_score
         ));
 
+        if !profile.candidate_keys.is_empty() {
+            report.push_str("## Candidate Keys\n\n");
+            for key in &profile.candidate_keys {
+                report.push_str(&format!("- {}\n", key));
+            }
+            report.push('\n');
+        }
+
         if !profile.recommendations.is_empty() {
             report.push_str("## Recommendations\n\n");
             for rec in &profile.recommendations {
@@ -181,6 +204,12 @@ impl super::profiler::DataProfiler {
             report.push_str("\n");
         }
 
+        if let Some(matrix) = &profile.correlations {
+            report.push_str("## Correlations\n\n");
+            report.push_str(&render_correlations(matrix));
+            report.push('\n');
+        }
+
         report.push_str("## Column Details\n\n");
 
         for column in &profile.columns {
@@ -221,6 +250,12 @@ impl super::profiler::DataProfiler {
                     numeric_stats.median,
                     numeric_stats.std_dev
                 ));
+
+                if let Some(histogram) = &numeric_stats.histogram {
+                    report.push_str("- **Histogram**:\n\n```\n");
+                    report.push_str(&render_histogram(histogram));
+                    report.push_str("```\n");
+                }
             }
 
             if let Some(length_stats) = &column.length_stats {
@@ -244,3 +279,45 @@ impl super::profiler::DataProfiler {
         report
     }
 }
+
+/// Absolute correlation above which two columns are flagged as redundant
+const HIGH_CORRELATION: f64 = 0.9;
+
+/// Render a correlation matrix as a markdown table
+fn render_correlations(matrix: &CorrelationMatrix) -> String {
+    let mut table = format!("| | {} |\n", matrix.columns.join(" | "));
+    table.push_str(&format!("|---|{}\n", "---|".repeat(matrix.columns.len())));
+    for (name, row) in matrix.columns.iter().zip(&matrix.values) {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|r| r.map_or_else(|| "-".to_string(), |r| format!("{r:.2}")))
+            .collect();
+        table.push_str(&format!("| {} | {} |\n", name, cells.join(" | ")));
+    }
+    table
+}
+
+/// Widest histogram bar in the report, in characters
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Render histogram bins as ASCII bars scaled to the largest bin
+fn render_histogram(histogram: &[(String, usize)]) -> String {
+    let label_width = histogram
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    let max_count = histogram
+        .iter()
+        .map(|&(_, count)| count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut chart = String::new();
+    for (label, count) in histogram {
+        let bar = "#".repeat(count * HISTOGRAM_WIDTH / max_count);
+        chart.push_str(&format!("{label:>label_width$} | {bar} {count}\n"));
+    }
+    chart
+}
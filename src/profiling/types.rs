@@ -68,6 +68,8 @@ pub struct NumericStats {
     pub iqr: f64,
     pub skewness: f64,
     pub kurtosis: f64,
+    /// Equal-width bins from min to max as `(range label, count)`
+    pub histogram: Option<Vec<(String, usize)>>,
 }
 
 /// Date statistics
@@ -95,6 +97,14 @@ pub struct TextStats {
     pub mixed_case: usize,
 }
 
+/// Pearson correlations between the numeric columns of a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationMatrix {
+    pub columns: Vec<String>,
+    /// `values[i][j]` pairs `columns[i]` with `columns[j]`; `None` when either has no variance
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
 /// Overall data profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataProfile {
@@ -107,6 +117,10 @@ pub struct DataProfile {
     pub duplicate_rows: usize,
     pub duplicate_percentage: f64,
     pub columns: Vec<ColumnProfile>,
+    /// Correlations between numeric columns; `None` with fewer than two
+    pub correlations: Option<CorrelationMatrix>,
+    /// Columns, or comma-joined column pairs, whose values are unique and never empty
+    pub candidate_keys: Vec<String>,
     pub data_quality_score: f64,
     pub recommendations: Vec<String>,
     pub profiling_timestamp: String,
@@ -2,9 +2,19 @@
 //!
 //! Provides streaming capabilities for processing large datasets incrementally.
 
-use anyhow::Result;
+use crate::columnar::{ParquetHandler, ParquetWriteOptions};
+use crate::csv_handler::{StreamingCsvReader, StreamingCsvWriter};
+use crate::helpers::{ColumnType, infer_column_type};
+use crate::json::row_object;
+use crate::operations::ProgressCallback;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use tokio::sync::broadcast;
 
 /// Streaming data chunk
@@ -225,3 +235,523 @@ impl StreamingDataReader for CsvStreamingReader {
         Ok(())
     }
 }
+
+/// Fixed-size Bloom filter for approximate set membership
+///
+/// Memory is bounded by the requested capacity and false-positive rate.
+/// A lookup can wrongly report an unseen item as present, but never the
+/// reverse.
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Size a filter for `expected_items` with the target false-positive rate
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = (-(n * p.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round().clamp(1.0, 16.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Insert an item, returning `true` if it was (probably) not present before
+    pub fn insert(&mut self, item: &str) -> bool {
+        let (h1, h2) = Self::hash_pair(item);
+        let mut newly_set = false;
+
+        for i in 0..self.num_hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                self.bits[word] |= mask;
+                newly_set = true;
+            }
+        }
+
+        newly_set
+    }
+
+    /// Check whether an item may have been inserted
+    pub fn contains(&self, item: &str) -> bool {
+        let (h1, h2) = Self::hash_pair(item);
+
+        (0..self.num_hashes as u64).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            self.bits[(bit / 64) as usize] & (1u64 << (bit % 64)) != 0
+        })
+    }
+
+    /// Memory used by the bit array in bytes
+    pub fn memory_bytes(&self) -> usize {
+        self.bits.len() * std::mem::size_of::<u64>()
+    }
+
+    fn hash_pair(item: &str) -> (u64, u64) {
+        let mut first = DefaultHasher::new();
+        item.hash(&mut first);
+
+        let mut second = DefaultHasher::new();
+        0x9e37_79b9_7f4a_7c15u64.hash(&mut second);
+        item.hash(&mut second);
+
+        // An odd step guarantees the probe sequence visits distinct bits
+        (first.finish(), second.finish() | 1)
+    }
+}
+
+/// How a streaming dedupe remembers rows it has already seen
+pub enum DedupeMode {
+    /// Keep every key in a `HashSet` (exact, memory grows with unique rows)
+    Exact,
+    /// Keep keys in a Bloom filter (bounded memory, may drop a small
+    /// fraction of unique rows as false positives)
+    Approximate {
+        expected_rows: usize,
+        false_positive_rate: f64,
+    },
+}
+
+enum SeenRows {
+    Exact(HashSet<String>),
+    Approximate(BloomFilter),
+}
+
+impl SeenRows {
+    fn insert(&mut self, key: String) -> bool {
+        match self {
+            SeenRows::Exact(set) => set.insert(key),
+            SeenRows::Approximate(filter) => filter.insert(&key),
+        }
+    }
+}
+
+/// Summary of a streaming dedupe run
+#[derive(Debug, Clone, Default)]
+pub struct DedupeStats {
+    pub rows_read: usize,
+    pub rows_written: usize,
+}
+
+impl DedupeStats {
+    pub fn duplicates_removed(&self) -> usize {
+        self.rows_read - self.rows_written
+    }
+}
+
+/// Remove duplicate rows from a CSV file one row at a time
+///
+/// The first row is treated as the header and always written. When
+/// `key_columns` is `None` whole rows are compared (and the header takes part
+/// in the comparison, matching `DataOperations::deduplicate`); otherwise only
+/// the listed columns form the key.
+pub fn stream_dedupe(
+    input: &str,
+    output: &str,
+    key_columns: Option<&[usize]>,
+    mode: DedupeMode,
+) -> Result<DedupeStats> {
+    let reader = StreamingCsvReader::open(input)?;
+    let mut writer = StreamingCsvWriter::create(output)?;
+
+    let mut seen = match mode {
+        DedupeMode::Exact => SeenRows::Exact(HashSet::new()),
+        DedupeMode::Approximate {
+            expected_rows,
+            false_positive_rate,
+        } => SeenRows::Approximate(BloomFilter::new(expected_rows, false_positive_rate)),
+    };
+
+    let mut stats = DedupeStats::default();
+
+    for (idx, row) in reader.enumerate() {
+        let row = row?;
+        stats.rows_read += 1;
+
+        let keep = match key_columns {
+            None => seen.insert(row.join("\0")),
+            Some(_) if idx == 0 => true,
+            Some(cols) => {
+                let key: Vec<&str> = cols
+                    .iter()
+                    .filter_map(|&c| row.get(c).map(|s| s.as_str()))
+                    .collect();
+                seen.insert(key.join("\0"))
+            }
+        };
+
+        if keep {
+            writer.write_row(&row)?;
+            stats.rows_written += 1;
+        }
+    }
+
+    writer.finish()?;
+    Ok(stats)
+}
+
+/// Progress of a resumable streaming conversion, persisted between runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversionCheckpoint {
+    pub batch_size: usize,
+    pub batches_completed: usize,
+    pub rows_written: usize,
+    /// Length of the output after the last completed batch; missing in
+    /// checkpoints written before it was recorded
+    #[serde(default)]
+    pub output_bytes: Option<u64>,
+}
+
+impl ConversionCheckpoint {
+    fn load(path: &str) -> Result<Option<Self>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        let checkpoint = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid checkpoint file {}: {}", path, e))?;
+        Ok(Some(checkpoint))
+    }
+
+    fn save(&self, path: &str) -> Result<()> {
+        // Write then rename so an interruption never leaves a torn checkpoint
+        let tmp = format!("{path}.tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Summary of a streaming conversion run
+#[derive(Debug, Clone, Default)]
+pub struct StreamingConvertStats {
+    pub batches_skipped: usize,
+    pub batches_written: usize,
+    pub rows_written: usize,
+}
+
+/// Output side of `convert_streaming`; only append-friendly formats
+enum BatchSink {
+    Csv(Box<StreamingCsvWriter>),
+    Ndjson {
+        writer: BufWriter<File>,
+        header: Vec<String>,
+    },
+}
+
+impl BatchSink {
+    fn open(path: &str, header: &[String], resume: bool) -> Result<Self> {
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        match ext.as_str() {
+            "csv" => {
+                if resume {
+                    Ok(BatchSink::Csv(Box::new(StreamingCsvWriter::append(path)?)))
+                } else {
+                    let mut writer = StreamingCsvWriter::create(path)?;
+                    writer.write_row(header)?;
+                    Ok(BatchSink::Csv(Box::new(writer)))
+                }
+            }
+            "ndjson" | "jsonl" => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(resume)
+                    .truncate(!resume)
+                    .open(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path, e))?;
+                Ok(BatchSink::Ndjson {
+                    writer: BufWriter::new(file),
+                    header: header.to_vec(),
+                })
+            }
+            _ => anyhow::bail!(
+                "Streaming conversion supports only appendable outputs (csv, ndjson, jsonl), got: {}",
+                path
+            ),
+        }
+    }
+
+    fn write_row(&mut self, row: &[String]) -> Result<()> {
+        match self {
+            BatchSink::Csv(writer) => writer.write_row(row),
+            BatchSink::Ndjson { writer, header } => {
+                serde_json::to_writer(&mut *writer, &row_object(header, row))?;
+                writer.write_all(b"\n")?;
+                Ok(())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            BatchSink::Csv(writer) => writer.flush(),
+            BatchSink::Ndjson { writer, .. } => {
+                writer.flush()?;
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            BatchSink::Csv(writer) => writer.finish().map(|_| ()),
+            BatchSink::Ndjson { mut writer, .. } => {
+                writer.flush()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Convert a Parquet file to CSV or NDJSON one chunk at a time
+///
+/// Chunks come from `ParquetHandler::read_chunked`, so memory use is bounded
+/// by `batch_size` rather than the file size. `progress` is called after
+/// every chunk. The output starts with the Parquet column names.
+pub fn convert_parquet_streaming(
+    input: &str,
+    output: &str,
+    batch_size: usize,
+    progress: &mut dyn ProgressCallback,
+) -> Result<StreamingConvertStats> {
+    let handler = ParquetHandler::new();
+    let header: Vec<String> = handler
+        .get_schema(input)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let mut sink = BatchSink::open(output, &header, false)?;
+
+    let mut stats = StreamingConvertStats::default();
+    handler.read_chunked(input, batch_size.max(1), |chunk| {
+        for row in chunk {
+            sink.write_row(row)?;
+        }
+        sink.flush()?;
+
+        stats.batches_written += 1;
+        stats.rows_written += chunk.len();
+        progress.on_progress(stats.rows_written, None, "Converting");
+        Ok(())
+    })?;
+
+    sink.finish()?;
+    Ok(stats)
+}
+
+/// Convert a CSV file to Parquet in batches of `batch_size` rows
+///
+/// The input is read twice: a first pass infers every column's type over
+/// the whole file, then each batch is written as its own row group, so
+/// memory use is bounded by `batch_size` rather than the file size. Rows go
+/// to `<output>.partial`, which replaces `output` only once the write has
+/// succeeded. `progress` is called after every batch.
+pub fn convert_csv_to_parquet_streaming(
+    input: &str,
+    output: &str,
+    batch_size: usize,
+    options: &ParquetWriteOptions,
+    progress: &mut dyn ProgressCallback,
+) -> Result<StreamingConvertStats> {
+    let batch_size = batch_size.max(1);
+
+    let mut reader = csv_batches(input, batch_size)?;
+    let header = reader.header.clone();
+    let mut types: Vec<Option<ColumnType>> = vec![None; header.len()];
+    while let Some(batch) = reader.next_batch()? {
+        for (col, column_type) in types.iter_mut().enumerate() {
+            // Batches with no value in a column say nothing about its type
+            if batch
+                .iter()
+                .any(|row| row.get(col).is_some_and(|v| !v.trim().is_empty()))
+            {
+                let batch_type = infer_column_type(&batch, col);
+                *column_type = Some(column_type.map_or(batch_type, |t| t.widen(batch_type)));
+            }
+        }
+    }
+    let types: Vec<ColumnType> = types
+        .into_iter()
+        .map(|t| t.unwrap_or(ColumnType::Utf8))
+        .collect();
+
+    let partial = format!("{output}.partial");
+    let result = write_parquet_batches(input, &partial, batch_size, &types, options, progress);
+    match result {
+        Ok(stats) => {
+            fs::rename(&partial, output)
+                .with_context(|| format!("Failed to move {partial} to {output}"))?;
+            Ok(stats)
+        }
+        Err(e) => {
+            fs::remove_file(&partial).ok();
+            Err(e)
+        }
+    }
+}
+
+/// Second pass of `convert_csv_to_parquet_streaming`
+fn write_parquet_batches(
+    input: &str,
+    output: &str,
+    batch_size: usize,
+    types: &[ColumnType],
+    options: &ParquetWriteOptions,
+    progress: &mut dyn ProgressCallback,
+) -> Result<StreamingConvertStats> {
+    let mut reader = csv_batches(input, batch_size)?;
+    let handler = ParquetHandler::new();
+    let schema = handler.schema_for_types(&reader.header, types);
+    let mut writer = handler.open_writer_with_options(output, schema, options)?;
+
+    let mut stats = StreamingConvertStats::default();
+    while let Some(batch) = reader.next_batch()? {
+        writer.write_rows(&batch)?;
+        stats.batches_written += 1;
+        stats.rows_written += batch.len();
+        progress.on_progress(stats.rows_written, None, "Converting");
+    }
+
+    writer.close()?;
+    Ok(stats)
+}
+
+/// A CSV file's header row and the rows after it, `batch_size` at a time
+struct CsvBatches {
+    header: Vec<String>,
+    rows: StreamingCsvReader,
+    batch_size: usize,
+}
+
+fn csv_batches(input: &str, batch_size: usize) -> Result<CsvBatches> {
+    let mut rows = StreamingCsvReader::open(input)?;
+    let header = match rows.next() {
+        Some(row) => row?,
+        None => anyhow::bail!("Input file is empty: {}", input),
+    };
+    Ok(CsvBatches {
+        header,
+        rows,
+        batch_size,
+    })
+}
+
+impl CsvBatches {
+    /// The next batch of rows, or `None` at the end of the file
+    fn next_batch(&mut self) -> Result<Option<Vec<Vec<String>>>> {
+        let batch = self
+            .rows
+            .by_ref()
+            .take(self.batch_size)
+            .collect::<Result<Vec<_>>>()?;
+        Ok((!batch.is_empty()).then_some(batch))
+    }
+}
+
+/// Convert a CSV file to CSV or NDJSON in batches of `batch_size` rows
+///
+/// `progress` is called after every batch. When `checkpoint` is given, the
+/// number of completed batches and the output length are recorded there
+/// after each batch is flushed. A later run with the same checkpoint cuts
+/// the output back to that length, skips those batches and appends the rest. The checkpoint is kept after completion, so re-running is a
+/// no-op; delete it to convert from scratch.
+///
+/// Formats that cannot be appended to (xlsx, parquet, avro) are rejected;
+/// use `convert_csv_to_parquet_streaming` for Parquet and
+/// `Converter::convert` for the others.
+pub fn convert_streaming(
+    input: &str,
+    output: &str,
+    batch_size: usize,
+    progress: &mut dyn ProgressCallback,
+    checkpoint: Option<&str>,
+) -> Result<StreamingConvertStats> {
+    let batch_size = batch_size.max(1);
+
+    let state = match checkpoint {
+        Some(path) => ConversionCheckpoint::load(path)?,
+        None => None,
+    };
+    if let Some(state) = state.as_ref().filter(|s| s.batch_size != batch_size) {
+        anyhow::bail!(
+            "Checkpoint was written with batch size {}, not {}",
+            state.batch_size,
+            batch_size
+        );
+    }
+    let mut state = state.unwrap_or(ConversionCheckpoint {
+        batch_size,
+        ..Default::default()
+    });
+    let resume = state.batches_completed > 0;
+    // Drop whatever part of an unfinished batch reached the output
+    if let (true, Some(len)) = (resume, state.output_bytes) {
+        OpenOptions::new()
+            .write(true)
+            .open(output)
+            .and_then(|file| file.set_len(len))
+            .with_context(|| format!("Failed to truncate {output} to its checkpoint"))?;
+    }
+
+    let mut reader = StreamingCsvReader::open(input)?;
+    let header = match reader.next() {
+        Some(row) => row?,
+        None => anyhow::bail!("Input file is empty: {}", input),
+    };
+    let mut sink = BatchSink::open(output, &header, resume)?;
+
+    let mut stats = StreamingConvertStats::default();
+    let mut batch_idx = 0;
+    let mut rows_seen = 0;
+    let mut batch = Vec::with_capacity(batch_size);
+
+    loop {
+        batch.clear();
+        for row in reader.by_ref().take(batch_size) {
+            batch.push(row?);
+        }
+        if batch.is_empty() {
+            break;
+        }
+
+        if batch_idx < state.batches_completed {
+            stats.batches_skipped += 1;
+        } else {
+            for row in &batch {
+                sink.write_row(row)?;
+            }
+            sink.flush()?;
+
+            stats.batches_written += 1;
+            stats.rows_written += batch.len();
+            state.batches_completed = batch_idx + 1;
+            state.rows_written += batch.len();
+            if let Some(path) = checkpoint {
+                state.output_bytes = Some(fs::metadata(output)?.len());
+                state.save(path)?;
+            }
+        }
+
+        batch_idx += 1;
+        rows_seen += batch.len();
+        progress.on_progress(rows_seen, None, "Converting");
+    }
+
+    sink.finish()?;
+    Ok(stats)
+}
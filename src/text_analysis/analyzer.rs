@@ -1,14 +1,16 @@
 //! Main text analyzer implementation
 
 use crate::common::collection;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use super::stemmer::stem;
 use super::types::*;
 
 /// Text analyzer
 pub struct TextAnalyzer {
     stop_words: HashSet<String>,
     sentiment_words: SentimentWords,
+    stem: bool,
 }
 
 impl TextAnalyzer {
@@ -17,9 +19,25 @@ impl TextAnalyzer {
         Self {
             stop_words: Self::default_stop_words(),
             sentiment_words: Self::default_sentiment_words(),
+            stem: false,
         }
     }
 
+    /// Replace the stop words skipped by keyword extraction
+    ///
+    /// Words are compared in lowercase; start from
+    /// `TextAnalyzer::default_stop_words()` to extend the English list.
+    pub fn with_stopwords(mut self, stop_words: HashSet<String>) -> Self {
+        self.stop_words = stop_words.into_iter().map(|w| w.to_lowercase()).collect();
+        self
+    }
+
+    /// Reduce keywords to their stems so that e.g. "running" and "runs" count as one
+    pub fn with_stemming(mut self, stem: bool) -> Self {
+        self.stem = stem;
+        self
+    }
+
     /// Analyze text statistics
     pub fn analyze_stats(&self, text: &str) -> TextStats {
         let words = self.extract_words(text);
@@ -118,57 +136,234 @@ impl TextAnalyzer {
         let words = self.extract_words(text);
 
         // Filter out stop words and count frequencies
-        let mut word_frequencies: std::collections::HashMap<String, usize> =
-            std::collections::HashMap::new();
+        let mut word_frequencies: HashMap<String, usize> = HashMap::new();
+        for term in self.keyword_terms(text) {
+            *word_frequencies.entry(term).or_insert(0) += 1;
+        }
 
-        for word in &words {
-            let lower_word = word.to_lowercase();
-            if !self.stop_words.contains(&lower_word) && word.len() > 2 {
-                *word_frequencies.entry(lower_word.clone()).or_insert(0) += 1;
+        Self::rank_keywords(word_frequencies, words.len(), max_keywords)
+    }
+
+    /// Extract keywords that may be phrases of up to `max_n` words
+    ///
+    /// Phrases from `extract_ngrams` compete with single words on the same
+    /// score, which favours longer terms, so "machine learning" ranks above
+    /// "machine" and "learning" when it accounts for all their uses.
+    pub fn extract_phrase_keywords(
+        &self,
+        text: &str,
+        max_n: usize,
+        max_keywords: usize,
+    ) -> KeywordResult {
+        let words = self.extract_words(text);
+
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        for term in self.keyword_terms(text) {
+            *frequencies.entry(term).or_insert(0) += 1;
+        }
+        for n in 2..=max_n {
+            frequencies.extend(self.extract_ngrams(text, n, usize::MAX));
+        }
+
+        Self::rank_keywords(frequencies, words.len(), max_keywords)
+    }
+
+    /// Most frequent sequences of `n` words, with their counts
+    ///
+    /// Sequences never cross a sentence end or a line break, and those that
+    /// start or end with a stop word are skipped, so "the model" is dropped
+    /// but "state of the art" is kept.
+    pub fn extract_ngrams(&self, text: &str, n: usize, top_n: usize) -> Vec<(String, usize)> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for sentence in self.extract_sentences(text) {
+            for line in self.extract_paragraphs(&sentence) {
+                let words: Vec<String> = self
+                    .extract_words(&line)
+                    .into_iter()
+                    .map(|word| word.to_lowercase())
+                    .collect();
+
+                for gram in words.windows(n) {
+                    if self.stop_words.contains(&gram[0]) || self.stop_words.contains(&gram[n - 1])
+                    {
+                        continue;
+                    }
+                    let phrase: Vec<String> = gram.iter().map(|w| self.term(w.clone())).collect();
+                    *counts.entry(phrase.join(" ")).or_insert(0) += 1;
+                }
             }
         }
 
-        // Calculate TF-IDF-like scores
-        let total_words = words.len();
-        let mut keywords: Vec<Keyword> = word_frequencies
-            .into_iter()
-            .map(|(word, frequency)| {
-                let tf = frequency as f64 / total_words as f64;
-                let score = tf * (1.0 + word.len() as f64 * 0.1);
+        let mut ngrams: Vec<(String, usize)> = counts.into_iter().collect();
+        ngrams.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ngrams.truncate(top_n);
+        ngrams
+    }
 
-                let importance = if frequency >= total_words / 10 {
-                    Importance::High
-                } else if frequency >= total_words / 20 {
-                    Importance::Medium
-                } else {
-                    Importance::Low
-                };
+    /// Extract the top TF-IDF keywords of each document
+    ///
+    /// Each entry of `documents` (e.g. each cell of a column) is one document.
+    /// A term's score is its frequency within the document times the smoothed
+    /// inverse document frequency `ln((1 + n) / (1 + df)) + 1`, so words that
+    /// appear in every document rank below words distinctive to a few. Terms
+    /// found in only one document are `High` importance, those in at most
+    /// half of the documents `Medium`, and the rest `Low`.
+    pub fn extract_keywords_tfidf(&self, documents: &[String], top_n: usize) -> Vec<KeywordResult> {
+        let terms: Vec<Vec<String>> = documents.iter().map(|d| self.keyword_terms(d)).collect();
+
+        let mut document_frequencies: HashMap<&str, usize> = HashMap::new();
+        for doc_terms in &terms {
+            for term in doc_terms.iter().collect::<HashSet<_>>() {
+                *document_frequencies.entry(term).or_insert(0) += 1;
+            }
+        }
 
-                Keyword {
-                    word,
-                    score,
-                    frequency,
-                    importance,
+        let n = documents.len() as f64;
+        terms
+            .iter()
+            .map(|doc_terms| {
+                let mut frequencies: HashMap<&str, usize> = HashMap::new();
+                for term in doc_terms {
+                    *frequencies.entry(term).or_insert(0) += 1;
+                }
+
+                let mut keywords: Vec<Keyword> = frequencies
+                    .into_iter()
+                    .map(|(term, frequency)| {
+                        let df = document_frequencies[term];
+                        let tf = frequency as f64 / doc_terms.len() as f64;
+                        let idf = ((1.0 + n) / (1.0 + df as f64)).ln() + 1.0;
+
+                        let importance = if df == 1 {
+                            Importance::High
+                        } else if df as f64 <= n / 2.0 {
+                            Importance::Medium
+                        } else {
+                            Importance::Low
+                        };
+
+                        Keyword {
+                            word: term.to_string(),
+                            score: tf * idf,
+                            frequency,
+                            importance,
+                        }
+                    })
+                    .collect();
+
+                keywords.sort_by(|a, b| {
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then_with(|| a.word.cmp(&b.word))
+                });
+                keywords.truncate(top_n);
+                let total_keywords = keywords.len();
+
+                KeywordResult {
+                    keywords,
+                    total_keywords,
                 }
             })
-            .collect();
+            .collect()
+    }
 
-        // Sort by score and frequency
-        keywords.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-                .then_with(|| b.frequency.cmp(&a.frequency))
-        });
+    /// Analyze each cell of `column` on its own and append the results as new columns
+    ///
+    /// The header row gains the result column names and every data row its
+    /// values: `sentiment, confidence` for sentiment, `language,
+    /// language_confidence` for language, `word_count, sentence_count,
+    /// readability_score` for stats, and a `keywords` column of the top
+    /// keywords joined with `; ` for keywords and tfidf. Short rows are padded
+    /// to the header width first (longer rows keep their extra cells), and
+    /// missing cells are analyzed as empty text.
+    pub fn analyze_column(
+        &self,
+        data: &[Vec<String>],
+        column: usize,
+        op: TextOperation,
+    ) -> Vec<Vec<String>> {
+        let Some((header, rows)) = data.split_first() else {
+            return Vec::new();
+        };
+        let cells: Vec<String> = rows
+            .iter()
+            .map(|row| row.get(column).cloned().unwrap_or_default())
+            .collect();
 
-        // Limit to max_keywords
-        keywords.truncate(max_keywords);
-        let total_keywords = keywords.len();
+        let (names, values): (&[&str], Vec<Vec<String>>) = match op {
+            TextOperation::Sentiment => (
+                &["sentiment", "confidence"],
+                cells
+                    .iter()
+                    .map(|cell| {
+                        let result = self.analyze_sentiment(cell);
+                        vec![
+                            format!("{:?}", result.sentiment),
+                            format!("{:.4}", result.confidence),
+                        ]
+                    })
+                    .collect(),
+            ),
+            TextOperation::Language => (
+                &["language", "language_confidence"],
+                cells
+                    .iter()
+                    .map(|cell| {
+                        let result = self.detect_language(cell);
+                        vec![result.language, format!("{:.4}", result.confidence)]
+                    })
+                    .collect(),
+            ),
+            TextOperation::Stats => (
+                &["word_count", "sentence_count", "readability_score"],
+                cells
+                    .iter()
+                    .map(|cell| {
+                        let stats = self.analyze_stats(cell);
+                        vec![
+                            stats.word_count.to_string(),
+                            stats.sentence_count.to_string(),
+                            format!("{:.2}", stats.readability_score),
+                        ]
+                    })
+                    .collect(),
+            ),
+            TextOperation::Keywords => (
+                &["keywords"],
+                cells
+                    .iter()
+                    .map(|cell| vec![join_keywords(&self.extract_keywords(cell, ROW_KEYWORDS))])
+                    .collect(),
+            ),
+            TextOperation::Tfidf => (
+                &["keywords"],
+                self.extract_keywords_tfidf(&cells, ROW_KEYWORDS)
+                    .iter()
+                    .map(|result| vec![join_keywords(result)])
+                    .collect(),
+            ),
+        };
 
-        KeywordResult {
-            keywords,
-            total_keywords,
+        let width = header.len();
+        let mut output = Vec::with_capacity(data.len());
+        let mut header = header.clone();
+        header.extend(names.iter().map(|name| name.to_string()));
+        output.push(header);
+        for (row, row_values) in rows.iter().zip(values) {
+            let mut row = row.clone();
+            // Pad short rows so the new columns line up; never drop cells
+            if row.len() < width {
+                row.resize(width, String::new());
+            }
+            row.extend(row_values);
+            output.push(row);
         }
+        output
     }
 
     /// Detect language of text (simplified)
@@ -222,6 +417,79 @@ impl TextAnalyzer {
             supported_languages,
         }
     }
+
+    /// Score term frequencies by frequency and term length, best first
+    fn rank_keywords(
+        frequencies: HashMap<String, usize>,
+        total_words: usize,
+        max_keywords: usize,
+    ) -> KeywordResult {
+        // Calculate TF-IDF-like scores
+        let mut keywords: Vec<Keyword> = frequencies
+            .into_iter()
+            .map(|(word, frequency)| {
+                let tf = frequency as f64 / total_words as f64;
+                let score = tf * (1.0 + word.len() as f64 * 0.1);
+
+                let importance = if frequency >= total_words / 10 {
+                    Importance::High
+                } else if frequency >= total_words / 20 {
+                    Importance::Medium
+                } else {
+                    Importance::Low
+                };
+
+                Keyword {
+                    word,
+                    score,
+                    frequency,
+                    importance,
+                }
+            })
+            .collect();
+
+        // Sort by score and frequency
+        keywords.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.frequency.cmp(&a.frequency))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+
+        // Limit to max_keywords
+        keywords.truncate(max_keywords);
+        let total_keywords = keywords.len();
+
+        KeywordResult {
+            keywords,
+            total_keywords,
+        }
+    }
+
+    /// Lowercased words of `text` worth scoring as keywords, skipping stop
+    /// words and words of two letters or fewer, stemmed if enabled
+    fn keyword_terms(&self, text: &str) -> Vec<String> {
+        self.extract_words(text)
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .filter(|word| word.len() > 2 && !self.stop_words.contains(word))
+            .map(|word| self.term(word))
+            .collect()
+    }
+
+    /// A lowercase word as counted: its stem if stemming is enabled
+    fn term(&self, word: String) -> String {
+        if self.stem { stem(&word) } else { word }
+    }
+}
+
+/// Keywords kept per row by `analyze_column`
+const ROW_KEYWORDS: usize = 5;
+
+fn join_keywords(result: &KeywordResult) -> String {
+    let words: Vec<&str> = result.keywords.iter().map(|k| k.word.as_str()).collect();
+    words.join("; ")
 }
 
 impl Default for TextAnalyzer {
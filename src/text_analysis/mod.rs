@@ -5,11 +5,12 @@
 
 pub mod analyzer;
 pub mod helpers;
+pub mod stemmer;
 pub mod types;
 
 // Re-export main types for convenience
 pub use analyzer::TextAnalyzer;
 pub use types::{
     Importance, Keyword, KeywordResult, LanguageResult, Sentiment, SentimentResult, SentimentWords,
-    TextStats,
+    TextOperation, TextStats,
 };
@@ -0,0 +1,108 @@
+//! Porter-style suffix stripping
+//!
+//! Implements steps 1a–1c of the Porter stemmer: plurals (`caresses` →
+//! `caress`, `ponies` → `poni`, `runs` → `run`), `-ed`/`-ing` endings with
+//! their clean-ups (`running` → `run`, `hoping` → `hope`) and a final `y` →
+//! `i`. That is enough to fold common inflections together for keyword
+//! counting without the over-stemming of the later steps.
+
+/// Stem a lowercase word; words that are not ASCII letters are returned unchanged
+pub fn stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+
+    let mut word = word.as_bytes().to_vec();
+    step_1a(&mut word);
+    step_1b(&mut word);
+    step_1c(&mut word);
+    String::from_utf8(word).expect("stemming keeps ASCII")
+}
+
+fn step_1a(word: &mut Vec<u8>) {
+    if word.ends_with(b"sses") || word.ends_with(b"ies") {
+        word.truncate(word.len() - 2);
+    } else if word.ends_with(b"s") && !word.ends_with(b"ss") {
+        word.pop();
+    }
+}
+
+fn step_1b(word: &mut Vec<u8>) {
+    if word.ends_with(b"eed") {
+        if measure(&word[..word.len() - 3]) > 0 {
+            word.pop();
+        }
+        return;
+    }
+
+    let suffix_len = if word.ends_with(b"ed") {
+        2
+    } else if word.ends_with(b"ing") {
+        3
+    } else {
+        return;
+    };
+    let stem_len = word.len() - suffix_len;
+    if !has_vowel(&word[..stem_len]) {
+        return;
+    }
+    word.truncate(stem_len);
+
+    if word.ends_with(b"at") || word.ends_with(b"bl") || word.ends_with(b"iz") {
+        word.push(b'e');
+    } else if ends_with_double_consonant(word) && !matches!(word.last(), Some(b'l' | b's' | b'z')) {
+        word.pop();
+    } else if measure(word) == 1 && ends_cvc(word) {
+        word.push(b'e');
+    }
+}
+
+fn step_1c(word: &mut [u8]) {
+    let len = word.len();
+    if word.ends_with(b"y") && has_vowel(&word[..len - 1]) {
+        word[len - 1] = b'i';
+    }
+}
+
+/// Whether the letter at `i` is a consonant; `y` is a consonant only at the
+/// start or after a vowel
+fn is_consonant(word: &[u8], i: usize) -> bool {
+    match word[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => false,
+        b'y' => i == 0 || !is_consonant(word, i - 1),
+        _ => true,
+    }
+}
+
+fn has_vowel(word: &[u8]) -> bool {
+    (0..word.len()).any(|i| !is_consonant(word, i))
+}
+
+/// Number of vowel-consonant sequences, Porter's `m`
+fn measure(word: &[u8]) -> usize {
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for i in 0..word.len() {
+        let vowel = !is_consonant(word, i);
+        if previous_vowel && !vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    count
+}
+
+fn ends_with_double_consonant(word: &[u8]) -> bool {
+    let len = word.len();
+    len >= 2 && word[len - 1] == word[len - 2] && is_consonant(word, len - 1)
+}
+
+/// Consonant-vowel-consonant ending where the last consonant is not `w`, `x` or `y`
+fn ends_cvc(word: &[u8]) -> bool {
+    let len = word.len();
+    len >= 3
+        && is_consonant(word, len - 3)
+        && !is_consonant(word, len - 2)
+        && is_consonant(word, len - 1)
+        && !matches!(word[len - 1], b'w' | b'x' | b'y')
+}
@@ -66,6 +66,35 @@ pub struct LanguageResult {
     pub supported_languages: Vec<String>,
 }
 
+/// Analysis run by the text-analysis command and `TextAnalyzer::analyze_column`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextOperation {
+    Stats,
+    Sentiment,
+    Keywords,
+    /// Keywords scored by TF-IDF, treating each cell as a document
+    Tfidf,
+    Language,
+}
+
+impl std::str::FromStr for TextOperation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "stats" => Ok(TextOperation::Stats),
+            "sentiment" => Ok(TextOperation::Sentiment),
+            "keywords" => Ok(TextOperation::Keywords),
+            "tfidf" => Ok(TextOperation::Tfidf),
+            "language" => Ok(TextOperation::Language),
+            _ => anyhow::bail!(
+                "Unknown text analysis: {}. Use: stats, sentiment, keywords, tfidf, language",
+                s
+            ),
+        }
+    }
+}
+
 /// Sentiment word lists
 #[derive(Debug, Clone)]
 pub struct SentimentWords {
@@ -0,0 +1,201 @@
+//! Character encoding adapters for text formats
+//!
+//! Wraps byte streams so CSV parsing always sees UTF-8, whatever encoding
+//! the file on disk uses.
+
+use anyhow::Result;
+use encoding_rs::{CoderResult, Decoder, Encoder, Encoding, UTF_8};
+use std::io::{self, Read, Write};
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Look up an encoding by WHATWG label, e.g. `utf-8`, `latin1`, `utf-16le`
+pub fn encoding_for_label(label: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| anyhow::anyhow!("Unknown encoding '{label}'"))
+}
+
+/// Reader that transcodes its input to UTF-8
+///
+/// A leading UTF-8 or UTF-16 byte order mark overrides the configured
+/// encoding and is removed. Malformed sequences become U+FFFD.
+pub struct DecodingReader<R> {
+    inner: R,
+    decoder: Decoder,
+    input: Vec<u8>,
+    input_start: usize,
+    input_end: usize,
+    output: Vec<u8>,
+    output_start: usize,
+    output_end: usize,
+    eof: bool,
+    finished: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    pub fn new(inner: R, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            input: vec![0; BUFFER_SIZE],
+            input_start: 0,
+            input_end: 0,
+            output: vec![0; BUFFER_SIZE],
+            output_start: 0,
+            output_end: 0,
+            eof: false,
+            finished: false,
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.output_start < self.output_end {
+                let n = buf.len().min(self.output_end - self.output_start);
+                buf[..n].copy_from_slice(&self.output[self.output_start..self.output_start + n]);
+                self.output_start += n;
+                return Ok(n);
+            }
+            if self.finished {
+                return Ok(0);
+            }
+
+            if self.input_start == self.input_end && !self.eof {
+                self.input_start = 0;
+                self.input_end = self.inner.read(&mut self.input)?;
+                self.eof = self.input_end == 0;
+            }
+
+            let (result, read, written, _) = self.decoder.decode_to_utf8(
+                &self.input[self.input_start..self.input_end],
+                &mut self.output,
+                self.eof,
+            );
+            self.input_start += read;
+            self.output_start = 0;
+            self.output_end = written;
+
+            if self.eof && result == CoderResult::InputEmpty {
+                self.finished = true;
+            }
+        }
+    }
+}
+
+/// Writer that transcodes UTF-8 text to another encoding
+///
+/// Characters the target encoding cannot represent are written as numeric
+/// character references, as `encoding_rs` does. Call [`finish`](Self::finish)
+/// when done; dropping an unfinished writer finishes it but loses any error.
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoder: Encoder,
+    pending: Vec<u8>,
+    output: Vec<u8>,
+    finished: bool,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    /// Fails for encodings `encoding_rs` can only decode, such as UTF-16
+    pub fn new(inner: W, encoding: &'static Encoding) -> Result<Self> {
+        if encoding.output_encoding() != encoding {
+            anyhow::bail!("Writing {} is not supported", encoding.name());
+        }
+
+        Ok(Self {
+            inner,
+            encoder: encoding.new_encoder(),
+            pending: Vec::new(),
+            output: vec![0; BUFFER_SIZE],
+            finished: false,
+        })
+    }
+
+    /// Write the closing sequence of a stateful encoding and flush
+    ///
+    /// Fails if the text ended partway through a UTF-8 sequence.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        if !self.pending.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete UTF-8 sequence at end of output",
+            ));
+        }
+        self.encode("", true)?;
+        self.inner.flush()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    fn encode(&mut self, text: &str, last: bool) -> io::Result<()> {
+        let mut text = text;
+        loop {
+            let (result, read, written, _) =
+                self.encoder.encode_from_utf8(text, &mut self.output, last);
+            self.inner.write_all(&self.output[..written])?;
+            text = &text[read..];
+            if result == CoderResult::InputEmpty {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Keep an incomplete trailing UTF-8 sequence for the next call
+        self.pending.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+
+        let mut pending = std::mem::take(&mut self.pending);
+        let text = std::str::from_utf8(&pending[..valid]).expect("prefix validated above");
+        self.encode(text, false)?;
+        pending.drain(..valid);
+        self.pending = pending;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for EncodingWriter<W> {
+    fn drop(&mut self) {
+        // Let stateful encodings emit their closing sequence
+        let _ = self.finish();
+    }
+}
+
+/// Wrap `reader` so it yields UTF-8 decoded from `encoding`
+pub fn decode_reader<'a, R: Read + 'a>(
+    reader: R,
+    encoding: &'static Encoding,
+) -> Box<dyn Read + 'a> {
+    Box::new(DecodingReader::new(reader, encoding))
+}
+
+/// Wrap `writer` so UTF-8 written to it is stored as `encoding`
+pub fn encode_writer<'a, W: Write + 'a>(
+    writer: W,
+    encoding: &'static Encoding,
+) -> Result<Box<dyn Write + 'a>> {
+    if encoding == UTF_8 {
+        Ok(Box::new(writer))
+    } else {
+        Ok(Box::new(EncodingWriter::new(writer, encoding)?))
+    }
+}
@@ -5,9 +5,9 @@
 
 use crate::common::string;
 use anyhow::Result;
-use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Timelike};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Time series resampling intervals
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +35,19 @@ pub enum TimeSeriesAgg {
     Count,
 }
 
+/// How [`TimeSeriesProcessor::resample`] fills buckets that have no data points
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GapFill {
+    /// Leave empty buckets out of the result
+    #[default]
+    None,
+    Zero,
+    /// Repeat the previous bucket's value
+    Forward,
+    /// Interpolate in time between the surrounding buckets
+    Linear,
+}
+
 /// Rolling window configuration
 #[derive(Debug, Clone)]
 pub struct RollingWindow {
@@ -71,9 +84,24 @@ pub enum TrendDirection {
     Unknown,
 }
 
+/// Additive decomposition of a series into `observed = trend + seasonal + remainder`
+///
+/// All fields run parallel to `timestamps`. The centred moving average that
+/// gives the trend is undefined for the first and last half period, so the
+/// trend and remainder are `None` there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decomposition {
+    pub timestamps: Vec<NaiveDateTime>,
+    pub observed: Vec<f64>,
+    pub trend: Vec<Option<f64>>,
+    pub seasonal: Vec<f64>,
+    pub remainder: Vec<Option<f64>>,
+}
+
 /// Time series processor
 pub struct TimeSeriesProcessor {
     date_format: String,
+    fill_gaps: GapFill,
 }
 
 impl TimeSeriesProcessor {
@@ -81,9 +109,16 @@ impl TimeSeriesProcessor {
     pub fn new(date_format: &str) -> Self {
         Self {
             date_format: date_format.to_string(),
+            fill_gaps: GapFill::None,
         }
     }
 
+    /// Fill empty buckets when resampling instead of leaving them out
+    pub fn with_fill_gaps(mut self, fill_gaps: GapFill) -> Self {
+        self.fill_gaps = fill_gaps;
+        self
+    }
+
     /// Parse date string to NaiveDateTime
     pub fn parse_date(&self, date_str: &str) -> Result<NaiveDateTime> {
         if let Ok(date) = NaiveDate::parse_from_str(date_str, &self.date_format) {
@@ -146,6 +181,9 @@ impl TimeSeriesProcessor {
     }
 
     /// Resample time series data
+    ///
+    /// With a [`GapFill`] other than `None`, every bucket between the first
+    /// and last one with data is emitted, filled as configured.
     pub fn resample(
         &self,
         data: &[TimeSeriesPoint],
@@ -157,18 +195,81 @@ impl TimeSeriesProcessor {
         }
 
         let grouped = self.group_by_interval(data, interval)?;
-        let mut resampled = Vec::new();
-
+        let mut buckets = BTreeMap::new();
         for (timestamp, values) in grouped {
-            let aggregated_value = self.aggregate_values(&values, agg)?;
-            resampled.push(TimeSeriesPoint {
-                timestamp,
-                value: aggregated_value,
-            });
+            buckets.insert(timestamp, self.aggregate_values(&values, agg)?);
         }
 
-        resampled.sort_by_key(|p| p.timestamp);
-        Ok(resampled)
+        if self.fill_gaps == GapFill::None {
+            return Ok(buckets
+                .into_iter()
+                .map(|(timestamp, value)| TimeSeriesPoint { timestamp, value })
+                .collect());
+        }
+        self.fill_buckets(&buckets, interval)
+    }
+
+    /// Emit every bucket from the first to the last key of `buckets`
+    fn fill_buckets(
+        &self,
+        buckets: &BTreeMap<NaiveDateTime, f64>,
+        interval: &ResampleInterval,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let (Some((&first, _)), Some((&last, _))) =
+            (buckets.first_key_value(), buckets.last_key_value())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut filled = Vec::new();
+        let mut timestamp = first;
+        while timestamp <= last {
+            let value = match buckets.get(&timestamp) {
+                Some(&value) => value,
+                None => {
+                    // Both neighbours exist: the bucket lies strictly between first and last
+                    let (&prev_time, &prev) = buckets.range(..timestamp).next_back().unwrap();
+                    let (&next_time, &next) = buckets.range(timestamp..).next().unwrap();
+                    match self.fill_gaps {
+                        GapFill::None | GapFill::Zero => 0.0,
+                        GapFill::Forward => prev,
+                        GapFill::Linear => {
+                            let span = (next_time - prev_time).num_seconds() as f64;
+                            let offset = (timestamp - prev_time).num_seconds() as f64;
+                            prev + (next - prev) * offset / span
+                        }
+                    }
+                }
+            };
+            filled.push(TimeSeriesPoint { timestamp, value });
+            timestamp = self.next_interval_key(timestamp, interval)?;
+        }
+
+        Ok(filled)
+    }
+
+    /// The bucket key following `key`
+    fn next_interval_key(
+        &self,
+        key: NaiveDateTime,
+        interval: &ResampleInterval,
+    ) -> Result<NaiveDateTime> {
+        let next = match interval {
+            ResampleInterval::Daily => key.checked_add_signed(Duration::days(1)),
+            ResampleInterval::Weekly => key.checked_add_signed(Duration::weeks(1)),
+            ResampleInterval::Monthly => key.checked_add_months(Months::new(1)),
+            ResampleInterval::Quarterly => key.checked_add_months(Months::new(3)),
+            ResampleInterval::Yearly => key.checked_add_months(Months::new(12)),
+            ResampleInterval::Hourly => key.checked_add_signed(Duration::hours(1)),
+            ResampleInterval::Minute => key.checked_add_signed(Duration::minutes(1)),
+            ResampleInterval::Custom(duration) if duration.num_seconds() > 0 => {
+                key.checked_add_signed(*duration)
+            }
+            ResampleInterval::Custom(_) => {
+                anyhow::bail!("Custom resample interval must be at least one second")
+            }
+        };
+        next.ok_or_else(|| anyhow::anyhow!("Resample interval overflows after {}", key))
     }
 
     /// Group time series by interval
@@ -303,6 +404,149 @@ impl TimeSeriesProcessor {
         Ok(result)
     }
 
+    /// Time-based rolling aggregate
+    ///
+    /// Each output point aggregates the points in `(t - window, t]` for its
+    /// timestamp `t`, so irregularly spaced series get windows of equal
+    /// duration rather than an equal number of rows. `series` must be sorted
+    /// by timestamp, as [`TimeSeriesProcessor::csv_to_timeseries`] returns it.
+    pub fn rolling(
+        &self,
+        series: &[TimeSeriesPoint],
+        window: Duration,
+        agg: TimeSeriesAgg,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        if window <= Duration::zero() {
+            anyhow::bail!("Rolling window must be positive");
+        }
+        ensure_sorted(series)?;
+
+        let values: Vec<f64> = series.iter().map(|p| p.value).collect();
+        let mut start = 0;
+        series
+            .iter()
+            .enumerate()
+            .map(|(end, point)| {
+                // A window reaching past the earliest representable time covers everything
+                if let Some(cutoff) = point.timestamp.checked_sub_signed(window) {
+                    while series[start].timestamp <= cutoff {
+                        start += 1;
+                    }
+                }
+                Ok(TimeSeriesPoint {
+                    timestamp: point.timestamp,
+                    value: self.aggregate_values(&values[start..=end], &agg)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Expanding aggregate: each point aggregates every point up to and including it
+    ///
+    /// Aggregates other than the median keep a running total, so this is
+    /// linear in the length of the series.
+    pub fn expanding(
+        &self,
+        series: &[TimeSeriesPoint],
+        agg: TimeSeriesAgg,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        ensure_sorted(series)?;
+
+        let values: Vec<f64> = series.iter().map(|p| p.value).collect();
+        let (mut sum, mut min, mut max) = (0.0, f64::INFINITY, f64::NEG_INFINITY);
+        series
+            .iter()
+            .enumerate()
+            .map(|(end, point)| {
+                sum += point.value;
+                min = min.min(point.value);
+                max = max.max(point.value);
+                let count = (end + 1) as f64;
+
+                let value = match agg {
+                    TimeSeriesAgg::Sum => sum,
+                    TimeSeriesAgg::Mean => sum / count,
+                    TimeSeriesAgg::Min => min,
+                    TimeSeriesAgg::Max => max,
+                    TimeSeriesAgg::First => values[0],
+                    TimeSeriesAgg::Last => point.value,
+                    TimeSeriesAgg::Count => count,
+                    TimeSeriesAgg::Median => self.aggregate_values(&values[..=end], &agg)?,
+                };
+                Ok(TimeSeriesPoint {
+                    timestamp: point.timestamp,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// Classic additive seasonal decomposition with a season of `period` points
+    ///
+    /// The trend is a centred moving average over one period (a 2×`period`
+    /// average for even periods), the seasonal component is the mean
+    /// detrended value at each position in the period, shifted to sum to
+    /// zero, and the remainder is what is left.
+    pub fn decompose(&self, series: &[TimeSeriesPoint], period: usize) -> Result<Decomposition> {
+        if period < 2 {
+            anyhow::bail!("Decomposition period must be at least 2");
+        }
+        if series.len() < 2 * period {
+            anyhow::bail!(
+                "Decomposition needs at least two periods ({} points), got {}",
+                2 * period,
+                series.len()
+            );
+        }
+
+        let observed: Vec<f64> = series.iter().map(|p| p.value).collect();
+        let n = observed.len();
+        let half = period / 2;
+
+        let trend: Vec<Option<f64>> = (0..n)
+            .map(|i| {
+                if i < half || i + half >= n {
+                    return None;
+                }
+                let sum = if period.is_multiple_of(2) {
+                    observed[i + 1 - half..i + half].iter().sum::<f64>()
+                        + 0.5 * (observed[i - half] + observed[i + half])
+                } else {
+                    observed[i - half..=i + half].iter().sum::<f64>()
+                };
+                Some(sum / period as f64)
+            })
+            .collect();
+
+        let mut phase_sums = vec![0.0; period];
+        let mut phase_counts = vec![0usize; period];
+        for (i, trend) in trend.iter().enumerate() {
+            if let Some(trend) = trend {
+                phase_sums[i % period] += observed[i] - trend;
+                phase_counts[i % period] += 1;
+            }
+        }
+        let phase_means: Vec<f64> = phase_sums
+            .iter()
+            .zip(&phase_counts)
+            .map(|(&sum, &count)| sum / count as f64)
+            .collect();
+        let offset = phase_means.iter().sum::<f64>() / period as f64;
+
+        let seasonal: Vec<f64> = (0..n).map(|i| phase_means[i % period] - offset).collect();
+        let remainder = (0..n)
+            .map(|i| trend[i].map(|t| observed[i] - t - seasonal[i]))
+            .collect();
+
+        Ok(Decomposition {
+            timestamps: series.iter().map(|p| p.timestamp).collect(),
+            observed,
+            trend,
+            seasonal,
+            remainder,
+        })
+    }
+
     /// Detect trend in time series
     pub fn detect_trend(&self, data: &[TimeSeriesPoint]) -> TrendDirection {
         if data.len() < 2 {
@@ -440,6 +684,46 @@ impl TimeSeriesProcessor {
 
         result
     }
+
+    /// Convert a decomposition to CSV rows, leaving undefined trend and remainder cells empty
+    pub fn decomposition_to_csv(&self, decomposition: &Decomposition) -> Vec<Vec<String>> {
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        let mut result = vec![
+            ["timestamp", "observed", "trend", "seasonal", "remainder"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+        ];
+
+        for i in 0..decomposition.timestamps.len() {
+            result.push(vec![
+                decomposition.timestamps[i]
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+                decomposition.observed[i].to_string(),
+                optional(decomposition.trend[i]),
+                decomposition.seasonal[i].to_string(),
+                optional(decomposition.remainder[i]),
+            ]);
+        }
+
+        result
+    }
+}
+
+/// Fail unless `series` is in timestamp order
+fn ensure_sorted(series: &[TimeSeriesPoint]) -> Result<()> {
+    if let Some(pair) = series
+        .windows(2)
+        .find(|pair| pair[1].timestamp < pair[0].timestamp)
+    {
+        anyhow::bail!(
+            "Time series must be sorted by timestamp: {} comes after {}",
+            pair[1].timestamp,
+            pair[0].timestamp
+        );
+    }
+    Ok(())
 }
 
 impl Default for TimeSeriesProcessor {
@@ -522,4 +806,195 @@ mod tests {
         assert!(!resampled.is_empty());
         assert!(resampled.len() < data.len());
     }
+
+    fn day(d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 1, d)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    /// Days 1, 2 and 5 of January, with two readings on day 2
+    fn sparse_daily() -> Vec<TimeSeriesPoint> {
+        [(1, 10.0), (2, 20.0), (2, 40.0), (5, 60.0)]
+            .into_iter()
+            .map(|(d, value)| TimeSeriesPoint {
+                timestamp: day(d),
+                value,
+            })
+            .collect()
+    }
+
+    fn values(points: &[TimeSeriesPoint]) -> Vec<f64> {
+        points.iter().map(|p| p.value).collect()
+    }
+
+    #[test]
+    fn test_resample_forward_fills_missing_days() {
+        let processor = TimeSeriesProcessor::default().with_fill_gaps(GapFill::Forward);
+
+        let resampled = processor
+            .resample(
+                &sparse_daily(),
+                &ResampleInterval::Daily,
+                &TimeSeriesAgg::Mean,
+            )
+            .unwrap();
+
+        let timestamps: Vec<NaiveDateTime> = resampled.iter().map(|p| p.timestamp).collect();
+        assert_eq!(timestamps, (1..=5).map(day).collect::<Vec<_>>());
+        assert_eq!(values(&resampled), [10.0, 30.0, 30.0, 30.0, 60.0]);
+    }
+
+    #[test]
+    fn test_resample_gap_fill_modes() {
+        let resample = |fill| {
+            let processor = TimeSeriesProcessor::default().with_fill_gaps(fill);
+            let resampled = processor
+                .resample(
+                    &sparse_daily(),
+                    &ResampleInterval::Daily,
+                    &TimeSeriesAgg::Mean,
+                )
+                .unwrap();
+            values(&resampled)
+        };
+
+        assert_eq!(resample(GapFill::None), [10.0, 30.0, 60.0]);
+        assert_eq!(resample(GapFill::Zero), [10.0, 30.0, 0.0, 0.0, 60.0]);
+        assert_eq!(resample(GapFill::Linear), [10.0, 30.0, 40.0, 50.0, 60.0]);
+    }
+
+    #[test]
+    fn test_resample_fills_missing_months() {
+        let processor = TimeSeriesProcessor::default().with_fill_gaps(GapFill::Zero);
+        let data = vec![
+            TimeSeriesPoint {
+                timestamp: day(31),
+                value: 1.0,
+            },
+            TimeSeriesPoint {
+                timestamp: day(1) + Duration::days(90),
+                value: 2.0,
+            },
+        ];
+
+        let resampled = processor
+            .resample(&data, &ResampleInterval::Monthly, &TimeSeriesAgg::Sum)
+            .unwrap();
+
+        let months: Vec<u32> = resampled.iter().map(|p| p.timestamp.month()).collect();
+        assert_eq!(months, [1, 2, 3, 4]);
+        assert_eq!(values(&resampled), [1.0, 0.0, 0.0, 2.0]);
+    }
+
+    /// Readings at irregular times on 1 January
+    fn irregular() -> Vec<TimeSeriesPoint> {
+        [(0, 1.0), (10, 2.0), (15, 4.0), (40, 8.0), (100, 16.0)]
+            .into_iter()
+            .map(|(minute, value)| TimeSeriesPoint {
+                timestamp: day(1) + Duration::minutes(minute),
+                value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling_uses_time_window_on_irregular_series() {
+        let processor = TimeSeriesProcessor::default();
+
+        let sums = processor
+            .rolling(&irregular(), Duration::minutes(30), TimeSeriesAgg::Sum)
+            .unwrap();
+        // At minute 40 the window (10, 40] has dropped the points at 0 and 10
+        assert_eq!(values(&sums), [1.0, 3.0, 7.0, 12.0, 16.0]);
+
+        let counts = processor
+            .rolling(&irregular(), Duration::minutes(15), TimeSeriesAgg::Count)
+            .unwrap();
+        assert_eq!(values(&counts), [1.0, 2.0, 2.0, 1.0, 1.0]);
+        assert_eq!(counts[2].timestamp, day(1) + Duration::minutes(15));
+    }
+
+    #[test]
+    fn test_expanding_accumulates_from_start() {
+        let processor = TimeSeriesProcessor::default();
+
+        let maxes = processor
+            .expanding(&irregular(), TimeSeriesAgg::Max)
+            .unwrap();
+        assert_eq!(values(&maxes), [1.0, 2.0, 4.0, 8.0, 16.0]);
+
+        let sums = processor
+            .expanding(&irregular(), TimeSeriesAgg::Sum)
+            .unwrap();
+        assert_eq!(values(&sums), [1.0, 3.0, 7.0, 15.0, 31.0]);
+    }
+
+    /// Monthly points over four years: a linear trend plus a yearly sine wave
+    fn sine_plus_trend() -> Vec<TimeSeriesPoint> {
+        (0..48)
+            .map(|i| TimeSeriesPoint {
+                timestamp: day(1) + Duration::days(30 * i),
+                value: 0.5 * i as f64 + 10.0 * (2.0 * std::f64::consts::PI * i as f64 / 12.0).sin(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decompose_recovers_repeating_seasonal_component() {
+        let processor = TimeSeriesProcessor::default();
+        let decomposition = processor.decompose(&sine_plus_trend(), 12).unwrap();
+
+        for i in 0..36 {
+            assert!((decomposition.seasonal[i] - decomposition.seasonal[i + 12]).abs() < 1e-12);
+        }
+        for (i, seasonal) in decomposition.seasonal.iter().enumerate() {
+            let expected = 10.0 * (2.0 * std::f64::consts::PI * i as f64 / 12.0).sin();
+            assert!((seasonal - expected).abs() < 1e-9, "{i}: {seasonal}");
+        }
+
+        assert!(decomposition.trend[..6].iter().all(Option::is_none));
+        assert!(decomposition.trend[42..].iter().all(Option::is_none));
+        assert!((decomposition.trend[20].unwrap() - 10.0).abs() < 1e-9);
+        for remainder in decomposition.remainder.iter().flatten() {
+            assert!(remainder.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_decomposition_to_csv() {
+        let processor = TimeSeriesProcessor::default();
+        let decomposition = processor.decompose(&sine_plus_trend(), 12).unwrap();
+
+        let rows = processor.decomposition_to_csv(&decomposition);
+
+        assert_eq!(rows.len(), 49);
+        assert_eq!(
+            rows[0],
+            ["timestamp", "observed", "trend", "seasonal", "remainder"]
+        );
+        assert_eq!(rows[1][0], "2023-01-01 00:00:00");
+        assert_eq!(rows[1][2], "");
+        assert_eq!(rows[7][2], decomposition.trend[6].unwrap().to_string());
+        assert!(processor.decompose(&sine_plus_trend()[..20], 12).is_err());
+    }
+
+    #[test]
+    fn test_rolling_rejects_unsorted_series_and_empty_window() {
+        let processor = TimeSeriesProcessor::default();
+        let mut series = irregular();
+        series.swap(0, 1);
+
+        assert!(
+            processor
+                .rolling(&series, Duration::minutes(5), TimeSeriesAgg::Mean)
+                .is_err()
+        );
+        assert!(
+            processor
+                .rolling(&irregular(), Duration::zero(), TimeSeriesAgg::Mean)
+                .is_err()
+        );
+    }
 }
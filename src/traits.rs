@@ -136,6 +136,7 @@ pub trait CellRangeProvider: Send + Sync {
 
 /// Trait for sorting operations
 pub trait SortOperator: Send + Sync {
+    /// Sort data rows by `column`, keeping the header row (row 0) in place
     fn sort(&self, data: &mut Vec<Vec<String>>, column: usize, ascending: bool) -> Result<()>;
 }
 
@@ -1,21 +1,159 @@
 //! Workflow orchestration
 //!
 //! Provides pipeline execution capabilities for chaining multiple operations.
+//!
+//! A pipeline is an ordered list of steps read from YAML, JSON or TOML. Each
+//! step names its `operation` and takes that operation's parameters under
+//! `args`; any step may also set `input` to load a file in place of the
+//! current dataset and `output` to save its result:
+//!
+//! ```yaml
+//! name: north-sales
+//! steps:
+//!   - operation: read
+//!     input: sales.csv
+//!   - operation: filter
+//!     args: { column: region, operator: "=", value: north }
+//!   - operation: groupby
+//!     args:
+//!       by: [product]
+//!       aggregations: [{ column: amount, func: sum }]
+//!   - operation: write
+//!     output: north.csv
+//! ```
+//!
+//! A single dataset is threaded through the steps, and the final one is also
+//! written to the pipeline's top-level `output` when set. Columns are referred
+//! to by header name; a failing step is reported with its 1-based index.
 
 use crate::handler_registry::HandlerRegistry;
-use crate::operations::DataOperations;
+use crate::operations::{AggFunc, DataOperations, JoinType, SortOrder};
 use crate::traits::DataWriteOptions;
-use anyhow::Result;
+use crate::validation::{DataValidator, ValidationConfig};
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::Path;
 
 /// Workflow step
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowStep {
-    pub operation: String,
+    #[serde(flatten)]
+    pub action: StepAction,
+    /// File to read as this step's input instead of the current dataset
     pub input: Option<String>,
+    /// File to write this step's result to
     pub output: Option<String>,
-    pub args: Option<serde_json::Value>,
+}
+
+/// A pipeline operation and its parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "operation", content = "args", rename_all = "lowercase")]
+pub enum StepAction {
+    /// Load the step's `input`
+    Read,
+    /// Keep rows whose `column` compares to `value` with `operator`, such as `>=`
+    Filter {
+        column: String,
+        #[serde(default = "default_operator")]
+        operator: String,
+        value: String,
+    },
+    Sort {
+        column: String,
+        #[serde(default = "default_ascending")]
+        ascending: bool,
+    },
+    /// Group by the `by` columns, one output column per aggregation
+    Groupby {
+        by: Vec<String>,
+        aggregations: Vec<Aggregation>,
+    },
+    /// Join the dataset with the file `right`
+    Join {
+        right: String,
+        left_on: String,
+        /// Defaults to `left_on`
+        right_on: Option<String>,
+        #[serde(default = "default_join")]
+        how: String,
+    },
+    /// Save the dataset to the step's `output`
+    Write,
+    Select {
+        columns: Vec<String>,
+    },
+    /// Add a column computed by a formula such as `price*qty`
+    Mutate {
+        column: String,
+        formula: String,
+    },
+    Transform(TransformArgs),
+    /// Validate against a rules file path or inline rules, stopping the pipeline on errors
+    Validate {
+        rules: serde_json::Value,
+        report: Option<String>,
+    },
+    Describe,
+}
+
+/// One groupby aggregation, like `{column: amount, func: sum}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Aggregation {
+    pub column: String,
+    /// Any aggregation the groupby command accepts, such as `sum`, `mean` or `p90`
+    pub func: String,
+}
+
+/// Parameters of the `transform` step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformArgs {
+    /// `replace`, `dedupe`, `transpose`, `fillna` or `dropna`
+    pub operation: String,
+    pub column: Option<String>,
+    pub find: Option<String>,
+    pub replace: Option<String>,
+    pub value: Option<String>,
+}
+
+impl TransformArgs {
+    /// `value`, or an error naming the missing `key`
+    fn required<'a>(&self, value: &'a Option<String>, key: &str) -> Result<&'a str> {
+        value
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("transform '{}' requires '{}'", self.operation, key))
+    }
+}
+
+fn default_operator() -> String {
+    "=".to_string()
+}
+
+fn default_ascending() -> bool {
+    true
+}
+
+fn default_join() -> String {
+    "inner".to_string()
+}
+
+impl StepAction {
+    /// The `operation` name used in pipeline files
+    pub fn name(&self) -> &'static str {
+        match self {
+            StepAction::Read => "read",
+            StepAction::Filter { .. } => "filter",
+            StepAction::Sort { .. } => "sort",
+            StepAction::Groupby { .. } => "groupby",
+            StepAction::Join { .. } => "join",
+            StepAction::Write => "write",
+            StepAction::Select { .. } => "select",
+            StepAction::Mutate { .. } => "mutate",
+            StepAction::Transform(_) => "transform",
+            StepAction::Validate { .. } => "validate",
+            StepAction::Describe => "describe",
+        }
+    }
 }
 
 /// Workflow configuration
@@ -24,6 +162,36 @@ pub struct WorkflowConfig {
     pub name: String,
     pub description: Option<String>,
     pub steps: Vec<WorkflowStep>,
+    /// File to write the final dataset to
+    pub output: Option<String>,
+}
+
+impl WorkflowConfig {
+    /// Read a pipeline file, choosing the parser by extension
+    ///
+    /// `.yaml`/`.yml`, `.json` and `.toml` files use that format; other
+    /// files are tried as JSON, then YAML, then TOML.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workflow config: {}", path))?;
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+
+        let config = match extension.as_deref() {
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(anyhow::Error::from),
+            Some("json") => serde_json::from_str(&text).map_err(anyhow::Error::from),
+            Some("toml") => toml::from_str(&text).map_err(anyhow::Error::from),
+            _ => serde_json::from_str(&text)
+                .map_err(anyhow::Error::from)
+                .or_else(|_| serde_yaml::from_str(&text).map_err(anyhow::Error::from))
+                .or_else(|_| toml::from_str(&text).map_err(anyhow::Error::from)),
+        };
+        config.with_context(|| {
+            format!("Failed to parse workflow config {path}. Expected YAML, JSON or TOML")
+        })
+    }
 }
 
 /// Workflow executor
@@ -31,6 +199,12 @@ pub struct WorkflowExecutor {
     registry: HandlerRegistry,
 }
 
+impl Default for WorkflowExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WorkflowExecutor {
     pub fn new() -> Self {
         Self {
@@ -40,158 +214,202 @@ impl WorkflowExecutor {
 
     /// Execute workflow from config file
     pub fn execute(&self, config_path: &str) -> Result<()> {
-        let config_str = fs::read_to_string(config_path)
-            .with_context(|| format!("Failed to read workflow config: {}", config_path))?;
-
-        let config: WorkflowConfig = toml::from_str(&config_str)
-            .or_else(|_| serde_json::from_str(&config_str))
-            .with_context(|| "Failed to parse workflow config. Expected TOML or JSON")?;
+        let config = WorkflowConfig::from_file(config_path)?;
+        self.run(&config).map(|_| ())
+    }
 
+    /// Run a parsed workflow, returning the final dataset
+    pub fn run(&self, config: &WorkflowConfig) -> Result<Vec<Vec<String>>> {
         println!("Executing workflow: {}", config.name);
 
         let mut current_data: Option<Vec<Vec<String>>> = None;
 
         for (step_idx, step) in config.steps.iter().enumerate() {
-            println!("Step {}: {}", step_idx + 1, step.operation);
-
-            // Get input data
-            let input_data = if let Some(ref input) = step.input {
-                self.registry.read(input)?
-            } else if let Some(ref data) = current_data {
-                data.clone()
-            } else {
-                anyhow::bail!("No input data available for step {}", step_idx + 1);
-            };
-
-            // Execute operation
-            let output_data =
-                self.execute_step(&step.operation, &input_data, step.args.as_ref())?;
-
-            // Save output if specified
-            if let Some(ref output) = step.output {
-                let options = DataWriteOptions::default();
-                self.registry.write(output, &output_data, options)?;
-                println!("  Output saved to: {}", output);
-            }
+            let name = step.action.name();
+            println!("Step {}: {}", step_idx + 1, name);
 
+            let output_data = self
+                .run_step(step, current_data.take())
+                .with_context(|| format!("Step {} ({}) failed", step_idx + 1, name))?;
             current_data = Some(output_data);
         }
 
-        Ok(())
+        let data = current_data.unwrap_or_default();
+        if let Some(ref output) = config.output {
+            self.registry
+                .write(output, &data, DataWriteOptions::default())?;
+            println!("Result saved to: {}", output);
+        }
+        Ok(data)
+    }
+
+    fn run_step(
+        &self,
+        step: &WorkflowStep,
+        current: Option<Vec<Vec<String>>>,
+    ) -> Result<Vec<Vec<String>>> {
+        let input_data = match (&step.input, current) {
+            (Some(input), _) => self.registry.read(input)?,
+            (None, Some(data)) => data,
+            (None, None) if matches!(step.action, StepAction::Read) => {
+                anyhow::bail!("read step requires an 'input' file")
+            }
+            (None, None) => anyhow::bail!("No input data available"),
+        };
+
+        let output_data = self.execute_step(&step.action, input_data)?;
+
+        if let Some(ref output) = step.output {
+            self.registry
+                .write(output, &output_data, DataWriteOptions::default())?;
+            println!("  Output saved to: {}", output);
+        } else if matches!(step.action, StepAction::Write) {
+            anyhow::bail!("write step requires an 'output' file");
+        }
+
+        Ok(output_data)
     }
 
     fn execute_step(
         &self,
-        operation: &str,
-        data: &[Vec<String>],
-        args: Option<&serde_json::Value>,
+        action: &StepAction,
+        mut data: Vec<Vec<String>>,
     ) -> Result<Vec<Vec<String>>> {
-        let mut result = data.to_vec();
         let ops = DataOperations::new();
 
-        match operation {
-            "read" => Ok(data.to_vec()),
+        match action {
+            StepAction::Read | StepAction::Write => Ok(data),
 
-            "filter" => {
-                if let Some(args) = args {
-                    if let Some(column_idx) = args.get("column").and_then(|v| v.as_u64()) {
-                        if let Some(where_clause) = args.get("where").and_then(|v| v.as_str()) {
-                            result = ops.filter_rows(&result, column_idx as usize, where_clause, "")?;
-                        }
-                    }
-                }
-                Ok(result)
+            StepAction::Filter {
+                column,
+                operator,
+                value,
+            } => {
+                let col = column_index(&data, column)?;
+                let Some((header, rows)) = data.split_first() else {
+                    return Ok(data);
+                };
+                let mut filtered = vec![header.clone()];
+                filtered.extend(ops.filter_rows(rows, col, operator, value)?);
+                Ok(filtered)
             }
 
-            "sort" => {
-                if let Some(args) = args {
-                    if let Some(column_idx) = args.get("column").and_then(|v| v.as_u64()) {
-                        let ascending = args.get("ascending")
-                            .and_then(|v| v.as_bool())
-                            .unwrap_or(true);
+            StepAction::Sort { column, ascending } => {
+                let col = column_index(&data, column)?;
+                let order = if *ascending {
+                    SortOrder::Ascending
+                } else {
+                    SortOrder::Descending
+                };
+                ops.sort_by_column(&mut data, col, order, true)?;
+                Ok(data)
+            }
 
-                        use crate::operations::types::SortOrder;
-                        let order = if ascending { SortOrder::Ascending } else { SortOrder::Descending };
-                        ops.sort_by_column(&mut result, column_idx as usize, order)?;
-                    }
-                }
-                Ok(result)
+            StepAction::Groupby { by, aggregations } => {
+                let group_cols = by
+                    .iter()
+                    .map(|name| column_index(&data, name))
+                    .collect::<Result<Vec<_>>>()?;
+                let aggregations = aggregations
+                    .iter()
+                    .map(|agg| {
+                        Ok((
+                            column_index(&data, &agg.column)?,
+                            AggFunc::from_str(&agg.func)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                ops.groupby_multi(&data, &group_cols, &aggregations)
             }
 
-            "transform" => {
-                if let Some(args) = args {
-                    if let Some(op_type) = args.get("operation").and_then(|v| v.as_str()) {
-                        match op_type {
-                            "replace" => {
-                                if let Some(find) = args.get("find").and_then(|v| v.as_str()) {
-                                    if let Some(replace) = args.get("replace").and_then(|v| v.as_str()) {
-                                        if let Some(column_idx) = args.get("column").and_then(|v| v.as_u64()) {
-                                            let _count = ops.replace(&mut result, column_idx as usize, find, replace);
-                                            println!("  Replaced '{}' with '{}' in column {}", find, replace, column_idx);
-                                        }
-                                    }
-                                }
-                            }
-                            "dedupe" => {
-                                let count = ops.deduplicate_mut(&mut result);
-                                println!("  Removed {} duplicate rows", count);
-                            }
-                            "transpose" => {
-                                result = ops.transpose(&result);
-                            }
-                            "fillna" => {
-                                if let Some(value) = args.get("value").and_then(|v| v.as_str()) {
-                                    ops.fillna(&mut result, value);
-                                }
-                            }
-                            "dropna" => {
-                                result = ops.dropna(&result);
-                            }
-                            _ => anyhow::bail!("Unknown transform operation: {}", op_type),
-                        }
-                    }
-                }
-                Ok(result)
+            StepAction::Join {
+                right,
+                left_on,
+                right_on,
+                how,
+            } => {
+                let how = JoinType::from_str(how)?;
+                let right_data = self.registry.read(right)?;
+                let left_col = column_index(&data, left_on)?;
+                let right_col = column_index(&right_data, right_on.as_deref().unwrap_or(left_on))
+                    .with_context(|| format!("in {}", right))?;
+                ops.join(&data, &right_data, left_col, right_col, how)
             }
 
-            "mutate" => {
-                if let Some(args) = args {
-                    if let Some(_column) = args.get("column").and_then(|v| v.as_str()) {
-                        if let Some(_formula) = args.get("formula").and_then(|v| v.as_str()) {
-                            // For now, just add a placeholder column
-                            // Full formula evaluation with mutate is complex
-                            for row in &mut result {
-                                row.push("MUTATED".to_string());
-                            }
-                        }
-                    }
-                }
-                Ok(result)
+            StepAction::Select { columns } => {
+                let names: Vec<&str> = columns.iter().map(String::as_str).collect();
+                ops.select_columns_by_name(&data, &names)
             }
 
-            "select" => {
-                if let Some(args) = args {
-                    if let Some(columns) = args.get("columns").and_then(|v| v.as_array()) {
-                        let column_names: Vec<&str> = columns
-                            .iter()
-                            .filter_map(|v| v.as_str())
-                            .collect();
+            StepAction::Mutate { column, formula } => {
+                ops.mutate(&mut data, column, formula)?;
+                Ok(data)
+            }
 
-                        result = ops.select_columns_by_name(&result, &column_names)?;
+            StepAction::Transform(args) => {
+                match args.operation.as_str() {
+                    "replace" => {
+                        let col = column_index(&data, args.required(&args.column, "column")?)?;
+                        let (find, replace) = (
+                            args.required(&args.find, "find")?,
+                            args.required(&args.replace, "replace")?,
+                        );
+                        let count = ops.replace(&mut data, col, find, replace);
+                        println!(
+                            "  Replaced {} occurrences of '{}' with '{}'",
+                            count, find, replace
+                        );
+                    }
+                    "dedupe" => {
+                        let count = ops.deduplicate_mut(&mut data);
+                        println!("  Removed {} duplicate rows", count);
                     }
+                    "transpose" => data = ops.transpose(&data),
+                    "fillna" => ops.fillna(&mut data, args.required(&args.value, "value")?),
+                    "dropna" => data = ops.dropna(&data),
+                    other => anyhow::bail!("Unknown transform operation: {}", other),
                 }
-                Ok(result)
+                Ok(data)
             }
 
-            "describe" => {
-                let desc = ops.describe(&result)?;
-                println!("  Statistics: {:?}", desc);
-                Ok(desc)
+            StepAction::Validate { rules, report } => {
+                let validator = match rules {
+                    serde_json::Value::String(path) => DataValidator::from_config_file(path)?,
+                    rules => {
+                        let config: ValidationConfig = serde_json::from_value(rules.clone())
+                            .with_context(|| "Invalid inline validation rules")?;
+                        DataValidator::new(config)
+                    }
+                };
+
+                let validation = validator.validate(&data)?;
+
+                if let Some(report) = report {
+                    let report_text = validator.generate_report(&validation);
+                    fs::write(report, report_text)
+                        .with_context(|| format!("Failed to write validation report: {report}"))?;
+                    println!("  Validation report saved to: {}", report);
+                }
+
+                if !validation.is_valid {
+                    anyhow::bail!(
+                        "Validation failed with {} errors; aborting workflow",
+                        validation.stats.total_errors
+                    );
+                }
+
+                println!("  Validation passed ({} rows)", validation.stats.total_rows);
+                Ok(data)
             }
 
-            _ => anyhow::bail!("Unknown operation: {}", operation),
+            StepAction::Describe => ops.describe(&data),
         }
     }
 }
 
-use anyhow::Context;
+/// Index of the header column `name`
+fn column_index(data: &[Vec<String>], name: &str) -> Result<usize> {
+    data.first()
+        .and_then(|header| header.iter().position(|h| h == name))
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))
+}
@@ -0,0 +1,13 @@
+;; Plugin fixture: tries to grow its memory by 1 GiB and reports whether it could
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "[[\22capped\22]]")
+
+  (func (export "alloc") (param $len i32) (result i32)
+    (i32.const 1024))
+
+  ;; Returns the 12-byte rows at offset 0 only when the growth was refused
+  (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+    (if (i32.eq (memory.grow (i32.const 16384)) (i32.const -1))
+      (then (return (i64.const 12))))
+    unreachable))
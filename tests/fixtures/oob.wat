@@ -0,0 +1,9 @@
+;; Plugin fixture: returns a 4 GiB range that its 64 KiB memory cannot hold
+(module
+  (memory (export "memory") 1)
+
+  (func (export "alloc") (param $len i32) (result i32)
+    (i32.const 1024))
+
+  (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+    (i64.const 0xffffffff)))
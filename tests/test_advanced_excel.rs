@@ -503,7 +503,7 @@ fn test_sanitize_csv_row() {
 
 #[test]
 fn test_csv_write_records_safe() {
-    let handler = CsvHandler;
+    let handler = CsvHandler::new();
     let output_path = unique_path("csv_safe", "csv");
 
     let records = vec![
@@ -526,7 +526,7 @@ fn test_csv_write_records_safe() {
 
 #[test]
 fn test_csv_append_records_safe() {
-    let handler = CsvHandler;
+    let handler = CsvHandler::new();
     let output_path = unique_path("csv_append_safe", "csv");
 
     // Write initial data
@@ -0,0 +1,105 @@
+//! Tests for anomaly detection
+
+use datacell::{AnomalyDetector, AnomalyMethod};
+
+fn sales(rows: &[(&str, &str)]) -> Vec<Vec<String>> {
+    std::iter::once(("store", "sales"))
+        .chain(rows.iter().copied())
+        .map(|(store, sales)| vec![store.to_string(), sales.to_string()])
+        .collect()
+}
+
+// ============ Grouped Detection Tests ============
+
+#[test]
+fn test_detect_grouped_flags_value_extreme_within_its_group() {
+    let mut rows = Vec::new();
+    for (a, b) in ["100", "102", "98", "101", "99", "100", "103", "97"]
+        .into_iter()
+        .zip(["10", "11", "9", "10", "12", "10", "11", "60"])
+    {
+        rows.push(("A", a));
+        rows.push(("B", b));
+    }
+    let data = sales(&rows);
+    let detector = AnomalyDetector::new(AnomalyMethod::ZScore { threshold: 2.0 });
+
+    // Across both stores 60 sits between the two clusters
+    let global = detector.detect(&data, 1).unwrap();
+    assert_eq!(global.total_anomalies, 0);
+
+    let grouped = detector.detect_grouped(&data, 1, 0).unwrap();
+    assert_eq!(grouped.total_anomalies, 1);
+    let anomaly = &grouped.anomalies[0];
+    assert_eq!(anomaly.row, 16);
+    assert_eq!(data[anomaly.row], vec!["B", "60"]);
+    assert!((grouped.anomaly_percentage - 100.0 / 16.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_detect_reports_original_rows_past_non_numeric_cells() {
+    let data = sales(&[
+        ("A", "n/a"),
+        ("A", "10"),
+        ("A", "10"),
+        ("A", "10"),
+        ("A", "10"),
+        ("A", "500"),
+    ]);
+    let detector = AnomalyDetector::new(AnomalyMethod::ZScore { threshold: 1.5 });
+
+    let result = detector.detect(&data, 1).unwrap();
+    assert_eq!(result.anomalies.len(), 1);
+    assert_eq!(result.anomalies[0].row, 6);
+
+    assert!(detector.detect_grouped(&data, 1, 5).is_err());
+}
+
+// ============ MAD Tests ============
+
+#[test]
+fn test_mad_flags_gross_outliers_that_mask_zscore() {
+    let mut rows: Vec<(&str, &str)> = ["10", "11", "9", "10", "12", "8", "10", "11", "9", "10"]
+        .into_iter()
+        .map(|v| ("A", v))
+        .collect();
+    rows.extend([("A", "1000"), ("A", "1200"), ("A", "1100")]);
+    let data = sales(&rows);
+
+    // The outliers inflate the standard deviation enough to hide themselves
+    let zscore = AnomalyDetector::new(AnomalyMethod::ZScore { threshold: 3.0 });
+    assert_eq!(zscore.detect(&data, 1).unwrap().total_anomalies, 0);
+
+    let mad = AnomalyDetector::new(AnomalyMethod::Mad { threshold: 3.5 });
+    let result = mad.detect(&data, 1).unwrap();
+    let rows: Vec<usize> = result.anomalies.iter().map(|a| a.row).collect();
+    assert_eq!(rows, vec![11, 12, 13]);
+    // Median 10 and MAD 1.4826: the scale ignores how far out the outliers are
+    assert!((result.anomalies[0].score - 990.0 / 1.4826).abs() < 1e-6);
+}
+
+// ============ Moving Average Tests ============
+
+#[test]
+fn test_moving_average_flags_spike_but_not_trend() {
+    let values: Vec<String> = (1..=30)
+        .map(|day| if day == 20 { 100 } else { day * 3 }.to_string())
+        .collect();
+    let data = sales(&values.iter().map(|v| ("A", v.as_str())).collect::<Vec<_>>());
+
+    let detector = AnomalyDetector::new(AnomalyMethod::MovingAverage {
+        window: 5,
+        threshold: 3.0,
+    });
+    let result = detector.detect(&data, 1).unwrap();
+
+    let flagged: Vec<&str> = result.anomalies.iter().map(|a| a.value.as_str()).collect();
+    assert_eq!(flagged, vec!["100"]);
+    assert_eq!(result.anomalies[0].row, 20);
+
+    let zero_window = AnomalyDetector::new(AnomalyMethod::MovingAverage {
+        window: 0,
+        threshold: 3.0,
+    });
+    assert!(zero_window.detect(&data, 1).is_err());
+}
@@ -0,0 +1,231 @@
+//! Tests for the REST API server
+
+#![cfg(feature = "api")]
+
+use datacell::{ApiConfig, ApiServer};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use tokio::runtime::Runtime;
+
+const SALES_CSV: &str = "region,amount\nnorth,10\nsouth,20\nnorth,30\n";
+
+/// A config serving files under `root`
+fn rooted(root: &Path) -> ApiConfig {
+    ApiConfig {
+        root: root.to_path_buf(),
+        ..ApiConfig::default()
+    }
+}
+
+/// Start a server on an ephemeral port; it runs until the runtime is dropped
+fn start_server(config: ApiConfig) -> (Runtime, SocketAddr) {
+    let runtime = Runtime::new().unwrap();
+    let listener = runtime
+        .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    runtime.spawn(async move { ApiServer::new(config).serve(listener).await });
+    (runtime, addr)
+}
+
+/// POST `body` and return the status line, headers and body of the response
+fn post(addr: SocketAddr, path: &str, content_type: &str, body: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {addr}\r\nOrigin: http://example.com\r\n\
+         Content-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.to_string(), body.to_string())
+}
+
+fn json(body: &str) -> serde_json::Value {
+    serde_json::from_str(body).unwrap_or_else(|e| panic!("{e}: {body}"))
+}
+
+#[test]
+fn test_profile_endpoint_reads_path() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("sales.csv"), SALES_CSV).unwrap();
+    let (_runtime, addr) = start_server(ApiConfig {
+        cors_enabled: true,
+        cors_origins: vec!["http://example.com".to_string()],
+        ..rooted(dir.path())
+    });
+
+    let request = serde_json::json!({ "input": "sales.csv" }).to_string();
+    let (head, body) = post(addr, "/profile", "application/json", &request);
+
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert!(
+        head.to_lowercase()
+            .contains("access-control-allow-origin: http://example.com"),
+        "{head}"
+    );
+    let response = json(&body);
+    assert_eq!(response["success"], true);
+    assert_eq!(response["data"]["total_rows"], 3);
+    assert_eq!(response["data"]["total_columns"], 2);
+    assert_eq!(response["data"]["columns"][1]["name"], "amount");
+}
+
+#[test]
+fn test_profile_endpoint_accepts_upload() {
+    let (_runtime, addr) = start_server(ApiConfig::default());
+
+    let body = format!(
+        "--boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"sales.csv\"\r\n\
+         Content-Type: text/csv\r\n\r\n{SALES_CSV}\r\n--boundary--\r\n"
+    );
+    let (head, body) = post(
+        addr,
+        "/profile",
+        "multipart/form-data; boundary=boundary",
+        &body,
+    );
+
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert!(
+        !head.to_lowercase().contains("access-control-allow-origin"),
+        "{head}"
+    );
+    let response = json(&body);
+    assert_eq!(response["data"]["total_rows"], 3);
+    // The staged temp path is never echoed back
+    assert_eq!(response["data"]["file_path"], "sales.csv");
+    assert!(!body.contains("datacell-upload"), "{body}");
+}
+
+#[test]
+fn test_operations_endpoint_returns_csv() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("sales.csv"), SALES_CSV).unwrap();
+    let (_runtime, addr) = start_server(rooted(dir.path()));
+
+    let request = serde_json::json!({
+        "input": "sales.csv",
+        "operations": [
+            { "op": "where", "clause": "region = 'north'" },
+            { "op": "sort", "col": "amount", "descending": true },
+        ],
+        "format": "csv",
+    })
+    .to_string();
+    let (head, body) = post(addr, "/operations", "application/json", &request);
+
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert!(head.contains("text/csv"), "{head}");
+    assert_eq!(body, "region,amount\nnorth,30\nnorth,10\n");
+
+    let request = serde_json::json!({
+        "input": "sales.csv",
+        "operations": [{ "op": "pivot" }],
+    })
+    .to_string();
+    let (head, body) = post(addr, "/operations", "application/json", &request);
+    assert!(head.starts_with("HTTP/1.1 400"), "{head}");
+    assert!(
+        json(&body)["error"]
+            .as_str()
+            .unwrap()
+            .contains("Unknown operation")
+    );
+}
+
+#[test]
+fn test_paths_outside_root_are_rejected() {
+    let outside = tempfile::tempdir().unwrap();
+    let secret = outside.path().join("secret.csv");
+    std::fs::write(&secret, SALES_CSV).unwrap();
+    let root = tempfile::tempdir().unwrap();
+    let (_runtime, addr) = start_server(rooted(root.path()));
+
+    for input in [
+        secret.to_string_lossy().into_owned(),
+        format!(
+            "../{}/secret.csv",
+            outside.path().file_name().unwrap().to_string_lossy()
+        ),
+    ] {
+        let request = serde_json::json!({ "input": input }).to_string();
+        let (head, body) = post(addr, "/read", "application/json", &request);
+        assert!(head.starts_with("HTTP/1.1 403"), "{head}");
+        assert!(!body.contains("north"), "{body}");
+    }
+}
+
+#[test]
+fn test_multipart_requires_file_part() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("secret.csv"), SALES_CSV).unwrap();
+    let (_runtime, addr) = start_server(ApiConfig {
+        allow_output: true,
+        ..rooted(dir.path())
+    });
+
+    let body = "--boundary\r\nContent-Disposition: form-data; name=\"input\"\r\n\r\nsecret.csv\r\n\
+                --boundary\r\nContent-Disposition: form-data; name=\"output\"\r\n\r\npwned.csv\r\n\
+                --boundary--\r\n";
+    let (head, _) = post(
+        addr,
+        "/convert",
+        "multipart/form-data; boundary=boundary",
+        body,
+    );
+
+    assert!(head.starts_with("HTTP/1.1 400"), "{head}");
+    assert!(!dir.path().join("pwned.csv").exists());
+}
+
+#[test]
+fn test_upload_with_unknown_extension_is_rejected() {
+    let (_runtime, addr) = start_server(ApiConfig::default());
+
+    let body = format!(
+        "--boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"x.csv/../../evil\"\r\n\
+         Content-Type: text/csv\r\n\r\n{SALES_CSV}\r\n--boundary--\r\n"
+    );
+    let (head, _) = post(
+        addr,
+        "/read",
+        "multipart/form-data; boundary=boundary",
+        &body,
+    );
+
+    assert!(head.starts_with("HTTP/1.1 400"), "{head}");
+}
+
+#[test]
+fn test_convert_output_requires_opt_in() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("sales.csv"), SALES_CSV).unwrap();
+    let request = serde_json::json!({ "input": "sales.csv", "output": "copy.csv" }).to_string();
+
+    let (_runtime, addr) = start_server(rooted(dir.path()));
+    let (head, _) = post(addr, "/convert", "application/json", &request);
+    assert!(head.starts_with("HTTP/1.1 403"), "{head}");
+    assert!(!dir.path().join("copy.csv").exists());
+
+    let (_runtime, addr) = start_server(ApiConfig {
+        allow_output: true,
+        ..rooted(dir.path())
+    });
+    let (head, _) = post(addr, "/convert", "application/json", &request);
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("copy.csv")).unwrap(),
+        SALES_CSV
+    );
+
+    let request =
+        serde_json::json!({ "input": "sales.csv", "output": "../escaped.csv" }).to_string();
+    let (head, _) = post(addr, "/convert", "application/json", &request);
+    assert!(head.starts_with("HTTP/1.1 403"), "{head}");
+    assert!(!dir.path().parent().unwrap().join("escaped.csv").exists());
+}
@@ -106,6 +106,8 @@ fn test_file_format_detection() {
     use datacell::common::format;
 
     assert_eq!(format::from_extension("data.csv"), "csv");
+    assert_eq!(format::from_extension("data.csv.gz"), "csv");
+    assert_eq!(format::from_extension("data.tsv"), "tsv");
     assert_eq!(format::from_extension("data.xlsx"), "excel");
     assert_eq!(format::from_extension("data.xls"), "excel");
     assert_eq!(format::from_extension("data.ods"), "ods");
@@ -219,3 +221,969 @@ fn test_error_utilities() {
     assert!(col_err.to_string().contains("test.csv"));
     assert!(col_err.to_string().contains("price"));
 }
+
+#[test]
+fn test_cli_peek_command() {
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("peek.csv");
+    fs::write(&input, "id,name,score\n1,Alice,90\n2,,85\n3,Carol,\n")
+        .expect("Failed to write test CSV");
+
+    let input = input.to_string_lossy().to_string();
+    let output = run_with_stdin(&["peek", "-i", &input, "-n", "2"], "");
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], format!("{input}: 3 rows x 3 columns"));
+    assert_eq!(
+        &lines[2..6],
+        [
+            "Columns:",
+            "  id     Integer   nulls   0.0%  min 1 max 3 mean 2.00",
+            "  name   String    nulls  33.3%",
+            "  score  Integer   nulls  33.3%  min 85 max 90 mean 87.50",
+        ]
+    );
+    assert_eq!(lines[7], "First 2 rows:");
+    assert_eq!(lines[8], "| id | name  | score |");
+    assert_eq!(
+        &lines[10..],
+        ["| 1  | Alice | 90    |", "| 2  |       | 85    |"]
+    );
+
+    let handler = DefaultCommandHandler::new();
+
+    let missing = handler.handle(Commands::Peek {
+        input: temp_dir
+            .path()
+            .join("missing.csv")
+            .to_string_lossy()
+            .to_string(),
+        n: 5,
+    });
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_cli_select_writes_multiple_outputs() {
+    use clap::Parser;
+    use datacell::Converter;
+    use datacell::cli::{Cli, CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("people.csv");
+    fs::write(&input, "id,name,age\n1,Alice,30\n2,Bob,25\n").expect("Failed to write test CSV");
+
+    // A comma is part of a path, not a separator
+    let csv_out = temp_dir.path().join("out,v1.csv");
+    let parquet_out = temp_dir.path().join("out.parquet");
+
+    let cli = Cli::try_parse_from([
+        "datacell".to_string(),
+        "select".to_string(),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-o".to_string(),
+        csv_out.to_string_lossy().to_string(),
+        "-o".to_string(),
+        parquet_out.to_string_lossy().to_string(),
+        "-c".to_string(),
+        "name,age".to_string(),
+    ])
+    .unwrap();
+    assert!(matches!(&cli.command, Commands::Select { output, .. } if output.len() == 2));
+
+    DefaultCommandHandler::new()
+        .handle(cli.command)
+        .expect("select should succeed");
+
+    let converter = Converter::new();
+    let from_csv = converter
+        .read_any_data(&csv_out.to_string_lossy(), None)
+        .unwrap();
+    let from_parquet = converter
+        .read_any_data(&parquet_out.to_string_lossy(), None)
+        .unwrap();
+
+    assert_eq!(from_csv[0], vec!["name", "age"]);
+    assert_eq!(from_csv.len(), 3);
+    assert_eq!(from_csv, from_parquet);
+}
+
+#[test]
+fn test_cli_batch_parses() {
+    use clap::Parser;
+    use datacell::cli::{Cli, Commands};
+
+    let argv = "datacell batch -i a.csv,b.csv -o out -p convert -a parquet --jobs 2";
+    let cli = Cli::try_parse_from(argv.split(' ')).unwrap();
+    assert!(matches!(
+        &cli.command,
+        Commands::Batch(args)
+            if args.output_dir == "out" && args.operation == "convert" && args.jobs == Some(2)
+    ));
+}
+
+#[test]
+fn test_cli_batch_convert_parallel() {
+    use datacell::cli::args::BatchArgs;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let out_dir = temp_dir.path().join("out");
+
+    let mut inputs = Vec::new();
+    for i in 0..6 {
+        let path = temp_dir.path().join(format!("part{i}.csv"));
+        fs::write(&path, format!("id,value\n{i},{}\n", i * 10)).expect("Failed to write CSV");
+        inputs.push(path.to_string_lossy().to_string());
+    }
+    // One missing input must not stop the rest of the batch
+    let missing = temp_dir.path().join("missing.csv");
+    inputs.push(missing.to_string_lossy().to_string());
+
+    let result = DefaultCommandHandler::new().handle(Commands::Batch(Box::new(BatchArgs {
+        inputs: inputs.join(","),
+        output_dir: out_dir.to_string_lossy().to_string(),
+        operation: "convert".to_string(),
+        args: vec!["parquet".to_string()],
+        jobs: Some(3),
+    })));
+
+    let err = result.expect_err("missing input should be reported");
+    assert!(err.to_string().contains("missing.csv"));
+    for i in 0..6 {
+        assert!(out_dir.join(format!("part{i}.parquet")).exists());
+    }
+}
+
+#[test]
+fn test_cli_clip_parses() {
+    use clap::Parser;
+    use datacell::cli::{Cli, Commands};
+
+    let argv = "datacell clip -i a.csv -o b.csv -c value -m 0 -M 10";
+    let cli = Cli::try_parse_from(argv.split(' ')).unwrap();
+    assert!(matches!(
+        &cli.command,
+        Commands::Clip(args) if args.min == "0" && args.max == "10"
+    ));
+}
+
+#[test]
+fn test_cli_head_index_flag() {
+    use clap::Parser;
+    use datacell::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["datacell", "head", "-i", "a.csv", "--index"]).unwrap();
+    assert!(matches!(&cli.command, Commands::Head(args) if args.index == Some(0)));
+
+    let cli = Cli::try_parse_from(["datacell", "head", "-i", "a.csv", "--index", "1"]).unwrap();
+    assert!(matches!(&cli.command, Commands::Head(args) if args.index == Some(1)));
+
+    let cli = Cli::try_parse_from(["datacell", "head", "-i", "a.csv"]).unwrap();
+    assert!(matches!(&cli.command, Commands::Head(args) if args.index.is_none()));
+}
+
+#[test]
+fn test_cli_groupby_multiple_keys() {
+    use datacell::cli::args::GroupbyArgs;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("sales.csv");
+    let output = temp_dir.path().join("grouped.csv");
+    fs::write(
+        &input,
+        "region,product,units\nnorth,apple,10\nnorth,apple,5\nnorth,pear,1\nsouth,apple,2\n",
+    )
+    .expect("Failed to write test CSV");
+
+    DefaultCommandHandler::new()
+        .handle(Commands::Groupby(Box::new(GroupbyArgs {
+            input: input.to_string_lossy().to_string(),
+            output: vec![output.to_string_lossy().to_string()].into(),
+            by: "region, product".to_string(),
+            agg: "sum:units".to_string(),
+            concat_sep: ",".to_string(),
+        })))
+        .expect("groupby should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(
+        content,
+        "region,product,sum_units\nnorth,apple,15.00\nnorth,pear,1.00\nsouth,apple,2.00\n"
+    );
+}
+
+#[test]
+fn test_cli_melt_command() {
+    use datacell::cli::args::MeltArgs;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("wide.csv");
+    let output = temp_dir.path().join("long.csv");
+    fs::write(&input, "id,q1,q2\n1,10,20\n2,30,40\n").expect("Failed to write test CSV");
+
+    DefaultCommandHandler::new()
+        .handle(Commands::Melt(Box::new(MeltArgs {
+            input: input.to_string_lossy().to_string(),
+            output: vec![output.to_string_lossy().to_string()].into(),
+            id_vars: "id".to_string(),
+            value_vars: None,
+        })))
+        .expect("melt should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(
+        content,
+        "id,variable,value\n1,q1,10\n1,q2,20\n2,q1,30\n2,q2,40\n"
+    );
+}
+
+#[test]
+fn test_cli_sort_natural_flag() {
+    use clap::Parser;
+    use datacell::cli::{Cli, CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("files.csv");
+    let output = temp_dir.path().join("sorted.csv");
+    fs::write(&input, "name,size\nfile10,1\nfile2,2\nfile1,3\n").expect("Failed to write CSV");
+
+    let cli = Cli::try_parse_from([
+        "datacell",
+        "sort",
+        "-i",
+        &input.to_string_lossy(),
+        "-o",
+        &output.to_string_lossy(),
+        "-c",
+        "name",
+        "-a",
+        "--natural",
+    ])
+    .unwrap();
+    assert!(matches!(&cli.command, Commands::Sort(args) if args.natural));
+
+    DefaultCommandHandler::new()
+        .handle(cli.command)
+        .expect("natural sort should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "name,size\nfile1,3\nfile2,2\nfile10,1\n");
+
+    let conflicting = Cli::try_parse_from([
+        "datacell",
+        "sort",
+        "-i",
+        "a.csv",
+        "-o",
+        "b.csv",
+        "-c",
+        "name",
+        "--natural",
+        "--as",
+        "date",
+    ]);
+    assert!(conflicting.is_err());
+}
+
+#[test]
+fn test_cli_rolling_command() {
+    use datacell::cli::args::RollingArgs;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("series.csv");
+    let output = temp_dir.path().join("rolling.csv");
+    fs::write(&input, "t,v\n1,1\n2,5\n3,3\n").expect("Failed to write test CSV");
+
+    DefaultCommandHandler::new()
+        .handle(Commands::Rolling(Box::new(RollingArgs {
+            input: input.to_string_lossy().to_string(),
+            output: vec![output.to_string_lossy().to_string()].into(),
+            column: "v".to_string(),
+            window: 2,
+            agg: "max".to_string(),
+        })))
+        .expect("rolling should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "t,v,rolling_max_v\n1,1,\n2,5,5.00\n3,3,5.00\n");
+}
+
+#[test]
+fn test_cli_rank_command() {
+    use datacell::cli::args::RankArgs;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("scores.csv");
+    let output = temp_dir.path().join("ranked.csv");
+    fs::write(&input, "name,score\na,5\nb,9\nc,5\n").expect("Failed to write test CSV");
+
+    DefaultCommandHandler::new()
+        .handle(Commands::Rank(Box::new(RankArgs {
+            input: input.to_string_lossy().to_string(),
+            output: vec![output.to_string_lossy().to_string()].into(),
+            column: "score".to_string(),
+            method: "min".to_string(),
+            descending: true,
+        })))
+        .expect("rank should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "name,score,rank\na,5,2\nb,9,1\nc,5,2\n");
+}
+
+#[test]
+fn test_cli_cross_join_without_on() {
+    use clap::Parser;
+    use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let left = temp_dir.path().join("sizes.csv");
+    let right = temp_dir.path().join("colors.csv");
+    let output = temp_dir.path().join("combos.csv");
+    fs::write(&left, "size\nS\nM\n").expect("Failed to write CSV");
+    fs::write(&right, "color\nred\n").expect("Failed to write CSV");
+
+    let cli = Cli::try_parse_from([
+        "datacell",
+        "join",
+        "--left",
+        &left.to_string_lossy(),
+        "--right",
+        &right.to_string_lossy(),
+        "--output",
+        &output.to_string_lossy(),
+        "--how",
+        "cross",
+    ])
+    .unwrap();
+    DefaultCommandHandler::new()
+        .handle(cli.command)
+        .expect("cross join should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "size,color\nS,red\nM,red\n");
+}
+
+#[test]
+fn test_cli_convert_with_delimiter() {
+    use clap::Parser;
+    use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("prices.csv");
+    let output = temp_dir.path().join("prices_tab.csv");
+    fs::write(&input, "item;price\nTea;1,50\n").expect("Failed to write CSV");
+
+    let cli = Cli::try_parse_from([
+        "datacell",
+        "convert",
+        "--input",
+        &input.to_string_lossy(),
+        "--output",
+        &output.to_string_lossy(),
+        "--delimiter",
+        "tab",
+    ])
+    .unwrap();
+    DefaultCommandHandler::new()
+        .handle(cli.command)
+        .expect("convert should succeed");
+
+    // Input delimiter is detected, output uses the requested one
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "item\tprice\nTea\t1,50\n");
+
+    let read = Cli::try_parse_from([
+        "datacell",
+        "read",
+        "--input",
+        &output.to_string_lossy(),
+        "--delimiter",
+        "\\t",
+    ])
+    .unwrap();
+    DefaultCommandHandler::new()
+        .handle(read.command)
+        .expect("read should succeed");
+
+    let bad = Cli::try_parse_from([
+        "datacell",
+        "convert",
+        "--input",
+        &input.to_string_lossy(),
+        "--output",
+        &output.to_string_lossy(),
+        "--delimiter",
+        "::",
+    ])
+    .unwrap();
+    assert!(DefaultCommandHandler::new().handle(bad.command).is_err());
+}
+
+#[test]
+fn test_cli_convert_with_encoding() {
+    use clap::Parser;
+    use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("export.csv");
+    let output = temp_dir.path().join("export_utf8.csv");
+    fs::write(&input, b"city;country\nS\xe3o Paulo;Brasil\n").expect("Failed to write CSV");
+
+    let cli = Cli::try_parse_from([
+        "datacell",
+        "convert",
+        "--input",
+        &input.to_string_lossy(),
+        "--output",
+        &output.to_string_lossy(),
+        "--encoding",
+        "latin1",
+    ])
+    .unwrap();
+    DefaultCommandHandler::new()
+        .handle(cli.command)
+        .expect("convert should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "city,country\nSão Paulo,Brasil\n");
+}
+
+#[test]
+fn test_cli_select_from_parquet() {
+    use datacell::ParquetHandler;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("wide.parquet");
+    let output = temp_dir.path().join("narrow.csv");
+    let names: Vec<String> = ["id", "name", "age", "city"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    ParquetHandler::new()
+        .write(
+            &input.to_string_lossy(),
+            &[vec![
+                "1".to_string(),
+                "Alice".to_string(),
+                "30".to_string(),
+                "Oslo".to_string(),
+            ]],
+            Some(&names),
+        )
+        .unwrap();
+
+    DefaultCommandHandler::new()
+        .handle(Commands::Select {
+            input: input.to_string_lossy().to_string(),
+            output: vec![output.to_string_lossy().to_string()].into(),
+            columns: "city,id".to_string(),
+        })
+        .expect("select should succeed");
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "city,id\nOslo,1\n");
+}
+
+#[test]
+fn test_cli_parses_and_dispatches_on_a_small_stack() {
+    use clap::Parser;
+    use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("data.csv");
+    let output = temp_dir.path().join("data.tsv");
+    fs::write(&input, "id,name\n1,Alice\n").expect("Failed to write CSV");
+    let args = [
+        "datacell".to_string(),
+        "convert".to_string(),
+        "--input".to_string(),
+        input.to_string_lossy().to_string(),
+        "--output".to_string(),
+        output.to_string_lossy().to_string(),
+    ];
+
+    // Half the default test thread stack, so a regrown parser or handler frame shows up here
+    std::thread::Builder::new()
+        .stack_size(1024 * 1024)
+        .spawn(move || {
+            let cli = Cli::try_parse_from(args).unwrap();
+            DefaultCommandHandler::new().handle(cli.command).unwrap();
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(fs::read_to_string(&output).unwrap(), "id\tname\n1\tAlice\n");
+}
+
+#[test]
+fn test_cli_convert_with_compression() {
+    use clap::Parser;
+    use datacell::ParquetHandler;
+    use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("data.csv");
+    let output = temp_dir.path().join("data.parquet");
+    fs::write(&input, "id,name\n1,Alice\n2,Bob\n").expect("Failed to write CSV");
+
+    let cli = Cli::try_parse_from([
+        "datacell",
+        "convert",
+        "--input",
+        &input.to_string_lossy(),
+        "--output",
+        &output.to_string_lossy(),
+        "--compression",
+        "zstd",
+    ])
+    .unwrap();
+    DefaultCommandHandler::new()
+        .handle(cli.command)
+        .expect("convert should succeed");
+
+    let data = ParquetHandler::new()
+        .read(&output.to_string_lossy())
+        .unwrap();
+    assert_eq!(data[0], vec!["1", "Alice"]);
+
+    let bad = Cli::try_parse_from([
+        "datacell",
+        "convert",
+        "--input",
+        &input.to_string_lossy(),
+        "--output",
+        &output.to_string_lossy(),
+        "--compression",
+        "rar",
+    ])
+    .unwrap();
+    assert!(DefaultCommandHandler::new().handle(bad.command).is_err());
+}
+
+#[test]
+fn test_cli_convert_to_columnar_keeps_header_and_types() {
+    use clap::Parser;
+    use datacell::cli::{Cli, CommandHandler, DefaultCommandHandler};
+    use datacell::{AvroHandler, Converter, ParquetHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("typed.csv");
+    fs::write(
+        &input,
+        "id,price,active,name\n1,9.5,true,Alice\n2,12,false,Bob\n",
+    )
+    .expect("Failed to write CSV");
+
+    for extension in ["parquet", "avro"] {
+        let output = temp_dir.path().join(format!("typed.{extension}"));
+        let output = output.to_string_lossy();
+        let cli = Cli::try_parse_from([
+            "datacell",
+            "convert",
+            "--input",
+            &input.to_string_lossy(),
+            "--output",
+            &output,
+        ])
+        .unwrap();
+        DefaultCommandHandler::new()
+            .handle(cli.command)
+            .expect("convert should succeed");
+
+        let schema = if extension == "parquet" {
+            ParquetHandler::new().schema(&output).unwrap()
+        } else {
+            AvroHandler::new().schema(&output).unwrap()
+        };
+        let names: Vec<&str> = schema.iter().map(|(name, _)| name.as_str()).collect();
+        let types: Vec<&str> = schema.iter().map(|(_, t)| t.as_str()).collect();
+        assert_eq!(names, ["id", "price", "active", "name"]);
+        if extension == "parquet" {
+            assert_eq!(types, ["Int64", "Float64", "Boolean", "Utf8"]);
+        } else {
+            assert_eq!(types, ["long", "double", "boolean", "string"]);
+        }
+
+        let data = Converter::new().read_any_data(&output, None).unwrap();
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0], vec!["id", "price", "active", "name"]);
+        assert_eq!(data[1][3], "Alice");
+    }
+}
+
+#[test]
+fn test_cli_schema_command() {
+    use datacell::ParquetHandler;
+    use datacell::cli::{CommandHandler, Commands, DefaultCommandHandler};
+
+    let temp_dir = setup_temp_dir();
+    let input = temp_dir.path().join("typed.parquet");
+    let csv = temp_dir.path().join("plain.csv");
+    let header = vec!["id".to_string(), "name".to_string()];
+    let data = vec![vec!["1".to_string(), "Alice".to_string()]];
+    ParquetHandler::new()
+        .write(&input.to_string_lossy(), &data, Some(&header))
+        .unwrap();
+    fs::write(&csv, "id,name\n1,Alice\n").expect("Failed to write CSV");
+
+    let handler = DefaultCommandHandler::new();
+    handler
+        .handle(Commands::Schema {
+            input: input.to_string_lossy().to_string(),
+        })
+        .expect("schema should succeed for Parquet");
+
+    // CSV stores no types, so there is nothing to report
+    assert!(
+        handler
+            .handle(Commands::Schema {
+                input: csv.to_string_lossy().to_string(),
+            })
+            .is_err()
+    );
+}
+
+/// Run the datacell binary with `stdin`, returning its stdout
+fn run_with_stdin(args: &[&str], stdin: &str) -> String {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_datacell"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to start datacell");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "datacell {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_cli_filter_and_sort_through_pipes() {
+    let people = "name,age\nAlice,30\nBob,25\nCara,41\n";
+
+    let filtered = run_with_stdin(&["filter", "-i", "-", "-o", "-", "-w", "age > 26"], people);
+    // Only data reaches stdout; the status line goes to stderr
+    assert_eq!(filtered, "name,age\nAlice,30\nCara,41\n");
+
+    let sorted = run_with_stdin(&["sort", "-i", "-", "-o", "-", "-c", "name"], &filtered);
+    assert_eq!(sorted, "name,age\nCara,41\nAlice,30\n");
+}
+
+#[test]
+fn test_cli_tail_index_counts_skipped_rows() {
+    let temp_dir = setup_temp_dir();
+    let csv = temp_dir.path().join("ids.csv");
+    let parquet = temp_dir.path().join("ids.parquet");
+    let rows: String = (0..20).map(|i| format!("{i}\n")).collect();
+    fs::write(&csv, format!("id\n{rows}")).expect("Failed to write test CSV");
+    let csv = csv.to_string_lossy().to_string();
+    let parquet = parquet.to_string_lossy().to_string();
+    run_with_stdin(&["convert", "-i", &csv, "-o", &parquet], "");
+
+    let expected = "17,17\n18,18\n19,19\n";
+    for input in [&csv, &parquet] {
+        let tail = run_with_stdin(&["tail", "-i", input, "-n", "3", "--index"], "");
+        assert_eq!(tail, expected, "tail of {input}");
+    }
+}
+
+#[test]
+fn test_cli_output_commands_index_flags() {
+    let people = "name,age\nAlice,30\nBob,25\nCara,41\n";
+
+    let filtered = run_with_stdin(
+        &[
+            "filter", "-i", "-", "-o", "-", "-w", "age > 26", "--index", "1",
+        ],
+        people,
+    );
+    assert_eq!(filtered, "index,name,age\n1,Alice,30\n2,Cara,41\n");
+
+    let dropped = run_with_stdin(
+        &["convert", "-i", "-", "-o", "-", "--drop-index"],
+        &filtered,
+    );
+    assert_eq!(dropped, "name,age\nAlice,30\nCara,41\n");
+
+    let selected = run_with_stdin(
+        &["select", "-i", "-", "-o", "-", "-c", "name", "--index"],
+        people,
+    );
+    assert_eq!(selected, "index,name\n0,Alice\n1,Bob\n2,Cara\n");
+}
+
+#[test]
+fn test_cli_plugin_reads_input_format_and_keeps_stdout_clean() {
+    let people = "name\tcity\nalice\tparis\n";
+
+    let upper = run_with_stdin(
+        &[
+            "--input-format",
+            "tsv",
+            "plugin",
+            "-f",
+            "uppercase",
+            "-i",
+            "-",
+            "-o",
+            "-",
+            "-a",
+            "0",
+        ],
+        people,
+    );
+    assert_eq!(upper, "name,city\nALICE,paris\n");
+}
+
+#[test]
+fn test_cli_replace_and_fillna_keep_stdout_clean() {
+    let people = "name,city\nAlice,\nBob,Paris\n";
+
+    let replaced = run_with_stdin(
+        &["replace", "-i", "-", "-o", "-", "-f", "Paris", "-r", "Rome"],
+        people,
+    );
+    assert_eq!(replaced, "name,city\nAlice,\nBob,Rome\n");
+
+    let filled = run_with_stdin(&["fillna", "-i", "-", "-o", "-", "-v", "n/a"], &replaced);
+    assert_eq!(filled, "name,city\nAlice,n/a\nBob,Rome\n");
+
+    let filled = run_with_stdin(
+        &["fillna", "-i", "-", "-o", "-", "-v", "?", "-c", "city"],
+        &replaced,
+    );
+    assert_eq!(filled, "name,city\nAlice,?\nBob,Rome\n");
+}
+
+#[test]
+fn test_cli_stdin_input_format() {
+    let people = "name\tage\nAlice\t30\nBob\t25\n";
+
+    let filtered = run_with_stdin(
+        &[
+            "--input-format",
+            "tsv",
+            "filter",
+            "-i",
+            "-",
+            "-o",
+            "-",
+            "-w",
+            "age > 26",
+        ],
+        people,
+    );
+    assert_eq!(filtered, "name,age\nAlice,30\n");
+}
+
+#[test]
+fn test_cli_dtypes_and_info() {
+    let people = "id,email\n1,ann@example.com\n2,\n3,cy@example.net\n4,dee@example.com\n5,ed@example.com\n6,fi@example.com\n";
+
+    let dtypes = run_with_stdin(&["dtypes", "-i", "-"], people);
+    assert!(dtypes.contains("id: Integer"), "{dtypes}");
+    assert!(dtypes.contains("email: Email"), "{dtypes}");
+
+    let info = run_with_stdin(&["info", "-i", "-"], people);
+    assert!(info.contains("Rows: 6"), "{info}");
+    assert!(info.contains("email: Email (5 non-null, 1 null)"), "{info}");
+    assert!(info.contains("Memory: ~"), "{info}");
+}
+
+#[test]
+fn test_cli_normalize_zscore_append() {
+    let output = run_with_stdin(
+        &[
+            "normalize",
+            "-i",
+            "-",
+            "-o",
+            "-",
+            "-c",
+            "x",
+            "-m",
+            "zscore",
+            "--append",
+        ],
+        "x\n2\n4\n6\n",
+    );
+    assert_eq!(output, "x,x_zscore\n2,-1.2247\n4,0.0000\n6,1.2247\n");
+}
+
+#[test]
+fn test_cli_mutate() {
+    let output = run_with_stdin(
+        &[
+            "mutate",
+            "-i",
+            "-",
+            "-o",
+            "-",
+            "-c",
+            "total",
+            "-f",
+            "price*qty",
+        ],
+        "price,qty\n2.5,4\n3,x\n",
+    );
+    assert_eq!(output, "price,qty,total\n2.5,4,10\n3,x,#VALUE!\n");
+}
+
+#[test]
+fn test_cli_fillna_methods() {
+    let readings = "day,temp\n1,10\n2,\n3,\n4,16\n";
+
+    let forward = run_with_stdin(&["fillna", "-i", "-", "-o", "-", "-m", "ffill"], readings);
+    assert_eq!(forward, "day,temp\n1,10\n2,10\n3,10\n4,16\n");
+
+    let linear = run_with_stdin(
+        &["fillna", "-i", "-", "-o", "-", "-m", "linear", "-c", "temp"],
+        readings,
+    );
+    assert_eq!(linear, "day,temp\n1,10\n2,12\n3,14\n4,16\n");
+}
+
+#[test]
+fn test_cli_detect_anomalies_mad() {
+    let output = run_with_stdin(
+        &["detect-anomalies", "-i", "-", "-c", "x", "-m", "mad"],
+        "x\n10\n11\n9\n10\n12\n500\n",
+    );
+    let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(report["total_anomalies"], 1, "{output}");
+    assert_eq!(report["anomalies"][0]["row"], 6);
+}
+
+#[test]
+fn test_cli_text_analysis_keywords_with_stopwords_and_stems() {
+    let temp_dir = setup_temp_dir();
+    let stopwords = temp_dir.path().join("stopwords.txt");
+    fs::write(&stopwords, "coach\nshoes\n").unwrap();
+
+    let output = run_with_stdin(
+        &[
+            "text-analysis",
+            "-i",
+            "-",
+            "-c",
+            "review",
+            "-p",
+            "keywords",
+            "--stem",
+            "--stopwords",
+            stopwords.to_str().unwrap(),
+        ],
+        "review\nThe coach loves running\nShoes for runs\n",
+    );
+    let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let words: Vec<&str> = report["keywords"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|k| k["word"].as_str().unwrap())
+        .collect();
+    assert!(words.contains(&"run"), "{output}");
+    assert!(!words.contains(&"coach"), "{output}");
+    assert!(!words.contains(&"the"), "{output}");
+}
+
+#[test]
+fn test_cli_text_analysis_per_row_output() {
+    let output = run_with_stdin(
+        &[
+            "text-analysis",
+            "-i",
+            "-",
+            "-c",
+            "review",
+            "-p",
+            "sentiment",
+            "-o",
+            "-",
+        ],
+        "review\ngreat amazing\nterrible awful\n",
+    );
+    assert_eq!(
+        output,
+        "review,sentiment,confidence\ngreat amazing,Positive,1.0000\nterrible awful,Negative,1.0000\n"
+    );
+}
+
+#[test]
+fn test_cli_password_never_comes_from_argv() {
+    use clap::Parser;
+    use datacell::cli::Cli;
+    use std::process::{Command, Stdio};
+
+    let argv = "datacell encrypt -i a.csv -o a.enc -a aes-gcm --password hunter2";
+    assert!(Cli::try_parse_from(argv.split(' ')).is_err());
+
+    let temp_dir = setup_temp_dir();
+    let file = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+    let (plain, sealed, opened) = (file("plain.csv"), file("plain.enc"), file("opened.csv"));
+    let password_file = file("password.txt");
+    let contents = "id,secret\n1,hunter2\n";
+    fs::write(&plain, contents).unwrap();
+    fs::write(&password_file, "correct horse\n").unwrap();
+
+    // Passwords imply aes-gcm and refuse the XOR placeholders
+    let encrypt = |algorithm: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_datacell"))
+            .args(["encrypt", "-i", &plain, "-o", &sealed])
+            .args(algorithm)
+            .args(["--password-file", &password_file])
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+    assert!(!encrypt(&["-a", "xor"]).status.success());
+    let encrypted = encrypt(&[]);
+    assert!(encrypted.status.success(), "{encrypted:?}");
+
+    // The same password from the environment decrypts it, with no algorithm named
+    let decrypt = |password: Option<&str>| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_datacell"));
+        command
+            .args(["decrypt", "-i", &sealed, "-o", &opened])
+            .arg("--password")
+            .env_remove("DATACELL_PASSWORD")
+            .stdin(Stdio::null());
+        if let Some(password) = password {
+            command.env("DATACELL_PASSWORD", password);
+        }
+        command.output().unwrap()
+    };
+    assert!(!decrypt(Some("wrong horse")).status.success());
+    let decrypted = decrypt(Some("correct horse"));
+    assert!(decrypted.status.success(), "{decrypted:?}");
+    assert_eq!(fs::read_to_string(&opened).unwrap(), contents);
+
+    // Without any password source there is no terminal to prompt on
+    let missing = decrypt(None);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("DATACELL_PASSWORD"));
+}
@@ -170,6 +170,73 @@ fn test_parquet_file_exists() {
     fs::remove_file(&path).ok();
 }
 
+/// Write `rows` sequential ids split into row groups of `group_size` rows
+fn write_multi_group_parquet(path: &str, rows: usize, group_size: usize) {
+    use arrow_array::{ArrayRef, RecordBatch, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, true)]));
+    let ids: Vec<String> = (0..rows).map(|i| i.to_string()).collect();
+    let column: ArrayRef = Arc::new(StringArray::from(ids));
+    let batch = RecordBatch::try_new(schema.clone(), vec![column]).unwrap();
+
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(group_size)
+        .build();
+    let file = fs::File::create(path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+}
+
+#[test]
+fn test_parquet_read_head_multi_row_group() {
+    let handler = ParquetHandler::new();
+    let path = unique_path("parquet_head", "parquet");
+    write_multi_group_parquet(&path, 100, 10);
+
+    let head = handler.read_head(&path, 15).unwrap();
+    assert_eq!(head.len(), 16);
+    assert_eq!(head[0][0], "id");
+    assert_eq!(head[1][0], "0");
+    assert_eq!(head[15][0], "14");
+
+    // Asking for more rows than exist returns everything
+    let all = handler.read_head(&path, 500).unwrap();
+    assert_eq!(all.len(), 101);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_parquet_read_tail_multi_row_group() {
+    let handler = ParquetHandler::new();
+    let path = unique_path("parquet_tail", "parquet");
+    write_multi_group_parquet(&path, 100, 10);
+
+    // Spans the last two row groups
+    let tail = handler.read_tail(&path, 13).unwrap();
+    assert_eq!(tail.len(), 14);
+    assert_eq!(tail[0][0], "id");
+    assert_eq!(tail[1][0], "87");
+    assert_eq!(tail[13][0], "99");
+
+    let full = handler.read_with_headers(&path).unwrap();
+    assert_eq!(&tail[1..], &full[88..]);
+
+    let all = handler.read_tail(&path, 500).unwrap();
+    assert_eq!(all.len(), 101);
+    assert_eq!(all[1][0], "0");
+
+    let none = handler.read_tail(&path, 0).unwrap();
+    assert_eq!(none.len(), 1);
+
+    fs::remove_file(&path).ok();
+}
+
 // ============ Avro Tests ============
 
 #[test]
@@ -255,3 +322,175 @@ fn test_parquet_get_schema() {
 
     fs::remove_file(&path).ok();
 }
+
+// ============ Type Inference Tests ============
+
+fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+    data.iter()
+        .map(|row| row.iter().map(|s| s.to_string()).collect())
+        .collect()
+}
+
+#[test]
+fn test_parquet_write_infers_integer_column() {
+    use parquet::basic::Type as PhysicalType;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let handler = ParquetHandler::new();
+    let header = vec!["id".to_string(), "name".to_string()];
+    let data = rows(&[&["1", "Alice"], &["2", "Bob"], &["-30", "Carol"]]);
+    let path = unique_path("parquet_int", "parquet");
+
+    handler.write(&path, &data, Some(&header)).unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let schema = reader.metadata().file_metadata().schema_descr();
+    assert_eq!(schema.column(0).physical_type(), PhysicalType::INT64);
+    assert_eq!(schema.column(1).physical_type(), PhysicalType::BYTE_ARRAY);
+
+    let read_data = handler.read_with_headers(&path).unwrap();
+    assert_eq!(read_data[0], header);
+    assert_eq!(read_data[1..], data[..]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_parquet_write_infers_float_and_boolean_columns() {
+    let handler = ParquetHandler::new();
+    let header: Vec<String> = ["price", "active", "zip", "mixed"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let data = rows(&[
+        &["1.5", "true", "02134", "10"],
+        &["2", "FALSE", "10001", "n/a"],
+        &["", "", "", ""],
+    ]);
+    let path = unique_path("parquet_types", "parquet");
+
+    handler.write(&path, &data, Some(&header)).unwrap();
+
+    let types: Vec<String> = handler
+        .get_schema(&path)
+        .unwrap()
+        .into_iter()
+        .map(|(_, t)| t)
+        .collect();
+    assert_eq!(types, vec!["Float64", "Boolean", "Utf8", "Utf8"]);
+
+    // Typed values render back as plain strings; empty cells come back empty
+    let read_data = handler.read(&path).unwrap();
+    assert_eq!(read_data[0], vec!["1.5", "true", "02134", "10"]);
+    assert_eq!(read_data[1], vec!["2", "false", "10001", "n/a"]);
+    assert_eq!(read_data[2], vec!["", "", "", ""]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_avro_write_infers_typed_fields() {
+    let handler = AvroHandler::new();
+    let header: Vec<String> = ["id", "score", "ok", "label"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let data = rows(&[&["1", "0.5", "true", "a"], &["2", "", "false", "b"]]);
+    let path = unique_path("avro_types", "avro");
+
+    handler.write(&path, &data, Some(&header)).unwrap();
+
+    let types: Vec<String> = handler
+        .schema(&path)
+        .unwrap()
+        .into_iter()
+        .map(|(_, t)| t)
+        .collect();
+    assert_eq!(types, vec!["long", "double", "boolean", "string"]);
+
+    let read_data = handler.read(&path).unwrap();
+    assert_eq!(read_data, data);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_parquet_schema_matches_written_types() {
+    let handler = ParquetHandler::new();
+    let header: Vec<String> = ["id", "ratio", "flag", "name"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let data = rows(&[&["7", "0.25", "false", "x"], &["8", "1", "true", "y"]]);
+    let path = unique_path("parquet_schema_typed", "parquet");
+
+    handler.write(&path, &data, Some(&header)).unwrap();
+
+    let schema = handler.schema(&path).unwrap();
+    let expected: Vec<(String, String)> = header
+        .iter()
+        .cloned()
+        .zip(["Int64", "Float64", "Boolean", "Utf8"].map(String::from))
+        .collect();
+    assert_eq!(schema, expected);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_avro_schema_reports_field_names() {
+    let handler = AvroHandler::new();
+    let header = vec!["when".to_string(), "count".to_string()];
+    let data = rows(&[&["monday", "3"]]);
+    let path = unique_path("avro_schema_typed", "avro");
+
+    handler.write(&path, &data, Some(&header)).unwrap();
+
+    let schema = handler.schema(&path).unwrap();
+    assert_eq!(
+        schema,
+        vec![
+            ("when".to_string(), "string".to_string()),
+            ("count".to_string(), "long".to_string()),
+        ]
+    );
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_avro_write_sanitizes_field_names() {
+    let handler = AvroHandler::new();
+    let header: Vec<String> = ["first name", "price ($)", "2nd", "first_name", "\"q\""]
+        .map(String::from)
+        .to_vec();
+    let data = rows(&[&["Ann", "9.5", "x", "y", "z"]]);
+    let path = unique_path("avro_field_names", "avro");
+
+    handler.write(&path, &data, Some(&header)).unwrap();
+
+    let names: Vec<String> = handler
+        .schema(&path)
+        .unwrap()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(
+        names,
+        vec!["first_name", "price____", "_2nd", "first_name_2", "_q_"]
+    );
+    let read = handler.read_with_headers(&path).unwrap();
+    assert_eq!(read[1], data[0]);
+
+    // Renamed fields keep the original header as their alias
+    let reader = apache_avro::Reader::new(fs::File::open(&path).unwrap()).unwrap();
+    let apache_avro::Schema::Record(record) = reader.writer_schema() else {
+        panic!("Avro schema is not a record");
+    };
+    let aliases = |i: usize| record.fields[i].aliases.clone().unwrap_or_default();
+    assert_eq!(aliases(0), ["first name"]);
+    assert_eq!(aliases(1), ["price ($)"]);
+    assert_eq!(aliases(3), ["first_name"]);
+
+    fs::remove_file(&path).ok();
+}
@@ -1,4 +1,4 @@
-use datacell::{Converter, CsvHandler, ExcelHandler};
+use datacell::{Converter, CsvHandler, ExcelHandler, PROGRESS_INTERVAL, ProgressCallback};
 use std::fs;
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -47,6 +47,63 @@ fn test_convert_csv_to_xlsx_with_sheet_name() {
     fs::remove_file(&xlsx_path).ok();
 }
 
+struct Recording(Vec<(usize, Option<usize>)>);
+
+impl ProgressCallback for Recording {
+    fn on_progress(&mut self, current: usize, total: Option<usize>, _message: &str) {
+        self.0.push((current, total));
+    }
+}
+
+#[test]
+fn test_convert_with_progress() {
+    let converter = Converter::new();
+    let csv_path = "examples/sales.csv";
+    let parquet_path = unique_path("conv_progress", "parquet");
+
+    let mut progress = Recording(Vec::new());
+    converter
+        .convert_with_progress(csv_path, &parquet_path, None, &mut progress)
+        .unwrap();
+
+    let rows = CsvHandler::new().read(csv_path).unwrap().lines().count();
+    assert_eq!(progress.0, vec![(0, Some(rows)), (rows, Some(rows))]);
+
+    fs::remove_file(&parquet_path).ok();
+}
+
+#[test]
+fn test_convert_to_csv_reports_every_interval() {
+    let rows = 2 * PROGRESS_INTERVAL + 10;
+    let csv_path = unique_path("conv_interval_in", "csv");
+    let tsv_path = unique_path("conv_interval_out", "tsv");
+    let data: Vec<Vec<String>> = (0..rows).map(|i| vec![i.to_string()]).collect();
+    CsvHandler::new().write_records(&csv_path, data).unwrap();
+
+    let mut progress = Recording(Vec::new());
+    Converter::new()
+        .convert_with_progress(&csv_path, &tsv_path, None, &mut progress)
+        .unwrap();
+
+    let total = Some(rows);
+    assert_eq!(
+        progress.0,
+        vec![
+            (0, total),
+            (PROGRESS_INTERVAL, total),
+            (2 * PROGRESS_INTERVAL, total),
+            (rows, total)
+        ]
+    );
+    assert_eq!(
+        CsvHandler::new().read(&tsv_path).unwrap().lines().count(),
+        rows
+    );
+
+    fs::remove_file(&csv_path).ok();
+    fs::remove_file(&tsv_path).ok();
+}
+
 // ============ Excel to CSV Conversion ============
 
 #[test]
@@ -272,3 +329,104 @@ fn test_convert_parquet_to_xlsx() {
 
     fs::remove_file(&output_path).ok();
 }
+
+#[test]
+fn test_convert_csv_to_ods() {
+    let converter = Converter::new();
+    let csv_path = unique_path("conv_csv_ods_src", "csv");
+    let ods_path = unique_path("conv_csv_ods", "ods");
+    fs::write(&csv_path, "city,population\nOslo,709000\nBergen,291000\n").unwrap();
+
+    converter
+        .convert(&csv_path, &ods_path, Some("Cities"))
+        .unwrap();
+
+    let handler = ExcelHandler::new();
+    let data = handler.read_ods_data(&ods_path, Some("Cities")).unwrap();
+    assert_eq!(data[0], vec!["city", "population"]);
+    assert_eq!(data[2], vec!["Bergen", "291000"]);
+
+    fs::remove_file(&csv_path).ok();
+    fs::remove_file(&ods_path).ok();
+}
+
+#[test]
+fn test_convert_csv_through_tsv_round_trip() {
+    let converter = Converter::new();
+    let csv_path = unique_path("conv_tsv_src", "csv");
+    let tsv_path = unique_path("conv_tsv", "tsv");
+    let back_path = unique_path("conv_tsv_back", "csv");
+    let rows = vec![
+        vec!["name".to_string(), "address".to_string()],
+        vec!["Ada".to_string(), "1 Main St, Springfield".to_string()],
+        vec!["Bob".to_string(), "tab\there".to_string()],
+    ];
+    converter.write_any_data(&csv_path, &rows, None).unwrap();
+
+    converter.convert(&csv_path, &tsv_path, None).unwrap();
+    let tsv = fs::read_to_string(&tsv_path).unwrap();
+    assert!(tsv.contains("Ada\t1 Main St, Springfield\n"));
+    assert!(tsv.contains("Bob\t\"tab\there\"\n"));
+
+    converter.convert(&tsv_path, &back_path, None).unwrap();
+    let data = converter.read_any_data(&back_path, None).unwrap();
+    assert_eq!(data, rows);
+
+    fs::remove_file(&csv_path).ok();
+    fs::remove_file(&tsv_path).ok();
+    fs::remove_file(&back_path).ok();
+}
+
+#[test]
+fn test_convert_dir_csv_to_parquet() {
+    let input_dir = unique_path("conv_dir_in", "d");
+    let output_dir = unique_path("conv_dir_out", "d");
+    fs::create_dir_all(&input_dir).unwrap();
+    for (name, rows) in [("north", "1,2"), ("south", "3,4"), ("west", "5,6")] {
+        fs::write(format!("{input_dir}/{name}.csv"), format!("a,b\n{rows}\n")).unwrap();
+    }
+    // Not actually Parquet, so reading it fails
+    fs::write(format!("{input_dir}/broken.parquet"), "a,b\n1,2\n").unwrap();
+    fs::write(format!("{input_dir}/notes.txt"), "not a data file").unwrap();
+
+    let converter = Converter::new();
+    let summary = converter
+        .convert_dir(&input_dir, &output_dir, "parquet", None)
+        .unwrap();
+
+    assert_eq!(summary.converted.len(), 3);
+    for name in ["north", "south", "west"] {
+        assert!(Path::new(&format!("{output_dir}/{name}.parquet")).exists());
+    }
+    assert_eq!(summary.failed.len(), 1);
+    assert!(summary.failed[0].0.ends_with("broken.parquet"));
+
+    fs::remove_dir_all(&input_dir).ok();
+    fs::remove_dir_all(&output_dir).ok();
+}
+
+#[test]
+fn test_convert_dir_keeps_same_stem_inputs_apart() {
+    let input_dir = unique_path("conv_dir_stem_in", "d");
+    let output_dir = unique_path("conv_dir_stem_out", "d");
+    fs::create_dir_all(&input_dir).unwrap();
+    fs::write(format!("{input_dir}/x.csv"), "a,b\n1,2\n").unwrap();
+    fs::write(format!("{input_dir}/x.tsv"), "a\tb\n3\t4\n").unwrap();
+    fs::write(format!("{input_dir}/y.csv"), "a,b\n5,6\n").unwrap();
+
+    let converter = Converter::new();
+    let summary = converter
+        .convert_dir(&input_dir, &output_dir, "csv", None)
+        .unwrap();
+
+    assert!(summary.failed.is_empty(), "{:?}", summary.failed);
+    assert_eq!(summary.converted.len(), 3);
+    let read = |name: &str| fs::read_to_string(format!("{output_dir}/{name}")).unwrap();
+    assert_eq!(read("x.csv.csv"), "a,b\n1,2\n");
+    assert_eq!(read("x.tsv.csv"), "a,b\n3,4\n");
+    assert_eq!(read("y.csv"), "a,b\n5,6\n");
+    assert!(!Path::new(&format!("{output_dir}/x.csv")).exists());
+
+    fs::remove_dir_all(&input_dir).ok();
+    fs::remove_dir_all(&output_dir).ok();
+}
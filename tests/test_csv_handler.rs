@@ -1,6 +1,9 @@
 //! Tests for CSV handler and streaming
 
-use datacell::{CellRange, CsvHandler, StreamingCsvReader, StreamingCsvWriter};
+use datacell::{
+    CellRange, CsvHandler, CsvOptions, DataReader, DefaultFormatDetector, FormatDetector,
+    StreamingCsvReader, StreamingCsvWriter,
+};
 use std::fs;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -106,6 +109,290 @@ fn test_csv_handler_read_range_middle() {
     assert_eq!(data[0][1], "3");
 }
 
+#[test]
+fn test_csv_handler_detects_delimiter() {
+    let semicolon = unique_path("detect_semicolon");
+    let tab = unique_path("detect_tab");
+    fs::write(
+        &semicolon,
+        "name;city;note\nAda;Paris;\"a, b\"\nBob;Rome;c\n",
+    )
+    .unwrap();
+    fs::write(&tab, "id\tvalue\n1\t2,5\n2\t3,5\n").unwrap();
+
+    assert_eq!(CsvHandler::detect_delimiter(&semicolon).unwrap(), b';');
+    assert_eq!(CsvHandler::detect_delimiter(&tab).unwrap(), b'\t');
+
+    let handler = CsvHandler::new();
+    let data = DataReader::read(&handler, &semicolon).unwrap();
+    assert_eq!(data[0], vec!["name", "city", "note"]);
+    assert_eq!(data[1], vec!["Ada", "Paris", "a, b"]);
+
+    let data = DataReader::read(&handler, &tab).unwrap();
+    assert_eq!(data[1], vec!["1", "2,5"]);
+
+    fs::remove_file(&semicolon).ok();
+    fs::remove_file(&tab).ok();
+}
+
+#[test]
+fn test_csv_handler_detect_delimiter_defaults_to_comma() {
+    let path = unique_path("detect_single_column");
+    fs::write(&path, "value\n1\n2\n").unwrap();
+
+    assert_eq!(CsvHandler::detect_delimiter(&path).unwrap(), b',');
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_handler_with_delimiter_override() {
+    let path = unique_path("delimiter_override");
+    fs::write(&path, "a;b|c\n1;2|3\n").unwrap();
+
+    let data = DataReader::read(&CsvHandler::with_delimiter(b'|'), &path).unwrap();
+    assert_eq!(data[0], vec!["a;b", "c"]);
+    assert_eq!(data[1], vec!["1;2", "3"]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_options_round_trip_semicolon_and_tab() {
+    let rows = vec![
+        vec!["name".to_string(), "price".to_string(), "note".to_string()],
+        vec!["Café".to_string(), "1,50".to_string(), "a;b\tc".to_string()],
+        vec![
+            "Tea".to_string(),
+            "2,00".to_string(),
+            "say \"hi\"".to_string(),
+        ],
+    ];
+
+    for delimiter in [b';', b'\t'] {
+        let path = unique_path("options_round_trip");
+        let handler = CsvHandler::with_options(CsvOptions {
+            delimiter,
+            ..CsvOptions::default()
+        });
+
+        handler.write_records(&path, rows.clone()).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(&format!("name{}price", delimiter as char)));
+
+        assert_eq!(DataReader::read(&handler, &path).unwrap(), rows);
+        // Auto-detection reads the same file back unchanged
+        assert_eq!(DataReader::read(&CsvHandler::new(), &path).unwrap(), rows);
+
+        fs::remove_file(&path).ok();
+    }
+}
+
+#[test]
+fn test_csv_options_quote_and_headers() {
+    let path = unique_path("options_quote");
+    fs::write(&path, "id;label\n1;'x;y'\n").unwrap();
+
+    let options = CsvOptions {
+        delimiter: b';',
+        quote: b'\'',
+        has_headers: true,
+        ..CsvOptions::default()
+    };
+    let data = DataReader::read(&CsvHandler::with_options(options), &path).unwrap();
+    assert_eq!(data, vec![vec!["1", "x;y"]]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_options_range_and_copy() {
+    let input = unique_path("options_range_in");
+    let output = unique_path("options_range_out");
+    fs::write(&input, "a;b;c\n1;2;3\n").unwrap();
+
+    let handler = CsvHandler::with_delimiter(b';');
+    let range = CellRange::parse("B1:C2").unwrap();
+    assert_eq!(
+        handler.read_range(&input, &range).unwrap(),
+        vec![vec!["b", "c"], vec!["2", "3"]]
+    );
+
+    handler.write_from_csv(&input, &output).unwrap();
+    handler
+        .write_range(&output, &[vec!["9".to_string()]], 1, 1)
+        .unwrap();
+    assert_eq!(fs::read_to_string(&output).unwrap(), "a;b;c\n1;9;3\n");
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
+#[test]
+fn test_csv_options_copy_keeps_header() {
+    let input = unique_path("options_copy_in");
+    let output = unique_path("options_copy_out");
+    fs::write(&input, "name;qty\nbolt;4\n").unwrap();
+
+    let options = CsvOptions {
+        delimiter: b';',
+        has_headers: true,
+        ..CsvOptions::default()
+    };
+    CsvHandler::with_options(options)
+        .write_from_csv(&input, &output)
+        .unwrap();
+    assert_eq!(fs::read_to_string(&output).unwrap(), "name;qty\nbolt;4\n");
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
+#[test]
+fn test_csv_options_parse_delimiter() {
+    assert_eq!(CsvOptions::parse_delimiter(";").unwrap(), b';');
+    assert_eq!(CsvOptions::parse_delimiter("tab").unwrap(), b'\t');
+    assert_eq!(CsvOptions::parse_delimiter("\\t").unwrap(), b'\t');
+    assert_eq!(CsvOptions::parse_delimiter("Pipe").unwrap(), b'|');
+    assert!(CsvOptions::parse_delimiter(";;").is_err());
+    assert!(CsvOptions::parse_delimiter("").is_err());
+}
+
+#[test]
+fn test_csv_handler_reads_latin1() {
+    let path = unique_path("latin1");
+    // "name;city\nJosé;Zürich\n" encoded as ISO-8859-1
+    let bytes = b"name;city\nJos\xe9;Z\xfcrich\n";
+    fs::write(&path, bytes).unwrap();
+
+    let handler = CsvHandler::new().with_encoding(encoding_rs::WINDOWS_1252);
+    let data = DataReader::read(&handler, &path).unwrap();
+    assert_eq!(data[1], vec!["José", "Zürich"]);
+
+    // Without the option the bytes are not valid UTF-8
+    let lossy = DataReader::read(&CsvHandler::new(), &path).unwrap();
+    assert_eq!(lossy[1][0], "Jos\u{FFFD}");
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_handler_reads_utf16le_with_bom() {
+    let path = unique_path("utf16");
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in "id\tnaïve\n1\t日本\n".encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    fs::write(&path, bytes).unwrap();
+
+    // The BOM is detected even though the handler defaults to UTF-8
+    let data = DataReader::read(&CsvHandler::new(), &path).unwrap();
+    assert_eq!(data, vec![vec!["id", "naïve"], vec!["1", "日本"]]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_handler_strips_utf8_bom() {
+    let path = unique_path("utf8_bom");
+    fs::write(&path, b"\xEF\xBB\xBFname,age\nAda,36\n").unwrap();
+
+    let data = DataReader::read(&CsvHandler::new(), &path).unwrap();
+    assert_eq!(data[0], vec!["name", "age"]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_handler_writes_latin1() {
+    let path = unique_path("latin1_write");
+    let handler = CsvHandler::new().with_encoding(encoding_rs::WINDOWS_1252);
+    let rows = vec![
+        vec!["name".to_string(), "note".to_string()],
+        vec!["Müller".to_string(), "crème brûlée".to_string()],
+    ];
+
+    handler.write_records(&path, rows.clone()).unwrap();
+    assert_eq!(
+        fs::read(&path).unwrap(),
+        b"name,note\nM\xfcller,cr\xe8me br\xfbl\xe9e\n"
+    );
+    assert_eq!(DataReader::read(&handler, &path).unwrap(), rows);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_encoding_writer_finish() {
+    use datacell::text_encoding::EncodingWriter;
+    use std::io::Write;
+
+    // A stateful encoding only returns to ASCII in its closing sequence
+    let mut writer = EncodingWriter::new(Vec::new(), encoding_rs::ISO_2022_JP).unwrap();
+    writer.write_all("日本".as_bytes()).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(writer.get_mut(), b"\x1b$BF|K\\\x1b(B");
+
+    // Text cut off inside a UTF-8 sequence is reported, not dropped
+    let mut writer = EncodingWriter::new(Vec::new(), encoding_rs::WINDOWS_1252).unwrap();
+    writer.write_all(&"é".as_bytes()[..1]).unwrap();
+    assert!(writer.finish().is_err());
+}
+
+#[test]
+fn test_csv_handler_rejects_writing_utf16() {
+    let path = unique_path("utf16_write");
+    let handler = CsvHandler::new().with_encoding(encoding_rs::UTF_16LE);
+
+    assert!(
+        handler
+            .write_records(&path, vec![vec!["a".to_string()]])
+            .is_err()
+    );
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_handler_read_text_strips_bom() {
+    let path = unique_path("bom_text");
+    fs::write(&path, b"\xEF\xBB\xBFProduct,Price\nTea,2\n").unwrap();
+
+    let content = CsvHandler::new().read(&path).unwrap();
+    assert_eq!(content, "Product,Price\nTea,2\n");
+
+    let data = datacell::Converter::new()
+        .read_any_data(&path, None)
+        .unwrap();
+    assert_eq!(data[0][0], "Product");
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_csv_handler_write_bom_only_when_requested() {
+    let plain = unique_path("no_bom");
+    let with_bom = unique_path("with_bom");
+    let rows = vec![vec!["a".to_string(), "b".to_string()]];
+
+    CsvHandler::new()
+        .write_records(&plain, rows.clone())
+        .unwrap();
+    assert_eq!(fs::read(&plain).unwrap(), b"a,b\n");
+
+    let handler = CsvHandler::with_options(CsvOptions {
+        write_bom: true,
+        ..CsvOptions::default()
+    });
+    handler.write_records(&with_bom, rows.clone()).unwrap();
+    // Appending to a non-empty file must not insert a second BOM
+    handler.append_records(&with_bom, &rows).unwrap();
+    assert_eq!(fs::read(&with_bom).unwrap(), b"\xEF\xBB\xBFa,b\na,b\n");
+
+    fs::remove_file(&plain).ok();
+    fs::remove_file(&with_bom).ok();
+}
+
 // ============ CsvHandler Write Tests ============
 
 #[test]
@@ -269,6 +556,125 @@ fn test_streaming_large_file() {
     fs::remove_file(&path).ok();
 }
 
+#[test]
+fn test_streaming_csv_with_options() {
+    let path = unique_path("streaming_options");
+    let options = CsvOptions {
+        delimiter: b'\t',
+        ..CsvOptions::default()
+    };
+
+    {
+        let mut writer = StreamingCsvWriter::create_with_options(&path, options).unwrap();
+        writer
+            .write_row(&["A".to_string(), "B,C".to_string()])
+            .unwrap();
+        writer.flush().unwrap();
+    }
+    {
+        let mut writer = StreamingCsvWriter::append_with_options(&path, options).unwrap();
+        writer
+            .write_row(&["1".to_string(), "2".to_string()])
+            .unwrap();
+        writer.flush().unwrap();
+    }
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "A\tB,C\n1\t2\n");
+
+    let reader = StreamingCsvReader::open_with_options(&path, options).unwrap();
+    let rows: Vec<_> = reader.filter_map(|r| r.ok()).collect();
+    assert_eq!(rows, vec![vec!["A", "B,C"], vec!["1", "2"]]);
+
+    fs::remove_file(&path).ok();
+}
+
+// ============ Gzip Tests ============
+
+fn gunzip(path: &str) -> Vec<u8> {
+    use std::io::Read;
+
+    let mut bytes = Vec::new();
+    flate2::read::MultiGzDecoder::new(fs::File::open(path).unwrap())
+        .read_to_end(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn test_csv_handler_gzip_round_trip() {
+    let plain = unique_path("gzip_plain");
+    let gz = format!("{}.gz", unique_path("gzip_round_trip"));
+    let rows = vec![
+        vec!["id".to_string(), "note".to_string()],
+        vec!["1".to_string(), "a, \"quoted\" value".to_string()],
+        vec!["2".to_string(), "é".to_string()],
+    ];
+
+    let handler = CsvHandler::new();
+    handler.write_records(&plain, rows.clone()).unwrap();
+    handler.write_records(&gz, rows.clone()).unwrap();
+
+    let compressed = fs::read(&gz).unwrap();
+    assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+    assert_eq!(gunzip(&gz), fs::read(&plain).unwrap());
+
+    assert_eq!(
+        DefaultFormatDetector.detect_format(&gz).unwrap(),
+        "csv".to_string()
+    );
+    assert_eq!(DataReader::read(&handler, &gz).unwrap(), rows);
+    assert_eq!(
+        datacell::Converter::new().read_any_data(&gz, None).unwrap(),
+        rows
+    );
+
+    fs::remove_file(&plain).ok();
+    fs::remove_file(&gz).ok();
+}
+
+#[test]
+fn test_csv_handler_gzip_append_and_stream() {
+    let gz = format!("{}.gz", unique_path("gzip_stream"));
+
+    {
+        let mut writer = StreamingCsvWriter::create(&gz).unwrap();
+        writer
+            .write_row(&["A".to_string(), "B".to_string()])
+            .unwrap();
+        writer
+            .write_row(&["1".to_string(), "2".to_string()])
+            .unwrap();
+    }
+    CsvHandler::new()
+        .append_records(&gz, &[vec!["3".to_string(), "4".to_string()]])
+        .unwrap();
+
+    assert_eq!(gunzip(&gz), b"A,B\n1,2\n3,4\n");
+
+    let reader = StreamingCsvReader::open(&gz).unwrap();
+    let rows: Vec<_> = reader.filter_map(|r| r.ok()).collect();
+    assert_eq!(rows, vec![vec!["A", "B"], vec!["1", "2"], vec!["3", "4"]]);
+
+    fs::remove_file(&gz).ok();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_csv_handler_gzip_reports_write_errors() {
+    // Compressed bytes only reach the file when the trailer is written
+    let dir = tempfile::tempdir().unwrap();
+    let gz = dir.path().join("full.csv.gz");
+    std::os::unix::fs::symlink("/dev/full", &gz).unwrap();
+    let gz = gz.to_string_lossy().to_string();
+    let rows = vec![vec!["id".to_string()], vec!["1".to_string()]];
+
+    assert!(CsvHandler::new().write_records(&gz, rows.clone()).is_err());
+
+    let mut writer = StreamingCsvWriter::create(&gz).unwrap();
+    writer.write_row(&rows[1]).unwrap();
+    assert!(writer.finish().is_err());
+}
+
 // ============ Edge Cases ============
 
 #[test]
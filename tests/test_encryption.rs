@@ -1,6 +1,6 @@
 //! Tests for encryption module
 
-use datacell::encryption::{DataEncryptor, EncryptionAlgorithm};
+use datacell::encryption::{DataEncryptor, EncryptionAlgorithm, SALT_LEN};
 use std::fs;
 use tempfile::tempdir;
 
@@ -138,3 +138,130 @@ fn test_load_key_from_nonexistent_file() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_aes_gcm_file_round_trip() {
+    let dir = tempdir().unwrap();
+    let input_path = dir.path().join("input.csv");
+    let encrypted_path = dir.path().join("encrypted.bin");
+    let decrypted_path = dir.path().join("decrypted.csv");
+    let key_path = dir.path().join("key.bin");
+
+    let original_data = "id,secret\n1,alpha\n2,beta\n";
+    fs::write(&input_path, original_data).unwrap();
+    fs::write(&key_path, [7u8; 32]).unwrap();
+
+    let encryptor = DataEncryptor::new(EncryptionAlgorithm::Aes256Gcm);
+    let key = encryptor
+        .load_key_from_file(key_path.to_str().unwrap())
+        .unwrap();
+
+    encryptor
+        .encrypt_file(
+            input_path.to_str().unwrap(),
+            encrypted_path.to_str().unwrap(),
+            &key,
+        )
+        .unwrap();
+
+    // 12-byte nonce, ciphertext, 16-byte tag
+    let encrypted = fs::read(&encrypted_path).unwrap();
+    assert_eq!(encrypted.len(), 12 + original_data.len() + 16);
+
+    encryptor
+        .decrypt_file(
+            encrypted_path.to_str().unwrap(),
+            decrypted_path.to_str().unwrap(),
+            &key,
+        )
+        .unwrap();
+    assert_eq!(fs::read_to_string(&decrypted_path).unwrap(), original_data);
+
+    // A fresh nonce makes each encryption of the same data differ
+    let again = encryptor
+        .encrypt_data(original_data.as_bytes(), &key)
+        .unwrap();
+    assert_ne!(again, encrypted);
+}
+
+#[test]
+fn test_aes_gcm_detects_tampering() {
+    let encryptor = DataEncryptor::new(EncryptionAlgorithm::Aes256Gcm);
+    let key = [42u8; 32];
+
+    let mut encrypted = encryptor.encrypt_data(b"amount,100", &key).unwrap();
+    let last = encrypted.len() - 1;
+    encrypted[last] ^= 0x01;
+
+    let error = encryptor.decrypt_data(&encrypted, &key).unwrap_err();
+    assert!(
+        error.to_string().contains("authentication failed"),
+        "{error}"
+    );
+
+    let wrong_key = encryptor.encrypt_data(b"amount,100", &[1u8; 32]).unwrap();
+    assert!(encryptor.decrypt_data(&wrong_key, &key).is_err());
+
+    let short_key = encryptor.encrypt_data(b"data", b"too-short");
+    assert!(short_key.unwrap_err().to_string().contains("32-byte key"));
+}
+
+#[test]
+fn test_password_derived_key_round_trip() {
+    let encryptor = DataEncryptor::new(EncryptionAlgorithm::Aes256Gcm);
+    let data = b"id,salary\n1,50000\n";
+
+    let encrypted = encryptor
+        .encrypt_data_with_password(data, "correct horse battery staple")
+        .unwrap();
+    assert!(encrypted.starts_with(b"DCPWD1"));
+
+    // The algorithm id follows the header, then the salt that reproduces the key
+    assert_eq!(encrypted[6], 1);
+    let salt = &encrypted[7..7 + SALT_LEN];
+    let key = DataEncryptor::derive_key("correct horse battery staple", salt).unwrap();
+    assert_eq!(
+        key,
+        DataEncryptor::derive_key("correct horse battery staple", salt).unwrap()
+    );
+    assert_eq!(
+        encryptor
+            .decrypt_data(&encrypted[7 + SALT_LEN..], &key)
+            .unwrap(),
+        data
+    );
+
+    let decrypted = encryptor
+        .decrypt_data_with_password(&encrypted, "correct horse battery staple")
+        .unwrap();
+    assert_eq!(decrypted, data);
+
+    let wrong = encryptor
+        .decrypt_data_with_password(&encrypted, "Tr0ub4dor&3")
+        .unwrap_err();
+    assert!(wrong.to_string().contains("authentication failed"));
+
+    let raw = encryptor.encrypt_data(data, &key).unwrap();
+    assert!(encryptor.decrypt_data_with_password(&raw, "x").is_err());
+}
+
+#[test]
+fn test_password_encryption_is_always_aes_gcm() {
+    let data = b"id,salary\n1,50000\n";
+    for algorithm in [EncryptionAlgorithm::Aes256, EncryptionAlgorithm::Xor] {
+        let encryptor = DataEncryptor::new(algorithm);
+        let err = encryptor
+            .encrypt_data_with_password(data, "secret")
+            .unwrap_err();
+        assert!(err.to_string().contains("AES-256-GCM"), "{err}");
+    }
+
+    // Decryption follows the header, not the encryptor's algorithm
+    let encrypted = DataEncryptor::new(EncryptionAlgorithm::Aes256Gcm)
+        .encrypt_data_with_password(data, "secret")
+        .unwrap();
+    let xor = DataEncryptor::new(EncryptionAlgorithm::Xor);
+    let decrypted = xor.decrypt_data_with_password(&encrypted, "secret");
+    assert_eq!(decrypted.unwrap(), data);
+    assert!(xor.decrypt_data_with_password(&encrypted, "wrong").is_err());
+}
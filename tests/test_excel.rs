@@ -1,13 +1,16 @@
-use datacell::{CellStyle, ChartConfig, DataChartType, ExcelHandler, WriteOptions};
+use datacell::{
+    CellStyle, ChartConfig, ChartGrouping, DataChartType, ExcelHandler, FormulaEvaluator, RowData,
+    WriteOptions, XlsxWriter,
+};
 use std::fs;
 use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
 
-static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
-
-fn unique_path(prefix: &str, ext: &str) -> String {
-    let id = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
-    format!("test_{prefix}_{id}.{ext}")
+fn unique_path(dir: &TempDir, prefix: &str, ext: &str) -> String {
+    dir.path()
+        .join(format!("{prefix}.{ext}"))
+        .to_string_lossy()
+        .into_owned()
 }
 
 fn read_example_csv(name: &str) -> Vec<Vec<String>> {
@@ -71,10 +74,11 @@ fn test_excel_example_read_as_json() {
 
 #[test]
 fn test_excel_write_and_read() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = read_example_csv("numbers");
 
-    let output_path = unique_path("excel_rw", "xlsx");
+    let output_path = unique_path(&dir, "excel_rw", "xlsx");
 
     // Write to Excel
     let options = WriteOptions::default();
@@ -90,11 +94,45 @@ fn test_excel_write_and_read() {
     fs::remove_file(&output_path).ok();
 }
 
+#[test]
+fn test_excel_read_quoted_comma_and_newline() {
+    let dir = TempDir::new().unwrap();
+    use datacell::{Converter, DataReader};
+
+    let handler = ExcelHandler::new();
+    let data = vec![
+        vec!["id".to_string(), "note".to_string(), "city".to_string()],
+        vec!["1".to_string(), "a,b".to_string(), "Paris".to_string()],
+        vec![
+            "2".to_string(),
+            "line1\nline2".to_string(),
+            "Rome".to_string(),
+        ],
+    ];
+
+    let output_path = unique_path(&dir, "excel_quoted", "xlsx");
+    handler
+        .write_styled(&output_path, &data, &WriteOptions::default())
+        .unwrap();
+
+    let rows = DataReader::read(&handler, &output_path).unwrap();
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|r| r.len() == 3));
+    assert_eq!(rows[1][1], "a,b");
+    assert_eq!(rows[2][1], "line1\nline2");
+
+    let via_converter = Converter::new().read_any_data(&output_path, None).unwrap();
+    assert_eq!(via_converter, rows);
+
+    fs::remove_file(&output_path).ok();
+}
+
 #[test]
 fn test_excel_write_from_csv() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let csv_path = "examples/sales.csv";
-    let output_path = unique_path("excel_from_csv", "xlsx");
+    let output_path = unique_path(&dir, "excel_from_csv", "xlsx");
 
     handler
         .write_from_csv(csv_path, &output_path, Some("Sales"))
@@ -111,9 +149,10 @@ fn test_excel_write_from_csv() {
 
 #[test]
 fn test_excel_read_range() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let csv_path = "examples/numbers.csv";
-    let excel_path = unique_path("excel_range", "xlsx");
+    let excel_path = unique_path(&dir, "excel_range", "xlsx");
 
     // First create an Excel file
     handler.write_from_csv(csv_path, &excel_path, None).unwrap();
@@ -130,9 +169,10 @@ fn test_excel_read_range() {
 
 #[test]
 fn test_excel_list_sheets() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let csv_path = "examples/employees.csv";
-    let excel_path = unique_path("excel_sheets", "xlsx");
+    let excel_path = unique_path(&dir, "excel_sheets", "xlsx");
 
     handler
         .write_from_csv(csv_path, &excel_path, Some("Employees"))
@@ -148,9 +188,10 @@ fn test_excel_list_sheets() {
 
 #[test]
 fn test_excel_read_as_json() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let csv_path = "examples/lookup.csv";
-    let excel_path = unique_path("excel_json", "xlsx");
+    let excel_path = unique_path(&dir, "excel_json", "xlsx");
 
     handler.write_from_csv(csv_path, &excel_path, None).unwrap();
 
@@ -166,9 +207,10 @@ fn test_excel_read_as_json() {
 
 #[test]
 fn test_excel_write_styled_with_header() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = read_example_csv("sales");
-    let output_path = unique_path("excel_styled", "xlsx");
+    let output_path = unique_path(&dir, "excel_styled", "xlsx");
 
     let options = WriteOptions {
         sheet_name: Some("StyledSheet".to_string()),
@@ -176,6 +218,7 @@ fn test_excel_write_styled_with_header() {
         header_style: CellStyle::header(),
         column_styles: None,
         freeze_header: true,
+        freeze_panes: None,
         auto_filter: true,
         auto_fit: true,
     };
@@ -272,6 +315,7 @@ fn test_chart_config_default() {
 
 #[test]
 fn test_write_with_chart_column() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = vec![
         vec!["Category".to_string(), "Value".to_string()],
@@ -279,7 +323,7 @@ fn test_write_with_chart_column() {
         vec!["B".to_string(), "20".to_string()],
         vec!["C".to_string(), "30".to_string()],
     ];
-    let output_path = unique_path("chart_column", "xlsx");
+    let output_path = unique_path(&dir, "chart_column", "xlsx");
 
     let config = ChartConfig {
         chart_type: DataChartType::Column,
@@ -292,6 +336,9 @@ fn test_write_with_chart_column() {
         height: 400,
         show_legend: true,
         colors: None,
+        start_cell: None,
+        grouping: ChartGrouping::Clustered,
+        series_types: Vec::new(),
     };
 
     handler.write_with_chart(&output_path, &data, &config).unwrap();
@@ -306,9 +353,10 @@ fn test_write_with_chart_column() {
 
 #[test]
 fn test_write_with_chart_bar() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = read_example_csv("numbers");
-    let output_path = unique_path("chart_bar", "xlsx");
+    let output_path = unique_path(&dir, "chart_bar", "xlsx");
 
     let config = ChartConfig {
         chart_type: DataChartType::Bar,
@@ -326,6 +374,7 @@ fn test_write_with_chart_bar() {
 
 #[test]
 fn test_write_with_chart_line() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = vec![
         vec![
@@ -337,7 +386,7 @@ fn test_write_with_chart_line() {
         vec!["Feb".to_string(), "120".to_string(), "90".to_string()],
         vec!["Mar".to_string(), "140".to_string(), "100".to_string()],
     ];
-    let output_path = unique_path("chart_line", "xlsx");
+    let output_path = unique_path(&dir, "chart_line", "xlsx");
 
     let config = ChartConfig {
         chart_type: DataChartType::Line,
@@ -360,6 +409,7 @@ fn test_write_with_chart_line() {
 
 #[test]
 fn test_write_with_chart_pie() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = vec![
         vec!["Category".to_string(), "Share".to_string()],
@@ -367,7 +417,7 @@ fn test_write_with_chart_pie() {
         vec!["Furniture".to_string(), "30".to_string()],
         vec!["Office".to_string(), "25".to_string()],
     ];
-    let output_path = unique_path("chart_pie", "xlsx");
+    let output_path = unique_path(&dir, "chart_pie", "xlsx");
 
     let config = ChartConfig {
         chart_type: DataChartType::Pie,
@@ -385,6 +435,7 @@ fn test_write_with_chart_pie() {
 
 #[test]
 fn test_write_with_chart_custom_colors() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = vec![
         vec!["X".to_string(), "Y".to_string()],
@@ -392,7 +443,7 @@ fn test_write_with_chart_custom_colors() {
         vec!["2".to_string(), "20".to_string()],
         vec!["3".to_string(), "15".to_string()],
     ];
-    let output_path = unique_path("chart_colors", "xlsx");
+    let output_path = unique_path(&dir, "chart_colors", "xlsx");
 
     let config = ChartConfig {
         chart_type: DataChartType::Column,
@@ -411,13 +462,14 @@ fn test_write_with_chart_custom_colors() {
 
 #[test]
 fn test_write_with_chart_no_legend() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = vec![
         vec!["X".to_string(), "Y".to_string()],
         vec!["A".to_string(), "50".to_string()],
         vec!["B".to_string(), "75".to_string()],
     ];
-    let output_path = unique_path("chart_no_legend", "xlsx");
+    let output_path = unique_path(&dir, "chart_no_legend", "xlsx");
 
     let config = ChartConfig {
         chart_type: DataChartType::Column,
@@ -435,12 +487,13 @@ fn test_write_with_chart_no_legend() {
 
 #[test]
 fn test_write_range() {
+    let dir = TempDir::new().unwrap();
     let handler = ExcelHandler::new();
     let data = vec![
         vec!["X".to_string(), "Y".to_string()],
         vec!["1".to_string(), "2".to_string()],
     ];
-    let output_path = unique_path("excel_write_range", "xlsx");
+    let output_path = unique_path(&dir, "excel_write_range", "xlsx");
 
     // Write starting at B2 (row 1, col 1)
     handler
@@ -470,3 +523,294 @@ fn test_parse_cell_reference() {
     assert_eq!(row, 9);
     assert_eq!(col, 25);
 }
+
+// ============ Merged Cell Tests ============
+
+/// Write `csv` to an xlsx file, then mark `refs` (e.g. "B1:C1") as merged
+fn write_merged_fixture(csv: &str, refs: &[&str], xlsx_path: &str) {
+    use std::io::{Read, Write};
+
+    let handler = ExcelHandler::new();
+    let csv_path = format!("{xlsx_path}.csv");
+    let plain_path = format!("{xlsx_path}.plain.xlsx");
+    fs::write(&csv_path, csv).unwrap();
+    handler.write_from_csv(&csv_path, &plain_path, None).unwrap();
+
+    let merge_xml = format!(
+        r#"</sheetData><mergeCells count="{}">{}</mergeCells>"#,
+        refs.len(),
+        refs.iter()
+            .map(|r| format!(r#"<mergeCell ref="{r}"/>"#))
+            .collect::<String>()
+    );
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&plain_path).unwrap()).unwrap();
+    let mut writer = zip::ZipWriter::new(fs::File::create(xlsx_path).unwrap());
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let name = entry.name().to_string();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).unwrap();
+        if name == "xl/worksheets/sheet1.xml" {
+            content = String::from_utf8(content)
+                .unwrap()
+                .replace("</sheetData>", &merge_xml)
+                .into_bytes();
+        }
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&content).unwrap();
+    }
+    writer.finish().unwrap();
+
+    fs::remove_file(&csv_path).ok();
+    fs::remove_file(&plain_path).ok();
+}
+
+#[test]
+fn test_excel_read_range_fills_merged_header() {
+    let dir = TempDir::new().unwrap();
+    let handler = ExcelHandler::new();
+    let path = unique_path(&dir, "excel_merged", "xlsx");
+    write_merged_fixture(
+        "Region,Q1,,Q2,\nNorth,1,2,3,4\nSouth,5,6,7,8\n",
+        &["B1:C1", "D1:E1"],
+        &path,
+    );
+
+    let range = datacell::CellRange::parse("A1:E3").unwrap();
+
+    // By default covered cells stay blank, as Excel stores them
+    let plain = handler.read_range(&path, &range, None).unwrap();
+    assert_eq!(plain[0], vec!["Region", "Q1", "", "Q2", ""]);
+
+    let filled = handler
+        .read_range_with_options(&path, &range, None, true)
+        .unwrap();
+    assert_eq!(filled[0], vec!["Region", "Q1", "Q1", "Q2", "Q2"]);
+    assert_eq!(filled[1], vec!["North", "1", "2", "3", "4"]);
+
+    // The anchor value is used even when the range starts inside the merge
+    let range = datacell::CellRange::parse("C1:E2").unwrap();
+    let filled = handler
+        .read_range_with_options(&path, &range, None, true)
+        .unwrap();
+    assert_eq!(filled[0], vec!["Q1", "Q2", "Q2"]);
+
+    fs::remove_file(&path).ok();
+}
+
+// ============ Typed Read Tests ============
+
+/// Build a one-sheet xlsx whose second style (`s="1"`) uses number format `num_fmt_id`
+fn write_styled_fixture(xlsx_path: &str, num_fmt_id: u32, sheet_data: &str) {
+    use std::io::Write;
+
+    let files = [
+        (
+            "[Content_Types].xml",
+            r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#.to_string(),
+        ),
+        (
+            "_rels/.rels",
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_string(),
+        ),
+        (
+            "xl/workbook.xml",
+            r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#.to_string(),
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#.to_string(),
+        ),
+        (
+            "xl/styles.xml",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><cellXfs count="2"><xf numFmtId="0"/><xf numFmtId="{num_fmt_id}" applyNumberFormat="1"/></cellXfs></styleSheet>"#
+            ),
+        ),
+        (
+            "xl/worksheets/sheet1.xml",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>{sheet_data}</sheetData></worksheet>"#
+            ),
+        ),
+    ];
+
+    let mut writer = zip::ZipWriter::new(fs::File::create(xlsx_path).unwrap());
+    for (name, content) in files {
+        writer
+            .start_file(name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[test]
+fn test_excel_read_typed_dates_as_iso() {
+    let dir = TempDir::new().unwrap();
+    let handler = ExcelHandler::new();
+    let path = unique_path(&dir, "excel_dates", "xlsx");
+    // Style 1 is built-in format 22 (m/d/yy h:mm); A2 is a date, A3 a date with time
+    write_styled_fixture(
+        &path,
+        22,
+        r#"<row r="1"><c r="A1" t="inlineStr"><is><t>when</t></is></c><c r="B1" t="inlineStr"><is><t>amount</t></is></c><c r="C1" t="inlineStr"><is><t>paid</t></is></c></row><row r="2"><c r="A2" s="1"><v>45000</v></c><c r="B2"><v>45000</v></c><c r="C2" t="b"><v>1</v></c></row><row r="3"><c r="A3" s="1"><v>45000.5</v></c><c r="B3"><v>12.5</v></c><c r="C3" t="b"><v>0</v></c></row>"#,
+    );
+
+    let typed = handler.read_with_sheet_typed(&path, None, true).unwrap();
+    let lines: Vec<&str> = typed.lines().collect();
+    assert_eq!(lines[0], "when,amount,paid");
+    // Only the date-formatted cell is converted; the plain number stays a number
+    assert_eq!(lines[1], "2023-03-15,45000,true");
+    assert_eq!(lines[2], "2023-03-15T12:00:00,12.5,false");
+
+    // Without the flag the serial number is kept
+    let raw = handler.read_with_sheet(&path, None).unwrap();
+    assert!(raw.lines().nth(1).unwrap().starts_with("45000,"));
+
+    fs::remove_file(&path).ok();
+}
+
+// ============ Comment Tests ============
+
+#[test]
+fn test_xlsx_writer_comments_keep_data_readable() {
+    let dir = TempDir::new().unwrap();
+    let path = unique_path(&dir, "excel_comments", "xlsx");
+    let mut writer = XlsxWriter::new();
+    writer.add_sheet("Notes").unwrap();
+    writer.add_data(&[
+        vec!["item".to_string(), "qty".to_string()],
+        vec!["bolt".to_string(), "4".to_string()],
+    ]);
+    writer.add_comment("B2", "Ann", "Recount on Friday").unwrap();
+    let mut file = fs::File::create(&path).unwrap();
+    writer.save(&mut file).unwrap();
+
+    let archive = zip::ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+    assert!(archive.file_names().any(|name| name == "xl/comments1.xml"));
+
+    let handler = ExcelHandler::new();
+    let content = handler.read_with_sheet(&path, Some("Notes")).unwrap();
+    assert_eq!(content, "item,qty\nbolt,4\n");
+
+    fs::remove_file(&path).ok();
+}
+
+// ============ Formula Write-back Tests ============
+
+#[test]
+fn test_apply_formula_keeps_other_sheets() {
+    let dir = TempDir::new().unwrap();
+    let input = unique_path(&dir, "formula_sheets_in", "xlsx");
+    let output = unique_path(&dir, "formula_sheets_out", "xlsx");
+
+    let mut writer = XlsxWriter::new();
+    writer.add_sheet("Sheet1").unwrap();
+    let mut row = RowData::new();
+    row.add_string("code");
+    row.add_string("qty");
+    writer.add_row(row);
+    let mut row = RowData::new();
+    // Numeric-looking text must stay text
+    row.add_string("007");
+    row.add_number(12.0);
+    writer.add_row(row);
+    writer.set_column_width(0, 24.0);
+    writer.add_sheet("Sheet2").unwrap();
+    writer.add_data(&[vec!["1".to_string()], vec!["2".to_string()]]);
+    let mut file = fs::File::create(&input).unwrap();
+    writer.save(&mut file).unwrap();
+
+    FormulaEvaluator::new()
+        .apply_to_excel(&input, &output, "SUM(A1:A2)", "B1", Some("Sheet2"))
+        .unwrap();
+
+    let handler = ExcelHandler::new();
+    assert_eq!(
+        handler.list_sheets(&output).unwrap(),
+        vec!["Sheet1".to_string(), "Sheet2".to_string()]
+    );
+    let sheet1 = handler.read_with_sheet(&output, Some("Sheet1")).unwrap();
+    assert_eq!(sheet1, "code,qty\n007,12\n");
+    let sheet2 = handler.read_with_sheet(&output, Some("Sheet2")).unwrap();
+    assert_eq!(sheet2, "1,3\n2,\n");
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+    let mut sheet_xml = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.by_name("xl/worksheets/sheet1.xml").unwrap(),
+        &mut sheet_xml,
+    )
+    .unwrap();
+    assert!(sheet_xml.contains(r#"width="24""#));
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
+#[test]
+fn test_apply_formula_keeps_formulas_on_other_sheets() {
+    let dir = TempDir::new().unwrap();
+    let input = unique_path(&dir, "formula_keep_in", "xlsx");
+    let output = unique_path(&dir, "formula_keep_out", "xlsx");
+
+    let mut writer = XlsxWriter::new();
+    writer.add_sheet("Sheet1").unwrap();
+    writer.add_data(&[vec!["4".to_string()], vec!["5".to_string()]]);
+    let mut row = RowData::new();
+    row.add_formula("=SUM(A1:A2)");
+    writer.add_row(row);
+    writer.add_sheet("Sheet2").unwrap();
+    writer.add_data(&[vec!["1".to_string()], vec!["2".to_string()]]);
+    let mut file = fs::File::create(&input).unwrap();
+    writer.save(&mut file).unwrap();
+
+    FormulaEvaluator::new()
+        .apply_to_excel(&input, &output, "SUM(A1:A2)", "B1", Some("Sheet2"))
+        .unwrap();
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+    let mut sheet_xml = String::new();
+    std::io::Read::read_to_string(
+        &mut archive.by_name("xl/worksheets/sheet1.xml").unwrap(),
+        &mut sheet_xml,
+    )
+    .unwrap();
+    assert!(sheet_xml.contains(r#"<c r="A3"><f>SUM(A1:A2)</f></c>"#));
+    let sheet2 = ExcelHandler::new()
+        .read_with_sheet(&output, Some("Sheet2"))
+        .unwrap();
+    assert_eq!(sheet2, "1,3\n2,\n");
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
+// ============ ODS Write Tests ============
+
+#[test]
+fn test_ods_write_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let handler = ExcelHandler::new();
+    let path = unique_path(&dir, "ods_round_trip", "ods");
+    let data = vec![
+        vec!["name".to_string(), "qty".to_string(), "note".to_string()],
+        vec!["bolt & nut".to_string(), "4".to_string(), "".to_string()],
+        vec!["washer".to_string(), "2.5".to_string(), "<fragile>".to_string()],
+        // Numeric-looking text that must not become a float cell
+        vec!["02134".to_string(), "1e3".to_string(), "+5".to_string()],
+        vec!["1_000".to_string(), "-0.25".to_string(), "3.10".to_string()],
+    ];
+
+    handler.write_ods(&path, &data, Some("Parts")).unwrap();
+
+    assert_eq!(handler.list_ods_sheets(&path).unwrap(), vec!["Parts".to_string()]);
+    let read_back = handler.read_ods_data(&path, Some("Parts")).unwrap();
+    assert_eq!(read_back, data);
+
+    fs::remove_file(&path).ok();
+}
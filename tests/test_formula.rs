@@ -226,6 +226,62 @@ fn test_formula_if_less_than() {
     fs::remove_file(&output).ok();
 }
 
+#[test]
+fn test_formula_if_and_or_not() {
+    let csv = "5,50,200\n";
+    let eval = |formula: &str| eval_to_cell("if_logic", csv, formula, 3);
+
+    assert_eq!(eval("IF(AND(A1>0, B1<100), \"ok\", \"bad\")"), "ok");
+    assert_eq!(
+        eval("IF(AND(A1>0, B1<100, C1<100), \"ok\", \"bad\")"),
+        "bad"
+    );
+    assert_eq!(eval("IF(OR(A1>10, B1>10, C1>10), 1, 0)"), "1");
+    assert_eq!(eval("IF(OR(A1>10, B1>100), 1, 0)"), "0");
+    assert_eq!(eval("IF(NOT(A1=5), 1, 0)"), "0");
+    assert_eq!(eval("IF(and(A1=5, not(C1<B1)), 1, 0)"), "1");
+}
+
+#[test]
+fn test_formula_if_nested_logic() {
+    let csv = "5,50,200\n";
+    let eval = |formula: &str| eval_to_cell("if_nested_logic", csv, formula, 3);
+
+    assert_eq!(
+        eval("IF(AND(OR(A1>10, B1=50), NOT(AND(C1>100, A1<0))), \"yes\", \"no\")"),
+        "yes"
+    );
+    assert_eq!(
+        eval("IF(OR(AND(A1>10, B1=50), NOT(OR(C1>100, A1<0))), \"yes\", \"no\")"),
+        "no"
+    );
+}
+
+#[test]
+fn test_formula_if_logic_argument_counts() {
+    let evaluator = FormulaEvaluator::new();
+    let input = unique_path("if_logic_args_in");
+    let output = unique_path("if_logic_args_out");
+    fs::write(&input, "1,2\n").unwrap();
+
+    for formula in [
+        "IF(AND(A1>0), 1, 0)",
+        "IF(OR(A1>0), 1, 0)",
+        "IF(NOT(A1>0, B1>0), 1, 0)",
+        "IF(NOT(), 1, 0)",
+    ] {
+        assert!(
+            evaluator
+                .apply_to_csv(&input, &output, formula, "C1")
+                .is_err(),
+            "{formula} should fail"
+        );
+    }
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
 #[test]
 fn test_formula_sumif_greater() {
     let evaluator = FormulaEvaluator::new();
@@ -266,6 +322,70 @@ fn test_formula_countif_equal() {
     fs::remove_file(&output).ok();
 }
 
+/// Evaluate `formula` against `csv` and return the text written to `cell_col` of row 1
+fn eval_to_cell(prefix: &str, csv: &str, formula: &str, cell_col: usize) -> String {
+    let evaluator = FormulaEvaluator::new();
+    let input = unique_path(&format!("{prefix}_in"));
+    let output = unique_path(&format!("{prefix}_out"));
+    fs::write(&input, csv).unwrap();
+
+    let cell = format!("{}1", (b'A' + cell_col as u8) as char);
+    evaluator
+        .apply_to_csv(&input, &output, formula, &cell)
+        .unwrap();
+
+    let content = fs::read_to_string(&output).unwrap();
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+
+    content
+        .lines()
+        .next()
+        .unwrap()
+        .split(',')
+        .nth(cell_col)
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_formula_sumif_text_criteria_with_sum_range() {
+    // Criteria is matched case-insensitively; the non-numeric "x" is skipped
+    let csv = "apple,10\nBanana,20\nApple,30\ncherry,x\napple,5\n";
+    let value = eval_to_cell("sumif_text", csv, "SUMIF(A1:A5, \"=apple\", B1:B5)", 2);
+    assert_eq!(value.parse::<f64>().unwrap(), 45.0);
+
+    let value = eval_to_cell("sumif_bare", csv, "SUMIF(A1:A5, \"banana\", B1:B5)", 2);
+    assert_eq!(value.parse::<f64>().unwrap(), 20.0);
+}
+
+#[test]
+fn test_formula_averageif_mixed_column() {
+    // Text cells never satisfy numeric criteria
+    let csv = "5\n10\nabc\n20\n\n";
+    let value = eval_to_cell("avgif_mixed", csv, "AVERAGEIF(A1:A5, \">=10\")", 1);
+    assert_eq!(value.parse::<f64>().unwrap(), 15.0);
+
+    let csv = "north,4\nsouth,8\nnorth,6\n";
+    let value = eval_to_cell("avgif_range", csv, "AVERAGEIF(A1:A3, \"north\", B1:B3)", 2);
+    assert_eq!(value.parse::<f64>().unwrap(), 5.0);
+}
+
+#[test]
+fn test_formula_countif_mixed_criteria() {
+    let csv = "5\n10\nabc\n20\nABC\n";
+    let count = |formula: &str| {
+        eval_to_cell("countif_mixed", csv, formula, 1)
+            .parse::<f64>()
+            .unwrap()
+    };
+
+    assert_eq!(count("COUNTIF(A1:A5, \">5\")"), 2.0);
+    assert_eq!(count("COUNTIF(A1:A5, \"<=10\")"), 2.0);
+    assert_eq!(count("COUNTIF(A1:A5, \"=abc\")"), 2.0);
+    assert_eq!(count("COUNTIF(A1:A5, \"<>abc\")"), 3.0);
+}
+
 // ============ String Function Tests ============
 
 #[test]
@@ -306,6 +426,72 @@ fn test_formula_concat_numbers() {
     fs::remove_file(&output).ok();
 }
 
+#[test]
+fn test_formula_text_functions() {
+    let csv = "Hello,  padded   text  ,MiXeD\n";
+    let eval = |formula: &str| eval_to_cell("text_fn", csv, formula, 3);
+
+    assert_eq!(eval("LEFT(A1, 3)"), "Hel");
+    assert_eq!(eval("LEFT(\"Hello\", 3)"), "Hel");
+    assert_eq!(eval("LEFT(A1)"), "H");
+    assert_eq!(eval("RIGHT(A1, 2)"), "lo");
+    assert_eq!(eval("MID(A1, 2, 3)"), "ell");
+    assert_eq!(eval("TRIM(B1)"), "padded text");
+    assert_eq!(eval("UPPER(C1)"), "MIXED");
+    assert_eq!(eval("lower(C1)"), "mixed");
+}
+
+#[test]
+fn test_formula_text_functions_nested() {
+    let csv = "hello,  world  \n";
+    let eval = |formula: &str| eval_to_cell("text_nested", csv, formula, 2);
+
+    assert_eq!(eval("UPPER(LEFT(A1, 2))"), "HE");
+    assert_eq!(eval("LEFT(TRIM(B1), 3)"), "wor");
+    assert_eq!(eval("MID(UPPER(A1), 1 + 1, 2)"), "EL");
+}
+
+#[test]
+fn test_formula_text_functions_clamp_lengths() {
+    let csv = "abc,\n";
+    let eval = |formula: &str| eval_to_cell("text_clamp", csv, formula, 2);
+
+    assert_eq!(eval("LEFT(A1, 10)"), "abc");
+    assert_eq!(eval("RIGHT(A1, 10)"), "abc");
+    assert_eq!(eval("MID(A1, 2, 10)"), "bc");
+    assert_eq!(eval("MID(A1, 10, 2)"), "");
+    assert_eq!(eval("LEFT(A1, 0)"), "");
+    assert_eq!(eval("UPPER(B1)"), "");
+    assert_eq!(eval("LEFT(B1, 3)"), "");
+    // Cells beyond the data read as empty text
+    assert_eq!(eval("TRIM(Z9)"), "");
+}
+
+#[test]
+fn test_formula_text_functions_invalid_arguments() {
+    let evaluator = FormulaEvaluator::new();
+    let input = unique_path("text_invalid_in");
+    let output = unique_path("text_invalid_out");
+    fs::write(&input, "abc\n").unwrap();
+
+    for formula in [
+        "MID(A1, 0, 2)",
+        "LEFT(A1, -1)",
+        "MID(A1, 1)",
+        "UPPER(A1, B1)",
+    ] {
+        assert!(
+            evaluator
+                .apply_to_csv(&input, &output, formula, "B1")
+                .is_err(),
+            "{formula} should fail"
+        );
+    }
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
 // ============ Math Function Tests ============
 
 #[test]
@@ -346,6 +532,32 @@ fn test_formula_round_down() {
     fs::remove_file(&output).ok();
 }
 
+// ============ Date Function Tests ============
+
+#[test]
+fn test_formula_datedif_days_across_month() {
+    let csv = "2024-01-25,2024-02-03\n";
+    let eval = |formula: &str| eval_to_cell("datedif", csv, formula, 2);
+
+    assert_eq!(eval("DATEDIF(A1, B1, \"D\")"), "9");
+    assert_eq!(eval("DATEDIF(A1, B1, \"M\")"), "0");
+    assert_eq!(eval("DATEDIF(\"2020-03-15\", B1, \"Y\")"), "3");
+    assert_eq!(eval("DATEDIF(\"2020-03-15\", B1, \"M\")"), "46");
+}
+
+#[test]
+fn test_formula_year_extraction() {
+    let csv = "2024-07-04,15/03/2021\n";
+    let eval = |formula: &str| eval_to_cell("year", csv, formula, 2);
+
+    assert_eq!(eval("YEAR(A1)"), "2024");
+    assert_eq!(eval("MONTH(A1)"), "7");
+    assert_eq!(eval("DAY(A1)"), "4");
+    assert_eq!(eval("YEAR(B1)"), "2021");
+    assert_eq!(eval("YEAR(DATE(1999, 12, 31))"), "1999");
+    assert_eq!(eval("DATE(2024, 2, 29)"), "2024-02-29");
+}
+
 // ============ VLOOKUP Tests ============
 
 #[test]
@@ -388,6 +600,82 @@ fn test_formula_vlookup_last_column() {
     fs::remove_file(&output).ok();
 }
 
+// ============ Error Value Tests ============
+
+#[test]
+fn test_formula_division_by_zero_writes_div0() {
+    let csv = "5,0\n";
+    let eval = |formula: &str| eval_to_cell("div0", csv, formula, 2);
+
+    assert_eq!(eval("=1/0"), "#DIV/0!");
+    assert_eq!(eval("A1/B1"), "#DIV/0!");
+    // Errors propagate through enclosing functions
+    assert_eq!(eval("ROUND(A1/B1, 2)"), "#DIV/0!");
+}
+
+#[test]
+fn test_formula_out_of_range_reference_writes_ref() {
+    let csv = "1,2\n3,4\n";
+    let eval = |formula: &str| eval_to_cell("ref_err", csv, formula, 2);
+
+    assert_eq!(eval("A1+Z99"), "#REF!");
+    assert_eq!(eval("INDEX(A1:B2, 3, 1)"), "#REF!");
+    assert_eq!(eval("VLOOKUP(9, A1:B2, 2)"), "#N/A");
+    assert_eq!(eval("IF(A1>0, A1/0, 0)"), "#DIV/0!");
+}
+
+// ============ Range Fill Tests ============
+
+#[test]
+fn test_formula_fill_sum_down_column() {
+    let evaluator = FormulaEvaluator::new();
+    let input = unique_path("fill_column_in");
+    let output = unique_path("fill_column_out");
+    fs::write(&input, "1,10\n2,20\n3,30\n4,40\n5,50\n").unwrap();
+
+    evaluator
+        .apply_formula_to_range(&input, &output, "A1+B1", "C1:C5", None)
+        .unwrap();
+
+    let content = fs::read_to_string(&output).unwrap();
+    let results: Vec<&str> = content
+        .lines()
+        .map(|line| line.split(',').nth(2).unwrap())
+        .collect();
+    assert_eq!(results, vec!["11", "22", "33", "44", "55"]);
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
+#[test]
+fn test_formula_fill_anchors_and_row_placeholder() {
+    let evaluator = FormulaEvaluator::new();
+    let input = unique_path("fill_anchor_in");
+    let output = unique_path("fill_anchor_out");
+    fs::write(&input, "2,1\n3,2\n4,3\n").unwrap();
+
+    evaluator
+        .apply_formula_to_range(&input, &output, "A1*$A$1", "C1:C3", None)
+        .unwrap();
+    evaluator
+        .apply_formula_to_range(&output, &output, "A{row}*B{row}", "D1:D3", None)
+        .unwrap();
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert_eq!(content, "2,1,4,2\n3,2,6,6\n4,3,8,12\n");
+
+    // A single cell is not a range
+    assert!(
+        evaluator
+            .apply_formula_to_range(&input, &output, "A1+B1", "C1", None)
+            .is_err()
+    );
+
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+}
+
 // ============ Complex Formula Tests ============
 
 #[test]
@@ -410,6 +698,52 @@ fn test_formula_nested_arithmetic() {
     fs::remove_file(&output).ok();
 }
 
+#[test]
+fn test_formula_operator_precedence() {
+    let csv = "2,3,4\n";
+    let eval = |formula: &str| {
+        eval_to_cell("precedence", csv, formula, 3)
+            .parse::<f64>()
+            .unwrap()
+    };
+
+    assert_eq!(eval("A1+B1*C1"), 14.0);
+    assert_eq!(eval("A1*B1+C1"), 10.0);
+    assert_eq!(eval("=(A1+B1)*2"), 10.0);
+    assert_eq!(eval("C1/A1+B1*(A1+1)"), 11.0);
+    assert_eq!(eval("((A1+B1)*(C1-A1))/5"), 2.0);
+    assert_eq!(eval("-A1+C1"), 2.0);
+    assert_eq!(eval("A1*-B1"), -6.0);
+}
+
+#[test]
+fn test_formula_operator_associativity() {
+    let csv = "10,3,2,16,4\n";
+    let eval = |formula: &str| {
+        eval_to_cell("associativity", csv, formula, 5)
+            .parse::<f64>()
+            .unwrap()
+    };
+
+    // Operators of equal precedence evaluate left to right
+    assert_eq!(eval("A1-B1-C1"), 5.0);
+    assert_eq!(eval("D1/E1/C1"), 2.0);
+    assert_eq!(eval("A1-B1+C1"), 9.0);
+    assert_eq!(eval("D1/E1*C1"), 8.0);
+}
+
+#[test]
+fn test_formula_arithmetic_multi_digit_rows() {
+    let mut csv = String::new();
+    for i in 1..=10 {
+        csv.push_str(&format!("{}\n", i * 100));
+    }
+
+    // A10 must not be confused with A1
+    let value = eval_to_cell("multi_digit", &csv, "A10-A1", 1);
+    assert_eq!(value.parse::<f64>().unwrap(), 900.0);
+}
+
 #[test]
 fn test_formula_with_constants() {
     let evaluator = FormulaEvaluator::new();
@@ -429,3 +763,34 @@ fn test_formula_with_constants() {
     fs::remove_file(&input).ok();
     fs::remove_file(&output).ok();
 }
+
+#[test]
+fn test_formula_ignores_utf8_bom() {
+    let value = eval_to_cell("bom", "\u{FEFF}5,7\n", "A1+B1", 2);
+    assert_eq!(value.parse::<f64>().unwrap(), 12.0);
+}
+
+// ============ INDEX/MATCH Tests ============
+
+#[test]
+fn test_formula_match_exact() {
+    let csv = "apple,10\nbanana,20\ncherry,30\n";
+    let eval = |formula: &str| eval_to_cell("match_exact", csv, formula, 2);
+
+    assert_eq!(eval("MATCH(\"banana\", A1:A3, 0)"), "2");
+    assert_eq!(eval("MATCH(\"CHERRY\", A1:A3, 0)"), "3");
+    assert_eq!(eval("MATCH(20, B1:B3, 0)"), "2");
+    // Approximate matches over sorted data
+    assert_eq!(eval("MATCH(25, B1:B3, 1)"), "2");
+    assert_eq!(eval("MATCH(25, B1:B3)"), "2");
+}
+
+#[test]
+fn test_formula_index_match_returns_text() {
+    let csv = "1,Alice,90\n2,Bob,85\n3,Carol,95\n";
+    let eval = |formula: &str| eval_to_cell("index_match", csv, formula, 3);
+
+    assert_eq!(eval("INDEX(A1:C3, MATCH(2, A1:A3, 0), 2)"), "Bob");
+    assert_eq!(eval("INDEX(A1:C3, MATCH(\"carol\", B1:B3, 0), 3)"), "95");
+    assert_eq!(eval("INDEX(A1:C3, 1, 2)"), "Alice");
+}
@@ -0,0 +1,103 @@
+//! Tests for geospatial calculations
+
+use datacell::{Coordinate, DistanceFormula, GeospatialCalculator};
+
+fn point(latitude: f64, longitude: f64) -> Coordinate {
+    Coordinate {
+        latitude,
+        longitude,
+    }
+}
+
+// ============ Distance Formula Tests ============
+
+#[test]
+fn test_haversine_and_vincenty_agree_on_long_distance() {
+    let london = point(51.5074, -0.1278);
+    let new_york = point(40.7128, -74.0060);
+
+    let haversine = GeospatialCalculator::new().distance(&london, &new_york);
+    let vincenty = GeospatialCalculator::new()
+        .with_formula(DistanceFormula::Vincenty)
+        .distance(&london, &new_york);
+
+    assert!((haversine - 5570.2).abs() < 1.0, "{haversine}");
+    assert!(
+        (vincenty - haversine).abs() / vincenty < 0.005,
+        "{vincenty}"
+    );
+    assert_ne!(vincenty, haversine);
+}
+
+#[test]
+fn test_vincenty_matches_reference_and_falls_back() {
+    let vincenty = GeospatialCalculator::new().with_formula(DistanceFormula::Vincenty);
+
+    // Flinders Peak to Buninyong, Vincenty's own worked example: 54 972.271 m
+    let flinders_peak = point(-37.951_033_4, 144.424_867_9);
+    let buninyong = point(-37.652_821_1, 143.926_495_3);
+    let distance = vincenty.distance(&flinders_peak, &buninyong);
+    assert!((distance - 54.972_271).abs() < 0.001, "{distance}");
+
+    assert_eq!(vincenty.distance(&buninyong, &buninyong), 0.0);
+
+    // Nearly antipodal points do not converge and use Haversine instead
+    let from = point(0.0, 0.0);
+    let to = point(0.5, 179.7);
+    let haversine = GeospatialCalculator::new().distance(&from, &to);
+    assert_eq!(vincenty.distance(&from, &to), haversine);
+}
+
+#[test]
+fn test_distance_column() {
+    let data = vec![
+        vec!["name".to_string(), "lat".to_string(), "lon".to_string()],
+        vec!["origin".to_string(), "0".to_string(), "0".to_string()],
+        vec!["north".to_string(), "1".to_string(), "0".to_string()],
+        vec!["unknown".to_string(), "".to_string(), "5".to_string()],
+        vec!["short".to_string(), "1".to_string()],
+    ];
+
+    let calculator = GeospatialCalculator::new();
+    let result = calculator.distance_column(&data, 1, 2, &point(0.0, 0.0));
+
+    let distances: Vec<&str> = result
+        .iter()
+        .map(|row| row.last().unwrap().as_str())
+        .collect();
+    assert_eq!(distances, vec!["distance_km", "0.000", "111.195", "", ""]);
+    assert_eq!(result[1][..3], data[1][..]);
+    assert_eq!(result[4], vec!["short", "1", "", ""]);
+}
+
+// ============ Bearing and Midpoint Tests ============
+
+#[test]
+fn test_bearing_due_east() {
+    let calculator = GeospatialCalculator::new();
+
+    let bearing = calculator.bearing_from_strings("0,0", "0,10").unwrap();
+    assert!((bearing - 90.0).abs() < 1e-9, "{bearing}");
+
+    let north = calculator.bearing(&point(10.0, 5.0), &point(20.0, 5.0));
+    assert!(north.abs() < 1e-9, "{north}");
+    assert!(calculator.bearing_from_strings("0,0", "95,0").is_err());
+}
+
+#[test]
+fn test_midpoint() {
+    let calculator = GeospatialCalculator::new();
+
+    let meridian = calculator.midpoint_from_strings("0, 0", "60, 0").unwrap();
+    assert!((meridian.latitude - 30.0).abs() < 1e-9);
+    assert!(meridian.longitude.abs() < 1e-9);
+
+    let equator = calculator.midpoint(&point(0.0, 0.0), &point(0.0, 90.0));
+    assert!(equator.latitude.abs() < 1e-9);
+    assert!((equator.longitude - 45.0).abs() < 1e-9);
+
+    // Crossing the antimeridian stays within -180..180
+    let dateline = calculator.midpoint(&point(-10.0, 170.0), &point(10.0, -170.0));
+    assert!(dateline.latitude.abs() < 1e-9);
+    assert!((dateline.longitude.abs() - 180.0).abs() < 1e-9);
+}
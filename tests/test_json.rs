@@ -0,0 +1,86 @@
+use datacell::{Converter, JsonLinesHandler};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn unique_path(prefix: &str, ext: &str) -> String {
+    let id = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("test_{prefix}_{id}.{ext}")
+}
+
+// ============ JSON Lines Tests ============
+
+#[test]
+fn test_read_json_lines_heterogeneous_objects() {
+    let path = unique_path("jsonl_mixed", "jsonl");
+    fs::write(
+        &path,
+        concat!(
+            r#"{"level":"info","msg":"started","pid":42}"#,
+            "\n\n",
+            r#"{"msg":"retry","level":"warn","attempt":2,"ok":false}"#,
+            "\n",
+            r#"{"level":"error","tags":["db","io"],"pid":null}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let data = JsonLinesHandler::new().read(&path).unwrap();
+
+    // Columns follow first-seen key order
+    assert_eq!(
+        data[0],
+        vec!["level", "msg", "pid", "attempt", "ok", "tags"]
+    );
+    assert_eq!(data.len(), 4);
+    assert_eq!(data[1], vec!["info", "started", "42", "", "", ""]);
+    assert_eq!(data[2], vec!["warn", "retry", "", "2", "false", ""]);
+    assert_eq!(data[3], vec!["error", "", "", "", "", r#"["db","io"]"#]);
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_read_json_lines_rejects_non_object_line() {
+    let path = unique_path("jsonl_bad", "ndjson");
+    fs::write(&path, "{\"a\":1}\n[1,2]\n").unwrap();
+
+    let err = JsonLinesHandler::new().read(&path).unwrap_err();
+    assert!(err.to_string().contains("Line 2"), "{err}");
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_json_lines_round_trip_through_converter() {
+    let converter = Converter::new();
+    let csv_path = unique_path("jsonl_src", "csv");
+    let jsonl_path = unique_path("jsonl_round", "ndjson");
+    let back_path = unique_path("jsonl_back", "csv");
+    let rows = vec![
+        vec!["name".to_string(), "id".to_string(), "note".to_string()],
+        vec![
+            "Ada".to_string(),
+            "1".to_string(),
+            "says \"hi\", then\nleaves".to_string(),
+        ],
+        vec!["Bob".to_string(), "2".to_string(), String::new()],
+    ];
+    converter.write_any_data(&csv_path, &rows, None).unwrap();
+
+    converter.convert(&csv_path, &jsonl_path, None).unwrap();
+    let content = fs::read_to_string(&jsonl_path).unwrap();
+    assert_eq!(content.lines().count(), 2);
+    // Keys keep the header order
+    assert!(content.starts_with(r#"{"name":"Ada","id":"1","#));
+
+    converter.convert(&jsonl_path, &back_path, None).unwrap();
+    let data = converter.read_any_data(&back_path, None).unwrap();
+    assert_eq!(data, rows);
+
+    fs::remove_file(&csv_path).ok();
+    fs::remove_file(&jsonl_path).ok();
+    fs::remove_file(&back_path).ok();
+}
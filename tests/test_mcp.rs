@@ -0,0 +1,178 @@
+use datacell::DatacellMcpServer;
+use datacell::XlsxWriter;
+use datacell::mcp::{EvaluateRequest, ProfileRequest, TransformRequest};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::{CallToolResult, ErrorCode, ResourceContents};
+use serde_json::json;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn unique_path(prefix: &str, ext: &str) -> String {
+    let id = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+    format!("test_mcp_{prefix}_{id}.{ext}")
+}
+
+/// Parse the single text content of a tool result as JSON
+fn result_json(result: &CallToolResult) -> serde_json::Value {
+    let text = &result.content[0].as_text().expect("text content").text;
+    serde_json::from_str(text).expect("tool result is JSON")
+}
+
+// ============ Evaluate Formula Tool Tests ============
+
+#[tokio::test]
+async fn test_evaluate_formula_tool() {
+    let path = unique_path("evaluate", "csv");
+    fs::write(&path, "10\n20\n12.5\n").unwrap();
+
+    let server = DatacellMcpServer::new();
+    let result = server
+        .evaluate_formula(Parameters(EvaluateRequest {
+            path: path.clone(),
+            formula: "SUM(A1:A3)".to_string(),
+            sheet: None,
+        }))
+        .await;
+    fs::remove_file(&path).ok();
+
+    assert_eq!(
+        result_json(&result.unwrap()),
+        json!({"type": "number", "value": 42.5})
+    );
+}
+
+// ============ Profile Tool Tests ============
+
+#[tokio::test]
+async fn test_profile_file_tool() {
+    let path = unique_path("profile", "csv");
+    fs::write(&path, "id,email\n1,ann@example.com\n2,\n3,cy@example.net\n").unwrap();
+
+    let server = DatacellMcpServer::new();
+    let result = server
+        .profile_file(Parameters(ProfileRequest {
+            path: path.clone(),
+            sample_size: None,
+        }))
+        .await;
+    fs::remove_file(&path).ok();
+
+    let profile = result_json(&result.unwrap());
+    assert!(profile["data_quality_score"].is_number(), "{profile}");
+    assert_eq!(profile["total_rows"], 3);
+    assert_eq!(profile["columns"][1]["name"], "email");
+}
+
+#[tokio::test]
+async fn test_profile_file_tool_missing_file() {
+    let server = DatacellMcpServer::new();
+    let result = server
+        .profile_file(Parameters(ProfileRequest {
+            path: "no_such_file.csv".to_string(),
+            sample_size: Some(10),
+        }))
+        .await;
+
+    assert!(result.is_err());
+}
+
+// ============ Transform Tool Tests ============
+
+#[tokio::test]
+async fn test_transform_tool_filter_then_sort() {
+    let input = unique_path("transform_in", "csv");
+    let output = unique_path("transform_out", "csv");
+    fs::write(&input, "name,age\nDee,31\nBob,25\nAnn,44\nCal,30\n").unwrap();
+
+    let server = DatacellMcpServer::new();
+    let result = server
+        .transform(Parameters(TransformRequest {
+            input: input.clone(),
+            output: output.clone(),
+            operations: vec![
+                json!({"op": "filter", "col": "age", "operator": ">", "value": "30"}),
+                json!({"op": "sort", "col": "name"}),
+            ],
+        }))
+        .await;
+    let written = fs::read_to_string(&output);
+    fs::remove_file(&input).ok();
+    fs::remove_file(&output).ok();
+
+    assert!(result.is_ok(), "{result:?}");
+    assert_eq!(written.unwrap(), "name,age\nAnn,44\nDee,31\n");
+}
+
+#[tokio::test]
+async fn test_transform_tool_unknown_op() {
+    let input = unique_path("transform_bad", "csv");
+    let output = unique_path("transform_bad_out", "csv");
+    fs::write(&input, "name\nAnn\n").unwrap();
+
+    let server = DatacellMcpServer::new();
+    let result = server
+        .transform(Parameters(TransformRequest {
+            input: input.clone(),
+            output: output.clone(),
+            operations: vec![
+                json!({"op": "dedupe"}),
+                json!({"op": "explode", "col": "name"}),
+            ],
+        }))
+        .await;
+    fs::remove_file(&input).ok();
+
+    let error = result.unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    let data = error.data.unwrap();
+    assert_eq!(data["step"], 1);
+    assert_eq!(data["op"], "explode");
+    assert!(
+        data["supported"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("sort"))
+    );
+    // Nothing is written when a step fails
+    assert!(!std::path::Path::new(&output).exists());
+}
+
+// ============ Schema Resource Tests ============
+
+#[test]
+fn test_schema_resource_for_multi_sheet_workbook() {
+    let path = unique_path("schema", "xlsx");
+    let mut writer = XlsxWriter::new();
+    writer.add_sheet("People").unwrap();
+    writer.add_data(&[
+        vec!["id".to_string(), "email".to_string()],
+        vec!["1".to_string(), "ann@example.com".to_string()],
+        vec!["2".to_string(), "bo@example.org".to_string()],
+    ]);
+    writer.add_sheet("Notes").unwrap();
+    writer.add_data(&[vec!["text".to_string()], vec!["hello".to_string()]]);
+    let mut file = fs::File::create(&path).unwrap();
+    writer.save(&mut file).unwrap();
+
+    let server = DatacellMcpServer::new();
+    let templates = server.resource_templates();
+    assert_eq!(templates[0].raw.uri_template, "file:///{path}");
+
+    let uri = format!("file://{}", fs::canonicalize(&path).unwrap().display());
+    let result = server.read_schema(&uri);
+    fs::remove_file(&path).ok();
+
+    let ResourceContents::TextResourceContents { text, .. } = &result.unwrap().contents[0] else {
+        panic!("expected text contents");
+    };
+    let schema: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(schema["sheets"], json!(["People", "Notes"]));
+    assert_eq!(
+        schema["columns"],
+        json!([{"name": "id", "type": "Integer"}, {"name": "email", "type": "Email"}])
+    );
+
+    assert!(server.read_schema("https://example.com/data.csv").is_err());
+}
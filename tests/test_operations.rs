@@ -1,4 +1,7 @@
-use datacell::{AggFunc, DataOperations, JoinType, SortOrder};
+use datacell::{
+    AggFunc, ColumnType, CumOp, DataOperations, JoinType, NormalizeMethod, ProgressCallback,
+    RankMethod, SortOrder,
+};
 use std::fs;
 
 fn read_example_csv(name: &str) -> Vec<Vec<String>> {
@@ -19,11 +22,11 @@ fn test_sort_ascending_numeric() {
     let mut data = read_example_csv("numbers");
 
     // Sort by column A (index 0) ascending
-    ops.sort_by_column(&mut data, 0, SortOrder::Ascending)
+    ops.sort_by_column(&mut data, 0, SortOrder::Ascending, true)
         .unwrap();
 
     // Header stays first, data rows are sorted
-    // Verify data is sorted (header row may or may not stay first depending on impl)
+    assert_eq!(data[0][0], "A");
     assert!(data.len() > 1);
     // Check that sorting happened - smallest values should be near the top
     let has_small_value = data.iter().take(3).any(|r| r[0] == "1" || r[0] == "4");
@@ -38,7 +41,7 @@ fn test_sort_descending_numeric() {
     let ops = DataOperations::new();
     let mut data = read_example_csv("numbers");
 
-    ops.sort_by_column(&mut data, 0, SortOrder::Descending)
+    ops.sort_by_column(&mut data, 0, SortOrder::Descending, true)
         .unwrap();
 
     assert_eq!(data[0][0], "A"); // Header
@@ -46,13 +49,117 @@ fn test_sort_descending_numeric() {
     assert_eq!(data[2][0], "1"); // Smallest last
 }
 
+#[test]
+fn test_sort_keeps_header_row() {
+    let ops = DataOperations::new();
+    let rows = ["name,age", "bob,25", "alice,30", "zed,41"];
+    let parse = |rows: &[&str]| -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|l| l.split(',').map(|s| s.to_string()).collect())
+            .collect()
+    };
+
+    // "name" sorts after every name in descending order without a pinned header
+    let mut data = parse(&rows);
+    ops.sort_by_column(&mut data, 0, SortOrder::Descending, true)
+        .unwrap();
+    assert_eq!(data[0], vec!["name", "age"]);
+    let names: Vec<&str> = data[1..].iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(names, vec!["zed", "bob", "alice"]);
+
+    let mut data = parse(&rows[1..]);
+    ops.sort_by_column(&mut data, 1, SortOrder::Descending, false)
+        .unwrap();
+    let names: Vec<&str> = data.iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(names, vec!["zed", "alice", "bob"]);
+}
+
+#[test]
+fn test_sort_natural_vs_lexical() {
+    let ops = DataOperations::new();
+    let parse = |cells: &[&str]| -> Vec<Vec<String>> {
+        std::iter::once("file")
+            .chain(cells.iter().copied())
+            .map(|c| vec![c.to_string()])
+            .collect()
+    };
+    let column =
+        |data: &[Vec<String>]| -> Vec<String> { data[1..].iter().map(|r| r[0].clone()).collect() };
+
+    let files = ["file10", "file2", "file1"];
+
+    let mut lexical = parse(&files);
+    ops.sort_by_column(&mut lexical, 0, SortOrder::Ascending, true)
+        .unwrap();
+    assert_eq!(column(&lexical), vec!["file1", "file10", "file2"]);
+
+    let mut natural = parse(&files);
+    ops.sort_by_column_natural(&mut natural, 0, SortOrder::Ascending, true)
+        .unwrap();
+    assert_eq!(natural[0][0], "file");
+    assert_eq!(column(&natural), vec!["file1", "file2", "file10"]);
+
+    ops.sort_by_column_natural(&mut natural, 0, SortOrder::Descending, true)
+        .unwrap();
+    assert_eq!(column(&natural), vec!["file10", "file2", "file1"]);
+}
+
+#[test]
+fn test_sort_natural_edge_cases() {
+    let ops = DataOperations::new();
+    let mut data: Vec<Vec<String>> = ["v", "item010", "", "-5", "item2", "item10", "3", "item02"]
+        .iter()
+        .map(|c| vec![c.to_string()])
+        .collect();
+
+    ops.sort_by_column_natural(&mut data, 0, SortOrder::Ascending, true)
+        .unwrap();
+    let values: Vec<&str> = data[1..].iter().map(|r| r[0].as_str()).collect();
+    // Numbers first (negatives included), equal values order by leading zeros, blanks last
+    assert_eq!(
+        values,
+        vec!["-5", "3", "item2", "item02", "item10", "item010", ""]
+    );
+
+    ops.sort_by_column_natural(&mut data, 0, SortOrder::Descending, true)
+        .unwrap();
+    assert_eq!(data.last().unwrap()[0], "");
+    assert_eq!(data[1][0], "item010");
+}
+
+#[test]
+fn test_sort_by_date() {
+    let ops = DataOperations::new();
+    let mut data: Vec<Vec<String>> = [
+        "id,date",
+        "a,31/01/2024",
+        "b,n/a",
+        "c,05/12/2023",
+        "d,15/01/2024",
+        "e,",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    ops.sort_by_date(&mut data, 1, "%d/%m/%Y", SortOrder::Ascending)
+        .unwrap();
+    let ids: Vec<&str> = data.iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(ids, vec!["id", "c", "d", "a", "b", "e"]);
+
+    ops.sort_by_date(&mut data, 1, "%d/%m/%Y", SortOrder::Descending)
+        .unwrap();
+    let ids: Vec<&str> = data.iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(ids, vec!["id", "a", "d", "c", "b", "e"]);
+}
+
 #[test]
 fn test_sort_string_column() {
     let ops = DataOperations::new();
     let mut data = read_example_csv("employees");
 
     // Sort by Name (index 1) ascending
-    ops.sort_by_column(&mut data, 1, SortOrder::Ascending)
+    ops.sort_by_column(&mut data, 1, SortOrder::Ascending, true)
         .unwrap();
 
     // Verify sorting happened - Alice should be near the top
@@ -64,6 +171,53 @@ fn test_sort_string_column() {
     );
 }
 
+#[test]
+fn test_sort_by_columns_mixed_directions() {
+    let ops = DataOperations::new();
+    let mut data: Vec<Vec<String>> = [
+        "dept,salary,name",
+        "eng,100,a",
+        "ops,90,b",
+        "eng,120,c",
+        "ops,90,d",
+        "eng,100,e",
+        "ops,95,f",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    // Department ascending, then salary descending; ties keep input order
+    ops.sort_by_columns(
+        &mut data,
+        &[(0, SortOrder::Ascending), (1, SortOrder::Descending)],
+        true,
+    )
+    .unwrap();
+
+    let names: Vec<&str> = data.iter().map(|r| r[2].as_str()).collect();
+    assert_eq!(names, vec!["name", "c", "a", "e", "f", "b", "d"]);
+}
+
+#[test]
+fn test_sort_by_columns_without_header() {
+    let ops = DataOperations::new();
+    let mut data: Vec<Vec<String>> = ["b,2", "a,10", "b,1", "a,9"]
+        .iter()
+        .map(|l| l.split(',').map(|s| s.to_string()).collect())
+        .collect();
+
+    ops.sort_by_columns(
+        &mut data,
+        &[(0, SortOrder::Descending), (1, SortOrder::Ascending)],
+        false,
+    )
+    .unwrap();
+
+    let rows: Vec<String> = data.iter().map(|r| r.join(",")).collect();
+    assert_eq!(rows, vec!["b,1", "b,2", "a,9", "a,10"]);
+}
+
 // ============ Filter Tests ============
 
 #[test]
@@ -111,6 +265,84 @@ fn test_filter_contains() {
     }
 }
 
+fn people() -> Vec<Vec<String>> {
+    [
+        ["name", "age", "city"],
+        ["Alice", "34", "NYC"],
+        ["Bob", "28", "NYC"],
+        ["Cara", "41", "Boston"],
+        ["Dan Smith", "52", "New York"],
+    ]
+    .iter()
+    .map(|row| row.iter().map(|s| s.to_string()).collect())
+    .collect()
+}
+
+fn names(rows: &[Vec<String>]) -> Vec<&str> {
+    rows.iter().skip(1).map(|row| row[0].as_str()).collect()
+}
+
+#[test]
+fn test_filter_where_and() {
+    let ops = DataOperations::new();
+    let data = people();
+
+    let filtered = ops
+        .filter_where(&data, r#"age > 30 AND city = "NYC""#)
+        .unwrap();
+
+    assert_eq!(filtered[0], data[0]);
+    assert_eq!(names(&filtered), vec!["Alice"]);
+}
+
+#[test]
+fn test_filter_where_contains_and_or() {
+    let ops = DataOperations::new();
+    let data = people();
+
+    let filtered = ops.filter_where(&data, "name contains 'Smith'").unwrap();
+    assert_eq!(names(&filtered), vec!["Dan Smith"]);
+
+    // AND binds tighter than OR; parentheses override it
+    let filtered = ops
+        .filter_where(&data, "city = Boston or city = NYC and age < 30")
+        .unwrap();
+    assert_eq!(names(&filtered), vec!["Bob", "Cara"]);
+    let filtered = ops
+        .filter_where(&data, "(city = Boston OR city = NYC) AND age > 30")
+        .unwrap();
+    assert_eq!(names(&filtered), vec!["Alice", "Cara"]);
+
+    // Unquoted values may span several words
+    let filtered = ops.filter_where(&data, "city = New York").unwrap();
+    assert_eq!(names(&filtered), vec!["Dan Smith"]);
+}
+
+#[test]
+fn test_filter_where_errors() {
+    let ops = DataOperations::new();
+    let data = people();
+
+    assert!(ops.filter_where(&data, "height > 2").is_err());
+    assert!(ops.filter_where(&data, "age >").is_err());
+    assert!(ops.filter_where(&data, "city = 'NYC").is_err());
+    assert!(ops.filter_where(&data, "(age > 30").is_err());
+    assert!(ops.filter_where(&data, "age ~ 30").is_err());
+}
+
+#[test]
+fn test_filter_where_bang_in_value() {
+    let ops = DataOperations::new();
+    let data = people();
+
+    // A `!` that doesn't start `!=` belongs to the value
+    let filtered = ops.filter_where(&data, "name = Bob!").unwrap();
+    assert!(names(&filtered).is_empty());
+    let filtered = ops.filter_where(&data, "name != Bob! and age!=28").unwrap();
+    assert_eq!(names(&filtered), vec!["Alice", "Cara", "Dan Smith"]);
+    assert_eq!(ops.filter_where(&data, "name = !").unwrap().len(), 1);
+}
+
 // ============ Deduplicate Tests ============
 
 #[test]
@@ -224,6 +456,73 @@ fn test_fillna() {
     assert_eq!(data[2][0], "0");
 }
 
+fn column_with_gaps() -> Vec<Vec<String>> {
+    // A leading gap, then interior gaps, then a trailing gap
+    ["reading", "", "5", "", "", "8", "", "9", ""]
+        .iter()
+        .map(|v| vec![v.to_string(), "x".to_string()])
+        .collect()
+}
+
+fn first_column(data: &[Vec<String>]) -> Vec<&str> {
+    data.iter().map(|row| row[0].as_str()).collect()
+}
+
+#[test]
+fn test_fill_forward() {
+    let ops = DataOperations::new();
+    let mut data = column_with_gaps();
+
+    let filled = ops.fill_forward(&mut data, &[0]);
+
+    assert_eq!(filled, 4);
+    assert_eq!(
+        first_column(&data),
+        vec!["reading", "", "5", "5", "5", "8", "8", "9", "9"]
+    );
+}
+
+#[test]
+fn test_fill_backward() {
+    let ops = DataOperations::new();
+    let mut data = column_with_gaps();
+
+    let filled = ops.fill_backward(&mut data, &[0]);
+
+    assert_eq!(filled, 4);
+    assert_eq!(
+        first_column(&data),
+        vec!["reading", "5", "5", "8", "8", "8", "9", "9", ""]
+    );
+}
+
+fn column(values: &[&str]) -> Vec<Vec<String>> {
+    values.iter().map(|v| vec![v.to_string()]).collect()
+}
+
+#[test]
+fn test_interpolate_linear() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "1", "", "", "4"]);
+
+    assert_eq!(ops.interpolate_linear(&mut data, 0), 2);
+    assert_eq!(data, column(&["value", "1", "2", "3", "4"]));
+
+    // Leading and trailing gaps have only one neighbour
+    let mut data = column(&["value", "", "2", "", "3", ""]);
+    assert_eq!(ops.interpolate_linear(&mut data, 0), 1);
+    assert_eq!(data, column(&["value", "", "2", "2.5", "3", ""]));
+}
+
+#[test]
+fn test_interpolate_linear_skips_non_numeric_boundary() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "1", "", "n/a", "", "4"]);
+
+    assert_eq!(ops.interpolate_linear(&mut data, 0), 0);
+    assert_eq!(data, column(&["value", "1", "", "n/a", "", "4"]));
+}
+
 // ============ Drop NA Tests ============
 
 #[test]
@@ -254,8 +553,32 @@ fn test_value_counts() {
 
     // Should have header + unique categories
     assert!(counts.len() > 1);
-    // Header row exists with value/count columns
-    assert!(counts[0].len() >= 2);
+    assert_eq!(counts[0], vec!["value", "count", "percentage"]);
+
+    let total: f64 = counts[1..]
+        .iter()
+        .map(|row| row[2].parse::<f64>().unwrap())
+        .sum();
+    assert!((total - 100.0).abs() < 0.05, "percentages sum to {total}");
+}
+
+#[test]
+fn test_value_counts_ties_sorted_by_value() {
+    let ops = DataOperations::new();
+    let data = column(&["fruit", "pear", "apple", "fig", "pear", "apple", "kiwi"]);
+
+    let counts = ops.value_counts(&data, 0);
+
+    let rows: Vec<String> = counts[1..].iter().map(|row| row.join(",")).collect();
+    assert_eq!(
+        rows,
+        vec![
+            "apple,2,33.33",
+            "pear,2,33.33",
+            "fig,1,16.67",
+            "kiwi,1,16.67"
+        ]
+    );
 }
 
 // ============ Unique Tests ============
@@ -268,8 +591,63 @@ fn test_unique() {
     // Get unique values in Name column (index 0)
     let unique = ops.unique(&data, 0);
 
-    // Header + Apple, Banana, Cherry, Date = 5
-    assert_eq!(unique.len(), 5);
+    // Apple, Banana, Cherry, Date
+    assert_eq!(unique.len(), 4);
+}
+
+#[test]
+fn test_unique_keeps_first_seen_order() {
+    let ops = DataOperations::new();
+    let data = column(&["city", "Oslo", "Lima", "Oslo", "", "Accra", "Lima"]);
+
+    assert_eq!(ops.unique(&data, 0), vec!["Oslo", "Lima", "", "Accra"]);
+}
+
+// ============ Correlation Tests ============
+
+#[test]
+fn test_correlation_matrix() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = [
+        "x,double,inverse,name,flat",
+        "1,2,10,a,5",
+        "2,4,8,b,5",
+        "3,6,6,c,5",
+        "4,8,4,d,5",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    let matrix = ops.correlation(&data, None).unwrap();
+
+    // The text column is skipped
+    assert_eq!(matrix[0], vec!["", "x", "double", "inverse", "flat"]);
+    assert_eq!(matrix[1], vec!["x", "1.0000", "1.0000", "-1.0000", "NaN"]);
+    assert_eq!(matrix[3][3], "1.0000");
+    assert_eq!(matrix[4][4], "NaN");
+
+    let pair = ops.correlation(&data, Some(&[0, 2])).unwrap();
+    assert_eq!(pair.len(), 3);
+    let r: f64 = pair[1][2].parse().unwrap();
+    assert!((r + 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_correlation_matrix_pairwise_complete() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["x,y,flat", "1,2,5", "2,,5", "3,6,5", "4,8,5"]
+        .iter()
+        .map(|l| l.split(',').map(|s| s.to_string()).collect())
+        .collect();
+
+    let matrix = ops.correlation_matrix(&data, &[0, 1, 2]);
+
+    // The blank `y` drops only that row from the x/y pair
+    assert!((matrix[0][1].unwrap() - 1.0).abs() < 1e-9);
+    assert_eq!(matrix[0][1], matrix[1][0]);
+    assert_eq!(matrix[0][2], None);
+    assert_eq!(matrix[2][2], None);
 }
 
 // ============ Describe Tests ============
@@ -287,6 +665,48 @@ fn test_describe() {
     assert!(desc.iter().any(|r| r[0] == "count" || r[0] == "mean"));
 }
 
+#[test]
+fn test_column_stats_numeric() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["name,score", "a,10", "b,20", "c,", "d,30", "e,20"]
+        .iter()
+        .map(|l| l.split(',').map(|s| s.to_string()).collect())
+        .collect();
+
+    let stats = ops.column_stats(&data, 1).unwrap();
+    let get = |name: &str| {
+        stats
+            .iter()
+            .find(|r| r[0] == name)
+            .map(|r| r[1].clone())
+            .unwrap_or_default()
+    };
+
+    assert_eq!(stats[0], vec!["stat", "score"]);
+    assert_eq!(get("dtype"), "Integer");
+    assert_eq!(get("count"), "4");
+    assert_eq!(get("nulls"), "1");
+    assert_eq!(get("unique"), "3");
+    assert_eq!(get("min"), "10.00");
+    assert_eq!(get("max"), "30.00");
+    assert_eq!(get("mean"), "20.00");
+    assert_eq!(get("median"), "20.00");
+    assert_eq!(get("top1"), "20 (2)");
+}
+
+#[test]
+fn test_column_stats_categorical() {
+    let ops = DataOperations::new();
+    let data = read_example_csv("sales");
+
+    let stats = ops.column_stats(&data, 1).unwrap();
+
+    assert!(stats.iter().any(|r| r[0] == "dtype" && r[1] == "String"));
+    assert!(stats.iter().any(|r| r[0] == "top1"));
+    assert!(!stats.iter().any(|r| r[0] == "mean"));
+    assert!(stats.iter().filter(|r| r[0].starts_with("top")).count() <= 5);
+}
+
 // ============ Transpose Tests ============
 
 #[test]
@@ -305,6 +725,37 @@ fn test_transpose() {
     assert_eq!(transposed[0][1], "1");
 }
 
+#[test]
+fn test_to_html_escapes_cells() {
+    let ops = DataOperations::new();
+    let data = vec![
+        vec!["Name".to_string(), "Note".to_string()],
+        vec!["<b>".to_string(), "Tom & \"Jerry\"".to_string()],
+    ];
+
+    let html = ops.to_html(&data);
+
+    assert!(html.starts_with("<table>"));
+    assert!(html.contains("<thead>\n    <tr><th>Name</th><th>Note</th></tr>\n  </thead>"));
+    assert!(html.contains("<td>&lt;b&gt;</td>"));
+    assert!(html.contains("<td>Tom &amp; &quot;Jerry&quot;</td>"));
+    assert!(!html.contains("<b>"));
+}
+
+#[test]
+fn test_to_tsv_quotes_tabs_and_newlines() {
+    let ops = DataOperations::new();
+    let data = vec![
+        vec!["a".to_string(), "b,c".to_string()],
+        vec!["x\ty".to_string(), "line\n\"two\"".to_string()],
+    ];
+
+    assert_eq!(
+        ops.to_tsv(&data),
+        "a\tb,c\n\"x\ty\"\t\"line\n\"\"two\"\"\"\n"
+    );
+}
+
 // ============ Concat Tests ============
 
 #[test]
@@ -369,6 +820,107 @@ fn test_left_join() {
     assert_eq!(joined.len(), 4); // Header + 3 left rows
 }
 
+fn order_lines() -> (Vec<Vec<String>>, Vec<Vec<String>>) {
+    let parse = |rows: &[&str]| -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|l| l.split(',').map(|s| s.to_string()).collect())
+            .collect()
+    };
+    let left = parse(&["order_id,line_no,qty", "1,1,5", "1,2,3", "2,1,7", "3,1,9"]);
+    let right = parse(&[
+        "line_no,sku,order_id",
+        "1,apple,1",
+        "2,pear,1",
+        "1,plum,2",
+        "2,kiwi,2",
+    ]);
+    (left, right)
+}
+
+#[test]
+fn test_join_multi_inner() {
+    let ops = DataOperations::new();
+    let (left, right) = order_lines();
+
+    let joined = ops
+        .join_multi(&left, &right, &[0, 1], &[2, 0], JoinType::Inner)
+        .unwrap();
+
+    assert_eq!(
+        joined,
+        vec![
+            vec!["order_id", "line_no", "qty", "sku"],
+            vec!["1", "1", "5", "apple"],
+            vec!["1", "2", "3", "pear"],
+            vec!["2", "1", "7", "plum"],
+        ]
+    );
+}
+
+#[test]
+fn test_join_multi_left_and_outer() {
+    let ops = DataOperations::new();
+    let (left, right) = order_lines();
+
+    let joined = ops
+        .join_multi(&left, &right, &[0, 1], &[2, 0], JoinType::Left)
+        .unwrap();
+
+    assert_eq!(joined.len(), 5);
+    // Unmatched left row keeps its cells and gets a blank sku
+    assert_eq!(joined[4], vec!["3", "1", "9", ""]);
+
+    let outer = ops
+        .join_multi(&left, &right, &[0, 1], &[2, 0], JoinType::Outer)
+        .unwrap();
+    assert_eq!(outer.len(), 6);
+    assert_eq!(outer[5], vec!["2", "2", "", "kiwi"]);
+
+    assert!(
+        ops.join_multi(&left, &right, &[0, 1], &[2], JoinType::Inner)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_cross_join() {
+    let ops = DataOperations::new();
+    let left = vec![
+        vec!["size".to_string()],
+        vec!["S".to_string()],
+        vec!["M".to_string()],
+        vec!["L".to_string()],
+    ];
+    let right = vec![
+        vec!["color".to_string(), "hex".to_string()],
+        vec!["red".to_string(), "#f00".to_string()],
+        vec!["blue".to_string(), "#00f".to_string()],
+    ];
+
+    // Join columns are ignored for cross joins
+    let joined = ops.join(&left, &right, 0, 1, JoinType::Cross).unwrap();
+
+    assert_eq!(joined.len(), 7); // Header + 3 x 2 rows
+    assert!(joined.iter().all(|row| row.len() == 3));
+    assert_eq!(joined[0], vec!["size", "color", "hex"]);
+    assert_eq!(joined[1], vec!["S", "red", "#f00"]);
+    assert_eq!(joined[6], vec!["L", "blue", "#00f"]);
+    assert_eq!(JoinType::from_str("cross").unwrap(), JoinType::Cross);
+}
+
+#[test]
+fn test_cross_join_row_limit() {
+    let ops = DataOperations::new();
+    let left: Vec<Vec<String>> = (0..4).map(|i| vec![i.to_string()]).collect();
+    let right: Vec<Vec<String>> = (0..4).map(|i| vec![i.to_string()]).collect();
+
+    // 3 x 3 data rows exceeds a limit of 8
+    let err = ops.cross_join(&left, &right, Some(8)).unwrap_err();
+    assert!(err.to_string().contains("limit"));
+    assert_eq!(ops.cross_join(&left, &right, Some(9)).unwrap().len(), 10);
+    assert_eq!(ops.cross_join(&left, &right, None).unwrap().len(), 10);
+}
+
 // ============ Groupby Tests ============
 
 #[test]
@@ -396,6 +948,693 @@ fn test_groupby_count() {
     assert!(grouped.len() == 4); // Header + 3 departments
 }
 
+#[test]
+fn test_groupby_count_distinct() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = [
+        "region,customer,amount",
+        "north,alice,10",
+        "north,bob,5",
+        "north,alice,7",
+        "south,carol,3",
+        "south,carol,4",
+        "south,,9",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    let grouped = ops
+        .groupby(&data, 0, &[(1, AggFunc::CountDistinct), (2, AggFunc::Sum)])
+        .unwrap();
+
+    assert_eq!(
+        grouped,
+        vec![
+            vec!["region", "count_distinct_customer", "sum_amount"],
+            vec!["north", "2", "22.00"],
+            vec!["south", "1", "16.00"],
+        ]
+    );
+    assert!(matches!(
+        AggFunc::from_str("ndistinct").unwrap(),
+        AggFunc::CountDistinct
+    ));
+}
+
+#[test]
+fn test_groupby_concat() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["team,member", "x,a", "y,d", "x,b", "x,", "x,c"]
+        .iter()
+        .map(|l| l.split(',').map(|s| s.to_string()).collect())
+        .collect();
+
+    let concat = AggFunc::Concat {
+        sep: ";".to_string(),
+    };
+    let grouped = ops.groupby(&data, 0, &[(1, concat)]).unwrap();
+
+    assert_eq!(grouped[0], vec!["team", "concat_member"]);
+    assert_eq!(grouped[1], vec!["x", "a;b;c"]);
+    assert_eq!(grouped[2], vec!["y", "d"]);
+}
+
+#[test]
+fn test_groupby_multi_two_keys() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = [
+        "region,product,units,price",
+        "north,apple,10,1.0",
+        "south,apple,4,2.0",
+        "north,pear,3,3.0",
+        "north,apple,6,2.0",
+        "south,apple,2,4.0",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    let grouped = ops
+        .groupby_multi(&data, &[0, 1], &[(2, AggFunc::Sum), (3, AggFunc::Mean)])
+        .unwrap();
+
+    assert_eq!(
+        grouped,
+        vec![
+            vec!["region", "product", "sum_units", "mean_price"],
+            vec!["north", "apple", "16.00", "1.50"],
+            vec!["north", "pear", "3.00", "3.00"],
+            vec!["south", "apple", "6.00", "3.00"],
+        ]
+    );
+
+    // A single key matches the classic groupby
+    assert_eq!(
+        ops.groupby_multi(&data, &[0], &[(2, AggFunc::Sum)])
+            .unwrap(),
+        ops.groupby(&data, 0, &[(2, AggFunc::Sum)]).unwrap()
+    );
+    assert!(ops.groupby_multi(&data, &[], &[(2, AggFunc::Sum)]).is_err());
+}
+
+#[test]
+fn test_agg_median_even_length_and_percentile() {
+    let values: Vec<f64> = (1..=10).map(|v| v as f64).collect();
+
+    assert_eq!(AggFunc::Median.apply(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+    assert_eq!(AggFunc::Median.apply(&[3.0, 1.0, 2.0]), 2.0);
+
+    let p90 = AggFunc::from_str("p90").unwrap();
+    assert_eq!(p90.name(), "p90");
+    assert!((p90.apply(&values) - 9.1).abs() < 1e-9);
+    assert_eq!(AggFunc::from_str("P95").unwrap().name(), "p95");
+    assert!(AggFunc::from_str("p101").is_err());
+    assert!(AggFunc::from_str("pear").is_err());
+}
+
+#[test]
+fn test_agg_std_var() {
+    let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+    let var = AggFunc::from_str("var").unwrap().apply(&values);
+    assert!((var - 32.0 / 7.0).abs() < 1e-9);
+    let std = AggFunc::from_str("std").unwrap().apply(&values);
+    assert!((std - (32.0f64 / 7.0).sqrt()).abs() < 1e-9);
+    assert_eq!(AggFunc::Std.apply(&[5.0]), 0.0);
+}
+
+#[test]
+fn test_groupby_median_and_percentile() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["team,score", "a,1", "a,4", "a,2", "a,3", "b,10"]
+        .iter()
+        .map(|l| l.split(',').map(|s| s.to_string()).collect())
+        .collect();
+
+    let grouped = ops
+        .groupby(
+            &data,
+            0,
+            &[(1, AggFunc::Median), (1, AggFunc::from_str("p90").unwrap())],
+        )
+        .unwrap();
+
+    assert_eq!(grouped[0], vec!["team", "median_score", "p90_score"]);
+    assert_eq!(grouped[1], vec!["a", "2.50", "3.70"]);
+    assert_eq!(grouped[2], vec!["b", "10.00", "10.00"]);
+}
+
+#[test]
+fn test_pivot_region_by_quarter() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = [
+        "region,quarter,revenue",
+        "west,Q2,50",
+        "east,Q1,100",
+        "east,Q2,80",
+        "west,Q1,30",
+        "east,Q1,20",
+        "north,Q3,5",
+        "west,Q2,n/a",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    let pivoted = ops.pivot(&data, 0, 1, 2, AggFunc::Sum).unwrap();
+
+    assert_eq!(
+        pivoted,
+        vec![
+            vec!["region", "Q1", "Q2", "Q3"],
+            vec!["east", "120.00", "80.00", ""],
+            vec!["north", "", "", "5.00"],
+            vec!["west", "30.00", "50.00", ""],
+        ]
+    );
+
+    // Non-numeric cells are ignored rather than counted as zero
+    let means = ops.pivot(&data, 0, 1, 2, AggFunc::Mean).unwrap();
+    assert_eq!(means[3], vec!["west", "30.00", "50.00", ""]);
+}
+
+#[test]
+fn test_melt_wide_to_long() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = vec![
+        vec!["id".into(), "name".into(), "q1".into(), "q2".into()],
+        vec!["1".into(), "alice".into(), "10".into(), "20".into()],
+        // Ragged row: q2 is missing
+        vec!["2".into(), "bob".into(), "30".into()],
+    ];
+
+    let melted = ops.melt(&data, &[0, 1], &[2, 3]);
+
+    assert_eq!(
+        melted,
+        vec![
+            vec!["id", "name", "variable", "value"],
+            vec!["1", "alice", "q1", "10"],
+            vec!["1", "alice", "q2", "20"],
+            vec!["2", "bob", "q1", "30"],
+            vec!["2", "bob", "q2", ""],
+        ]
+    );
+}
+
+#[test]
+fn test_melt_inverts_pivot() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["region,quarter,revenue", "east,Q1,1.00", "east,Q2,2.00"]
+        .iter()
+        .map(|l| l.split(',').map(|s| s.to_string()).collect())
+        .collect();
+
+    let pivoted = ops.pivot(&data, 0, 1, 2, AggFunc::Max).unwrap();
+    let melted = ops.melt(&pivoted, &[0], &[1, 2]);
+
+    assert_eq!(melted[0], vec!["region", "variable", "value"]);
+    assert_eq!(melted[1..], data[1..]);
+}
+
+#[test]
+fn test_rolling_mean_window_three() {
+    let ops = DataOperations::new();
+    let mut data = vec![vec!["day".to_string(), "value".to_string()]];
+    for i in 1..=10 {
+        data.push(vec![i.to_string(), (i * 10).to_string()]);
+    }
+
+    let rolled = ops.rolling(&data, 1, 3, AggFunc::Mean).unwrap();
+
+    assert_eq!(rolled[0], vec!["day", "value", "rolling_mean_value"]);
+    let means: Vec<&str> = rolled[1..].iter().map(|r| r[2].as_str()).collect();
+    assert_eq!(
+        means,
+        vec![
+            "", "", "20.00", "30.00", "40.00", "50.00", "60.00", "70.00", "80.00", "90.00"
+        ]
+    );
+    // Original columns are untouched
+    assert_eq!(rolled[10][..2], data[10][..]);
+
+    assert!(ops.rolling(&data, 1, 0, AggFunc::Mean).is_err());
+}
+
+#[test]
+fn test_cumulative_sum_and_max() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["v", "3", "1", "n/a", "4", "2"]
+        .iter()
+        .map(|c| vec![c.to_string()])
+        .collect();
+
+    let cumsum = ops.cumulative(&data, 0, CumOp::Sum);
+    assert_eq!(cumsum[0], vec!["v", "cumsum_v"]);
+    let sums: Vec<&str> = cumsum[1..].iter().map(|r| r[1].as_str()).collect();
+    // The non-numeric cell adds nothing and repeats the running total
+    assert_eq!(sums, vec!["3.00", "4.00", "4.00", "8.00", "10.00"]);
+
+    let cummax = ops.cumulative(&data, 0, "cummax".parse::<CumOp>().unwrap());
+    assert_eq!(cummax[0], vec!["v", "cummax_v"]);
+    let maxes: Vec<&str> = cummax[1..].iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(maxes, vec!["3.00", "3.00", "3.00", "4.00", "4.00"]);
+}
+
+#[test]
+fn test_cumulative_leading_non_numeric() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["v", "", "2", "x", "5"]
+        .iter()
+        .map(|c| vec![c.to_string()])
+        .collect();
+
+    let cumprod = ops.cumulative(&data, 0, CumOp::Product);
+    let products: Vec<&str> = cumprod[1..].iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(products, vec!["", "2.00", "2.00", "10.00"]);
+
+    let cummin = ops.cumulative(&data, 0, CumOp::Min);
+    let mins: Vec<&str> = cummin[1..].iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(mins, vec!["", "2.00", "2.00", "2.00"]);
+}
+
+#[test]
+fn test_rank_three_way_tie() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = [
+        "name,score",
+        "a,10",
+        "b,20",
+        "c,20",
+        "d,n/a",
+        "e,20",
+        "f,30",
+    ]
+    .iter()
+    .map(|l| l.split(',').map(|s| s.to_string()).collect())
+    .collect();
+
+    let ranks = |order, method| -> Vec<String> {
+        let ranked = ops.rank(&data, 1, order, method);
+        assert_eq!(ranked[0], vec!["name", "score", "rank"]);
+        ranked[1..].iter().map(|r| r[2].clone()).collect()
+    };
+
+    assert_eq!(
+        ranks(SortOrder::Ascending, RankMethod::Dense),
+        vec!["1", "2", "2", "", "2", "3"]
+    );
+    assert_eq!(
+        ranks(SortOrder::Ascending, RankMethod::Min),
+        vec!["1", "2", "2", "", "2", "5"]
+    );
+    assert_eq!(
+        ranks(SortOrder::Ascending, RankMethod::Max),
+        vec!["1", "4", "4", "", "4", "5"]
+    );
+    assert_eq!(
+        ranks(SortOrder::Ascending, RankMethod::Average),
+        vec!["1", "3", "3", "", "3", "5"]
+    );
+    assert_eq!(
+        ranks(SortOrder::Ascending, RankMethod::First),
+        vec!["1", "2", "3", "", "4", "5"]
+    );
+    assert_eq!(
+        ranks(SortOrder::Descending, RankMethod::Dense),
+        vec!["3", "2", "2", "", "2", "1"]
+    );
+    assert_eq!("dense".parse::<RankMethod>().unwrap(), RankMethod::Dense);
+}
+
+#[test]
+fn test_rank_skips_nan_cells() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = ["score", "5", "NaN", "1", "nan", "5"]
+        .iter()
+        .map(|v| vec![v.to_string()])
+        .collect();
+
+    let ranked = ops.rank(&data, 0, SortOrder::Ascending, RankMethod::Min);
+
+    let ranks: Vec<&str> = ranked[1..].iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(ranks, vec!["2", "", "1", "", "2"]);
+}
+
+// ============ Binning Tests ============
+
+fn scores(values: &[&str]) -> Vec<Vec<String>> {
+    std::iter::once("score")
+        .chain(values.iter().copied())
+        .map(|v| vec![v.to_string()])
+        .collect()
+}
+
+#[test]
+fn test_cut_equal_width_boundaries() {
+    let ops = DataOperations::new();
+    let data = scores(&["0", "9.9", "10", "25", "n/a", "39", "40"]);
+
+    let binned = ops.cut(&data, 0, 4).unwrap();
+
+    assert_eq!(binned[0], vec!["score", "score_bin"]);
+    let labels: Vec<&str> = binned[1..].iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "[0,10)", "[0,10)", "[10,20)", "[20,30)", "", "[30,40]", "[30,40]"
+        ]
+    );
+    assert!(ops.cut(&data, 0, 0).is_err());
+}
+
+#[test]
+fn test_qcut_equal_sized_buckets() {
+    let ops = DataOperations::new();
+    let values: Vec<String> = (1..=100).map(|v| (v * v).to_string()).collect();
+    let values: Vec<&str> = values.iter().map(String::as_str).collect();
+    let data = scores(&values);
+
+    let binned = ops.qcut(&data, 0, 4).unwrap();
+
+    let mut counts: std::collections::BTreeMap<&str, usize> = Default::default();
+    for row in &binned[1..] {
+        *counts.entry(row[1].as_str()).or_default() += 1;
+    }
+    assert_eq!(counts.len(), 4);
+    assert!(
+        counts.values().all(|&n| (24..=26).contains(&n)),
+        "{counts:?}"
+    );
+
+    // Tied values collapse duplicate edges into fewer bins
+    let tied = scores(&["1", "1", "1", "1", "1", "1", "2", "3"]);
+    let binned = ops.qcut(&tied, 0, 4).unwrap();
+    let labels: Vec<&str> = binned[1..].iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "[1,1.25)", "[1,1.25)", "[1,1.25)", "[1,1.25)", "[1,1.25)", "[1,1.25)", "[1.25,3]",
+            "[1.25,3]"
+        ]
+    );
+}
+
+// ============ Clip/Normalize Tests ============
+
+#[test]
+fn test_clip() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "-5", "3", "12.5", "n/a", ""]);
+
+    let clipped = ops.clip_range(&mut data, 0, 0.0, 10.0).unwrap();
+
+    assert_eq!(clipped, 2);
+    assert_eq!(data, column(&["value", "0", "3", "10", "n/a", ""]));
+    assert!(ops.clip_range(&mut data, 0, 5.0, 1.0).is_err());
+}
+
+#[test]
+fn test_clip_open_bounds() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "-5", "3", "12.5"]);
+
+    assert_eq!(ops.clip(&mut data, 0, None, Some(10.0)).unwrap(), 1);
+    assert_eq!(data, column(&["value", "-5", "3", "10"]));
+    assert_eq!(ops.clip(&mut data, 0, Some(0.0), None).unwrap(), 1);
+    assert_eq!(data, column(&["value", "0", "3", "10"]));
+}
+
+#[test]
+fn test_normalize_minmax() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "10", "15", "30", "x"]);
+
+    ops.normalize_minmax(&mut data, 0).unwrap();
+    assert_eq!(data, column(&["value", "0.0000", "0.2500", "1.0000", "x"]));
+
+    // A constant column has no range to scale by
+    let mut data = column(&["value", "7", "7", "7"]);
+    ops.normalize_minmax(&mut data, 0).unwrap();
+    assert_eq!(data, column(&["value", "0.0000", "0.0000", "0.0000"]));
+}
+
+#[test]
+fn test_normalize_zscore() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "2", "4", "6"]);
+
+    ops.normalize(&mut data, 0, NormalizeMethod::ZScore)
+        .unwrap();
+
+    assert_eq!(data, column(&["value", "-1.2247", "0.0000", "1.2247"]));
+    assert!("median".parse::<NormalizeMethod>().is_err());
+}
+
+#[test]
+fn test_standardize() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "3", "9", "n/a", "12", "4", "22"]);
+
+    ops.standardize(&mut data, 0, true).unwrap();
+
+    assert_eq!(data[0], vec!["value", "value_zscore"]);
+    assert_eq!(data[3], vec!["n/a", ""]);
+    let scores: Vec<f64> = data
+        .iter()
+        .skip(1)
+        .filter_map(|row| row[1].parse().ok())
+        .collect();
+    let n = scores.len() as f64;
+    let mean = scores.iter().sum::<f64>() / n;
+    let std = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+    assert!(mean.abs() < 1e-3, "mean {mean}");
+    assert!((std - 1.0).abs() < 1e-3, "std {std}");
+
+    // Replacing in place; no spread gives all zeros
+    let mut data = column(&["value", "5", "5"]);
+    ops.standardize(&mut data, 0, false).unwrap();
+    assert_eq!(data, column(&["value", "0.0000", "0.0000"]));
+}
+
+// ============ Mutate Tests ============
+
+fn priced_items() -> Vec<Vec<String>> {
+    vec![
+        vec!["item".to_string(), "price".to_string(), "qty".to_string()],
+        vec!["pen".to_string(), "1.5".to_string(), "4".to_string()],
+        vec!["pad".to_string(), "3".to_string(), "2".to_string()],
+        vec!["ink".to_string(), "n/a".to_string(), "1".to_string()],
+    ]
+}
+
+#[test]
+fn test_mutate_by_column_name() {
+    let ops = DataOperations::new();
+    let mut data = priced_items();
+
+    ops.mutate(&mut data, "total", "price*qty").unwrap();
+
+    assert_eq!(data[0], vec!["item", "price", "qty", "total"]);
+    assert_eq!(data[1][3], "6");
+    assert_eq!(data[2][3], "6");
+    // A non-numeric price gives an error cell rather than failing the whole column
+    assert_eq!(data[3][3], "#VALUE!");
+}
+
+#[test]
+fn test_mutate_by_column_letter() {
+    let ops = DataOperations::new();
+    let mut data = priced_items();
+    data.pop();
+
+    ops.mutate(&mut data, "with_fee", "ROUND(B*C+0.25, 1)")
+        .unwrap();
+
+    assert_eq!(data[1][3], "6.3");
+    assert_eq!(data[2][3], "6.3");
+}
+
+// ============ Progress Tests ============
+
+/// Records every `current` it is given and checks the total stays fixed
+struct CountingProgress {
+    seen: Vec<usize>,
+    total: Option<usize>,
+}
+
+impl ProgressCallback for CountingProgress {
+    fn on_progress(&mut self, current: usize, total: Option<usize>, _message: &str) {
+        self.seen.push(current);
+        self.total = total;
+    }
+}
+
+fn assert_increasing_to(progress: &CountingProgress, total: usize) {
+    assert!(
+        progress.seen.len() > 2,
+        "too few reports: {:?}",
+        progress.seen
+    );
+    assert!(progress.seen.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(progress.seen.last(), Some(&total));
+    assert_eq!(progress.total, Some(total));
+}
+
+fn keyed_rows(header: &str, rows: usize) -> Vec<Vec<String>> {
+    let mut data = vec![header.split(',').map(|s| s.to_string()).collect()];
+    data.extend((0..rows).map(|i| vec![(i % 7).to_string(), i.to_string()]));
+    data
+}
+
+#[test]
+fn test_join_with_progress() {
+    let ops = DataOperations::new();
+    let left = keyed_rows("key,value", 3000);
+    let right: Vec<Vec<String>> = (0..7)
+        .map(|i| vec![i.to_string(), format!("k{i}")])
+        .collect();
+
+    let mut progress = CountingProgress {
+        seen: Vec::new(),
+        total: None,
+    };
+    let joined = ops
+        .join_with_progress(&left, &right, 0, 0, JoinType::Inner, &mut progress)
+        .unwrap();
+
+    assert_eq!(joined.len(), 3000);
+    assert_increasing_to(&progress, left.len());
+}
+
+#[test]
+fn test_groupby_with_progress() {
+    let ops = DataOperations::new();
+    let data = keyed_rows("key,value", 2500);
+
+    let mut progress = CountingProgress {
+        seen: Vec::new(),
+        total: None,
+    };
+    let grouped = ops
+        .groupby_with_progress(&data, 0, &[(1, AggFunc::Count)], &mut progress)
+        .unwrap();
+
+    assert_eq!(grouped.len(), 8);
+    assert_increasing_to(&progress, 2500);
+}
+
+// ============ Index Tests ============
+
+#[test]
+fn test_head_with_index() {
+    let ops = DataOperations::new();
+    let data = read_example_csv("employees");
+
+    let indexed = ops.add_index(&data, "index", 0);
+    let head = ops.head(&indexed, 4);
+
+    assert_eq!(head[0][0], "index");
+    assert_eq!(head[0][1..], data[0][..]);
+    let indices: Vec<&str> = head[1..].iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(indices, vec!["0", "1", "2"]);
+
+    let one_based = ops.add_index(&data, "index", 1);
+    assert_eq!(one_based[1][0], "1");
+
+    assert_eq!(ops.reset_index(&indexed, "index"), data);
+    assert_eq!(ops.reset_index(&data, "index"), data);
+}
+
+// ============ Astype Tests ============
+
+#[test]
+fn test_astype_float_to_int() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "3.0", "-12", "", "1e3"]);
+
+    let converted = ops.astype(&mut data, 0, ColumnType::Int).unwrap();
+
+    assert_eq!(converted, 3);
+    assert_eq!(data, column(&["value", "3", "-12", "", "1000"]));
+}
+
+#[test]
+fn test_astype_reports_failures() {
+    let ops = DataOperations::new();
+    let mut data = column(&["value", "1.5", "abc", "2", "n/a"]);
+
+    let err = ops
+        .astype(&mut data, 0, ColumnType::Float)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("2 cell(s)"), "{err}");
+    assert!(err.contains("row 3 ('abc')"), "{err}");
+    assert!(err.contains("row 5 ('n/a')"), "{err}");
+    // Nothing is cast when any cell fails
+    assert_eq!(data, column(&["value", "1.5", "abc", "2", "n/a"]));
+
+    let mut data = column(&["value", "1.5", "2"]);
+    ops.astype(&mut data, 0, ColumnType::Float).unwrap();
+    assert_eq!(data, column(&["value", "1.5", "2.0"]));
+}
+
+#[test]
+fn test_astype_bool_and_date() {
+    let ops = DataOperations::new();
+
+    let mut data = column(&["value", "yes", "N", "1", "False"]);
+    ops.astype(&mut data, 0, ColumnType::Bool).unwrap();
+    assert_eq!(data, column(&["value", "true", "false", "true", "false"]));
+
+    let target: ColumnType = "date:%d/%m/%Y".parse().unwrap();
+    let mut data = column(&["value", "17/10/2026", "01/02/2024"]);
+    ops.astype(&mut data, 0, target.clone()).unwrap();
+    assert_eq!(data, column(&["value", "2026-10-17", "2024-02-01"]));
+
+    let mut data = column(&["value", "2026-10-17"]);
+    assert!(ops.astype(&mut data, 0, target).is_err());
+    assert!("decimal".parse::<ColumnType>().is_err());
+}
+
+// ============ Parse Date Tests ============
+
+#[test]
+fn test_parse_date_reformats() {
+    let ops = DataOperations::new();
+    let data = column(&["joined", "17/10/2026", "", "01/02/2024"]);
+
+    let parsed = ops.parse_date(&data, 0, "%d/%m/%Y", "%Y-%m-%d").unwrap();
+
+    assert_eq!(parsed, column(&["joined", "2026-10-17", "", "2024-02-01"]));
+
+    let data = column(&["at", "2024-02-01 09:30:00"]);
+    let parsed = ops
+        .parse_date(&data, 0, "%Y-%m-%d %H:%M:%S", "%d %b %Y %H:%M")
+        .unwrap();
+    assert_eq!(parsed[1][0], "01 Feb 2024 09:30");
+}
+
+#[test]
+fn test_parse_date_reports_bad_rows() {
+    let ops = DataOperations::new();
+    let data = column(&["joined", "17/10/2026", "2026-10-17", "31/02/2024"]);
+
+    let err = ops
+        .parse_date(&data, 0, "%d/%m/%Y", "%Y-%m-%d")
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("2 cell(s)"), "{err}");
+    assert!(err.contains("row 3 ('2026-10-17')"), "{err}");
+    assert!(err.contains("row 4 ('31/02/2024')"), "{err}");
+
+    // The input has no time of day to write
+    assert!(ops.parse_date(&data[..2], 0, "%d/%m/%Y", "%H:%M").is_err());
+}
+
 // ============ Dtypes Tests ============
 
 #[test]
@@ -410,6 +1649,34 @@ fn test_dtypes() {
     // ID should be detected as integer, Salary as integer/float
 }
 
+#[test]
+fn test_infer_dtypes() {
+    let ops = DataOperations::new();
+    let data: Vec<Vec<String>> = [
+        ["id", "email", "score", "notes"],
+        ["17", "ann@example.com", "3.5", ""],
+        ["23", "bo@example.org", "4.25", ""],
+        ["42", "cy@example.net", "5", ""],
+    ]
+    .iter()
+    .map(|row| row.iter().map(|s| s.to_string()).collect())
+    .collect();
+
+    let dtypes = ops.infer_dtypes(&data);
+
+    let expected = [
+        ("id", "Integer"),
+        ("email", "Email"),
+        ("score", "Float"),
+        ("notes", "Unknown"),
+    ];
+    let expected: Vec<(String, String)> = expected
+        .iter()
+        .map(|(name, dtype)| (name.to_string(), dtype.to_string()))
+        .collect();
+    assert_eq!(dtypes, expected);
+}
+
 // ============ Info Tests ============
 
 #[test]
@@ -1,7 +1,7 @@
 //! Tests for plugin system
 
 use datacell::plugins::{
-    PluginFunction, PluginRegistry, PrefixPlugin, UppercasePlugin,
+    BUILTIN_FUNCTIONS, PluginFunction, PluginRegistry, PrefixPlugin, UppercasePlugin,
 };
 
 #[test]
@@ -196,3 +196,132 @@ fn test_plugin_with_out_of_bounds_column() {
 
     assert_eq!(result[1][0], "alice");
 }
+
+fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+    data.iter()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect()
+}
+
+#[test]
+fn test_default_registry_has_builtin_functions() {
+    let registry = PluginRegistry::default();
+    for name in BUILTIN_FUNCTIONS {
+        assert!(registry.get_metadata(name).is_some(), "{name}");
+    }
+}
+
+#[test]
+fn test_drop_empty_columns_removes_fully_empty_column() {
+    let registry = PluginRegistry::default();
+    let data = rows(&[
+        &["id", "notes", "name"],
+        &["1", "", "alice"],
+        &["2", "  ", "bob"],
+        &["3"],
+    ]);
+
+    let result = registry.execute("drop_empty_columns", &[], &data).unwrap();
+
+    assert_eq!(
+        result,
+        rows(&[&["id", "name"], &["1", "alice"], &["2", "bob"], &["3", ""]])
+    );
+}
+
+#[test]
+fn test_trim_whitespace_trims_padded_cells() {
+    let registry = PluginRegistry::default();
+    let data = rows(&[&[" id ", "name\t"], &["  1", " alice  "]]);
+
+    let result = registry.execute("trim_whitespace", &[], &data).unwrap();
+
+    assert_eq!(result, rows(&[&["id", "name"], &["1", "alice"]]));
+}
+
+#[test]
+fn test_header_cleanup_functions() {
+    let registry = PluginRegistry::default();
+    let data = rows(&[&["ID", "Name", "name", "name_2"], &["1", "a", "b", "c"]]);
+
+    let lowered = registry
+        .execute("to_lowercase_headers", &[], &data)
+        .unwrap();
+    let deduped = registry.execute("dedupe_headers", &[], &lowered).unwrap();
+
+    assert_eq!(deduped[0], ["id", "name", "name_3", "name_2"]);
+    assert_eq!(deduped[1], data[1]);
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_plugin_transforms_rows() {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/uppercase.wat");
+    let mut registry = PluginRegistry::new();
+    registry.register_wasm("shout", fixture).unwrap();
+
+    let data = vec![
+        vec!["name".to_string(), "city".to_string()],
+        vec!["alice".to_string(), "Paris".to_string()],
+    ];
+    let result = registry.execute("shout", &[], &data).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            vec!["NAME".to_string(), "CITY".to_string()],
+            vec!["ALICE".to_string(), "PARIS".to_string()],
+        ]
+    );
+    let with_args = registry.execute("shout", &["0".to_string()], &data);
+    assert!(with_args.is_err());
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_plugin_stops_when_out_of_fuel() {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/spin.wat");
+    let mut registry = PluginRegistry::new();
+    registry
+        .register_wasm_with_fuel("spin", fixture, 100_000)
+        .unwrap();
+
+    let data = vec![vec!["name".to_string()]];
+    let error = registry.execute("spin", &[], &data).unwrap_err();
+
+    assert!(error.to_string().contains("ran out of fuel"));
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_plugin_memory_is_capped() {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/grow.wat");
+    let mut registry = PluginRegistry::new();
+    registry.register_wasm("grow", fixture).unwrap();
+
+    let data = vec![vec!["name".to_string()]];
+    let result = registry.execute("grow", &[], &data).unwrap();
+
+    assert_eq!(result, vec![vec!["capped".to_string()]]);
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_plugin_rejects_rows_outside_memory() {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/oob.wat");
+    let mut registry = PluginRegistry::new();
+    registry.register_wasm("oob", fixture).unwrap();
+
+    let data = vec![vec!["name".to_string()]];
+    let error = registry.execute("oob", &[], &data).unwrap_err();
+
+    assert!(error.to_string().contains("outside its memory"));
+}
+
+#[cfg(not(feature = "wasm"))]
+#[test]
+fn test_register_wasm_requires_feature() {
+    let mut registry = PluginRegistry::new();
+    let error = registry.register_wasm("shout", "plugin.wasm").unwrap_err();
+    assert!(error.to_string().contains("'wasm' feature"));
+}
@@ -0,0 +1,248 @@
+//! Tests for data profiling
+
+use datacell::DataProfiler;
+use datacell::profiling::{
+    DataType, analysis::DEFAULT_DATE_FORMATS, profiler::MAX_KEY_PAIR_COLUMNS,
+};
+
+fn table(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> Vec<Vec<String>> {
+    let mut data = vec![header.iter().map(|h| h.to_string()).collect()];
+    data.extend(rows);
+    data
+}
+
+// ============ Histogram Tests ============
+
+#[test]
+fn test_uniform_column_has_even_histogram() {
+    let data = table(&["value"], (0..100).map(|i| vec![i.to_string()]));
+
+    let profile = DataProfiler::new()
+        .with_histogram_bins(5)
+        .profile(&data, "uniform.csv")
+        .unwrap();
+
+    let histogram = profile.columns[0]
+        .numeric_stats
+        .as_ref()
+        .unwrap()
+        .histogram
+        .as_ref()
+        .unwrap();
+    assert_eq!(histogram.len(), 5);
+    assert_eq!(histogram[0].0, "[0.00, 19.80)");
+    assert_eq!(histogram[4].0, "[79.20, 99.00]");
+    for (_, count) in histogram {
+        assert!((19..=21).contains(count), "{histogram:?}");
+    }
+    assert_eq!(histogram.iter().map(|(_, count)| count).sum::<usize>(), 100);
+}
+
+#[test]
+fn test_report_renders_histogram_bars() {
+    let data = table(
+        &["value"],
+        [1, 1, 1, 1, 9, 9, 9, 9].map(|v| vec![v.to_string()]),
+    );
+    let profiler = DataProfiler::new().with_histogram_bins(4);
+    let profile = profiler.profile(&data, "bimodal.csv").unwrap();
+
+    let report = profiler.generate_report(&profile);
+
+    assert!(report.contains("**Histogram**"));
+    assert!(report.contains(&format!("{} 4", "#".repeat(40))));
+    assert!(report.contains("[3.00, 5.00) |  0"));
+}
+
+#[test]
+fn test_histogram_can_be_disabled() {
+    let data = table(&["value"], (0..10).map(|i| vec![i.to_string()]));
+
+    let profile = DataProfiler::new()
+        .with_histogram_bins(0)
+        .profile(&data, "plain.csv")
+        .unwrap();
+
+    assert!(
+        profile.columns[0]
+            .numeric_stats
+            .as_ref()
+            .unwrap()
+            .histogram
+            .is_none()
+    );
+}
+
+// ============ Correlation Tests ============
+
+#[test]
+fn test_derived_column_is_flagged_as_correlated() {
+    let data = table(
+        &["price", "price_with_tax", "quantity"],
+        [(10, 3), (20, 1), (15, 4), (40, 2), (25, 5)].map(|(price, quantity)| {
+            vec![
+                price.to_string(),
+                format!("{:.2}", price as f64 * 1.2),
+                quantity.to_string(),
+            ]
+        }),
+    );
+
+    let profile = DataProfiler::new().profile(&data, "orders.csv").unwrap();
+
+    let matrix = profile.correlations.as_ref().unwrap();
+    assert_eq!(matrix.columns, ["price", "price_with_tax", "quantity"]);
+    assert!((matrix.values[0][1].unwrap() - 1.0).abs() < 1e-9);
+    assert!(matrix.values[0][2].unwrap().abs() < 0.9);
+    assert!(profile.recommendations.contains(
+        &"Columns 'price' and 'price_with_tax' are highly correlated (r = 1.00); consider dropping one."
+            .to_string()
+    ));
+    assert!(
+        !profile
+            .recommendations
+            .iter()
+            .any(|rec| rec.contains("'quantity'") && rec.contains("correlated"))
+    );
+
+    let report = DataProfiler::new().generate_report(&profile);
+    assert!(report.contains("## Correlations"));
+    assert!(report.contains("| price | 1.00 | 1.00 |"));
+}
+
+#[test]
+fn test_single_numeric_column_has_no_correlations() {
+    let data = table(
+        &["name", "age"],
+        [("ann", 31), ("bob", 45)].map(|(name, age)| vec![name.to_string(), age.to_string()]),
+    );
+
+    let profile = DataProfiler::new().profile(&data, "people.csv").unwrap();
+
+    assert!(profile.correlations.is_none());
+}
+
+#[test]
+fn test_correlations_can_be_turned_off() {
+    let data = table(
+        &["x", "y", "z"],
+        [(1, 2, 5), (2, 4, 3), (3, 7, 4)]
+            .map(|(x, y, z)| [x, y, z].map(|v: i32| v.to_string()).to_vec()),
+    );
+
+    let profile = DataProfiler::new().profile(&data, "xyz.csv").unwrap();
+    let matrix = profile.correlations.unwrap();
+    for i in 0..3 {
+        assert!((matrix.values[i][i].unwrap() - 1.0).abs() < 1e-12);
+        for j in 0..3 {
+            assert_eq!(matrix.values[i][j], matrix.values[j][i]);
+        }
+    }
+
+    let profile = DataProfiler::new()
+        .with_correlations(false)
+        .profile(&data, "xyz.csv")
+        .unwrap();
+    assert!(profile.correlations.is_none());
+}
+
+// ============ Candidate Key Tests ============
+
+#[test]
+fn test_unique_id_column_is_candidate_key() {
+    let data = table(
+        &["id", "first", "last", "team"],
+        [
+            ["1", "ann", "lee", "red"],
+            ["2", "ann", "kim", "red"],
+            ["3", "bob", "lee", ""],
+            ["4", "bob", "kim", "blue"],
+        ]
+        .map(|row| row.map(String::from).to_vec()),
+    );
+
+    let profile = DataProfiler::new().profile(&data, "people.csv").unwrap();
+
+    assert_eq!(profile.candidate_keys, ["id", "first,last"]);
+    let report = DataProfiler::new().generate_report(&profile);
+    assert!(report.contains("## Candidate Keys\n\n- id\n- first,last\n"));
+}
+
+#[test]
+fn test_no_candidate_keys_with_duplicate_rows() {
+    let data = table(
+        &["first", "last"],
+        [["ann", "lee"], ["ann", "lee"]].map(|row| row.map(String::from).to_vec()),
+    );
+
+    let profile = DataProfiler::new().profile(&data, "dupes.csv").unwrap();
+
+    assert!(profile.candidate_keys.is_empty());
+}
+
+#[test]
+fn test_pair_keys_are_skipped_for_wide_tables() {
+    let wide = |extra: usize| {
+        let mut header = vec!["first".to_string(), "last".to_string()];
+        header.extend((0..extra).map(|i| format!("flag{i}")));
+        let mut rows = vec![header];
+        for (first, last) in [("ann", "lee"), ("ann", "kim"), ("bob", "lee")] {
+            let mut row = vec![first.to_string(), last.to_string()];
+            row.extend((0..extra).map(|_| "y".to_string()));
+            rows.push(row);
+        }
+        rows
+    };
+
+    let profile = DataProfiler::new().profile(&wide(1), "narrow.csv").unwrap();
+    assert_eq!(profile.candidate_keys, ["first,last"]);
+
+    let profile = DataProfiler::new()
+        .profile(&wide(MAX_KEY_PAIR_COLUMNS), "wide.csv")
+        .unwrap();
+    assert!(profile.candidate_keys.is_empty());
+}
+
+// ============ Date Format Tests ============
+
+#[test]
+fn test_custom_date_format_is_classified_as_date() {
+    let data = table(
+        &["month"],
+        ["Jan 2023", "Mar 2023", "Feb 2024"].map(|v| vec![v.to_string()]),
+    );
+
+    let profile = DataProfiler::new().profile(&data, "months.csv").unwrap();
+    assert!(matches!(profile.columns[0].data_type, DataType::String));
+
+    let profile = DataProfiler::new()
+        .with_date_formats(vec!["%b %Y".to_string()])
+        .profile(&data, "months.csv")
+        .unwrap();
+
+    let column = &profile.columns[0];
+    assert!(matches!(column.data_type, DataType::Date));
+    let stats = column.date_stats.as_ref().unwrap();
+    assert_eq!(stats.min_date, "2023-01-01");
+    assert_eq!(stats.max_date, "2024-02-01");
+}
+
+#[test]
+fn test_date_formats_extend_defaults() {
+    let mut formats: Vec<String> = DEFAULT_DATE_FORMATS.iter().map(|f| f.to_string()).collect();
+    formats.push("%G-W%V".to_string());
+    let profiler = DataProfiler::new().with_date_formats(formats);
+    let data = table(
+        &["day", "week"],
+        [["20/03/2023", "2023-W12"], ["27/03/2023", "2023-W13"]]
+            .map(|row| row.map(String::from).to_vec()),
+    );
+
+    let profile = profiler.profile(&data, "weeks.csv").unwrap();
+
+    assert!(matches!(profile.columns[0].data_type, DataType::Date));
+    assert!(matches!(profile.columns[1].data_type, DataType::Date));
+    let stats = profile.columns[1].date_stats.as_ref().unwrap();
+    assert_eq!(stats.min_date, "2023-03-20");
+    assert_eq!(stats.most_common_day_of_week, "Monday");
+}
@@ -1,6 +1,13 @@
 //! Tests for streaming module
 
-use datacell::streaming::{ChunkMetadata, DataChunk, StreamingProcessor};
+use datacell::streaming::{
+    BloomFilter, ChunkMetadata, DataChunk, DedupeMode, StreamingProcessor,
+    convert_csv_to_parquet_streaming, convert_parquet_streaming, convert_streaming, stream_dedupe,
+};
+use datacell::{
+    CsvHandler, DataOperations, DataReader, ParquetHandler, ParquetWriteOptions, ProgressCallback,
+};
+use tempfile::TempDir;
 
 #[test]
 fn test_data_chunk_creation() {
@@ -149,3 +156,325 @@ fn test_multiple_chunks_sequence() {
     assert_eq!(chunks[0].sequence, 0);
     assert_eq!(chunks[4].sequence, 4);
 }
+
+fn write_duplicate_heavy_csv(path: &std::path::Path) -> Vec<Vec<String>> {
+    let mut rows = vec![vec!["id".to_string(), "city".to_string()]];
+    for i in 0..500 {
+        rows.push(vec![(i % 37).to_string(), format!("city_{}", i % 5)]);
+    }
+    CsvHandler::new()
+        .write_records(path.to_str().unwrap(), rows.clone())
+        .unwrap();
+    rows
+}
+
+#[test]
+fn test_stream_dedupe_exact_matches_in_memory() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("dups.csv");
+    let output = dir.path().join("deduped.csv");
+    let rows = write_duplicate_heavy_csv(&input);
+
+    let stats = stream_dedupe(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        None,
+        DedupeMode::Exact,
+    )
+    .unwrap();
+
+    let expected = DataOperations::new().deduplicate(&rows);
+    let streamed = DataReader::read(&CsvHandler::new(), output.to_str().unwrap()).unwrap();
+
+    assert_eq!(streamed, expected);
+    assert_eq!(stats.rows_read, rows.len());
+    assert_eq!(stats.rows_written, expected.len());
+    assert_eq!(stats.duplicates_removed(), rows.len() - expected.len());
+}
+
+#[test]
+fn test_stream_dedupe_by_key_columns() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("dups.csv");
+    let output = dir.path().join("deduped.csv");
+    write_duplicate_heavy_csv(&input);
+
+    stream_dedupe(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        Some(&[1]),
+        DedupeMode::Exact,
+    )
+    .unwrap();
+
+    let streamed = DataReader::read(&CsvHandler::new(), output.to_str().unwrap()).unwrap();
+    // Header plus one row per distinct city, in first-seen order
+    assert_eq!(streamed.len(), 6);
+    assert_eq!(streamed[0], vec!["id", "city"]);
+    assert_eq!(streamed[1][1], "city_0");
+    assert_eq!(streamed[5][1], "city_4");
+}
+
+#[test]
+fn test_stream_dedupe_approximate_removes_duplicates() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("dups.csv");
+    let output = dir.path().join("deduped.csv");
+    let rows = write_duplicate_heavy_csv(&input);
+
+    let stats = stream_dedupe(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        None,
+        DedupeMode::Approximate {
+            expected_rows: 1_000,
+            false_positive_rate: 0.0001,
+        },
+    )
+    .unwrap();
+
+    // A generously sized filter should not produce false positives here
+    let expected = DataOperations::new().deduplicate(&rows);
+    assert_eq!(stats.rows_written, expected.len());
+}
+
+#[test]
+fn test_bloom_filter_membership() {
+    let mut filter = BloomFilter::new(100, 0.01);
+
+    assert!(filter.insert("alpha"));
+    assert!(!filter.insert("alpha"));
+    assert!(filter.contains("alpha"));
+    assert!(!filter.contains("beta"));
+    assert!(filter.memory_bytes() > 0);
+}
+
+/// Records every progress report it receives
+struct RecordingProgress(Vec<usize>);
+
+impl ProgressCallback for RecordingProgress {
+    fn on_progress(&mut self, current: usize, _total: Option<usize>, _message: &str) {
+        self.0.push(current);
+    }
+}
+
+fn write_numbered_csv(path: &std::path::Path, rows: usize) {
+    let mut content = String::from("id,value\n");
+    for i in 0..rows {
+        content.push_str(&format!("{i},v{i}\n"));
+    }
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_convert_streaming_reports_progress_per_batch() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("in.csv");
+    let output = dir.path().join("out.ndjson");
+    write_numbered_csv(&input, 25);
+
+    let mut progress = RecordingProgress(Vec::new());
+    let stats = convert_streaming(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        10,
+        &mut progress,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(progress.0, vec![10, 20, 25]);
+    assert_eq!(stats.batches_written, 3);
+    assert_eq!(stats.rows_written, 25);
+
+    let lines: Vec<String> = std::fs::read_to_string(&output)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    assert_eq!(lines.len(), 25);
+    assert_eq!(lines[0], r#"{"id":"0","value":"v0"}"#);
+}
+
+#[test]
+fn test_convert_streaming_resume_does_not_duplicate_rows() {
+    let dir = TempDir::new().unwrap();
+    let partial = dir.path().join("partial.csv");
+    let input = dir.path().join("in.csv");
+    let output = dir.path().join("out.csv");
+    let checkpoint = dir.path().join("convert.checkpoint");
+    let (output_str, checkpoint_str) = (output.to_str().unwrap(), checkpoint.to_str().unwrap());
+
+    // An interrupted run: only the first two batches made it to the output
+    write_numbered_csv(&partial, 20);
+    let mut progress = RecordingProgress(Vec::new());
+    convert_streaming(
+        partial.to_str().unwrap(),
+        output_str,
+        10,
+        &mut progress,
+        Some(checkpoint_str),
+    )
+    .unwrap();
+
+    // The run died part-way through writing the third batch
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&output)
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"20,v20\n21,v2").unwrap();
+    drop(file);
+
+    // The re-run over the full input drops the partial batch and skips the completed ones
+    write_numbered_csv(&input, 35);
+    let stats = convert_streaming(
+        input.to_str().unwrap(),
+        output_str,
+        10,
+        &mut progress,
+        Some(checkpoint_str),
+    )
+    .unwrap();
+
+    assert_eq!(stats.batches_skipped, 2);
+    assert_eq!(stats.rows_written, 15);
+
+    let rows = DataReader::read(&CsvHandler::new(), output_str).unwrap();
+    assert_eq!(rows.len(), 36);
+    assert_eq!(rows[0], vec!["id", "value"]);
+    let ids: Vec<String> = rows[1..].iter().map(|r| r[0].clone()).collect();
+    let expected: Vec<String> = (0..35).map(|i| i.to_string()).collect();
+    assert_eq!(ids, expected);
+
+    // A finished conversion is a no-op when run again
+    let again = convert_streaming(
+        input.to_str().unwrap(),
+        output_str,
+        10,
+        &mut progress,
+        Some(checkpoint_str),
+    )
+    .unwrap();
+    assert_eq!(again.rows_written, 0);
+    let rows = DataReader::read(&CsvHandler::new(), output_str).unwrap();
+    assert_eq!(rows.len(), 36);
+}
+
+#[test]
+fn test_convert_streaming_rejects_non_appendable_output() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("in.csv");
+    write_numbered_csv(&input, 3);
+
+    let mut progress = RecordingProgress(Vec::new());
+    let result = convert_streaming(
+        input.to_str().unwrap(),
+        dir.path().join("out.xlsx").to_str().unwrap(),
+        10,
+        &mut progress,
+        None,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_convert_parquet_streaming_in_chunks() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("in.parquet");
+    let output = dir.path().join("out.csv");
+
+    let rows: Vec<Vec<String>> = (0..25)
+        .map(|i| vec![i.to_string(), format!("v{i}")])
+        .collect();
+    ParquetHandler::new()
+        .write(
+            input.to_str().unwrap(),
+            &rows,
+            Some(&["id".to_string(), "value".to_string()]),
+        )
+        .unwrap();
+
+    let mut progress = RecordingProgress(Vec::new());
+    let stats = convert_parquet_streaming(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        10,
+        &mut progress,
+    )
+    .unwrap();
+
+    assert_eq!(progress.0, vec![10, 20, 25]);
+    assert_eq!(stats.batches_written, 3);
+    assert_eq!(stats.rows_written, 25);
+
+    let data = DataReader::read(&CsvHandler::new(), output.to_str().unwrap()).unwrap();
+    assert_eq!(data.len(), 26);
+    assert_eq!(data[0], vec!["id", "value"]);
+    assert_eq!(data[25], vec!["24", "v24"]);
+}
+
+#[test]
+fn test_convert_csv_to_parquet_streaming_one_row_group_per_batch() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("in.csv");
+    let output = dir.path().join("out.parquet");
+    write_numbered_csv(&input, 50_000);
+
+    let mut progress = RecordingProgress(Vec::new());
+    let stats = convert_csv_to_parquet_streaming(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        1000,
+        &ParquetWriteOptions::default(),
+        &mut progress,
+    )
+    .unwrap();
+
+    assert_eq!(stats.batches_written, 50);
+    assert_eq!(stats.rows_written, 50_000);
+    // Each batch is written before the next is read, so no step exceeds it
+    assert_eq!(progress.0.len(), 50);
+    assert!(progress.0.windows(2).all(|w| w[1] - w[0] <= 1000));
+
+    let handler = ParquetHandler::new();
+    let mut row_groups = 0;
+    let total = handler
+        .read_chunked(output.to_str().unwrap(), 1_000_000, |_| {
+            row_groups += 1;
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(total, 50_000);
+    assert_eq!(row_groups, 50);
+
+    let schema = handler.get_schema(output.to_str().unwrap()).unwrap();
+    assert_eq!(schema[0], ("id".to_string(), "Int64".to_string()));
+    assert_eq!(schema[1], ("value".to_string(), "Utf8".to_string()));
+}
+
+#[test]
+fn test_convert_csv_to_parquet_streaming_widens_late_types() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("in.csv");
+    let output = dir.path().join("out.parquet");
+    std::fs::write(&input, "id,amount,code\n1,10,1\n2,20,\n3,30.5,four\n").unwrap();
+
+    let stats = convert_csv_to_parquet_streaming(
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        2,
+        &ParquetWriteOptions::default(),
+        &mut RecordingProgress(Vec::new()),
+    )
+    .unwrap();
+    assert_eq!(stats.rows_written, 3);
+
+    let handler = ParquetHandler::new();
+    let output = output.to_str().unwrap();
+    let schema = handler.get_schema(output).unwrap();
+    let types: Vec<&str> = schema.iter().map(|(_, t)| t.as_str()).collect();
+    assert_eq!(types, vec!["Int64", "Float64", "Utf8"]);
+    let rows = handler.read_with_headers(output).unwrap();
+    assert_eq!(rows[3], vec!["3", "30.5", "four"]);
+    assert!(!dir.path().join("out.parquet.partial").exists());
+}
@@ -0,0 +1,170 @@
+//! Tests for text analysis
+
+use datacell::TextAnalyzer;
+use datacell::text_analysis::{Keyword, KeywordResult, TextOperation, stemmer};
+use std::collections::HashSet;
+
+fn documents(texts: &[&str]) -> Vec<String> {
+    texts.iter().map(|t| t.to_string()).collect()
+}
+
+fn keyword<'a>(result: &'a KeywordResult, word: &str) -> &'a Keyword {
+    result.keywords.iter().find(|k| k.word == word).unwrap()
+}
+
+// ============ TF-IDF Tests ============
+
+#[test]
+fn test_tfidf_downweights_words_common_to_every_document() {
+    let docs = documents(&[
+        "shipping delayed, shipping refund requested",
+        "shipping arrived with broken zipper",
+        "shipping fast and friendly support",
+    ]);
+    let analyzer = TextAnalyzer::new();
+
+    let results = analyzer.extract_keywords_tfidf(&docs, 10);
+    assert_eq!(results.len(), 3);
+
+    // "zipper" and "shipping" each occur once in the second document, but
+    // only "shipping" occurs in every document
+    assert!(keyword(&results[1], "zipper").score > keyword(&results[1], "shipping").score);
+    assert_ne!(results[1].keywords[0].word, "shipping");
+    assert_eq!(keyword(&results[0], "shipping").frequency, 2);
+
+    let top_one = analyzer.extract_keywords_tfidf(&docs, 1);
+    assert!(top_one.iter().all(|r| r.total_keywords == 1));
+}
+
+// ============ Stop Word and Stemming Tests ============
+
+#[test]
+fn test_keywords_skip_default_and_custom_stopwords() {
+    let text = "The runner and the coach said the track was fast and the track was dry";
+    let words = |analyzer: &TextAnalyzer| -> HashSet<String> {
+        let result = analyzer.extract_keywords(text, 20);
+        result.keywords.into_iter().map(|k| k.word).collect()
+    };
+
+    let defaults = words(&TextAnalyzer::new());
+    assert!(defaults.contains("track"));
+    for stop_word in ["the", "and", "was"] {
+        assert!(!defaults.contains(stop_word), "{stop_word}");
+    }
+
+    let mut stop_words = TextAnalyzer::default_stop_words();
+    stop_words.extend(["track".to_string(), "Said".to_string()]);
+    let custom = words(&TextAnalyzer::new().with_stopwords(stop_words));
+    for stop_word in ["track", "said", "the"] {
+        assert!(!custom.contains(stop_word), "{stop_word}");
+    }
+    assert!(custom.contains("runner"));
+
+    let only_runner = HashSet::from(["runner".to_string()]);
+    let replaced = words(&TextAnalyzer::new().with_stopwords(only_runner));
+    assert!(replaced.contains("the"));
+    assert!(!replaced.contains("runner"));
+}
+
+#[test]
+fn test_stemming_collapses_inflections() {
+    assert_eq!(stemmer::stem("running"), "run");
+    assert_eq!(stemmer::stem("runs"), "run");
+    assert_eq!(stemmer::stem("caresses"), "caress");
+    assert_eq!(stemmer::stem("hoping"), "hope");
+    assert_eq!(stemmer::stem("agreed"), "agree");
+    assert_eq!(stemmer::stem("happy"), "happi");
+
+    let text = "running runs run walked";
+    let stemmed = TextAnalyzer::new()
+        .with_stemming(true)
+        .extract_keywords(text, 10);
+    let run = keyword(&stemmed, "run");
+    assert_eq!(run.frequency, 3);
+    assert!(stemmed.keywords.iter().any(|k| k.word == "walk"));
+
+    let unstemmed = TextAnalyzer::new().extract_keywords(text, 10);
+    assert_eq!(unstemmed.total_keywords, 4);
+}
+
+// ============ N-gram Tests ============
+
+#[test]
+fn test_repeated_bigram_outranks_its_words() {
+    let text = "Machine learning needs data. We teach machine learning with care, \
+                and machine learning rewards patience.";
+    let analyzer = TextAnalyzer::new();
+
+    let bigrams = analyzer.extract_ngrams(text, 2, 3);
+    assert_eq!(bigrams[0], ("machine learning".to_string(), 3));
+
+    let keywords = analyzer.extract_phrase_keywords(text, 2, 3);
+    let top: Vec<&str> = keywords.keywords.iter().map(|k| k.word.as_str()).collect();
+    assert_eq!(top, vec!["machine learning", "learning", "machine"]);
+}
+
+#[test]
+fn test_ngrams_stop_at_sentence_ends() {
+    let text = "Prices rose sharply. Sharply rising prices worry buyers.\nBuyers wait";
+    let analyzer = TextAnalyzer::new();
+
+    let bigrams: Vec<String> = analyzer
+        .extract_ngrams(text, 2, 100)
+        .into_iter()
+        .map(|(gram, _)| gram)
+        .collect();
+    assert!(bigrams.contains(&"rose sharply".to_string()));
+    assert!(!bigrams.contains(&"sharply sharply".to_string()));
+    assert!(!bigrams.contains(&"buyers buyers".to_string()));
+
+    let trigrams = analyzer.extract_ngrams(text, 3, 100);
+    assert_eq!(trigrams.len(), 4);
+    assert!(
+        trigrams
+            .iter()
+            .all(|(gram, count)| { gram.split(' ').count() == 3 && *count == 1 })
+    );
+    assert!(analyzer.extract_ngrams(text, 0, 10).is_empty());
+}
+
+// ============ Per-row Analysis Tests ============
+
+#[test]
+fn test_analyze_column_appends_sentiment_per_row() {
+    let data = vec![
+        vec!["id".to_string(), "review".to_string()],
+        vec!["1".to_string(), "great amazing".to_string()],
+        vec!["2".to_string(), "terrible awful".to_string()],
+        vec!["3".to_string(), "arrived on tuesday".to_string()],
+    ];
+    let analyzer = TextAnalyzer::new();
+
+    let analyzed = analyzer.analyze_column(&data, 1, TextOperation::Sentiment);
+    assert_eq!(analyzed[0], vec!["id", "review", "sentiment", "confidence"]);
+    let labels: Vec<&str> = analyzed[1..].iter().map(|row| row[2].as_str()).collect();
+    assert_eq!(labels, vec!["Positive", "Negative", "Neutral"]);
+    assert_eq!(analyzed[1][3], "1.0000");
+
+    let keywords = analyzer.analyze_column(&data, 1, TextOperation::Keywords);
+    assert_eq!(keywords[3][2], "arrived; tuesday");
+    assert_eq!(
+        "TFIDF".parse::<TextOperation>().unwrap(),
+        TextOperation::Tfidf
+    );
+    assert!("summary".parse::<TextOperation>().is_err());
+}
+
+#[test]
+fn test_analyze_column_aligns_ragged_rows() {
+    let data = vec![
+        vec!["id".to_string(), "review".to_string()],
+        vec!["1".to_string()],
+        vec!["2".to_string(), "great".to_string(), "extra".to_string()],
+    ];
+    let analyzer = TextAnalyzer::new();
+
+    let analyzed = analyzer.analyze_column(&data, 1, TextOperation::Keywords);
+    assert_eq!(analyzed[1], vec!["1", "", ""]);
+    // Cells beyond the header are kept, ahead of the analysis column
+    assert_eq!(analyzed[2], vec!["2", "great", "extra", "great"]);
+}
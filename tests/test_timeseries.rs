@@ -0,0 +1,81 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use datacell::{TimeSeriesAgg, TimeSeriesPoint, TimeSeriesProcessor};
+
+fn at(minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, minute, 0)
+        .unwrap()
+}
+
+/// Points at minutes 0, 1, 5, 6 and 20, valued 1 to 5
+fn irregular_series() -> Vec<TimeSeriesPoint> {
+    [0, 1, 5, 6, 20]
+        .iter()
+        .zip(1..)
+        .map(|(&minute, value)| TimeSeriesPoint {
+            timestamp: at(minute),
+            value: value as f64,
+        })
+        .collect()
+}
+
+fn values(series: &[TimeSeriesPoint]) -> Vec<f64> {
+    series.iter().map(|p| p.value).collect()
+}
+
+#[test]
+fn test_rolling_window_spans_duration_not_rows() {
+    let processor = TimeSeriesProcessor::new("%Y-%m-%d %H:%M:%S");
+    let series = irregular_series();
+
+    let sums = processor
+        .rolling(&series, Duration::minutes(5), TimeSeriesAgg::Sum)
+        .unwrap();
+    // Each window is (t - 5min, t]: minute 5 drops minute 0, minute 20 stands alone
+    assert_eq!(values(&sums), vec![1.0, 3.0, 5.0, 7.0, 5.0]);
+
+    let counts = processor
+        .rolling(&series, Duration::minutes(5), TimeSeriesAgg::Count)
+        .unwrap();
+    assert_eq!(values(&counts), vec![1.0, 2.0, 2.0, 2.0, 1.0]);
+}
+
+#[test]
+fn test_rolling_window_past_earliest_time_covers_everything() {
+    let processor = TimeSeriesProcessor::new("%Y-%m-%d %H:%M:%S");
+    let series = irregular_series();
+
+    // Longer than the whole representable calendar before 2024
+    let window = Duration::weeks(52 * 300_000);
+    let sums = processor
+        .rolling(&series, window, TimeSeriesAgg::Sum)
+        .unwrap();
+
+    assert_eq!(values(&sums), vec![1.0, 3.0, 6.0, 10.0, 15.0]);
+}
+
+#[test]
+fn test_expanding_aggregates() {
+    let processor = TimeSeriesProcessor::new("%Y-%m-%d %H:%M:%S");
+    let series: Vec<TimeSeriesPoint> = [3.0, 1.0, 4.0, 1.0]
+        .iter()
+        .enumerate()
+        .map(|(minute, &value)| TimeSeriesPoint {
+            timestamp: at(minute as u32),
+            value,
+        })
+        .collect();
+
+    let expanding = |agg| values(&processor.expanding(&series, agg).unwrap());
+    assert_eq!(expanding(TimeSeriesAgg::Sum), vec![3.0, 4.0, 8.0, 9.0]);
+    assert_eq!(
+        expanding(TimeSeriesAgg::Mean),
+        vec![3.0, 2.0, 8.0 / 3.0, 2.25]
+    );
+    assert_eq!(expanding(TimeSeriesAgg::Min), vec![3.0, 1.0, 1.0, 1.0]);
+    assert_eq!(expanding(TimeSeriesAgg::Max), vec![3.0, 3.0, 4.0, 4.0]);
+    assert_eq!(expanding(TimeSeriesAgg::Count), vec![1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(expanding(TimeSeriesAgg::First), vec![3.0; 4]);
+    assert_eq!(expanding(TimeSeriesAgg::Median), vec![3.0, 2.0, 3.0, 2.0]);
+}
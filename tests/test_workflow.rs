@@ -0,0 +1,158 @@
+//! Tests for workflow pipeline execution
+
+use datacell::WorkflowExecutor;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_pipeline(dir: &TempDir, input: &str, output: &str, report: &str) -> String {
+    let config = serde_json::json!({
+        "name": "validated-etl",
+        "steps": [
+            {
+                "operation": "validate",
+                "input": input,
+                "args": {
+                    "rules": {
+                        "rules": { "email": [{ "type": "NotNull" }, { "type": "Email" }] },
+                        "strict_mode": true,
+                        "stop_on_first_error": false
+                    },
+                    "report": report
+                }
+            },
+            {
+                "operation": "select",
+                "output": output,
+                "args": { "columns": ["email"] }
+            }
+        ]
+    });
+
+    let path = dir.path().join("pipeline.json");
+    fs::write(&path, config.to_string()).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_validate_step_passes_valid_data() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("good.csv");
+    let output = dir.path().join("out.csv");
+    let report = dir.path().join("report.md");
+    fs::write(
+        &input,
+        "name,email\nAlice,alice@example.com\nBob,bob@example.com\n",
+    )
+    .unwrap();
+
+    let pipeline = write_pipeline(
+        &dir,
+        &input.to_string_lossy(),
+        &output.to_string_lossy(),
+        &report.to_string_lossy(),
+    );
+
+    WorkflowExecutor::new().execute(&pipeline).unwrap();
+
+    assert!(output.exists());
+    assert!(fs::read_to_string(&report).unwrap().contains("PASSED"));
+}
+
+#[test]
+fn test_validate_step_stops_pipeline_on_invalid_data() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("bad.csv");
+    let output = dir.path().join("out.csv");
+    let report = dir.path().join("report.md");
+    fs::write(
+        &input,
+        "name,email\nAlice,alice@example.com\nBob,not-an-email\n",
+    )
+    .unwrap();
+
+    let pipeline = write_pipeline(
+        &dir,
+        &input.to_string_lossy(),
+        &output.to_string_lossy(),
+        &report.to_string_lossy(),
+    );
+
+    let result = WorkflowExecutor::new().execute(&pipeline);
+
+    assert!(result.is_err());
+    assert!(
+        !output.exists(),
+        "later steps must not run after a failed validation"
+    );
+    assert!(fs::read_to_string(&report).unwrap().contains("FAILED"));
+}
+
+fn write_sales(dir: &TempDir) -> String {
+    let input = dir.path().join("sales.csv");
+    fs::write(
+        &input,
+        "region,product,amount\nnorth,apple,10\nsouth,apple,20\nnorth,pear,5\nnorth,apple,7\n",
+    )
+    .unwrap();
+    input.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_yaml_pipeline_reads_groups_and_writes() {
+    let dir = TempDir::new().unwrap();
+    let input = write_sales(&dir);
+    let output = dir.path().join("totals.csv");
+    let pipeline = dir.path().join("pipeline.yaml");
+    fs::write(
+        &pipeline,
+        format!(
+            "name: north-totals
+steps:
+  - operation: read
+    input: {input}
+  - operation: groupby
+    args:
+      by: [region]
+      aggregations:
+        - {{ column: amount, func: sum }}
+  - operation: write
+    output: {}
+",
+            output.display()
+        ),
+    )
+    .unwrap();
+
+    WorkflowExecutor::new()
+        .execute(&pipeline.to_string_lossy())
+        .unwrap();
+
+    let written = fs::read_to_string(&output).unwrap();
+    let mut lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.remove(0), "region,sum_amount");
+    lines.sort();
+    assert_eq!(lines, ["north,22.00", "south,20.00"]);
+}
+
+#[test]
+fn test_unknown_column_fails_with_step_index() {
+    let dir = TempDir::new().unwrap();
+    let input = write_sales(&dir);
+    let pipeline = dir.path().join("pipeline.json");
+    let config = serde_json::json!({
+        "name": "bad-sort",
+        "steps": [
+            { "operation": "read", "input": input },
+            { "operation": "filter", "args": { "column": "region", "value": "north" } },
+            { "operation": "sort", "args": { "column": "price" } }
+        ]
+    });
+    fs::write(&pipeline, config.to_string()).unwrap();
+
+    let error = WorkflowExecutor::new()
+        .execute(&pipeline.to_string_lossy())
+        .unwrap_err();
+
+    assert_eq!(error.to_string(), "Step 3 (sort) failed");
+    assert!(format!("{error:#}").contains("Column 'price' not found"));
+}
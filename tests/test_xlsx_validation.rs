@@ -142,6 +142,7 @@ fn test_xlsx_with_freeze_and_autofilter() {
         header_style: CellStyle::default(),
         column_styles: None,
         freeze_header: true,
+        freeze_panes: None,
         auto_filter: true,
         auto_fit: false,
     };